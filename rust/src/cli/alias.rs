@@ -0,0 +1,91 @@
+use crate::cli::args::Cli;
+use crate::core::config::AliasValue;
+use crate::core::error::{PyForgeError, Result};
+use clap::CommandFactory;
+use std::collections::{BTreeMap, HashSet};
+
+/// Expand a user-defined alias in `args` (including `argv[0]`) before clap sees them.
+///
+/// Built-in subcommands always win over aliases of the same name, and aliases may
+/// refer to other aliases.
+pub fn expand(mut args: Vec<String>, aliases: &BTreeMap<String, AliasValue>) -> Result<Vec<String>> {
+    if aliases.is_empty() {
+        return Ok(args);
+    }
+
+    let command = Cli::command();
+    let builtins = builtins(&command);
+
+    let mut seen = HashSet::new();
+    while let Some(position) = subcommand_position(&command, &args) {
+        let name = args[position].clone();
+        if builtins.contains(name.as_str()) {
+            break;
+        }
+        let Some(expansion) = aliases.get(&name) else {
+            break;
+        };
+        if !seen.insert(name.clone()) {
+            return Err(PyForgeError::Usage {
+                message: format!("error: alias '{}' expands to itself\n", name),
+            });
+        }
+
+        args.splice(position..=position, expansion.args());
+    }
+
+    Ok(args)
+}
+
+/// Whether aliases are needed for `args`: only when the subcommand isn't
+/// built in, or there is none and the top-level help may list them
+pub fn may_expand(args: &[String]) -> bool {
+    let command = Cli::command();
+    subcommand_position(&command, args).is_none_or(|position| !builtins(&command).contains(args[position].as_str()))
+}
+
+fn builtins(command: &clap::Command) -> HashSet<&str> {
+    command
+        .get_subcommands()
+        .flat_map(|sub| std::iter::once(sub.get_name()).chain(sub.get_all_aliases()))
+        .chain(std::iter::once("help"))
+        .collect()
+}
+
+/// Help section listing configured aliases
+pub fn help_section(aliases: &BTreeMap<String, AliasValue>) -> String {
+    let width = aliases.keys().map(String::len).max().unwrap_or(0);
+    let mut section = String::from("Aliases:\n");
+    for (name, value) in aliases {
+        section.push_str(&format!("  {:width$}  {}\n", name, value.args().join(" "), width = width));
+    }
+    section
+}
+
+/// Index of the first positional argument, skipping global options and their values
+pub(crate) fn subcommand_position(command: &clap::Command, args: &[String]) -> Option<usize> {
+    let takes_value: HashSet<String> = command
+        .get_arguments()
+        .filter(|arg| arg.get_action().takes_values() && !arg.is_require_equals_set())
+        .flat_map(|arg| {
+            let long = arg.get_long().map(|l| format!("--{}", l));
+            let short = arg.get_short().map(|s| format!("-{}", s));
+            long.into_iter().chain(short)
+        })
+        .collect();
+
+    let mut i = 1;
+    while i < args.len() {
+        let arg = &args[i];
+        if arg == "--" {
+            return None;
+        } else if takes_value.contains(arg) {
+            i += 2;
+        } else if arg.starts_with('-') {
+            i += 1;
+        } else {
+            return Some(i);
+        }
+    }
+    None
+}
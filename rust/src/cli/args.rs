@@ -1,9 +1,15 @@
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches, Parser};
+use crate::core::config::AliasValue;
+use crate::core::timings::TimingsFormat;
+use crate::core::selfupdate::Channel;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "pyforge")]
 #[command(about = "CLI application for managing python projects", long_about = None)]
 #[command(version = "1.0")]
+#[command(disable_help_subcommand = true)]
 pub struct Cli {
     /// verbose mode
     #[arg(short, long)]
@@ -13,24 +19,1074 @@ pub struct Cli {
     #[arg(short, long)]
     pub file: Option<String>,
 
+    /// Also write structured (JSON lines) logs to this file
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// How errors are reported on stderr
+    #[arg(long, global = true, value_enum, default_value_t = ErrorFormat::Human)]
+    pub error_format: ErrorFormat,
+
+    /// Print how long each phase took (resolve, download, install, ...) to stderr
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "human"
+    )]
+    pub timings: Option<TimingsFormat>,
+
+    /// Skip optional downloads (release notes, vulnerability queries) on a metered connection
+    #[arg(long, global = true)]
+    pub metered: bool,
+
+    /// Also write the output of tests, tasks and builds to files in .pyforge/logs
+    #[arg(long, global = true)]
+    pub capture_logs: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
 
+/// Error output format; `json` prints the variant, exit code and context fields
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ErrorFormat {
+    #[default]
+    Human,
+    Json,
+}
+
+impl ErrorFormat {
+    /// Find `--error-format` in raw arguments, so errors raised before or during
+    /// argument parsing are reported in the requested format too
+    pub fn detect(args: &[String]) -> Self {
+        let mut value = None;
+        for (i, arg) in args.iter().enumerate() {
+            if arg == "--" {
+                break;
+            } else if let Some(v) = arg.strip_prefix("--error-format=") {
+                value = Some(v);
+            } else if arg == "--error-format" {
+                value = args.get(i + 1).map(String::as_str);
+            }
+        }
+
+        match value {
+            Some("json") => ErrorFormat::Json,
+            _ => ErrorFormat::Human,
+        }
+    }
+}
+
 #[derive(clap::Subcommand)]
 pub enum Commands {
     /// Init a new project
     Init {
         name: String,
+
+        /// Layout or template to start from: a built-in layout (lib, cli, fastapi, minimal; default lib),
+        /// a directory, a name under templates/ in the data directory, a git URL or `gh:owner/repo`
+        /// (append `#<ref>` for a branch or tag), or a .tar.gz/.zip URL
         #[arg(long)]
         template: Option<String>,
+
+        /// Set the mode of rendered template files matching a glob: `bin/*=+x`, `*.sh=755`
+        /// or `secrets.env=600` (repeatable; the last matching rule wins)
+        #[arg(long, value_name = "PATTERN=MODE")]
+        chmod: Vec<String>,
+
+        /// Set a template variable, overriding its default: `use_docker=yes` (repeatable)
+        #[arg(long = "var", value_name = "NAME=VALUE")]
+        vars: Vec<String>,
+
+        /// Tooling preset: minimal, strict, data, a user preset name, or a path to a preset file
+        #[arg(long)]
+        preset: Option<String>,
+
+        #[command(flatten)]
+        org: OrgFlags,
+
+        /// Pin the project to an installed Python version, e.g. `3.12`: written to
+        /// .python-version, and `requires-python` starts from it
+        #[arg(long, value_name = "VERSION")]
+        python: Option<String>,
     },
     
-    Build,
+    /// Add dependencies to pyproject.toml and update the lockfile
+    ///
+    /// Each package is checked against index metadata first, with a warning when
+    /// its name looks like a typo of a widely used package, or when it is very
+    /// new or has few releases. All packages are resolved together and the
+    /// lockfile is written once, e.g.
+    /// `pyforge add httpx --group dev pytest mypy --group docs sphinx`.
+    Add {
+        /// Requirements to add, e.g. `requests` or `httpx[http2]>=0.27`
+        #[arg(required_unless_present = "group")]
+        requirements: Vec<String>,
+
+        /// Add the positional requirements to this optional dependency group
+        /// instead of `dependencies`
+        #[arg(long, value_name = "GROUP", conflicts_with = "dev")]
+        optional: Option<String>,
+
+        /// Add the positional requirements to the `dev` optional dependency group
+        #[arg(long)]
+        dev: bool,
+
+        /// Add the requirements that follow to this optional dependency group;
+        /// may be repeated
+        #[arg(long, num_args = 2.., value_names = ["GROUP", "REQUIREMENT"], action = clap::ArgAction::Append)]
+        group: Vec<String>,
+
+        /// Each `--group`: the group name followed by its requirements
+        #[arg(skip)]
+        groups: Vec<Vec<String>>,
+    },
+
+    /// Remove dependencies from pyproject.toml and update the lockfile
+    Remove {
+        /// Packages to remove, e.g. `requests`
+        #[arg(required = true)]
+        packages: Vec<String>,
+
+        /// Remove them from this optional dependency group instead of `dependencies`
+        #[arg(long, visible_alias = "group", value_name = "GROUP", conflicts_with = "dev")]
+        optional: Option<String>,
+
+        /// Remove them from the `dev` optional dependency group
+        #[arg(long)]
+        dev: bool,
+    },
+
+    /// Show a package on the configured indexes: releases, how often they
+    /// come out and, for PyPI packages, recent downloads
+    Show {
+        /// Package name
+        package: String,
+    },
+
+    /// Build the project
+    ///
+    /// Steps from `[[tool.pyforge.build.steps]]` run first, in order, skipping any
+    /// whose inputs and outputs are unchanged since its last run.
+    Build {
+        #[command(flatten)]
+        lock: LockFlags,
+
+        /// Let the build backend access the network (builds run offline by default)
+        #[arg(long)]
+        allow_network: bool,
+
+        /// Run every build step, even those that are up to date
+        #[arg(long)]
+        no_cache: bool,
+
+        /// Compare each new distribution with the previous one in dist/: size,
+        /// added and removed files, and metadata
+        #[arg(long)]
+        diff_last: bool,
+
+        /// Fail when a [tool.pyforge.build.checks] check fails, instead of warning
+        #[arg(long)]
+        strict: bool,
+
+        /// Build only the sdist (with --wheel, both, as without either)
+        #[arg(long)]
+        sdist: bool,
+
+        /// Build only the wheel
+        #[arg(long)]
+        wheel: bool,
+
+        /// Directory to put the distributions in (default: dist/ in the project)
+        #[arg(long, value_name = "DIR")]
+        out_dir: Option<PathBuf>,
+    },
+
+    /// Diagnose common setup problems
+    Doctor {
+        /// Also measure latency to every configured index and mirror
+        #[arg(long)]
+        network: bool,
+    },
+
+    /// Run the test suite in the project's environment, or in a container
+    ///
+    /// The command is `[tool.pyforge.test] command`, else the `test` script, else
+    /// `python -m pytest`; extra arguments are appended to it. A run is skipped when
+    /// the last green run had identical inputs.
+    Test {
+        /// Run in a container with the project and a pip cache volume mounted
+        #[arg(long)]
+        in_docker: bool,
+
+        /// Container image profile from `[tool.pyforge.test.profiles]`
+        #[arg(long, requires = "in_docker")]
+        profile: Option<String>,
+
+        /// Run even if the tests passed before with the same sources, lockfile and command
+        #[arg(long)]
+        no_cache: bool,
+
+        #[command(flatten)]
+        reports: TestReportFlags,
+
+        #[command(flatten)]
+        lock: LockFlags,
+
+        /// Arguments passed on to the test command
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+
+    /// Upgrade locked dependencies to the newest versions the constraints and policy allow
+    ///
+    /// With --interactive the available updates are listed by impact (major,
+    /// minor, patch) to choose from; the others keep their locked versions.
+    ///
+    /// The tests run against the upgraded environment and the previous lockfile is
+    /// restored when they fail. With --create-pr the new lockfile is committed to a
+    /// branch, pushed, and proposed as a GitHub pull request or GitLab merge request
+    /// listing every version change.
+    #[command(visible_alias = "upgrade")]
+    Bump {
+        /// Choose which updates to apply in a checklist, then sync them
+        #[arg(long, short)]
+        interactive: bool,
+
+        /// Push the update to a new branch and open a pull/merge request for it
+        #[arg(long)]
+        create_pr: bool,
+
+        /// Keep the update without running the tests
+        #[arg(long)]
+        no_test: bool,
+
+        /// Summarize the release notes of each upgraded package (from PyPI and GitHub)
+        #[arg(long)]
+        changelog: bool,
+
+        /// Remote to push the branch to
+        #[arg(long, default_value = "origin")]
+        remote: String,
+    },
+
+    /// Pin dependencies in pyproject.toml to their locked versions
+    ///
+    /// Every requirement of `dependencies` and the optional groups becomes
+    /// `==<locked version>`, or `~=<locked version>` with --compatible, keeping
+    /// its extras and markers. Direct references, workspace members and
+    /// packages not locked for this platform are left as they are.
+    Pin {
+        /// Allow later patch releases (`~=1.4.2`) instead of exact pins
+        #[arg(long)]
+        compatible: bool,
+    },
+
+    /// Score the project's health: tests, coverage, known vulnerabilities,
+    /// outdated dependencies and lint findings
+    ///
+    /// Checks that can't run (no pytest-cov or ruff among the dependencies,
+    /// no network) are skipped and left out of the score.
+    Report {
+        /// Skip the test run, and with it coverage
+        #[arg(long)]
+        no_test: bool,
+
+        /// Also write the report as an HTML page
+        #[arg(long, value_name = "PATH")]
+        html: Option<PathBuf>,
+
+        /// Annotate lint findings for GitHub Actions (the default when GITHUB_ACTIONS is set)
+        #[arg(long)]
+        ci: bool,
+    },
+
+    /// Find the dependency release that broke a command by bisecting
+    ///
+    /// Each step installs a candidate into the project's environment and runs the
+    /// command given after `--` (by default the test command) in the current
+    /// sources: exit code 0 marks the candidate good, 125 skips it, anything else
+    /// marks it bad. The environment is synced back to pyforge.lock afterwards.
+    #[command(subcommand)]
+    Bisect(BisectCommand),
+
+    /// Resolve dependencies and write pyforge.lock
+    Lock {
+        /// Fail if the toolchain differs from the one recorded in the existing lockfile
+        #[arg(long, global = true)]
+        frozen_toolchain: bool,
+
+        /// Print the resolver's decisions about a package: constraints, candidates and rejected versions
+        #[arg(long, value_name = "PACKAGE")]
+        explain: Option<String>,
+
+        /// Resolve as if the indexes were frozen at this date (2024-05-01) or RFC 3339 timestamp
+        #[arg(long, value_name = "DATE")]
+        exclude_newer: Option<String>,
+
+        /// Also resolve from the wheels and sdists in this directory (repeatable)
+        #[arg(long, value_name = "DIR")]
+        find_links: Vec<PathBuf>,
+
+        #[command(subcommand)]
+        command: Option<LockCommand>,
+    },
+
+    /// Create the environment, lock and sync in one step, showing each phase
+    Install {
+        #[command(flatten)]
+        lock: LockFlags,
+
+        #[command(flatten)]
+        sync: SyncFlags,
+    },
+
+    /// Install the locked dependencies into the project's .venv
+    ///
+    /// Installed packages that aren't in pyforge.lock, and lock entries no
+    /// dependency requires any more (e.g. after merging lockfiles), are
+    /// reported; --prune removes them.
+    Sync {
+        #[command(flatten)]
+        lock: LockFlags,
+
+        #[command(flatten)]
+        sync: SyncFlags,
+    },
+
+    /// Check what the project publishes, and fix what can be fixed
+    #[command(subcommand)]
+    Check(CheckCommand),
+
+    /// Trust the project in the current directory to run the commands it defines
+    ///
+    /// Scripts, the test command, build steps and dev watchers from a project's
+    /// [tool.pyforge] only run in trusted directories. pyforge asks the first
+    /// time; this trusts a directory ahead of time, e.g. before a non-interactive run.
+    Trust {
+        /// Stop trusting the directory
+        #[arg(long, conflicts_with = "list")]
+        revoke: bool,
+
+        /// List the trusted directories
+        #[arg(long)]
+        list: bool,
+    },
+
+    /// Re-hash installed files against RECORD entries and the lockfile to detect tampering
+    Verify {
+        /// Files hashed at once (default: one per CPU)
+        #[arg(long, short)]
+        jobs: Option<usize>,
+    },
+
+    /// Download every locked dependency into a directory and install only from there
+    Vendor {
+        /// Output directory, relative to the project root
+        /// (default: vendor/, or <package>/_vendor with --inline)
+        #[arg(long)]
+        dir: Option<String>,
+
+        /// Copy pure-Python dependencies into the project's `_vendor` package and rewrite their imports
+        #[arg(long)]
+        inline: bool,
+
+        /// Fail if the toolchain differs from the one recorded in the lockfile
+        #[arg(long)]
+        frozen_toolchain: bool,
+
+        /// Files downloaded at once (default: one per CPU)
+        #[arg(long, short)]
+        jobs: Option<usize>,
+    },
+
+    /// Interactive dashboard with fuzzy search over commands, scripts and recent tasks
+    Ui,
+
+    /// Run the configured dev watchers side by side in a dashboard
+    Dev,
+
+    /// Replace this pyforge with the newest release of its channel, or another release
+    UpgradeSelf {
+        /// Release channel to follow (default: the configured or installed one)
+        #[arg(long, value_enum)]
+        channel: Option<Channel>,
+
+        /// Install this release tag instead, e.g. v0.4.1
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Allow installing a release older than the running one
+        #[arg(long)]
+        allow_downgrade: bool,
+
+        /// Only show which release would be installed
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Choose your default Python, author, package index and colors
+    Setup {
+        /// Write the detected settings without asking
+        #[arg(long)]
+        defaults: bool,
+    },
+
+    /// Check the global config and [tool.pyforge] for problems
+    #[command(subcommand)]
+    Config(ConfigCommand),
+
+    /// Show help for a command or a long-form topic (e.g. `pyforge help configuration`)
+    Help {
+        /// Topic or command path
+        topic: Vec<String>,
+    },
+
+    /// Write roff man pages (for packagers)
+    #[command(hide = true)]
+    GenerateMan {
+        /// Write one page per command here instead of printing pyforge.1
+        #[arg(long)]
+        out_dir: Option<PathBuf>,
+    },
+
+    /// Build the project and publish its distributions
+    ///
+    /// The sdist, the wheel and a SHA256SUMS file are attached to a new GitHub
+    /// release of the repository behind the git remote.
+    Publish {
+        /// Create a GitHub release for this tag, e.g. `v1.2.0`; it must match the project version
+        #[arg(long, value_name = "TAG")]
+        github_release: String,
+
+        /// Remote whose GitHub repository gets the release
+        #[arg(long, default_value = "origin")]
+        remote: String,
+
+        #[command(flatten)]
+        lock: LockFlags,
+
+        /// Let the build backend access the network (builds run offline by default)
+        #[arg(long)]
+        allow_network: bool,
+    },
+
+    /// Explain how to yank a release published on PyPI
+    Yank {
+        /// Version to yank, e.g. `1.2.0`
+        version: String,
+
+        /// Reason shown to users installing the yanked release
+        #[arg(long)]
+        reason: Option<String>,
+    },
+
+    /// Mark the project deprecated and publish a final release to PyPI that says so
+    Deprecate {
+        /// Why the project is deprecated; shown in the README and on import
+        #[arg(long)]
+        message: String,
+
+        /// Project to use instead
+        #[arg(long, value_name = "PROJECT")]
+        successor: Option<String>,
+
+        /// Do not ask for confirmation
+        #[arg(long, short)]
+        yes: bool,
+
+        #[command(flatten)]
+        lock: LockFlags,
+    },
+
+    /// Show the project version
+    Version {
+        /// Print only the version, derived from git tags when it is dynamic
+        #[arg(long)]
+        resolved: bool,
+    },
+
+    /// Run the project's scripts, locally or on other machines
+    #[command(subcommand)]
+    Task(TaskCommand),
+
+    /// Work with the template a project was generated from
+    #[command(subcommand)]
+    Template(TemplateCommand),
+
+    /// Read output captured with --capture-logs or [tool.pyforge.logs]
+    #[command(subcommand)]
+    Logs(LogsCommand),
+
+    /// Queue commands to run in the background one after another, e.g. `pyforge q test`
+    #[command(subcommand, name = "q", visible_alias = "queue")]
+    Queue(QueueCommand),
+
+    /// Add setup such as a Dockerfile, docs or CI to an existing project
+    #[command(subcommand)]
+    Feature(FeatureCommand),
+
+    /// Generate a dev container configuration for VS Code and Codespaces
+    #[command(subcommand)]
+    Devcontainer(DevcontainerCommand),
+
+    /// Point VS Code or PyCharm at the managed environment, test runner and formatter
+    #[command(subcommand)]
+    Editor(EditorCommand),
+
+    /// Create, inspect and use the project's environment
+    #[command(subcommand, visible_alias = "venv")]
+    Env(EnvCommand),
+
+    /// Find installed Python interpreters and pin one for the project
+    #[command(subcommand)]
+    Python(PythonCommand),
+
+    /// Switch to a registered project: prints shell code to cd into it and
+    /// activate its environment, for `eval "$(pyforge workon NAME)"`
+    Workon {
+        /// Project name or a fuzzy match of it; lists the projects when omitted
+        #[arg(conflicts_with_all = ["add", "forget"])]
+        query: Option<String>,
+
+        /// Open a shell in the project with its environment active instead
+        #[arg(long, requires = "query")]
+        shell: bool,
+
+        /// Register the project in DIR, or every project directly below it
+        #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = ".", conflicts_with = "forget")]
+        add: Option<PathBuf>,
+
+        /// Forget the registered project NAME
+        #[arg(long, value_name = "NAME")]
+        forget: Option<String>,
+    },
+
+    /// Manage tokens for GitHub, GitLab and other services
+    #[command(subcommand)]
+    Auth(AuthCommand),
+
+    /// Inspect and check the organization policy
+    #[command(subcommand)]
+    Policy(PolicyCommand),
+
+    /// Show and manage the project's collaborators on PyPI
+    #[command(subcommand)]
+    Owner(OwnerCommand),
+
+    /// Troubleshooting helpers
+    #[command(subcommand)]
+    Debug(DebugCommand),
+
+    /// Plumbing for scripts and other tools, printing JSON that stays stable across releases
+    #[command(subcommand)]
+    Internal(InternalCommand),
+}
+
+/// Where `pyforge init` takes organization defaults from
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct OrgFlags {
+    /// Organization defaults (authors, license, indexes, preset) to start from:
+    /// a path or http(s) URL; defaults to `[init] org-defaults` in the global config
+    #[arg(long, value_name = "URL", conflicts_with = "no_org")]
+    pub org: Option<String>,
+
+    /// Ignore the configured organization defaults
+    #[arg(long)]
+    pub no_org: bool,
+}
+
+/// Lockfile enforcement, mirroring cargo: CI should fail on drift instead of re-resolving
+#[derive(clap::Args, Debug, Clone, Copy, Default)]
+pub struct LockFlags {
+    /// Fail if pyforge.lock is missing or out of date instead of re-locking
+    #[arg(long)]
+    pub locked: bool,
+
+    /// Like --locked, and never access an index: locked files must be available locally
+    #[arg(long)]
+    pub frozen: bool,
+
+    /// Fail if the toolchain differs from the one recorded in the lockfile
+    #[arg(long)]
+    pub frozen_toolchain: bool,
+}
+
+/// Hermetic runs, to reproduce "works on my machine" failures
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct IsolationFlags {
+    /// Run with only PATH, the locale and allowed variables in the environment, an
+    /// empty temporary HOME and no user site-packages
+    #[arg(long)]
+    pub isolated: bool,
+
+    /// Pass this environment variable through to an isolated run (repeatable)
+    #[arg(long, value_name = "NAME", requires = "isolated")]
+    pub allow_env: Vec<String>,
+}
+
+/// Test results for CI to publish or show
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct TestReportFlags {
+    /// Write an HTML report of the run into this directory: every test
+    /// (from pytest's JUnit XML) and, with pytest-cov, coverage per file
+    #[arg(long, value_name = "DIR")]
+    pub report_html: Option<PathBuf>,
+
+    /// Write the results as JUnit XML to this file
+    #[arg(long, value_name = "PATH")]
+    pub junit_xml: Option<PathBuf>,
+
+    /// Annotate failing tests for GitHub Actions (the default when GITHUB_ACTIONS is set)
+    #[arg(long)]
+    pub ci: bool,
+}
+
+/// How the environment is brought in line with the lockfile
+#[derive(clap::Args, Debug, Clone, Copy, Default)]
+pub struct SyncFlags {
+    /// Reinstall every locked package, e.g. after `pyforge verify` found modified files
+    #[arg(long)]
+    pub reinstall: bool,
+
+    /// Uninstall packages that aren't locked and drop lock entries nothing requires
+    #[arg(long)]
+    pub prune: bool,
+
+    /// Files downloaded and hashed at once (default: one per CPU)
+    #[arg(long, short)]
+    pub jobs: Option<usize>,
+}
+
+#[derive(clap::Subcommand)]
+pub enum TaskCommand {
+    /// Run a `[tool.pyforge.scripts]` entry in the project's environment
+    ///
+    /// With --on (experimental), the project is copied to each host over SSH, its
+    /// environment is synced there with the remote pyforge, and the script runs on
+    /// every host at once, with the output of all hosts merged line by line.
+    Run {
+        /// Script name
+        name: String,
+
+        /// Comma-separated SSH hosts to run on instead of locally
+        #[arg(long, value_name = "HOSTS", value_delimiter = ',')]
+        on: Vec<String>,
+
+        #[command(flatten)]
+        isolation: IsolationFlags,
+
+        #[command(flatten)]
+        lock: LockFlags,
+
+        /// Arguments appended to the script
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(clap::Subcommand)]
+pub enum CheckCommand {
+    /// Check py.typed, the classifiers that follow from requires-python, the
+    /// license and typing, and [project.urls]; fails when something is off
+    Metadata {
+        /// Make the fixes instead of listing them
+        #[arg(long)]
+        fix: bool,
+    },
+}
+
+#[derive(clap::Subcommand)]
+pub enum ConfigCommand {
+    /// List unknown, mistyped and renamed keys, and rewrite renamed keys to
+    /// their current names (keeping a .bak copy of each file)
+    Doctor {
+        /// Rewrite renamed keys without asking
+        #[arg(long, short)]
+        yes: bool,
+    },
+}
+
+#[derive(clap::Subcommand)]
+pub enum TemplateCommand {
+    /// Re-render the template and merge its changes into the project
+    Update {
+        /// Template revision (branch, tag or commit) to update to; defaults to the latest
+        #[arg(long, value_name = "REF")]
+        to: Option<String>,
+    },
+
+    /// Render templates with the answers in their [snapshots] and compare the
+    /// results with the committed snapshots; fails when they differ
+    Snapshot {
+        /// Template directories or user template names; defaults to the template
+        /// in the current directory, or else every user template
+        templates: Vec<String>,
+
+        /// Write the renders as the new snapshots instead of comparing
+        #[arg(long)]
+        update: bool,
+    },
+}
+
+#[derive(clap::Subcommand)]
+pub enum LogsCommand {
+    /// List the captured logs, oldest first
+    List,
+
+    /// Print a captured log; defaults to the newest
+    Show {
+        /// Log file name from `pyforge logs list`, or a kind (test, task, build)
+        /// for the newest log of that kind
+        log: Option<String>,
+
+        /// Show the newest log
+        #[arg(long, conflicts_with = "log")]
+        last: bool,
+    },
+}
+
+#[derive(clap::Subcommand)]
+pub enum PythonCommand {
+    /// List the interpreters found on PATH, in pyenv and uv, and in the usual
+    /// install locations, newest first
+    List,
+
+    /// Pin the project to an installed version by writing .python-version
+    Pin {
+        /// Version or version prefix, e.g. `3.12` or `3.12.4`
+        version: String,
+    },
+}
+
+#[derive(clap::Subcommand)]
+pub enum QueueCommand {
+    /// List the queued, running and finished jobs
+    Status,
+
+    /// Print the output of a job; defaults to the newest one that started
+    Logs {
+        /// Job number from `pyforge q status`
+        id: Option<u64>,
+
+        /// Keep printing output until the job finishes
+        #[arg(long, short)]
+        follow: bool,
+    },
+
+    /// Remove finished jobs and their output
+    Clear,
+
+    /// Run the queued jobs; started by `pyforge q <command>`
+    #[command(hide = true)]
+    Work,
+
+    /// Any other pyforge command, with its arguments, is queued
+    #[command(external_subcommand)]
+    Run(Vec<String>),
+}
+
+#[derive(clap::Subcommand)]
+pub enum OwnerCommand {
+    /// List the project's owners and maintainers
+    List,
+
+    /// Invite a user to the project
+    Add {
+        /// PyPI user name
+        user: String,
+
+        #[arg(long, value_enum, default_value_t = OwnerRole::Maintainer)]
+        role: OwnerRole,
+    },
+
+    /// Remove a collaborator from the project
+    Remove {
+        /// PyPI user name
+        user: String,
+    },
+}
+
+/// Collaborator roles on PyPI
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum OwnerRole {
+    Owner,
+    Maintainer,
+}
+
+impl std::fmt::Display for OwnerRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            OwnerRole::Owner => "Owner",
+            OwnerRole::Maintainer => "Maintainer",
+        })
+    }
+}
+
+#[derive(clap::Subcommand)]
+pub enum FeatureCommand {
+    /// Add features to the project: files, dependency groups, tool settings and pre-commit hooks
+    ///
+    /// Nothing is written when a feature's file already exists with other content,
+    /// or pyproject.toml sets one of its settings differently.
+    Add {
+        /// Feature names (docker, docs, ci, pre-commit, or one from features/ in the config directory)
+        /// or paths to feature files
+        #[arg(required = true)]
+        features: Vec<String>,
+
+        /// Overwrite conflicting files and settings
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// List the available features
+    List,
+}
+
+#[derive(clap::Subcommand)]
+pub enum DevcontainerCommand {
+    /// Write .devcontainer/devcontainer.json, setting up the environment with `pyforge sync`
+    Init {
+        /// Base image; defaults to the devcontainers Python image for the locked Python version
+        #[arg(long)]
+        image: Option<String>,
+
+        /// Overwrite an existing devcontainer.json
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(clap::Subcommand)]
+pub enum EditorCommand {
+    /// Write editor settings for the project
+    Init {
+        /// Write .vscode/settings.json
+        #[arg(long, required_unless_present = "pycharm")]
+        vscode: bool,
+
+        /// Write the .idea project files
+        #[arg(long)]
+        pycharm: bool,
+    },
+
+    /// Rewrite the settings of every editor already set up, after the environment,
+    /// test command or formatter changed
+    Sync,
+}
+
+#[derive(clap::Subcommand)]
+pub enum EnvCommand {
+    /// Create the project's environment with the configured interpreter, without
+    /// installing dependencies (`pyforge sync` does that)
+    Create {
+        /// Remove an existing environment and create it again
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Delete the project's environment
+    Remove,
+
+    /// Show where the environment is, its interpreter and when it was created and synced
+    Info,
+
+    /// Run a command inside the environment of the project in the current directory
+    Exec {
+        #[command(flatten)]
+        isolation: IsolationFlags,
+
+        /// Command and arguments, e.g. `pyforge env exec -- pyright-langserver --stdio`
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+
+    /// Print the directory of the project's environment, and nothing else; it
+    /// need not exist yet
+    Path,
+
+    /// Print the interpreter to use for a file or directory, and nothing else.
+    ///
+    /// For a workspace member without its own environment, this is the
+    /// environment of a project in the repository that depends on it by path.
+    PythonPath {
+        /// File or directory; defaults to the current directory
+        path: Option<PathBuf>,
+    },
+
+    /// Upgrade pip and the other seed packages in the project's environment,
+    /// and apply the current `[tool.pyforge.venv]` settings to it
+    Upgrade,
+
+    /// Write the synced environment as a portable archive, to import on
+    /// another machine of the same platform instead of installing again
+    Export {
+        /// Archive to write; defaults to `<project>-env-py<version>-<platform>.tar.gz`
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Install an archive written by `pyforge env export` as the project's
+    /// environment, rewriting the paths it was exported with
+    Import {
+        archive: PathBuf,
+        /// Replace an existing environment
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(clap::Subcommand)]
+pub enum AuthCommand {
+    /// Store a token for a service (`github`, `gitlab`, `pypi`, ...) or a
+    /// package index given by URL, read from stdin
+    Login { service: String },
+
+    /// Remove the stored token for a service or index URL
+    Logout { service: String },
+
+    /// Show which services have a token and where it comes from
+    Status,
+}
+
+#[derive(clap::Subcommand)]
+pub enum LockCommand {
+    /// Resolve git merge conflicts in pyforge.lock, re-resolving only the conflicting packages
+    Merge,
+    /// Rewrite pyforge.lock in the current format without re-resolving
+    Migrate,
+    /// Convert poetry.lock, uv.lock, Pipfile.lock or pylock.toml into pyforge.lock, keeping their pins
+    Import {
+        /// Lockfile to convert (defaults to the one next to pyproject.toml)
+        path: Option<PathBuf>,
+    },
+    /// Write the lock as a standard pylock.toml (PEP 751) for other tools
+    Export {
+        /// Where to write it (defaults to pylock.toml next to pyproject.toml)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Fail if pyforge.lock is missing or out of date instead of re-locking
+        #[arg(long)]
+        locked: bool,
+    },
+}
+
+#[derive(clap::Subcommand)]
+pub enum BisectCommand {
+    /// Bisect the releases of one package, re-resolving the others around each
+    Versions {
+        /// Package whose releases to bisect
+        package: String,
+
+        /// A version that works
+        #[arg(long)]
+        good: String,
+
+        /// A version that fails (default: the newest release)
+        #[arg(long)]
+        bad: Option<String>,
+
+        /// Command to run at each step (default: the test command)
+        #[arg(last = true)]
+        command: Vec<String>,
+    },
+    /// Bisect the commits that changed pyforge.lock, installing the lock of each
+    Lock {
+        /// A commit, branch or tag whose lockfile works
+        #[arg(long)]
+        good: String,
+
+        /// A commit, branch or tag whose lockfile fails
+        #[arg(long, default_value = "HEAD")]
+        bad: String,
+
+        /// Command to run at each step (default: the test command)
+        #[arg(last = true)]
+        command: Vec<String>,
+    },
+}
+
+#[derive(clap::Subcommand)]
+pub enum PolicyCommand {
+    /// Show the active policy and where it was loaded from
+    Show,
+
+    /// Check the current project against the policy
+    Check,
+}
+
+#[derive(clap::Subcommand)]
+pub enum DebugCommand {
+    /// Collect logs, redacted config and environment info into a tarball for bug reports
+    Bundle {
+        /// Output path (defaults to pyforge-debug-<timestamp>.tar.gz)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// List renamed commands and flags with the releases that remove them
+    Deprecations {
+        /// Print the list as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(clap::Subcommand)]
+pub enum InternalCommand {
+    /// Resolve requirements, or the project's dependencies, without writing a lockfile
+    ResolveOnly {
+        /// Requirements to resolve instead of the project's
+        requirements: Vec<String>,
+    },
+    /// Print the project's lockfile, or another one, as JSON
+    PrintLockJson {
+        /// Lockfile to read instead of the project's
+        #[arg(long)]
+        path: Option<PathBuf>,
+    },
+    /// Normalize package names per PEP 503
+    NormalizeName {
+        #[arg(required = true)]
+        names: Vec<String>,
+    },
+    /// Evaluate a PEP 508 marker against an interpreter
+    EvaluateMarker {
+        marker: String,
+        /// Extra to treat as requested (repeatable)
+        #[arg(long = "extra")]
+        extras: Vec<String>,
+        /// Interpreter to evaluate against (defaults to the configured one)
+        #[arg(long)]
+        python: Option<String>,
+    },
 }
 
 impl Cli {
     pub fn parse() -> Result<Self, clap::Error> {
         <Self as Parser>::try_parse()
     }
+
+    /// Parse already alias-expanded arguments, listing `aliases` in `--help`
+    pub fn parse_with_aliases(args: Vec<String>, aliases: &BTreeMap<String, AliasValue>) -> Result<Self, clap::Error> {
+        let mut command = <Self as CommandFactory>::command();
+        if !aliases.is_empty() {
+            command = command.after_help(crate::cli::alias::help_section(aliases));
+        }
+        let matches = command.try_get_matches_from(args)?;
+        let mut cli = <Self as FromArgMatches>::from_arg_matches(&matches)?;
+        // Derived fields flatten repeated multi-value options, so `add` reads
+        // which requirements came with which `--group` from the matches
+        if let Some(Commands::Add { groups, .. }) = &mut cli.command
+            && let Some(add) = matches.subcommand_matches("add")
+            && let Some(occurrences) = add.get_occurrences::<String>("group")
+        {
+            *groups = occurrences.map(|values| values.cloned().collect()).collect();
+        }
+        Ok(cli)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,125 @@
+use crate::cli::args::LockFlags;
+use crate::cli::commands::lock;
+use crate::core::config::{Config, IndexConfig};
+use crate::core::dist::DistFilename;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::index::{IndexClient, IndexFile};
+use crate::core::manifest;
+use crate::core::pep440::Version;
+use crate::core::pep508::Requirement;
+use crate::core::policy::Policy;
+use crate::core::project::Project;
+use crate::core::vetting;
+use colored::*;
+use std::fs;
+
+pub fn run(requirements: &[String], optional: Option<&str>, groups: &[Vec<String>]) -> Result<()> {
+    let mut project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let policy = Policy::load()?;
+    let client = IndexClient::new(&config)?;
+
+    let mut added: Vec<(Option<&str>, Requirement)> = Vec::new();
+    for (group, spec) in targets(requirements, optional, groups) {
+        let mut requirement = Requirement::parse(spec)?;
+        if let Some((_, earlier)) = added
+            .iter()
+            .find(|(target, earlier)| *target == group && earlier.normalized_name() == requirement.normalized_name())
+        {
+            return Err(PyForgeError::Usage {
+                message: format!("'{}' and '{}' are both added to {}; name each package once per group", earlier, spec, describe(group)),
+            });
+        }
+        if let Some(policy) = &policy {
+            policy.enforce(policy.check_package(&requirement.name, None).into_iter().collect())?;
+        }
+
+        if requirement.url.is_none() {
+            let files = project_files(&client, &config, &requirement.name)?;
+            for concern in vetting::vet(&requirement.name, &files) {
+                println!("{} {} {}", "⚠️".yellow(), concern.message, format!("[{}]", concern.kind).bright_black());
+            }
+            if requirement.specifier.is_empty()
+                && let Some(latest) = latest_release(&files, &requirement.name)
+            {
+                requirement.specifier = format!(">={}", latest).parse()?;
+            }
+        }
+        added.push((group, requirement));
+    }
+
+    let pyproject = project.pyproject_path();
+    let original = fs::read_to_string(&pyproject)?;
+    let mut replaced = Vec::new();
+    project.try_update_pyproject(|document| {
+        for (group, requirement) in &added {
+            if let Some(previous) = manifest::add_dependency(document, requirement, *group)? {
+                replaced.push(previous);
+            }
+        }
+        Ok(())
+    })?;
+
+    // One resolution for every group; undo the edit if locking fails so
+    // pyproject.toml and the lockfile stay consistent
+    if let Err(error) = lock::ensure(&project, &config, &LockFlags::default()) {
+        fs::write(&pyproject, original)?;
+        return Err(error);
+    }
+
+    for (group, requirement) in &added {
+        println!("{} Added {} to {}", "✅".green(), requirement.to_string().cyan(), describe(*group));
+    }
+    for previous in replaced {
+        println!("   {} replaced {}", "•".bright_black(), previous);
+    }
+    Ok(())
+}
+
+/// Each requirement with the optional group it goes to: the positional ones
+/// to `optional`, the ones after `--group NAME` to that group
+fn targets<'a>(
+    requirements: &'a [String],
+    optional: Option<&'a str>,
+    groups: &'a [Vec<String>],
+) -> impl Iterator<Item = (Option<&'a str>, &'a String)> {
+    let positional = requirements.iter().map(move |spec| (optional, spec));
+    let grouped = groups
+        .iter()
+        .filter_map(|values| values.split_first())
+        .flat_map(|(group, specs)| specs.iter().map(move |spec| (Some(group.as_str()), spec)));
+    positional.chain(grouped)
+}
+
+pub(crate) fn describe(group: Option<&str>) -> String {
+    group.map_or("dependencies".to_string(), |group| format!("optional group '{}'", group))
+}
+
+/// The package's files on the first configured index that has it
+fn project_files(client: &IndexClient, config: &Config, name: &str) -> Result<Vec<IndexFile>> {
+    Ok(find_project(client, config, name)?.1)
+}
+
+/// The first configured index that has the package, with its files there
+pub(crate) fn find_project(client: &IndexClient, config: &Config, name: &str) -> Result<(IndexConfig, Vec<IndexFile>)> {
+    for index in config.indexes() {
+        if let Some(files) = client.project_files(&index, name)? {
+            return Ok((index, files));
+        }
+    }
+    Err(PyForgeError::ResolutionFailed {
+        package: name.to_string(),
+        reason: "not found on any configured index".to_string(),
+    })
+}
+
+/// Newest version that is not a pre-release and has a file that isn't yanked
+pub(crate) fn latest_release(files: &[IndexFile], name: &str) -> Option<Version> {
+    files
+        .iter()
+        .filter(|file| !file.yanked)
+        .filter_map(|file| DistFilename::parse(&file.filename, name))
+        .map(|parsed| parsed.version)
+        .filter(|version| !version.is_prerelease())
+        .max()
+}
@@ -0,0 +1,89 @@
+use crate::cli::args::AuthCommand;
+use crate::core::auth::{self, TokenSource};
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::project::Project;
+use colored::*;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// Services whose status is always shown
+const KNOWN_SERVICES: [&str; 2] = ["github", "gitlab"];
+
+pub fn run(command: AuthCommand) -> Result<()> {
+    match command {
+        AuthCommand::Login { service } => login(&service),
+        AuthCommand::Logout { service } => logout(&service),
+        AuthCommand::Status => status(),
+    }
+}
+
+fn login(service: &str) -> Result<()> {
+    let key = auth::service_key(service)?;
+    if io::stdin().is_terminal() {
+        print!("Token for {}: ", service.cyan());
+        io::stdout().flush()?;
+    }
+    let mut secret = String::new();
+    io::stdin().lock().read_line(&mut secret)?;
+    let secret = secret.trim();
+    if secret.is_empty() {
+        return Err(PyForgeError::Usage {
+            message: "No token given".to_string(),
+        });
+    }
+
+    auth::store(&key, Some(secret))?;
+    println!(
+        "{} Stored the {} token in {}",
+        "🔑".green(),
+        service,
+        auth::credentials_path().display()
+    );
+    Ok(())
+}
+
+fn logout(service: &str) -> Result<()> {
+    let key = auth::service_key(service)?;
+    if !auth::stored()?.contains_key(&key) {
+        println!("{} No stored token for {}", "ℹ️".blue(), service);
+        return Ok(());
+    }
+    auth::store(&key, None)?;
+    println!("{} Removed the stored {} token", "✅".green(), service);
+    Ok(())
+}
+
+fn status() -> Result<()> {
+    let mut services: Vec<String> = KNOWN_SERVICES.iter().map(|s| s.to_string()).collect();
+    // Index tokens are shown with their indexes below
+    services.extend(
+        auth::stored()?
+            .into_keys()
+            .filter(|s| !KNOWN_SERVICES.contains(&s.as_str()) && !auth::is_index_service(s)),
+    );
+
+    for service in services {
+        match auth::token(&service)? {
+            Some(token) => {
+                let source = match token.source {
+                    TokenSource::Environment(variable) => format!("${}", variable),
+                    TokenSource::CredentialsFile => auth::credentials_path().display().to_string(),
+                };
+                println!("{} {}: {}", "✅".green(), service.bold(), source);
+            }
+            None => println!("{} {}: {}", "➖".bright_black(), service.bold(), "not configured".bright_black()),
+        }
+    }
+
+    // Indexes fall back to credentials set up for pip, uv and netrc
+    let project = Project::discover()?;
+    let config = Config::load(project.as_ref())?;
+    for index in config.indexes() {
+        let label = format!("index {}", index.name);
+        match auth::index_credentials(&index, &index.url)? {
+            Some(credentials) => println!("{} {}: {}", "✅".green(), label.bold(), credentials.source),
+            None => println!("{} {}: {}", "➖".bright_black(), label.bold(), "no credentials".bright_black()),
+        }
+    }
+    Ok(())
+}
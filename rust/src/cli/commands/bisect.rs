@@ -0,0 +1,239 @@
+use crate::cli::args::LockFlags;
+use crate::cli::commands::{add, lock, trust};
+use crate::core::bisect::{self, Outcome, Verdict};
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::git::{self, Worktree};
+use crate::core::index::IndexClient;
+use crate::core::installer;
+use crate::core::lockfile::{self, LOCKFILE, Lockfile};
+use crate::core::parallel;
+use crate::core::pep440::Version;
+use crate::core::pep508::{Requirement, normalize_name};
+use crate::core::process::{self, shell_quote};
+use crate::core::project::Project;
+use crate::core::stats;
+use crate::core::venv::{SyncState, Venv};
+use colored::*;
+use std::collections::BTreeSet;
+
+/// Bisect the releases of `package` between `good` and `bad`
+pub fn versions(package: &str, good: &str, bad: Option<&str>, command: &[String]) -> Result<()> {
+    let session = Session::start(command)?;
+    let name = normalize_name(package);
+    let parse = |version: &str| {
+        Version::parse(version).map_err(|_| PyForgeError::Usage {
+            message: format!("'{}' is not a valid version", version),
+        })
+    };
+    let good = parse(good)?;
+    let bad = bad.map(parse).transpose()?;
+
+    let client = IndexClient::new(&session.config)?;
+    let (_, files) = add::find_project(&client, &session.config, &name)?;
+    let newest = add::latest_release(&files, &name);
+    let bad = bad.or(newest).ok_or_else(|| PyForgeError::Usage {
+        message: format!("{} has no final release; pass --bad", name),
+    })?;
+    if bad <= good {
+        return Err(PyForgeError::Usage {
+            message: format!("--bad {} must be newer than --good {}", bad, good),
+        });
+    }
+    // Pre-releases in between are left out, but either end may be one
+    let candidates: Vec<Version> = stats::releases(&files, &name)
+        .into_iter()
+        .filter(|release| !release.yanked && release.version > good && release.version < bad && !release.version.is_prerelease())
+        .map(|release| release.version)
+        .chain([good.clone(), bad.clone()])
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    println!(
+        "{} Bisecting {} release(s) of {} between {} (good) and {} (bad)",
+        "🔎".cyan(),
+        candidates.len() - 2,
+        name.cyan(),
+        good.to_string().green(),
+        bad.to_string().red()
+    );
+    let requirements = lockfile::requirements(&session.project)?;
+    let outcome = session.bisect(candidates.len(), |index| {
+        let version = &candidates[index];
+        println!("{} Trying {} {}", "🔎".cyan(), name, version);
+        let mut pinned = requirements.clone();
+        pinned.push(Requirement::parse(&format!("{}=={}", name, version))?);
+        match lock::resolve_requirements(&session.project, &session.config, pinned, &session.lockfile.packages, None) {
+            Ok(lockfile) => Ok(Some(lockfile)),
+            Err(e) => {
+                println!("   {} Can't resolve with {} {}: {}", "⏭️".yellow(), name, version, e);
+                Ok(None)
+            }
+        }
+    })?;
+
+    let label = |index: usize| format!("{} {}", name, candidates[index]);
+    report(&outcome, label);
+    Ok(())
+}
+
+/// Bisect the commits between `good` and `bad` that changed the lockfile
+pub fn lock(good: &str, bad: &str, command: &[String]) -> Result<()> {
+    let session = Session::start(command)?;
+    let root = &session.project.root;
+    if Worktree::discover(root).is_none() {
+        return Err(PyForgeError::Usage {
+            message: "pyforge bisect lock needs the project to be in a git repository".to_string(),
+        });
+    }
+    let history = git::file_history(root, &format!("{}..{}", good, bad), LOCKFILE).ok_or_else(|| PyForgeError::Usage {
+        message: format!("git could not list the commits between '{}' and '{}'", good, bad),
+    })?;
+    if history.is_empty() {
+        return Err(PyForgeError::Usage {
+            message: format!("No commit between '{}' and '{}' changed {}", good, bad, LOCKFILE),
+        });
+    }
+    let candidates: Vec<(String, String)> = std::iter::once((good.to_string(), "(--good)".to_string())).chain(history).collect();
+
+    println!(
+        "{} Bisecting {} change(s) to {} between {} (good) and {} (bad)",
+        "🔎".cyan(),
+        candidates.len() - 1,
+        LOCKFILE.cyan(),
+        good.green(),
+        bad.red()
+    );
+    let load = |index: usize| -> Result<Lockfile> {
+        let (revision, _) = &candidates[index];
+        let content = git::show_file(root, revision, LOCKFILE).ok_or_else(|| PyForgeError::Usage {
+            message: format!("{} does not exist at '{}'", LOCKFILE, revision),
+        })?;
+        Lockfile::parse(&content, &format!("{} at {}", LOCKFILE, revision))
+    };
+    let outcome = session.bisect(candidates.len(), |index| {
+        let (revision, subject) = &candidates[index];
+        println!("{} Trying {} {}", "🔎".cyan(), short(revision).yellow(), subject);
+        load(index).map(Some)
+    })?;
+
+    report(&outcome, |index| {
+        let (revision, subject) = &candidates[index];
+        format!("{} {}", short(revision), subject)
+    });
+    let changes = load(outcome.bad)?.changes_since(&load(outcome.good)?);
+    if outcome.skipped.is_empty() && !changes.is_empty() {
+        println!("   Package changes in that commit:");
+        for change in changes {
+            let old = change.old.as_deref().unwrap_or("absent");
+            let new = change.new.as_deref().unwrap_or("absent");
+            println!("   {} {} {} → {}", "•".bright_black(), change.name, old, new);
+        }
+    }
+    Ok(())
+}
+
+/// The project and environment being bisected, and what to run at each step
+struct Session {
+    project: Project,
+    config: Config,
+    venv: Venv,
+    /// The current lock, restored at the end
+    lockfile: Lockfile,
+    /// Packages installed outside the lock before bisecting, which are left alone
+    unlocked: Vec<String>,
+    command: String,
+}
+
+impl Session {
+    fn start(command: &[String]) -> Result<Self> {
+        let project = Project::require()?;
+        let config = Config::load(Some(&project))?;
+        if command.is_empty() {
+            trust::require(&project)?;
+        }
+        let lockfile = lock::ensure(&project, &config, &LockFlags::default())?;
+        let venv = Venv::ensure(&project, &config)?;
+        let unlocked = installer::installed(&venv)?
+            .into_keys()
+            .filter(|name| lockfile.package(name).is_none())
+            .collect();
+        let command = if command.is_empty() {
+            config.test_command()
+        } else {
+            command.iter().map(|argument| shell_quote(argument)).collect::<Vec<_>>().join(" ")
+        };
+        Ok(Self {
+            project,
+            config,
+            venv,
+            lockfile,
+            unlocked,
+            command,
+        })
+    }
+
+    /// Run the search, installing the lockfile `candidate` returns for each step
+    /// (`None` skips it), then restore the environment
+    fn bisect(&self, count: usize, mut candidate: impl FnMut(usize) -> Result<Option<Lockfile>>) -> Result<Outcome> {
+        println!("{} Running {} at each step", "🧪".cyan(), self.command.cyan());
+        let outcome = bisect::search(count, |index, steps| {
+            println!("{}", format!("── about {} step(s) left", steps).bright_black());
+            let verdict = match candidate(index)? {
+                Some(lockfile) => self.test(&lockfile)?,
+                None => Verdict::Skip,
+            };
+            match verdict {
+                Verdict::Good => println!("   {} good", "✅".green()),
+                Verdict::Bad => println!("   {} bad", "❌".red()),
+                Verdict::Skip => println!("   {} skipped", "⏭️".yellow()),
+            }
+            Ok(verdict)
+        });
+
+        println!("{} Restoring {} from {}", "📦".green(), self.venv.display(&self.project).cyan(), LOCKFILE.cyan());
+        self.install(&self.lockfile)?;
+        self.venv.record_sync(&SyncState::current(&self.project, &self.lockfile))?;
+        outcome
+    }
+
+    fn test(&self, lockfile: &Lockfile) -> Result<Verdict> {
+        self.install(lockfile)?;
+        let mut shell = process::shell_command(&self.command);
+        shell.current_dir(&self.project.root);
+        self.venv.activate(&mut shell)?;
+        let status = shell.status().map_err(|e| PyForgeError::file_error(format!("Could not run '{}'", self.command), e))?;
+        Ok(Verdict::from_status(status))
+    }
+
+    /// Make the environment match `lockfile`, apart from packages that were unlocked to begin with
+    fn install(&self, lockfile: &Lockfile) -> Result<()> {
+        let jobs = parallel::jobs(&self.config, None);
+        let report = installer::sync(&self.venv, lockfile, &self.project.root, &self.config, false, false, jobs)?;
+        let stale: Vec<String> = report.orphans.into_iter().filter(|name| !self.unlocked.contains(name)).collect();
+        installer::remove(&self.venv, &stale)
+    }
+}
+
+fn report(outcome: &Outcome, label: impl Fn(usize) -> String) {
+    if outcome.skipped.is_empty() {
+        println!("{} First bad: {}", "🎯".red(), label(outcome.bad).bold());
+        println!("   Last good: {}", label(outcome.good));
+    } else {
+        println!("{} Skipped candidates hide the exact change; the first bad one is among:", "⚠️".yellow());
+        for &index in outcome.skipped.iter().chain([&outcome.bad]) {
+            println!("   {} {}", "•".bright_black(), label(index));
+        }
+        println!("   Last good: {}", label(outcome.good));
+    }
+}
+
+/// Abbreviated commit hash, or the revision as given
+fn short(revision: &str) -> &str {
+    if revision.len() == 40 && revision.chars().all(|c| c.is_ascii_hexdigit()) {
+        &revision[..10]
+    } else {
+        revision
+    }
+}
@@ -1,6 +1,260 @@
-use crate::core::error::{PyForgeError, Result, validation};
+use crate::cli::args::LockFlags;
+use crate::cli::commands::{lock, trust};
+use crate::core::build::{BuildOptions, Builder, Distribution};
+use crate::core::config::Config;
+use crate::core::datafiles::DataFiles;
+use crate::core::dist::DistFilename;
+use crate::core::distcheck::Checks;
+use crate::core::distdiff::{self, Contents};
+use crate::core::error::{PyForgeError, Result};
+use crate::core::lockfile::Lockfile;
+use crate::core::pipeline::{self, StepState};
+use crate::core::project::Project;
+use crate::core::utils::file_name;
+use colored::*;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
 
-pub fn run() -> Result<()> {
-    println!("Building project");
+/// Files listed when a build changes the size of more than this many
+const SHOWN_RESIZED: usize = 10;
+
+pub fn run(
+    flags: &LockFlags,
+    allow_network: bool,
+    no_cache: bool,
+    diff_last: bool,
+    strict: bool,
+    distributions: &[Distribution],
+    out_dir: Option<&Path>,
+) -> Result<()> {
+    let project = Project::require()?;
+    let mut config = Config::load(Some(&project))?;
+    config.build.checks.strict |= strict;
+    if !config.build.steps.is_empty() {
+        trust::require(&project)?;
+    }
+    let out_dir = match out_dir {
+        Some(dir) => std::env::current_dir()?.join(dir),
+        None => project.root.join("dist"),
+    };
+    // Read before building, since a rebuild of the same version overwrites it
+    let previous = if diff_last { previous_builds(&project, &out_dir)? } else { Vec::new() };
+    let built = build(&project, &config, flags, allow_network, no_cache, distributions, &out_dir)?;
+    if diff_last {
+        compare_with_previous(&previous, &built)?;
+    }
+    Ok(())
+}
+
+/// The newest sdist and wheel of the project in `dist`, with whether each is a wheel
+fn previous_builds(project: &Project, dist: &Path) -> Result<Vec<(bool, String, Contents)>> {
+    let name = project.name().unwrap_or_default();
+    let mut newest: [Option<(SystemTime, PathBuf)>; 2] = [None, None];
+    if dist.is_dir() {
+        for entry in fs::read_dir(dist)? {
+            let entry = entry?;
+            let filename = entry.file_name().to_string_lossy().to_string();
+            let Some(parsed) = DistFilename::parse(&filename, name) else {
+                continue;
+            };
+            let modified = entry.metadata()?.modified()?;
+            let slot = &mut newest[parsed.is_wheel() as usize];
+            if slot.as_ref().is_none_or(|(time, _)| modified > *time) {
+                *slot = Some((modified, entry.path()));
+            }
+        }
+    }
+
+    let mut previous = Vec::new();
+    for (wheel, found) in newest.into_iter().enumerate() {
+        if let Some((_, path)) = found {
+            previous.push((wheel == 1, file_name(&path), Contents::read(&path)?));
+        }
+    }
+    Ok(previous)
+}
+
+/// Print how each built distribution differs from the previous one of its kind
+fn compare_with_previous(previous: &[(bool, String, Contents)], built: &[PathBuf]) -> Result<()> {
+    let mut suspicious = 0;
+    for path in built {
+        let wheel = path.extension().is_some_and(|extension| extension == "whl");
+        let kind = if wheel { "wheel" } else { "sdist" };
+        let Some((_, filename, old)) = previous.iter().find(|(is_wheel, _, _)| *is_wheel == wheel) else {
+            println!("{} No previous {} to compare with", "📊".cyan(), kind);
+            continue;
+        };
+        let new = Contents::read(path)?;
+        let diff = distdiff::diff(old, &new);
+        println!("{} {} compared with {}", "📊".cyan(), file_name(path).cyan(), filename.cyan());
+        println!(
+            "   size {} → {} ({})",
+            distdiff::size(diff.old_size),
+            distdiff::size(diff.new_size),
+            distdiff::delta(diff.old_size, diff.new_size)
+        );
+        if diff.is_empty() {
+            println!("   {} same files and metadata", "•".bright_black());
+            continue;
+        }
+        for (file, size) in &diff.added {
+            if distdiff::suspicious(file) {
+                suspicious += 1;
+                println!("   {} {} ({}) {}", "+".green(), file.yellow(), distdiff::size(*size), "⚠️ check this belongs here".yellow());
+            } else {
+                println!("   {} {} ({})", "+".green(), file, distdiff::size(*size));
+            }
+        }
+        for (file, size) in &diff.removed {
+            println!("   {} {} ({})", "-".red(), file, distdiff::size(*size));
+        }
+        for (file, old_size, new_size) in diff.resized.iter().take(SHOWN_RESIZED) {
+            println!("   {} {} ({})", "~".cyan(), file, distdiff::delta(*old_size, *new_size));
+        }
+        if diff.resized.len() > SHOWN_RESIZED {
+            println!("   {} and {} more changed file(s)", "~".cyan(), diff.resized.len() - SHOWN_RESIZED);
+        }
+        for change in &diff.metadata {
+            println!(
+                "   {} {}: {} → {}",
+                "•".bright_black(),
+                change.field,
+                metadata_values(&change.old).red(),
+                metadata_values(&change.new).green()
+            );
+        }
+    }
+    if suspicious > 0 {
+        println!(
+            "{} {} new file(s) look like test data or secrets; check them before publishing",
+            "⚠️".yellow(),
+            suspicious
+        );
+    }
+    Ok(())
+}
+
+/// Values of a metadata field on one line, long ones cut at their first line
+fn metadata_values(values: &[String]) -> String {
+    if values.is_empty() {
+        return "(none)".to_string();
+    }
+    values
+        .iter()
+        .map(|value| match value.split_once('\n') {
+            Some((first, _)) => format!("{}…", first),
+            None => value.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Run the build steps, then build `distributions` into `out_dir`, returning their paths
+pub fn build(
+    project: &Project,
+    config: &Config,
+    flags: &LockFlags,
+    allow_network: bool,
+    no_cache: bool,
+    distributions: &[Distribution],
+    out_dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let lockfile = if flags.locked || flags.frozen {
+        Some(lock::ensure(project, config, flags)?)
+    } else {
+        Lockfile::load(project)?
+    };
+
+    let checks = Checks::new(&config.build.checks)?;
+    for step in &config.build.steps {
+        let state = StepState::current(project, config, step)?;
+        if !no_cache && state.is_fresh(project, step) {
+            println!("{} Step {} is up to date", "⏭️".cyan(), step.name.cyan());
+            continue;
+        }
+        println!("{} Step {}: {}", "⚙️".cyan(), step.name.cyan(), step.command);
+        let started = Instant::now();
+        pipeline::run(project, config, step, &state)?;
+        println!(
+            "{} Step {} finished in {:.1}s",
+            "✅".green(),
+            step.name,
+            started.elapsed().as_secs_f64()
+        );
+    }
+
+    let data = DataFiles::collect(project, &config.build.include)?;
+    for pattern in &data.unmatched {
+        println!(
+            "{} [tool.pyforge.build.include] pattern {} matches no files",
+            "⚠️".yellow(),
+            pattern.yellow()
+        );
+    }
+
+    let options = BuildOptions {
+        out_dir: out_dir.to_path_buf(),
+        sandboxed: config.build.sandbox,
+        allow_network: allow_network || config.build.allow_network,
+        offline: flags.frozen,
+        lockfile: lockfile.as_ref(),
+    };
+    println!("{} Preparing build environment", "🔨".cyan());
+    let builder = Builder::prepare(project, config, options)?;
+    println!(
+        "{} Building with {} ({})",
+        "🔨".cyan(),
+        builder.backend().cyan(),
+        builder.sandbox().describe()
+    );
+
+    if let Some(version) = builder.stamped_version() {
+        println!("   {} version {} from git", "🏷️".cyan(), version.cyan());
+    }
+    if let Some(path) = &config.build.info.path {
+        println!("   {} build info in {}", "🏷️".cyan(), path.cyan());
+    }
+    for requirement in builder.pinned() {
+        println!("   {} path dependency published as {}", "📌".cyan(), requirement.cyan());
+    }
+
+    let mut built = Vec::new();
+    for &distribution in distributions {
+        let path = builder.build(distribution)?;
+        let added = match distribution {
+            Distribution::Sdist => data.add_to_sdist(&path, project)?,
+            Distribution::Wheel => data.add_to_wheel(&path)?,
+        };
+        let shown = path.strip_prefix(&project.root).unwrap_or(&path);
+        let content =
+            fs::read(&path).map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+        println!("{} Built {} ({})", "✅".green(), shown.display(), distdiff::size(content.len() as u64));
+        println!("   {} sha256 {:x}", "•".bright_black(), Sha256::digest(&content));
+        if added > 0 {
+            println!("   {} added {} data file(s) from [tool.pyforge.build.include]", "📎".cyan(), added);
+        }
+        built.push(path);
+    }
+    check_distributions(&checks, config.build.checks.strict, &built)?;
+    Ok(built)
+}
+
+/// Run `[tool.pyforge.build.checks]` on the built distributions, failing when `strict`
+fn check_distributions(checks: &Checks, strict: bool, built: &[PathBuf]) -> Result<()> {
+    let mut violations = Vec::new();
+    for path in built {
+        violations.extend(checks.check(&file_name(path), &Contents::read(path)?));
+    }
+    if violations.is_empty() {
+        return Ok(());
+    }
+    if strict {
+        return Err(PyForgeError::ArtifactCheckFailed { violations });
+    }
+    for violation in &violations {
+        println!("{} {}", "⚠️".yellow(), violation);
+    }
     Ok(())
-}
\ No newline at end of file
+}
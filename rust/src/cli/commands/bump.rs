@@ -0,0 +1,343 @@
+use crate::cli::args::{LockFlags, SyncFlags};
+use crate::cli::commands::{lock, sync, test};
+use crate::cli::picker;
+use crate::core::changelog::{self, Notes};
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::forge::Forge;
+use crate::core::git::{self, Worktree};
+use crate::core::index::IndexClient;
+use crate::core::lockfile::{LOCKFILE, LockedPackage, Lockfile, VersionChange};
+use crate::core::pep440::Version;
+use crate::core::project::Project;
+use crate::core::reach::{self, Radius};
+use crate::core::venv::Venv;
+use colored::*;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::Path;
+
+/// Where a bump is proposed: the forge, and the branch it starts from
+struct Proposal {
+    forge: Forge,
+    remote: String,
+    base: String,
+}
+
+pub fn run(interactive: bool, create_pr: bool, no_test: bool, changelog: bool, remote: &str) -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let previous = Lockfile::require(&project)?;
+
+    if interactive && !std::io::stdin().is_terminal() {
+        return Err(PyForgeError::Usage {
+            message: "--interactive needs a terminal".to_string(),
+        });
+    }
+    // Check everything a pull request needs before changing any file
+    let proposal = create_pr.then(|| proposal(&project, &config, remote)).transpose()?;
+
+    let path = Lockfile::path(&project);
+    let original = fs::read_to_string(&path)
+        .map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+    let restore = || {
+        fs::write(&path, &original).map_err(|e| PyForgeError::file_error(format!("Could not restore {}", path.display()), e))
+    };
+
+    let lockfile = match lock::lock(&project, &config, false, None) {
+        Ok(lockfile) => lockfile,
+        Err(e) => {
+            restore()?;
+            return Err(e);
+        }
+    };
+    let mut lockfile = lockfile;
+    let mut changes = lockfile.changes_since(&previous);
+    if changes.is_empty() {
+        restore()?;
+        println!("{} All dependencies are at the newest allowed versions", "✅".green());
+        return Ok(());
+    }
+
+    // The environment still has the old versions, which tell what each package provides
+    let site_packages = Venv::open(&project, &config).and_then(|venv| venv.site_packages().ok());
+    let analysis = reach::analyze(&project, &previous, &changes, site_packages.as_deref());
+
+    if interactive {
+        let offered = changes.iter().filter(|change| change.old.is_some() && change.new.is_some()).count();
+        let chosen = match picker::pick(&changes, &analysis) {
+            Ok(Some(chosen)) if !chosen.is_empty() => chosen,
+            Ok(_) => {
+                restore()?;
+                println!("{} No updates chosen; {} is unchanged", "ℹ️".cyan(), LOCKFILE.cyan());
+                return Ok(());
+            }
+            Err(e) => {
+                restore()?;
+                return Err(e);
+            }
+        };
+        if chosen.len() < offered {
+            // Everything not chosen keeps its locked version where the chosen updates allow
+            let kept: Vec<LockedPackage> =
+                previous.packages.iter().filter(|package| !chosen.contains(&package.name)).cloned().collect();
+            lockfile = match lock::write_lock(&project, &config, &kept, None) {
+                Ok(lockfile) => lockfile,
+                Err(e) => {
+                    restore()?;
+                    return Err(e);
+                }
+            };
+            changes = lockfile.changes_since(&previous);
+        }
+    }
+
+    println!("{} {} package(s) changed:", "⬆️".cyan(), changes.len());
+    for change in &changes {
+        println!(
+            "   {} {}: {} → {}",
+            "•".bright_black(),
+            change.name,
+            change.old.as_deref().unwrap_or("absent"),
+            change.new.as_deref().unwrap_or("removed").green()
+        );
+    }
+    print_reach(&analysis, &changes);
+    let notes = if changelog { release_notes(&config, &lockfile, &changes)? } else { Vec::new() };
+
+    let tested = if no_test {
+        None
+    } else {
+        let command = config.test_command();
+        match test::run_in_venv(&project, &config, &lockfile, &command, false) {
+            Ok(()) => Some(command),
+            Err(e) => {
+                restore()?;
+                println!("{} Restored the previous {}", "↩️".yellow(), LOCKFILE.cyan());
+                println!("   Run 'pyforge sync' to put the environment back in line with it.");
+                return Err(e);
+            }
+        }
+    };
+
+    // Tests sync the environment; chosen updates are installed right away too
+    if interactive && tested.is_none() {
+        let venv = Venv::ensure(&project, &config)?;
+        sync::sync_into(&project, &config, &venv, &lockfile, &LockFlags::default(), &SyncFlags::default())?;
+    }
+
+    match proposal {
+        Some(proposal) => propose(&project, &proposal, &changes, &notes, tested.as_deref(), restore),
+        None => {
+            println!("{} Updated {}", "✅".green(), LOCKFILE.cyan());
+            Ok(())
+        }
+    }
+}
+
+fn proposal(project: &Project, config: &Config, remote: &str) -> Result<Proposal> {
+    let usage = |message: String| PyForgeError::Usage { message };
+    let worktree =
+        Worktree::discover(&project.root).ok_or_else(|| usage("--create-pr needs a git repository".to_string()))?;
+    let base = worktree
+        .branch
+        .ok_or_else(|| usage("--create-pr needs a checked-out branch, not a detached HEAD".to_string()))?;
+    if git::is_modified(&project.root, LOCKFILE) {
+        return Err(usage(format!("{} has uncommitted changes; commit or discard them first", LOCKFILE)));
+    }
+    let url = git::remote_url(&project.root, remote)
+        .ok_or_else(|| usage(format!("git remote '{}' is not configured", remote)))?;
+
+    Ok(Proposal {
+        forge: Forge::from_remote(&url, config)?,
+        remote: remote.to_string(),
+        base,
+    })
+}
+
+/// Commit the new lockfile to a fresh branch, push it and open a pull/merge request.
+/// The current branch is checked out again afterwards, with its own lockfile.
+fn propose(
+    project: &Project,
+    proposal: &Proposal,
+    changes: &[VersionChange],
+    notes: &[(String, Notes)],
+    tested: Option<&str>,
+    restore: impl Fn() -> Result<()>,
+) -> Result<()> {
+    let now = time::OffsetDateTime::now_utc();
+    let branch = format!(
+        "pyforge/bump-{}{:02}{:02}-{:02}{:02}{:02}",
+        now.year(),
+        u8::from(now.month()),
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    );
+    let title = match changes {
+        [change] => format!("Bump {} to {}", change.name, change.new.as_deref().unwrap_or("nothing")),
+        _ => format!("Bump {} dependencies", changes.len()),
+    };
+
+    let root: &Path = &project.root;
+    let pushed = git::run(root, &["checkout", "-b", &branch])
+        .and_then(|_| git::run(root, &["commit", "-m", &title, "--", LOCKFILE]))
+        .and_then(|_| git::run(root, &["push", "--set-upstream", &proposal.remote, &branch]));
+    // Leave the user on their branch, which still has the old lockfile
+    let returned = git::run(root, &["checkout", &proposal.base]);
+    if pushed.is_err() && returned.is_ok() {
+        restore()?;
+    }
+    pushed?;
+    returned?;
+    println!("{} Pushed {} to {}", "📤".green(), branch.cyan(), proposal.remote);
+
+    let url = proposal
+        .forge
+        .open_request(&branch, &proposal.base, &title, &description(changes, notes, tested))?;
+    println!(
+        "{} Opened {}: {}",
+        "✅".green(),
+        proposal.forge.kind.request_name(),
+        url.cyan()
+    );
+    Ok(())
+}
+
+/// Changes listed in the blast radius, widest first; the rest are counted
+const SHOWN_REACHES: usize = 10;
+
+/// Modules named for each change; the rest are counted
+const SHOWN_MODULES: usize = 4;
+
+/// Print which direct dependencies and project modules each change reaches
+fn print_reach(analysis: &reach::Analysis, changes: &[VersionChange]) {
+    let mut reaches: Vec<&reach::Reach> = changes.iter().filter_map(|change| analysis.get(&change.name)).collect();
+    if reaches.is_empty() {
+        return;
+    }
+    reaches.sort_by_key(|reach| std::cmp::Reverse((reach.radius, reach.modules.len())));
+    let touched: std::collections::BTreeSet<&String> = reaches.iter().flat_map(|reach| &reach.modules).collect();
+    let radius = analysis.radius();
+    let paint = |radius: Radius| match radius {
+        Radius::High => radius.to_string().red().bold(),
+        Radius::Medium => radius.to_string().yellow().bold(),
+        Radius::Low => radius.to_string().green().bold(),
+    };
+    println!(
+        "{} Blast radius: {} ({} of {} project module(s) import upgraded packages)",
+        "💥".cyan(),
+        paint(radius),
+        touched.len(),
+        analysis.modules
+    );
+    for reach in reaches.iter().take(SHOWN_REACHES) {
+        let mut parts = Vec::new();
+        if reach.breaking {
+            parts.push("breaking version".to_string());
+        }
+        if reach.direct {
+            parts.push("direct dependency".to_string());
+        }
+        if !reach.via.is_empty() {
+            parts.push(format!("via {}", reach.via.join(", ")));
+        }
+        parts.push(match reach.modules.len() {
+            0 => "no module imports it".to_string(),
+            count => {
+                let mut shown = reach.modules.iter().take(SHOWN_MODULES).cloned().collect::<Vec<_>>().join(", ");
+                if count > SHOWN_MODULES {
+                    shown.push_str(&format!(" and {} more", count - SHOWN_MODULES));
+                }
+                format!("imported by {}", shown)
+            }
+        });
+        println!("   {} {} {}: {}", "•".bright_black(), reach.package, paint(reach.radius), parts.join("; "));
+    }
+    if reaches.len() > SHOWN_REACHES {
+        println!("   {} and {} more", "•".bright_black(), reaches.len() - SHOWN_REACHES);
+    }
+}
+
+/// Print a summary of the release notes of every upgraded package locked
+/// from PyPI, and return them for the pull request
+fn release_notes(config: &Config, lockfile: &Lockfile, changes: &[VersionChange]) -> Result<Vec<(String, Notes)>> {
+    if config.network.is_metered() {
+        println!("\n{} {}", "📝".cyan(), "Release notes skipped on a metered connection".bright_black());
+        return Ok(Vec::new());
+    }
+    let client = IndexClient::new(config)?;
+    let mut found = Vec::new();
+    for change in changes {
+        let (Some(old), Some(new)) = (change.old.as_deref(), change.new.as_deref()) else {
+            continue;
+        };
+        let (Ok(old), Ok(new)) = (Version::parse(old), Version::parse(new)) else {
+            continue;
+        };
+        let public = lockfile.packages.iter().any(|package| package.name == change.name && changelog::is_public(&package.index));
+        if new <= old || !public {
+            continue;
+        }
+
+        println!("\n{} {} {} → {}", "📝".cyan(), change.name.bold(), old, new);
+        let notes = match changelog::notes(&client, config, &change.name, &old, &new) {
+            Ok(notes) => notes,
+            Err(error) => {
+                log::debug!("release notes of {}: {}", change.name, error);
+                println!("   {}", "release notes unavailable".bright_black());
+                continue;
+            }
+        };
+        for release in &notes.releases {
+            println!("   {} {}", "•".bright_black(), release.version.to_string().cyan());
+            for line in &release.summary {
+                println!("     {}", line);
+            }
+            if release.truncated > 0 {
+                println!("     {}", format!("… {} more line(s)", release.truncated).bright_black());
+            }
+        }
+        match &notes.url {
+            Some(url) => println!("   {} {}", "Changelog:".bright_black(), url),
+            None if notes.releases.is_empty() => println!("   {}", "no release notes found".bright_black()),
+            None => {}
+        }
+        found.push((change.name.clone(), notes));
+    }
+    Ok(found)
+}
+
+/// Markdown body listing the lock changes
+fn description(changes: &[VersionChange], notes: &[(String, Notes)], tested: Option<&str>) -> String {
+    let mut body = format!(
+        "Updates `{}` to the newest versions allowed by `pyproject.toml`.\n\n\
+         | Package | From | To |\n|---|---|---|\n",
+        LOCKFILE
+    );
+    for change in changes {
+        body.push_str(&format!(
+            "| {} | {} | {} |\n",
+            change.name,
+            change.old.as_deref().unwrap_or("—"),
+            change.new.as_deref().unwrap_or("removed")
+        ));
+    }
+    body.push('\n');
+    let links: Vec<String> = notes
+        .iter()
+        .filter_map(|(name, notes)| notes.url.as_ref().map(|url| format!("- {}: {}\n", name, url)))
+        .collect();
+    if !links.is_empty() {
+        body.push_str("Release notes:\n\n");
+        body.push_str(&links.concat());
+        body.push('\n');
+    }
+    match tested {
+        Some(command) => body.push_str(&format!("Tests passed with the new versions: `{}`\n", command)),
+        None => body.push_str("Tests were not run (`--no-test`).\n"),
+    }
+    body.push_str("\nGenerated by `pyforge bump --create-pr`.\n");
+    body
+}
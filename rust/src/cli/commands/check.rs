@@ -0,0 +1,37 @@
+use crate::cli::args::CheckCommand;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::metacheck;
+use crate::core::project::Project;
+use colored::*;
+
+pub fn run(command: CheckCommand) -> Result<()> {
+    match command {
+        CheckCommand::Metadata { fix } => metadata(fix),
+    }
+}
+
+fn metadata(fix: bool) -> Result<()> {
+    let mut project = Project::require()?;
+    let review = metacheck::review(&project)?;
+    for note in &review.notes {
+        println!("{} {}", "ℹ️".cyan(), note.bright_black());
+    }
+    if review.fixes.is_empty() {
+        println!("{} Metadata looks right", "✅".green());
+        return Ok(());
+    }
+
+    let (marker, heading) = if fix { ("✓".green(), "Fixing") } else { ("✗".red(), "To fix") };
+    println!("{} {}:", "🏷️".cyan(), heading);
+    for item in &review.fixes {
+        println!("   {} {} {}", marker, item.change, format!("({})", item.reason).bright_black());
+    }
+    if !fix {
+        return Err(PyForgeError::MetadataIssues {
+            issues: review.fixes.iter().map(|item| item.change.to_string()).collect(),
+        });
+    }
+    metacheck::apply(&mut project, &review.fixes)?;
+    println!("{} Fixed {} issue(s) in the project metadata", "✅".green(), review.fixes.len());
+    Ok(())
+}
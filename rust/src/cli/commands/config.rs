@@ -0,0 +1,129 @@
+use crate::cli::args::ConfigCommand;
+use crate::cli::prompt;
+use crate::core::config::{global_config_path, read_toml};
+use crate::core::error::{PyForgeError, Result};
+use crate::core::project::Project;
+use crate::core::schema::{self, Problem, Renamed, Scope};
+use colored::*;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use toml_edit::DocumentMut;
+
+pub fn run(command: ConfigCommand) -> Result<()> {
+    match command {
+        ConfigCommand::Doctor { yes } => doctor(yes),
+    }
+}
+
+/// A file with renamed keys, rewritten in memory
+struct Migration {
+    file: PathBuf,
+    document: DocumentMut,
+    renamed: Vec<Renamed>,
+}
+
+fn doctor(yes: bool) -> Result<()> {
+    println!("{} Checking configuration files", "🩺".green());
+    let mut files = vec![(global_config_path(), Scope::Global)];
+    if let Some(project) = Project::discover()? {
+        files.push((project.pyproject_path(), Scope::Project));
+    }
+
+    let mut problems = 0;
+    let mut migrations = Vec::new();
+    for (file, scope) in files {
+        if let Err(e) = read_toml(&file) {
+            problems += 1;
+            println!("  {} {}", "❌".red(), e.to_string().red());
+            continue;
+        }
+        let Ok(source) = fs::read_to_string(&file) else {
+            println!("  {} {} {}", "•".bright_black(), file.display(), "(not found)".bright_black());
+            continue;
+        };
+        let issues = schema::check(&file, &source, scope);
+        schema::mark_reported(&file);
+        if issues.is_empty() {
+            println!("  {} {}", "✅".green(), file.display());
+        }
+        for issue in &issues {
+            match issue.problem {
+                Problem::Mismatch { .. } => println!("  {} {}", "❌".red(), issue.to_string().red()),
+                Problem::Unknown { .. } | Problem::GlobalOnly => println!("  {} {}", "⚠️".yellow(), issue),
+                Problem::Deprecated { .. } => println!("  {} {}", "🔁".cyan(), issue),
+            }
+            if !matches!(issue.problem, Problem::Deprecated { .. }) {
+                problems += 1;
+            }
+        }
+
+        let Ok(mut document) = source.parse::<DocumentMut>() else {
+            continue;
+        };
+        let renamed = schema::rename(&mut document, scope);
+        if !renamed.is_empty() {
+            migrations.push(Migration { file, document, renamed });
+        }
+    }
+
+    if !migrations.is_empty() {
+        println!();
+        println!("{} Renamed keys to migrate:", "🔁".cyan());
+        for migration in &migrations {
+            println!("  {}", migration.file.display().to_string().bold());
+            for rename in &migration.renamed {
+                if rename.dropped {
+                    println!("   {} {} {}", "-".red(), rename.old, format!("({} is set already)", rename.new).bright_black());
+                } else {
+                    println!("   {} {} → {}", "~".yellow(), rename.old, rename.new.cyan());
+                }
+            }
+        }
+        if confirm(yes)? {
+            for migration in &migrations {
+                migrate(migration)?;
+            }
+        } else {
+            println!("   Run {} to rewrite them", "pyforge config doctor --yes".cyan());
+        }
+    }
+
+    println!();
+    if problems == 0 {
+        println!("{} No problems found", "✅".green());
+    } else {
+        println!("{} {} problem(s) found", "⚠️".yellow(), problems.to_string().yellow());
+    }
+    Ok(())
+}
+
+/// Whether to rewrite the files: `--yes`, or asking on a terminal
+fn confirm(yes: bool) -> Result<bool> {
+    if yes {
+        return Ok(true);
+    }
+    if !io::stdin().is_terminal() {
+        return Ok(false);
+    }
+    prompt::confirm("Rewrite these files?")
+}
+
+/// Write the rewritten file, keeping the original next to it as `<name>.bak`
+fn migrate(migration: &Migration) -> Result<()> {
+    let file = &migration.file;
+    let mut backup = file.clone().into_os_string();
+    backup.push(".bak");
+    let backup = PathBuf::from(backup);
+    fs::copy(file, &backup)
+        .map_err(|e| PyForgeError::file_error(format!("Could not back up {} to {}", file.display(), backup.display()), e))?;
+    fs::write(file, migration.document.to_string())
+        .map_err(|e| PyForgeError::file_error(format!("Could not write {}", file.display()), e))?;
+    println!(
+        "{} Rewrote {} (original kept as {})",
+        "✅".green(),
+        file.display().to_string().cyan(),
+        backup.display()
+    );
+    Ok(())
+}
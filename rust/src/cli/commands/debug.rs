@@ -0,0 +1,51 @@
+use crate::cli::args::DebugCommand;
+use crate::cli::deprecated::DEPRECATIONS;
+use crate::core::bundle;
+use crate::core::error::Result;
+use colored::*;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub fn run(command: DebugCommand) -> Result<()> {
+    match command {
+        DebugCommand::Bundle { output } => bundle_cmd(output),
+        DebugCommand::Deprecations { json } => deprecations(json),
+    }
+}
+
+fn bundle_cmd(output: Option<PathBuf>) -> Result<()> {
+    let output = output.unwrap_or_else(|| {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        PathBuf::from(format!("pyforge-debug-{}.tar.gz", secs))
+    });
+
+    println!("{} Collecting logs, configuration and environment info", "📦".green());
+    let path = bundle::create_bundle(&output)?;
+
+    println!("{} Support bundle written to {}", "✅".green(), path.display().to_string().cyan());
+    println!("   Secrets in configuration, environment and URLs in logs were redacted; review before sharing.");
+    Ok(())
+}
+
+fn deprecations(json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(DEPRECATIONS)?);
+        return Ok(());
+    }
+    if DEPRECATIONS.is_empty() {
+        println!("{} No deprecated commands or flags", "✅".green());
+        return Ok(());
+    }
+    for deprecation in DEPRECATIONS {
+        let status = if deprecation.removed() {
+            format!("removed in {}", deprecation.removal).red()
+        } else {
+            format!("deprecated since {}, removed in {}", deprecation.since, deprecation.removal).yellow()
+        };
+        println!("  pyforge {} → pyforge {}  ({})", deprecation.old, deprecation.new.cyan(), status);
+    }
+    Ok(())
+}
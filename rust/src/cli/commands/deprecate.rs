@@ -0,0 +1,159 @@
+use crate::cli::args::LockFlags;
+use crate::cli::commands::build;
+use crate::cli::prompt;
+use crate::core::build::Distribution;
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::pep440::Version;
+use crate::core::pep508::normalize_name;
+use crate::core::project::Project;
+use crate::core::pypi::{self, Repository};
+use crate::core::versioning;
+use colored::*;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use toml_edit::{Array, Item, value};
+
+/// Trove classifier for projects that are no longer maintained
+const INACTIVE: &str = "Development Status :: 7 - Inactive";
+const DESCRIPTION_PREFIX: &str = "[DEPRECATED]";
+
+pub fn run(message: &str, successor: Option<&str>, yes: bool, flags: &LockFlags) -> Result<()> {
+    let mut project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let usage = |message: String| PyForgeError::Usage { message };
+
+    let name = project
+        .name()
+        .ok_or_else(|| usage("[project] has no name".to_string()))?
+        .to_string();
+    if versioning::is_dynamic(&project) {
+        return Err(usage(
+            "The final release needs a static [project].version; tag the release in git and set it".to_string(),
+        ));
+    }
+    let current = versioning::resolve(&project, &config)?;
+    let version = versioning::next(&Version::parse(&current)?).to_string();
+    let reason = match successor {
+        Some(successor) => format!("{} Use {} instead.", message, successor),
+        None => message.to_string(),
+    };
+    let notice = format!("{} is deprecated: {}", name, reason);
+    let module = normalize_name(&name).replace('-', "_");
+    let init = [PathBuf::from("src").join(&module), PathBuf::from(&module)]
+        .into_iter()
+        .map(|dir| dir.join("__init__.py"))
+        .find(|path| project.root.join(path).is_file());
+    let readme = readme_path(&project);
+
+    // Resolve the token before touching any file
+    let repository = Repository::new(&config)?;
+
+    println!("{} Deprecating {} with a final release {}:", "🪦".yellow(), name.cyan(), version.cyan());
+    println!("   • pyproject.toml: version {}, classifier \"{}\", description marked {}", version, INACTIVE, DESCRIPTION_PREFIX);
+    if let Some(readme) = &readme {
+        println!("   • {}: notice at the top", readme.display());
+    }
+    match &init {
+        Some(init) => println!("   • {}: DeprecationWarning on import", init.display()),
+        None => println!("   • no {}/__init__.py found; no warning on import", module),
+    }
+    println!("   • build and upload to {}", repository.url);
+    println!("   Notice: {}", notice);
+    confirm(&name, yes)?;
+
+    project.update_pyproject(|document| {
+        let table = &mut document["project"];
+        table["version"] = value(version.as_str());
+        let description = table.get("description").and_then(Item::as_str).unwrap_or_default().to_string();
+        if !description.starts_with(DESCRIPTION_PREFIX) {
+            table["description"] = value(format!("{} {}", DESCRIPTION_PREFIX, description).trim_end());
+        }
+        if table.get("classifiers").and_then(Item::as_array).is_none() {
+            table["classifiers"] = value(Array::new());
+        }
+        if let Some(classifiers) = table["classifiers"].as_array_mut() {
+            classifiers.retain(|c| !c.as_str().is_some_and(|c| c.starts_with("Development Status ::")));
+            classifiers.push(INACTIVE);
+        }
+    })?;
+
+    if let Some(readme) = &readme {
+        let path = project.root.join(readme);
+        let existing = fs::read_to_string(&path).unwrap_or_default();
+        let (marker, banner) = if readme.extension().is_some_and(|ext| ext == "rst") {
+            (".. warning::", format!(".. warning::\n\n   {}\n\n", notice))
+        } else {
+            ("> **Deprecated:**", format!("> **Deprecated:** {}\n\n", reason))
+        };
+        if !existing.starts_with(marker) {
+            fs::write(&path, banner + &existing)
+                .map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))?;
+        }
+    }
+
+    if let Some(init) = &init {
+        let path = project.root.join(init);
+        let mut content = fs::read_to_string(&path)
+            .map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+        // Rerunning after a failed upload must not warn twice
+        if !content.contains("DeprecationWarning") {
+            if !content.is_empty() && !content.ends_with('\n') {
+                content.push('\n');
+            }
+            content.push_str(&format!(
+                "\nimport warnings as _warnings\n\n_warnings.warn({}, DeprecationWarning, stacklevel=2)\n",
+                serde_json::to_string(&notice).unwrap_or_default()
+            ));
+            fs::write(&path, content)
+                .map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))?;
+        }
+    }
+
+    let artifacts = build::build(&project, &config, flags, false, false, Distribution::ALL, &project.root.join("dist"))?;
+    for path in &artifacts {
+        let filename = path.file_name().unwrap_or_default().to_string_lossy();
+        repository.upload(path, &name, &version)?;
+        println!("{} Uploaded {}", "📤".green(), filename);
+    }
+
+    println!("{} Released {} {} as the final, deprecated version", "✅".green(), name, version);
+    println!(
+        "   Commit the changes, and consider yanking older releases: {}",
+        format!("pyforge yank {}", current).cyan()
+    );
+    println!("   Manage the project at {}", pypi::manage_release_url(&config, &name, &version));
+    Ok(())
+}
+
+/// `[project].readme` when it names a file, else README.md
+fn readme_path(project: &Project) -> Option<PathBuf> {
+    let readme = project.pyproject.get("project").and_then(|p| p.get("readme"));
+    let configured = match readme {
+        Some(toml::Value::String(file)) => Some(file.as_str()),
+        Some(toml::Value::Table(table)) => table.get("file").and_then(|f| f.as_str()),
+        _ => None,
+    };
+    let path = PathBuf::from(configured.unwrap_or("README.md"));
+    project.root.join(&path).is_file().then_some(path)
+}
+
+/// Ask for the project name on a terminal; elsewhere `--yes` is required
+fn confirm(name: &str, yes: bool) -> Result<()> {
+    if yes {
+        return Ok(());
+    }
+    if !io::stdin().is_terminal() {
+        return Err(PyForgeError::Usage {
+            message: "Refusing to publish a deprecation release without confirmation; pass --yes".to_string(),
+        });
+    }
+    let answer = prompt::ask(&format!("Type the project name ({}) to continue:", name.bold()))?;
+    if normalize_name(&answer) != normalize_name(name) {
+        return Err(PyForgeError::Usage {
+            message: "Confirmation did not match; nothing was changed".to_string(),
+        });
+    }
+    Ok(())
+}
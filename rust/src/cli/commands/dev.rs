@@ -0,0 +1,304 @@
+use crate::cli::commands::trust;
+use crate::core::config::{Config, WatcherConfig};
+use crate::core::error::{PyForgeError, Result};
+use crate::core::process;
+use crate::core::project::Project;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use regex::Regex;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Output lines kept per pane
+const SCROLLBACK: usize = 1000;
+
+/// How often watched files are checked for changes
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+enum Status {
+    Running,
+    Exited(Option<i32>),
+}
+
+/// A line of output from a pane's process, tagged with the run it came from
+struct Output {
+    pane: usize,
+    generation: u64,
+    line: String,
+}
+
+struct Pane {
+    name: String,
+    config: WatcherConfig,
+    error_pattern: Regex,
+    child: Option<Child>,
+    generation: u64,
+    status: Status,
+    lines: VecDeque<String>,
+    errors: usize,
+    fingerprint: Option<(usize, SystemTime)>,
+}
+
+pub fn run() -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+
+    if config.dev.watchers.is_empty() {
+        return Err(PyForgeError::MissingConfig {
+            key: "tool.pyforge.dev.watchers".to_string(),
+            example: "[tool.pyforge.dev.watchers.tests]\ncommand = \"pytest -q\"\nwatch = [\"src/**/*.py\", \"tests/**/*.py\"]".to_string(),
+        });
+    }
+    trust::require(&project)?;
+
+    let (sender, receiver) = mpsc::channel();
+    let mut panes = Vec::new();
+    for (index, (name, watcher)) in config.dev.watchers.into_iter().enumerate() {
+        let error_pattern = Regex::new(&watcher.error_pattern).map_err(|e| PyForgeError::InvalidConfig {
+            file: project.pyproject_path().display().to_string(),
+            source: Box::new(e),
+        })?;
+        let mut pane = Pane {
+            name,
+            config: watcher,
+            error_pattern,
+            child: None,
+            generation: 0,
+            status: Status::Exited(None),
+            lines: VecDeque::new(),
+            errors: 0,
+            fingerprint: None,
+        };
+        pane.fingerprint = fingerprint(&project.root, &pane.config.watch);
+        pane.start(index, &project.root, &sender);
+        panes.push(pane);
+    }
+
+    let mut terminal = ratatui::try_init()?;
+    let result = event_loop(&mut terminal, &mut panes, &project.root, &sender, &receiver);
+    ratatui::restore();
+
+    for pane in &mut panes {
+        pane.stop();
+    }
+    result
+}
+
+fn event_loop(
+    terminal: &mut DefaultTerminal,
+    panes: &mut [Pane],
+    root: &Path,
+    sender: &Sender<Output>,
+    receiver: &Receiver<Output>,
+) -> Result<()> {
+    let mut focused = 0;
+    let mut last_watch = Instant::now();
+
+    loop {
+        while let Ok(output) = receiver.try_recv() {
+            panes[output.pane].push(output);
+        }
+        for pane in panes.iter_mut() {
+            pane.poll_exit();
+        }
+
+        if last_watch.elapsed() >= WATCH_INTERVAL {
+            last_watch = Instant::now();
+            for (index, pane) in panes.iter_mut().enumerate() {
+                if pane.config.watch.is_empty() {
+                    continue;
+                }
+                let current = fingerprint(root, &pane.config.watch);
+                if current != pane.fingerprint {
+                    pane.fingerprint = current;
+                    pane.restart(index, root, sender, "files changed");
+                }
+            }
+        }
+
+        terminal.draw(|frame| render(frame, panes, focused))?;
+
+        if !event::poll(Duration::from_millis(100))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Tab | KeyCode::Down => focused = (focused + 1) % panes.len(),
+            KeyCode::BackTab | KeyCode::Up => focused = (focused + panes.len() - 1) % panes.len(),
+            KeyCode::Char(c @ '1'..='9') => {
+                let index = c as usize - '1' as usize;
+                if index < panes.len() {
+                    focused = index;
+                }
+            }
+            KeyCode::Char('r') => panes[focused].restart(focused, root, sender, "restart requested"),
+            KeyCode::Char('R') => {
+                for (index, pane) in panes.iter_mut().enumerate() {
+                    pane.restart(index, root, sender, "restart requested");
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Pane {
+    fn start(&mut self, index: usize, root: &Path, sender: &Sender<Output>) {
+        self.generation += 1;
+        self.errors = 0;
+
+        let spawned = process::shell_command(&self.config.command)
+            .current_dir(root)
+            .env("PYTHONUNBUFFERED", "1")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+
+        match spawned {
+            Ok(mut child) => {
+                if let Some(stdout) = child.stdout.take() {
+                    forward(stdout, index, self.generation, sender.clone());
+                }
+                if let Some(stderr) = child.stderr.take() {
+                    forward(stderr, index, self.generation, sender.clone());
+                }
+                self.child = Some(child);
+                self.status = Status::Running;
+            }
+            Err(e) => {
+                self.lines.push_back(format!("failed to start '{}': {}", self.config.command, e));
+                self.errors += 1;
+                self.status = Status::Exited(None);
+            }
+        }
+    }
+
+    fn stop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    fn restart(&mut self, index: usize, root: &Path, sender: &Sender<Output>, reason: &str) {
+        self.stop();
+        self.lines.push_back(format!("── {}, restarting ──", reason));
+        self.start(index, root, sender);
+    }
+
+    fn poll_exit(&mut self) {
+        if let Some(child) = &mut self.child
+            && let Ok(Some(status)) = child.try_wait()
+        {
+            self.status = Status::Exited(status.code());
+            self.child = None;
+        }
+    }
+
+    fn push(&mut self, output: Output) {
+        if output.generation != self.generation {
+            return;
+        }
+        if self.error_pattern.is_match(&output.line) {
+            self.errors += 1;
+        }
+        self.lines.push_back(output.line);
+        while self.lines.len() > SCROLLBACK {
+            self.lines.pop_front();
+        }
+    }
+}
+
+/// Stream a child's output into the dashboard channel from a background thread
+fn forward(stream: impl Read + Send + 'static, pane: usize, generation: u64, sender: Sender<Output>) {
+    std::thread::spawn(move || {
+        for line in BufReader::new(stream).lines().map_while(|l| l.ok()) {
+            if sender.send(Output { pane, generation, line }).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Number of watched files and their newest modification time
+fn fingerprint(root: &Path, patterns: &[String]) -> Option<(usize, SystemTime)> {
+    if patterns.is_empty() {
+        return None;
+    }
+
+    let mut count = 0;
+    let mut newest = SystemTime::UNIX_EPOCH;
+    for pattern in patterns {
+        let full: PathBuf = root.join(pattern);
+        let Ok(paths) = glob::glob(&full.to_string_lossy()) else {
+            continue;
+        };
+        for path in paths.flatten() {
+            if let Ok(modified) = path.metadata().and_then(|m| m.modified()) {
+                count += 1;
+                newest = newest.max(modified);
+            }
+        }
+    }
+    Some((count, newest))
+}
+
+fn render(frame: &mut Frame, panes: &[Pane], focused: usize) {
+    let [main, footer] = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+    let areas = Layout::vertical(vec![Constraint::Ratio(1, panes.len() as u32); panes.len()]).split(main);
+
+    for (index, (pane, area)) in panes.iter().zip(areas.iter()).enumerate() {
+        let (status, color) = match pane.status {
+            Status::Running => ("running".to_string(), Color::Green),
+            Status::Exited(Some(0)) => ("exited 0".to_string(), Color::Blue),
+            Status::Exited(Some(code)) => (format!("exited {}", code), Color::Red),
+            Status::Exited(None) => ("stopped".to_string(), Color::Red),
+        };
+        let errors = if pane.errors > 0 {
+            format!(" · {} errors", pane.errors).red()
+        } else {
+            " · 0 errors".dark_gray()
+        };
+        let title = Line::from(vec![
+            format!(" {} {} · ", index + 1, pane.name).bold(),
+            status.fg(color),
+            errors,
+            " ".into(),
+        ]);
+
+        let mut block = Block::bordered().title(title);
+        if index == focused {
+            block = block.border_style(Style::default().fg(Color::Cyan));
+        }
+
+        let visible = area.height.saturating_sub(2) as usize;
+        let skip = pane.lines.len().saturating_sub(visible);
+        let lines: Vec<Line> = pane.lines.iter().skip(skip).map(|l| Line::raw(l.as_str())).collect();
+        frame.render_widget(Paragraph::new(lines).block(block), *area);
+    }
+
+    let total: usize = panes.iter().map(|p| p.errors).sum();
+    let summary = format!(
+        "{} errors total · Tab/1-9 focus · r restart pane · R restart all · q quit",
+        total
+    );
+    let summary = if total > 0 { summary.red() } else { summary.dark_gray() };
+    frame.render_widget(Line::from(summary), footer);
+}
@@ -0,0 +1,66 @@
+use crate::cli::args::DevcontainerCommand;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::lockfile::Lockfile;
+use crate::core::markers::Environment;
+use crate::core::project::Project;
+use crate::core::venv::VENV_DIR;
+use colored::*;
+use serde_json::json;
+use std::fs;
+
+/// Directory VS Code and Codespaces read the configuration from
+const DEVCONTAINER_DIR: &str = ".devcontainer";
+
+pub fn run(command: DevcontainerCommand) -> Result<()> {
+    match command {
+        DevcontainerCommand::Init { image, force } => init(image, force),
+    }
+}
+
+fn init(image: Option<String>, force: bool) -> Result<()> {
+    let project = Project::require()?;
+    let path = project.root.join(DEVCONTAINER_DIR).join("devcontainer.json");
+    if path.exists() && !force {
+        return Err(PyForgeError::Usage {
+            message: format!("{} already exists; pass --force to overwrite it", path.display()),
+        });
+    }
+
+    // Match the Python the project is locked for
+    let python = match Lockfile::load(&project)? {
+        Some(lockfile) => lockfile.environment.python_version,
+        None => Environment::current()?.python_version,
+    };
+    let image = image.unwrap_or_else(|| format!("mcr.microsoft.com/devcontainers/python:{}", python));
+    let name = project.name().unwrap_or("project");
+
+    let config = json!({
+        "name": name,
+        "image": image,
+        // Keep the container's environment out of the host's .venv, which may be for another platform
+        "mounts": [format!(
+            "source={}-venv,target=${{containerWorkspaceFolder}}/{},type=volume",
+            name, VENV_DIR
+        )],
+        "postCreateCommand": "pipx install pyforge && pyforge sync",
+        "customizations": {
+            "vscode": {
+                "extensions": ["ms-python.python"],
+                "settings": {
+                    "python.defaultInterpreterPath": format!("${{containerWorkspaceFolder}}/{}/bin/python", VENV_DIR),
+                    "python.terminal.activateEnvironment": true
+                }
+            }
+        }
+    });
+
+    let dir = project.root.join(DEVCONTAINER_DIR);
+    fs::create_dir_all(&dir).map_err(|e| PyForgeError::file_error(format!("Could not create {}", dir.display()), e))?;
+    fs::write(&path, serde_json::to_string_pretty(&config)? + "\n")
+        .map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))?;
+
+    println!("{} Wrote {}", "✅".green(), path.strip_prefix(&project.root).unwrap_or(&path).display());
+    println!("   image: {}", image.cyan());
+    println!("   Open the folder in VS Code and choose 'Reopen in Container', or create a Codespace.");
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use crate::core::config::Config;
+use crate::core::dirs;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::index::IndexClient;
+use crate::core::project::Project;
+use crate::core::python;
+use crate::core::toolchain;
+use colored::*;
+
+pub fn run(network: bool) -> Result<()> {
+    println!("{} Checking your PyForge setup", "🩺".green());
+    let mut problems = 0;
+
+    match Project::discover() {
+        Ok(Some(project)) => pass(&format!(
+            "Project: {} ({})",
+            project.name().unwrap_or("<unnamed>"),
+            project.root.display()
+        )),
+        Ok(None) => warn("No pyproject.toml found in this directory or its parents"),
+        Err(e) => {
+            problems += 1;
+            fail(&e.to_string());
+        }
+    }
+
+    let config = match Config::load_current() {
+        Ok(config) => {
+            pass("Configuration loaded");
+            config
+        }
+        Err(e) => {
+            problems += 1;
+            fail(&e.to_string());
+            Config::default()
+        }
+    };
+
+    for (kind, dir) in [
+        ("Config", dirs::config_dir()),
+        ("Data", dirs::data_dir()),
+        ("Cache", dirs::cache_dir()),
+        ("State", dirs::state_dir()),
+    ] {
+        pass(&format!("{} directory: {}", kind, dir.display()));
+    }
+
+    match toolchain::python() {
+        Ok(path) => match python::version_of(&path) {
+            Some(version) => pass(&format!("Python {}: {}", version, path.display())),
+            None => pass(&format!("Python: {}", path.display())),
+        },
+        Err(e) => {
+            problems += 1;
+            fail(&e.to_string());
+        }
+    }
+
+    if network {
+        problems += check_network(&config)?;
+    }
+
+    println!();
+    if problems > 0 {
+        return Err(PyForgeError::HealthCheckFailed { problems });
+    }
+    println!("{} No problems found", "✅".green());
+    Ok(())
+}
+
+/// Probe every configured index and mirror, returning the number of unreachable indexes
+fn check_network(config: &Config) -> Result<usize> {
+    println!();
+    println!("{} Index latency", "🌐".green());
+
+    let client = IndexClient::new(config)?;
+    let mut unreachable = 0;
+
+    for index in config.indexes() {
+        println!("  {}", index.name.bold());
+        let mut reachable = false;
+
+        for url in index.urls() {
+            let probe = client.probe(url);
+            let latency = format!("{} ms", probe.latency.as_millis());
+            match &probe.outcome {
+                Ok(status) if probe.is_healthy() => {
+                    reachable = true;
+                    println!("    {} {}  {} (HTTP {})", "✅".green(), url, latency.cyan(), status);
+                }
+                Ok(status) => {
+                    println!("    {} {}  {} (HTTP {})", "❌".red(), url, latency, status.to_string().red());
+                }
+                Err(reason) => {
+                    println!("    {} {}  {}", "❌".red(), url, reason.red());
+                }
+            }
+        }
+
+        if !reachable {
+            unreachable += 1;
+        }
+    }
+
+    Ok(unreachable)
+}
+
+fn pass(message: &str) {
+    println!("  {} {}", "✅".green(), message);
+}
+
+fn warn(message: &str) {
+    println!("  {} {}", "⚠️".yellow(), message);
+}
+
+fn fail(message: &str) {
+    println!("  {} {}", "❌".red(), message.red());
+}
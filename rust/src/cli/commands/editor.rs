@@ -0,0 +1,39 @@
+use crate::cli::args::EditorCommand;
+use crate::core::config::Config;
+use crate::core::editor::{Editor, Settings};
+use crate::core::error::Result;
+use crate::core::project::Project;
+use colored::*;
+
+pub fn run(command: EditorCommand) -> Result<()> {
+    let project = Project::require()?;
+    let editors = match command {
+        EditorCommand::Init { vscode, pycharm } => {
+            let mut editors = Vec::new();
+            if vscode {
+                editors.push(Editor::Vscode);
+            }
+            if pycharm {
+                editors.push(Editor::Pycharm);
+            }
+            editors
+        }
+        EditorCommand::Sync => Editor::configured(&project),
+    };
+    if editors.is_empty() {
+        println!("{} No editor settings to sync; run 'pyforge editor init --vscode' or '--pycharm'", "ℹ️".blue());
+        return Ok(());
+    }
+
+    let config = Config::load(Some(&project))?;
+    let settings = Settings::detect(&project, &config);
+    for editor in editors {
+        let written = settings.write(&project, editor)?;
+        println!("{} Updated {} settings", "✅".green(), editor.name());
+        for path in written {
+            println!("   {} {}", "•".bright_black(), path.strip_prefix(&project.root).unwrap_or(&path).display());
+        }
+    }
+    println!("   interpreter: {}", settings.interpreter.display().to_string().cyan());
+    Ok(())
+}
@@ -0,0 +1,254 @@
+use crate::cli::args::{EnvCommand, IsolationFlags};
+use crate::cli::commands::trust;
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::installer;
+use crate::core::isolation::Isolation;
+use crate::core::lockfile::Lockfile;
+use crate::core::pep440::Version;
+use crate::core::project::Project;
+use crate::core::python::{self, Request};
+use crate::core::snapshot;
+use crate::core::venv::Venv;
+use crate::core::workspace;
+use std::fs;
+use std::path::{Path, PathBuf};
+use colored::*;
+use std::process::Command;
+
+pub fn run(command: EnvCommand) -> Result<()> {
+    match command {
+        EnvCommand::Create { force } => create(force),
+        EnvCommand::Remove => remove(),
+        EnvCommand::Info => info(),
+        EnvCommand::Exec { isolation, command } => exec(&isolation, &command),
+        EnvCommand::PythonPath { path } => {
+            let path = match path {
+                Some(path) => path,
+                None => std::env::current_dir()?,
+            };
+            let venv = locate(&path)?;
+            // Plain output: editor plugins read this line as-is
+            println!("{}", venv.python().display());
+            Ok(())
+        }
+        EnvCommand::Path => {
+            let project = Project::require()?;
+            let config = Config::load(Some(&project))?;
+            println!("{}", Venv::location(&project, &config).display());
+            Ok(())
+        }
+        EnvCommand::Upgrade => upgrade(),
+        EnvCommand::Export { output } => export(output),
+        EnvCommand::Import { archive, force } => import(&archive, force),
+    }
+}
+
+fn create(force: bool) -> Result<()> {
+    let project = Project::require()?;
+    trust::require_build_env(&project)?;
+    let config = Config::load(Some(&project))?;
+    if let Some(venv) = Venv::open(&project, &config) {
+        if !force {
+            println!(
+                "{} The environment at {} exists already; pass --force to create it again",
+                "✅".green(),
+                venv.display(&project)
+            );
+            return Ok(());
+        }
+        println!("{} Removing {}", "🗑️".cyan(), venv.display(&project));
+        venv.remove()?;
+    }
+    println!("{} Creating the environment at {}", "🐍".cyan(), Venv::location(&project, &config).display());
+    let venv = Venv::ensure(&project, &config)?;
+    println!(
+        "{} Created {} ({}); run {} to install the dependencies",
+        "✅".green(),
+        venv.display(&project),
+        venv.python_version().unwrap_or_else(|| "unknown version".to_string()),
+        "pyforge sync".cyan()
+    );
+    Ok(())
+}
+
+fn remove() -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let Some(venv) = Venv::open(&project, &config) else {
+        println!("{} The project has no environment at {}", "✅".green(), Venv::location(&project, &config).display());
+        return Ok(());
+    };
+    venv.remove()?;
+    println!("{} Removed the environment at {}", "🗑️".cyan(), venv.display(&project));
+    Ok(())
+}
+
+fn info() -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let Some(venv) = Venv::open(&project, &config) else {
+        println!(
+            "{} No environment yet at {}; run {} to create it",
+            "📭".cyan(),
+            Venv::location(&project, &config).display(),
+            "pyforge env create".cyan()
+        );
+        return Ok(());
+    };
+    println!("{} Environment at {}", "🐍".cyan(), venv.display(&project).cyan());
+    let bullet = "•".bright_black();
+    println!("   {} python {}", bullet, venv.python_version().unwrap_or_else(|| "(unknown version)".to_string()));
+    if let Some((Request::Version(pinned), source)) = python::pin(Some(&project), config.python.as_deref())
+        && let Some(current) = venv.python_version().and_then(|version| Version::parse(&version).ok())
+        && !python::version_matches(&current, &pinned)
+    {
+        println!(
+            "   {} {} pins Python {}; run {} to recreate it",
+            "⚠️".yellow(),
+            source,
+            pinned,
+            "pyforge env create --force".cyan()
+        );
+    }
+    println!("   {} interpreter {}", bullet, venv.python().display());
+    if let Some(base) = venv.base_python() {
+        println!("   {} created from {}", bullet, base.display());
+    }
+    if let Some(metadata) = venv.metadata() {
+        println!("   {} created {} by pyforge {}", bullet, metadata.created, metadata.pyforge);
+    }
+    match (venv.sync_state(), Lockfile::load(&project)?) {
+        (None, _) => println!("   {} never synced; run {}", bullet, "pyforge sync".cyan()),
+        (Some(state), lockfile) => {
+            let current = lockfile.is_some_and(|lockfile| lockfile.input_hash == state.input_hash);
+            let from = match &state.branch {
+                Some(branch) => format!("{} ({})", state.worktree.display(), branch),
+                None => state.worktree.display().to_string(),
+            };
+            if current {
+                println!("   {} synced from {}", bullet, from);
+            } else {
+                println!("   {} synced from {}, before pyforge.lock changed; run {}", "⚠️".yellow(), from, "pyforge sync".cyan());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn upgrade() -> Result<()> {
+    let project = Project::require()?;
+    trust::require_build_env(&project)?;
+    let config = Config::load(Some(&project))?;
+    let venv = Venv::open(&project, &config).ok_or_else(|| PyForgeError::Usage {
+        message: "The project has no environment yet; run 'pyforge sync' to create it".to_string(),
+    })?;
+    println!("{} Upgrading the environment at {}", "⬆️".cyan(), venv.display(&project));
+    venv.upgrade(&config.venv)?;
+    installer::seed(&venv, &config, &project.root)?;
+    println!("{} Environment upgraded", "✅".green());
+    Ok(())
+}
+
+fn export(output: Option<PathBuf>) -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let venv = Venv::open(&project, &config).ok_or_else(|| PyForgeError::Usage {
+        message: "The project has no environment yet; run 'pyforge sync' to create it".to_string(),
+    })?;
+    let lockfile = Lockfile::require(&project)?;
+    let manifest = snapshot::manifest(&project, &venv, &lockfile)?;
+    let output = output.unwrap_or_else(|| PathBuf::from(manifest.file_name(&project)));
+
+    println!("{} Exporting the environment at {}", "📦".cyan(), venv.display(&project));
+    snapshot::export(&venv, &manifest, &output)?;
+    let size = fs::metadata(&output).map(|metadata| metadata.len()).unwrap_or(0);
+    println!(
+        "{} Wrote {} ({:.1} MB, {} on {})",
+        "✅".green(),
+        output.display().to_string().cyan(),
+        size as f64 / 1_000_000.0,
+        manifest.python,
+        manifest.platform
+    );
+    Ok(())
+}
+
+fn import(archive: &Path, force: bool) -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let manifest = snapshot::read_manifest(archive)?;
+    snapshot::check_compatible(&manifest)?;
+
+    let destination = Venv::location(&project, &config);
+    if destination.exists() {
+        if !force {
+            return Err(PyForgeError::Usage {
+                message: format!("{} already exists; pass --force to replace it", destination.display()),
+            });
+        }
+        fs::remove_dir_all(&destination)
+            .map_err(|e| PyForgeError::file_error(format!("Could not remove {}", destination.display()), e))?;
+    }
+
+    println!("{} Importing {} ({} on {})", "📥".cyan(), archive.display(), manifest.python, manifest.platform);
+    let venv = snapshot::import(archive, &manifest, &project, &destination)?;
+    let lockfile = Lockfile::load(&project)?;
+    if snapshot::record_sync(&venv, &manifest, &project, lockfile.as_ref())? {
+        println!("{} Environment ready at {}", "✅".green(), venv.display(&project));
+    } else {
+        println!(
+            "{} Environment imported at {}, but it was synced from a different pyforge.lock; run {} to update it",
+            "⚠️".yellow(),
+            venv.display(&project),
+            "pyforge sync".cyan()
+        );
+    }
+    Ok(())
+}
+
+fn locate(path: &Path) -> Result<Venv> {
+    let (_, venv) = workspace::environment_for(path)?.ok_or_else(|| PyForgeError::Usage {
+        message: format!("No environment for {}; run 'pyforge sync' in its project", path.display()),
+    })?;
+    Ok(venv)
+}
+
+fn exec(isolation: &IsolationFlags, args: &[String]) -> Result<()> {
+    let venv = locate(&std::env::current_dir()?)?;
+    let mut command = Command::new(&args[0]);
+    command.args(&args[1..]);
+    if !isolation.isolated {
+        venv.activate(&mut command)?;
+        return replace_process(command, args);
+    }
+
+    // Wait for the command instead of replacing this process, so the temporary home is removed
+    let config = Config::load_current()?;
+    let isolated = Isolation::new(config.isolation.allow_env.iter().chain(&isolation.allow_env).cloned())?;
+    isolated.apply(&mut command);
+    venv.activate(&mut command)?;
+    let status = command.status().map_err(|_| PyForgeError::CommandNotFound { command: args[0].clone() })?;
+    if !status.success() {
+        return Err(PyForgeError::command_failed(args.join(" "), status.code().unwrap_or(-1)));
+    }
+    Ok(())
+}
+
+/// Replace this process so signals and stdio go straight to the command
+#[cfg(unix)]
+fn replace_process(mut command: Command, args: &[String]) -> Result<()> {
+    use std::os::unix::process::CommandExt;
+    let error = command.exec();
+    log::debug!("could not run {}: {}", args[0], error);
+    Err(PyForgeError::CommandNotFound { command: args[0].clone() })
+}
+
+#[cfg(not(unix))]
+fn replace_process(mut command: Command, args: &[String]) -> Result<()> {
+    let status = command.status().map_err(|_| PyForgeError::CommandNotFound { command: args[0].clone() })?;
+    if !status.success() {
+        return Err(PyForgeError::command_failed(args.join(" "), status.code().unwrap_or(-1)));
+    }
+    Ok(())
+}
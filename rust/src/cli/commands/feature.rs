@@ -0,0 +1,87 @@
+use crate::cli::args::FeatureCommand;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::feature::Feature;
+use crate::core::lockfile::Lockfile;
+use crate::core::markers::Environment;
+use crate::core::pep508::normalize_name;
+use crate::core::permissions::{self, Mode};
+use crate::core::project::Project;
+use crate::core::template;
+use colored::*;
+use std::fs;
+
+pub fn run(command: FeatureCommand) -> Result<()> {
+    match command {
+        FeatureCommand::Add { features, force } => add(&features, force),
+        FeatureCommand::List => list(),
+    }
+}
+
+fn add(names: &[String], force: bool) -> Result<()> {
+    let project = Project::require()?;
+    let features = names.iter().map(|name| Feature::load(name)).collect::<Result<Vec<_>>>()?;
+
+    let name = project.name().unwrap_or("project").to_string();
+    let python = match Lockfile::load(&project)? {
+        Some(lockfile) => lockfile.environment.python_version,
+        None => Environment::current()?.python_version,
+    };
+    let mut variables = template::context();
+    variables.extend([
+        ("module_name".to_string(), normalize_name(&name).replace('-', "_")),
+        ("project_name".to_string(), name),
+        ("python_version".to_string(), python),
+    ]);
+    variables.extend(template::environment(
+        features.iter().flat_map(|feature| feature.files.iter().map(|file| file.content.as_str())),
+    ));
+
+    // Check every feature before touching anything
+    let mut conflicts = Vec::new();
+    for feature in &features {
+        let plan = feature.plan(&project, &variables, force)?;
+        conflicts.extend(plan.conflicts.into_iter().map(|c| format!("{}: {}", feature.name, c)));
+    }
+    if !conflicts.is_empty() {
+        return Err(PyForgeError::Usage {
+            message: format!(
+                "Adding {} would overwrite existing setup:\n  - {}\nPass --force to replace it",
+                names.join(", "),
+                conflicts.join("\n  - ")
+            ),
+        });
+    }
+
+    for feature in &features {
+        // Re-read the project so features touching pyproject.toml build on each other
+        let project = Project::load(&project.root)?;
+        let plan = feature.plan(&project, &variables, force)?;
+        for (relative, content) in &plan.writes {
+            let path = project.root.join(relative);
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|e| PyForgeError::file_error(format!("Could not create {}", dir.display()), e))?;
+            }
+            fs::write(&path, content).map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))?;
+            if permissions::is_script(content.as_bytes()) {
+                permissions::apply(&path, Mode::Executable)?;
+            }
+        }
+
+        println!("{} Added feature '{}': {}", "🧩".green(), feature.name.cyan(), feature.description);
+        for (relative, _) in &plan.writes {
+            println!("   {} {}", "•".bright_black(), relative.display());
+        }
+        for relative in &plan.unchanged {
+            println!("   {} {} (already up to date)", "•".bright_black(), relative.display().to_string().bright_black());
+        }
+    }
+    Ok(())
+}
+
+fn list() -> Result<()> {
+    for name in Feature::available() {
+        let description = Feature::load(&name).map(|feature| feature.description).unwrap_or_default();
+        println!("{:<12} {}", name.cyan(), description);
+    }
+    Ok(())
+}
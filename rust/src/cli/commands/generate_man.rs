@@ -0,0 +1,43 @@
+use crate::cli::args::Cli;
+use crate::core::error::{PyForgeError, Result};
+use clap::CommandFactory;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// Emit roff man pages: `pyforge.1` on stdout, or one page per command into `out_dir`
+pub fn run(out_dir: Option<&Path>) -> Result<()> {
+    let mut command = Cli::command();
+    command.build();
+
+    match out_dir {
+        None => {
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(&render(&command)?)?;
+        }
+        Some(dir) => {
+            fs::create_dir_all(dir)?;
+            write_pages(&command, "pyforge", dir)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_pages(command: &clap::Command, name: &str, dir: &Path) -> Result<()> {
+    let command = command.clone().display_name(name.to_string());
+    let path = dir.join(format!("{}.1", name));
+    fs::write(&path, render(&command)?)
+        .map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))?;
+    println!("{}", path.display());
+
+    for sub in command.get_subcommands().filter(|s| !s.is_hide_set()) {
+        write_pages(sub, &format!("{}-{}", name, sub.get_name()), dir)?;
+    }
+    Ok(())
+}
+
+fn render(command: &clap::Command) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    clap_mangen::Man::new(command.clone()).render(&mut buffer)?;
+    Ok(buffer)
+}
@@ -0,0 +1,53 @@
+use crate::cli::args::Cli;
+use crate::cli::help::{self, TOPICS};
+use crate::core::error::{PyForgeError, Result};
+use clap::CommandFactory;
+use colored::*;
+
+pub fn run(topic: Vec<String>) -> Result<()> {
+    let mut command = Cli::command();
+    command.build();
+
+    if topic.is_empty() {
+        command.print_long_help()?;
+        println!();
+        print_topics();
+        return Ok(());
+    }
+
+    if let [name] = topic.as_slice()
+        && let Some(topic) = help::find(name)
+    {
+        print!("{}", help::render(topic.body));
+        return Ok(());
+    }
+
+    // Fall back to subcommand help, e.g. `pyforge help debug bundle`
+    let mut current = &mut command;
+    for name in &topic {
+        current = current.find_subcommand_mut(name).ok_or_else(|| unknown_topic(&topic))?;
+    }
+    current.print_long_help()?;
+    Ok(())
+}
+
+fn print_topics() {
+    println!("{}", "Help topics:".bold().underline());
+    let width = TOPICS.iter().map(|t| t.name.len()).max().unwrap_or(0);
+    for topic in TOPICS {
+        println!("  {:width$}  {}", topic.name.green(), topic.summary, width = width);
+    }
+    println!();
+    println!("Run '{}' to read a topic.", "pyforge help <topic>".yellow());
+}
+
+fn unknown_topic(topic: &[String]) -> PyForgeError {
+    let topics: Vec<&str> = TOPICS.iter().map(|t| t.name).collect();
+    PyForgeError::Usage {
+        message: format!(
+            "error: no help topic or command named '{}'\n\nAvailable topics: {}\n",
+            topic.join(" "),
+            topics.join(", ")
+        ),
+    }
+}
@@ -1,13 +1,37 @@
+use crate::cli::args::OrgFlags;
+use crate::core::config::Config;
 use crate::core::error::{PyForgeError, Result, validation};
+use crate::core::org::{Author, OrgDefaults};
+use crate::core::permissions::ChmodRule;
+use crate::core::policy::Policy;
+use crate::core::preset::Preset;
+use crate::core::project::Project;
+use crate::core::python;
+use crate::core::registry;
+use crate::core::scaffold::{DEFAULT_LAYOUT, Layout};
+use crate::core::template::{Template, user_template_dir};
+use crate::core::trust;
+use crate::core::git;
 use crate::{ensure};
+use std::fs;
 use std::path::Path;
 use colored::*;
+use toml_edit::{Array, InlineTable, Item, value};
 
-pub fn run(name: &str, template: &Option<String>) -> Result<()> {
-    println!("🚀 Creating project: {}", name);
-    println!("Project created successfully");
+pub fn run(
+    name: &str,
+    template: &Option<String>,
+    chmod: &[String],
+    vars: &[String],
+    preset: Option<&str>,
+    org: &OrgFlags,
+    python: Option<&str>,
+) -> Result<()> {
     // Validate project name
     validation::validate_project_name(name)?;
+    if let Some(version) = python {
+        validation::validate_python_version(version)?;
+    }
     
     // Check it doesn't exist
     ensure!(
@@ -18,12 +42,195 @@ pub fn run(name: &str, template: &Option<String>) -> Result<()> {
         }
     );
     
+    // Resolve the organization defaults, preset and template before touching the filesystem
+    let global = Config::load(None)?;
+    let org = if org.no_org { None } else { OrgDefaults::load(org.org.as_deref(), &global)? };
+    let preset = preset.or(org.as_ref().and_then(|o| o.preset.as_deref()));
+    let template = template.as_deref().or(org.as_ref().and_then(|o| o.template.as_deref()));
+    let preset = preset.map(Preset::load).transpose()?;
+    let (layout, template) = scaffold(template, &user_template_dir())?;
+    let rules = chmod.iter().map(|rule| ChmodRule::parse(rule)).collect::<Result<Vec<_>>>()?;
+    ensure!(
+        rules.is_empty() || template.is_some(),
+        PyForgeError::Usage {
+            message: "--chmod sets modes of template files; pass a --template other than the built-in layouts".to_string(),
+        }
+    );
+    let answers = vars
+        .iter()
+        .map(|var| match var.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => Ok((key.trim().to_string(), value.to_string())),
+            _ => Err(PyForgeError::Usage {
+                message: format!("Invalid --var '{}'; expected NAME=VALUE, e.g. 'use_docker=yes'", var),
+            }),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    ensure!(
+        answers.is_empty() || template.is_some(),
+        PyForgeError::Usage {
+            message: "--var sets template variables; pass a --template other than the built-in layouts".to_string(),
+        }
+    );
+    let template = template.map(|source| Template::fetch(source, None)).transpose()?;
+    let variables = template.as_ref().map(|template| {
+        let mut variables = template.variables(name);
+        variables.extend(answers);
+        variables
+    });
+    if let (Some(template), Some(variables)) = (&template, &variables) {
+        // Catches file names Windows can't create, invalid [chmod] and [when]
+        // rules, and invalid copy globs
+        template.modes(variables, &rules)?;
+        template.rendered(variables)?;
+    }
+    
     println!("{} Creating project: {}", "🚀".green(), name.cyan());
+    if let Some(org) = &org {
+        println!("{} Using defaults from {} ({})", "🏢".green(), org.name.cyan(), org.source.bright_black());
+    }
     
-    // Create project
-    // create_project_structure(name)
-    //     .map_err(|e| PyForgeError::file_error("Could not create project", e))?;
+    let pinned = python.map(|version| format!(">={}", version.split('.').take(2).collect::<Vec<_>>().join(".")));
+    let requires_python = org
+        .as_ref()
+        .and_then(|o| o.requires_python.as_deref())
+        .or(pinned.as_deref())
+        .unwrap_or(">=3.8");
+    let defaults = Layout::variables(name, requires_python);
+    fs::create_dir_all(name).map_err(|e| PyForgeError::file_error("Could not create project", e))?;
+    if let Some(version) = python {
+        python::write_pin(Path::new(name), version)?;
+        println!("{} Pinned Python {} in {}", "🐍".green(), version.cyan(), python::VERSION_FILE);
+    }
+    if let Some(layout) = &layout {
+        let written = layout.render(Path::new(name), &defaults)?;
+        println!("{} Created '{}' layout ({} file(s))", "📐".green(), layout.name.cyan(), written.len());
+    }
+    if let (Some(template), Some(variables)) = (&template, variables) {
+        let written = template.render(Path::new(name), &variables, &rules)?;
+        template.record(variables, &rules).write(Path::new(name))?;
+        let label = template.manifest.name.as_deref().unwrap_or(&template.source);
+        println!("{} Rendered template '{}' ({} file(s))", "📐".green(), label.cyan(), written.len());
+    }
+    
+    // Templates may bring their own pyproject.toml
+    let pyproject = Path::new(name).join("pyproject.toml");
+    if !pyproject.exists() {
+        fs::write(&pyproject, Layout::pyproject(&defaults)?)
+            .map_err(|e| PyForgeError::file_error("Could not create project", e))?;
+    }
+    
+    if let Some(org) = &org {
+        let mut project = Project::load(Path::new(name))?;
+        for key in org.apply(&mut project)? {
+            println!("   {} {}", "•".bright_black(), key);
+        }
+        check_policy(&project)?;
+    }
+    
+    if let Some(author) = global.init.author.clone().or_else(git_author) {
+        let mut project = Project::load(Path::new(name))?;
+        if add_author(&mut project, &author)? {
+            println!("   {} authors: {}", "•".bright_black(), author.name.as_deref().or(author.email.as_deref()).unwrap_or_default());
+        }
+    }
+    
+    if let Some(preset) = preset {
+        let written = preset.apply(Path::new(name))?;
+        println!("{} Applied preset '{}': {}", "🧰".green(), preset.name.cyan(), preset.description);
+        for path in written {
+            println!("   {} {}", "•".bright_black(), path.display());
+        }
+    }
     
+    // Commands from the user's own presets and org defaults need no prompt;
+    // a template's are someone else's, like a clone's
+    if template.is_none() {
+        trust::trust(Path::new(name))?;
+    }
+    registry::remember(&Project::load(Path::new(name))?)?;
+
     println!("{} Project '{}' created successfully!", "✅".green(), name.green());
     Ok(())
-}
\ No newline at end of file
+}
+
+/// The built-in layout or the template to render for `--template`: a user
+/// template shadows the built-in layout of the same name, the default one too
+fn scaffold<'a>(template: Option<&'a str>, user_templates: &Path) -> Result<(Option<Layout>, Option<&'a str>)> {
+    let name = template.unwrap_or(DEFAULT_LAYOUT);
+    let layout = Layout::builtin(name, user_templates)?;
+    let template = layout.is_none().then_some(name);
+    Ok((layout, template))
+}
+
+/// Set `[project].authors` to the configured author, unless the template or
+/// organization already set authors
+fn add_author(project: &mut Project, author: &Author) -> Result<bool> {
+    let mut added = false;
+    project.update_pyproject(|document| {
+        let Some(table) = document.get_mut("project").and_then(Item::as_table_mut) else {
+            return;
+        };
+        if table.contains_key("authors") {
+            return;
+        }
+        let mut entry = InlineTable::new();
+        if let Some(name) = &author.name {
+            entry.insert("name", name.as_str().into());
+        }
+        if let Some(email) = &author.email {
+            entry.insert("email", email.as_str().into());
+        }
+        let mut authors = Array::new();
+        authors.push(entry);
+        table.insert("authors", value(authors));
+        added = true;
+    })?;
+    Ok(added)
+}
+
+/// Warn when the organization's own defaults leave the project out of policy
+fn check_policy(project: &Project) -> Result<()> {
+    let Some(policy) = Policy::load()? else {
+        return Ok(());
+    };
+    let config = Config::load(Some(project))?;
+    for violation in policy.check_project(project, &config) {
+        println!("{} [{}] {}", "⚠️".yellow(), violation.rule, violation.message);
+    }
+    Ok(())
+}
+
+/// The author from git's `user.name` and `user.email`, when `[init] author` isn't set
+fn git_author() -> Option<Author> {
+    let author = Author {
+        name: git::global_config("user.name"),
+        email: git::global_config("user.email"),
+    };
+    (author.name.is_some() || author.email.is_some()).then_some(author)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn picked(template: Option<&str>, user_templates: &Path) -> (Option<String>, Option<String>) {
+        let (layout, template) = scaffold(template, user_templates).unwrap();
+        (layout.map(|layout| layout.name), template.map(str::to_string))
+    }
+
+    #[test]
+    fn user_template_shadows_the_default_layout() {
+        let user_templates = tempfile::tempdir().unwrap();
+        assert_eq!(picked(None, user_templates.path()), (Some(DEFAULT_LAYOUT.to_string()), None));
+
+        fs::create_dir(user_templates.path().join(DEFAULT_LAYOUT)).unwrap();
+        assert_eq!(picked(None, user_templates.path()), (None, Some(DEFAULT_LAYOUT.to_string())));
+        assert_eq!(picked(Some(DEFAULT_LAYOUT), user_templates.path()), (None, Some(DEFAULT_LAYOUT.to_string())));
+    }
+
+    #[test]
+    fn other_templates_are_not_layouts() {
+        let user_templates = tempfile::tempdir().unwrap();
+        assert_eq!(picked(Some("gh:org/template"), user_templates.path()), (None, Some("gh:org/template".to_string())));
+    }
+}
@@ -0,0 +1,54 @@
+use crate::cli::args::{LockFlags, SyncFlags};
+use crate::cli::commands::{lock, sync, trust};
+use crate::core::config::Config;
+use crate::core::error::Result;
+use crate::core::lockfile::{LOCKFILE, Lockfile};
+use crate::core::project::Project;
+use crate::core::venv::Venv;
+use colored::*;
+
+/// Create the environment, lock and sync, announcing each phase
+pub fn run(flags: &LockFlags, sync_flags: &SyncFlags) -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    trust::require_build_env(&project)?;
+    let name = project.name().unwrap_or("project").to_string();
+    println!("{} Installing {}", "🚀".green(), name.cyan());
+
+    phase(1, "Environment");
+    let venv = match Venv::open(&project, &config) {
+        Some(venv) => {
+            println!("   Using {}", venv.display(&project).cyan());
+            venv
+        }
+        None => {
+            let location = Venv { root: Venv::location(&project, &config) };
+            println!("   Creating {}", location.display(&project).cyan());
+            Venv::ensure(&project, &config)?
+        }
+    };
+
+    phase(2, "Lockfile");
+    let fresh = Lockfile::load(&project)?.is_some_and(|lockfile| lockfile.is_fresh(&project));
+    if fresh {
+        println!("   {} is up to date", LOCKFILE.cyan());
+    } else if !flags.locked && !flags.frozen {
+        println!("   Resolving dependencies");
+    }
+    let lockfile = lock::ensure(&project, &config, flags)?;
+
+    phase(3, "Packages");
+    sync::sync_into(&project, &config, &venv, &lockfile, flags, sync_flags)?;
+
+    println!(
+        "{} {} is ready; run commands in its environment with {}",
+        "🎉".green(),
+        name.cyan(),
+        "pyforge env exec -- <command>".cyan()
+    );
+    Ok(())
+}
+
+fn phase(number: usize, title: &str) {
+    println!("{} {}", format!("[{}/3]", number).bold(), title.bold());
+}
@@ -0,0 +1,84 @@
+use crate::cli::args::InternalCommand;
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::lockfile::{self, Lockfile};
+use crate::core::markers::Environment;
+use crate::core::pep508::{Requirement, normalize_name};
+use crate::core::project::Project;
+use crate::core::resolver::Resolver;
+use crate::core::workspace;
+use serde_json::{Value, json};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+
+/// Version of the output of every plumbing command; bumped only for
+/// incompatible changes, never for added fields
+const SCHEMA: u32 = 1;
+
+pub fn run(command: InternalCommand) -> Result<()> {
+    let output = match command {
+        InternalCommand::ResolveOnly { requirements } => resolve_only(&requirements)?,
+        InternalCommand::PrintLockJson { path } => print_lock_json(path)?,
+        InternalCommand::NormalizeName { names } => json!({
+            "names": names
+                .iter()
+                .map(|name| json!({ "name": name, "normalized": normalize_name(name) }))
+                .collect::<Vec<_>>(),
+        }),
+        InternalCommand::EvaluateMarker { marker, extras, python } => evaluate_marker(&marker, extras, python.as_deref())?,
+    };
+    print(output)
+}
+
+/// One JSON object on a single line, with the schema version
+fn print(output: Value) -> Result<()> {
+    let mut object = serde_json::Map::new();
+    object.insert("schema".to_string(), json!(SCHEMA));
+    if let Value::Object(fields) = output {
+        object.extend(fields);
+    }
+    println!("{}", serde_json::to_string(&Value::Object(object))?);
+    Ok(())
+}
+
+fn resolve_only(requirements: &[String]) -> Result<Value> {
+    let project = if requirements.is_empty() { Some(Project::require()?) } else { Project::discover()? };
+    let config = Config::load(project.as_ref())?;
+    let requirements = if requirements.is_empty() {
+        lockfile::requirements(project.as_ref().expect("required above"))?
+    } else {
+        requirements.iter().map(|requirement| Requirement::parse(requirement)).collect::<Result<Vec<_>>>()?
+    };
+
+    let environment = Environment::current()?;
+    let mut resolver = Resolver::new(&config, &environment)?;
+    if let Some(project) = &project {
+        resolver.set_members(workspace::members(project)?);
+        resolver.set_find_links(config.install.find_links.iter().map(|dir| project.root.join(dir)).collect());
+    }
+    let packages = resolver.resolve(&requirements, &|name| log::debug!("resolving {}", name))?;
+    Ok(json!({ "environment": environment, "packages": packages }))
+}
+
+fn print_lock_json(path: Option<PathBuf>) -> Result<Value> {
+    let lockfile = match path {
+        Some(path) => {
+            let content = fs::read_to_string(&path)
+                .map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+            Lockfile::parse(&content, &path.display().to_string())?
+        }
+        None => Lockfile::require(&Project::require()?)?,
+    };
+    Ok(json!({ "lockfile": lockfile }))
+}
+
+fn evaluate_marker(marker: &str, extras: Vec<String>, python: Option<&str>) -> Result<Value> {
+    let environment = match python {
+        Some(python) => Environment::from_interpreter(python)?,
+        None => Environment::current()?,
+    };
+    let extras: BTreeSet<String> = extras.iter().map(|extra| normalize_name(extra)).collect();
+    let result = environment.evaluate(marker, &extras)?;
+    Ok(json!({ "marker": marker, "extras": extras, "result": result, "environment": environment }))
+}
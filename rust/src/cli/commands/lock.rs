@@ -0,0 +1,364 @@
+use crate::cli::args::LockFlags;
+use crate::core::config::{Config, ResolutionStrategy};
+use crate::core::error::{PyForgeError, Result};
+use crate::core::git;
+use crate::core::lockfile::{self, LOCKFILE, LOCKFILE_VERSION, LockedPackage, Lockfile};
+use crate::core::lockimport::{self, Format};
+use crate::core::lockmerge;
+use crate::core::markers::Environment;
+use crate::core::pep440::{SpecifierSet, Version};
+use crate::core::pep508::Requirement;
+use crate::core::policy::Policy;
+use crate::core::project::Project;
+use crate::core::pylock::{PYLOCK, Pylock};
+use crate::core::resolver::Resolver;
+use crate::core::toolchain::{self, Toolchain};
+use crate::core::workspace;
+use colored::*;
+use std::fs;
+use std::path::PathBuf;
+
+pub fn run(
+    frozen_toolchain: bool,
+    explain: Option<&str>,
+    exclude_newer: Option<String>,
+    find_links: &[PathBuf],
+) -> Result<()> {
+    let project = Project::require()?;
+    let mut config = Config::load(Some(&project))?;
+    if exclude_newer.is_some() {
+        config.resolver.exclude_newer = exclude_newer;
+    }
+    for dir in find_links {
+        let absolute = dir.canonicalize().ok().filter(|dir| dir.is_dir()).ok_or_else(|| PyForgeError::DirectoryNotFound {
+            path: dir.display().to_string(),
+        })?;
+        config.install.find_links.push(absolute.display().to_string());
+    }
+    let lockfile = lock(&project, &config, frozen_toolchain, explain)?;
+    println!(
+        "{} Locked {} package(s) to {}",
+        "✅".green(),
+        lockfile.packages.len(),
+        LOCKFILE.cyan()
+    );
+    Ok(())
+}
+
+/// Resolve merge conflicts in the lockfile: keep what both sides agree on and
+/// re-resolve only the packages they changed differently
+pub fn merge(frozen_toolchain: bool) -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let path = Lockfile::path(&project);
+    let content = fs::read_to_string(&path)
+        .map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+
+    let Some(sides) = git::conflict_stages(&project.root, LOCKFILE).or_else(|| lockmerge::split_conflicts(&content))
+    else {
+        println!("{} {} has no merge conflicts", "✅".green(), LOCKFILE.cyan());
+        return Ok(());
+    };
+    let base = sides
+        .base
+        .as_deref()
+        .and_then(|base| Lockfile::parse(base, &format!("{} (base)", LOCKFILE)).ok());
+    let ours = Lockfile::parse(&sides.ours, &format!("{} (ours)", LOCKFILE))?;
+    let theirs = Lockfile::parse(&sides.theirs, &format!("{} (theirs)", LOCKFILE))?;
+    if frozen_toolchain || config.frozen_toolchain {
+        toolchain::check(&ours, true)?;
+    }
+
+    let merge = lockmerge::merge(base.as_ref(), &ours, &theirs);
+    println!(
+        "{} {} package(s) merged cleanly, {} conflicting",
+        "🔀".cyan(),
+        merge.agreed.len(),
+        merge.conflicts.len()
+    );
+    let lockfile = write_lock(&project, &config, &merge.agreed, None)?;
+
+    for conflict in &merge.conflicts {
+        let resolved = lockfile.package(&conflict.name).map_or("removed", |p| p.version.as_str());
+        println!(
+            "   {} {}: ours {}, theirs {} → {}",
+            "•".bright_black(),
+            conflict.name,
+            conflict.ours.as_deref().unwrap_or("absent"),
+            conflict.theirs.as_deref().unwrap_or("absent"),
+            resolved.green()
+        );
+    }
+    println!(
+        "{} Wrote {}; run 'git add {}' to mark it resolved",
+        "✅".green(),
+        LOCKFILE.cyan(),
+        LOCKFILE
+    );
+    Ok(())
+}
+
+/// Rewrite the lockfile in the current format, keeping every pin
+pub fn migrate() -> Result<()> {
+    let project = Project::require()?;
+    let lockfile = Lockfile::require(&project)?;
+    let Some(from) = lockfile.migrated_from else {
+        println!("{} {} already uses format {}", "✅".green(), LOCKFILE.cyan(), LOCKFILE_VERSION);
+        return Ok(());
+    };
+
+    lockfile.write(&project)?;
+    println!("{} Migrated {} from format {} to {}", "✅".green(), LOCKFILE.cyan(), from, LOCKFILE_VERSION);
+    for change in lockfile::changes_since_format(from) {
+        println!("   {} {}", "•".bright_black(), change);
+    }
+    Ok(())
+}
+
+/// Write pyforge.lock from another tool's lockfile, reporting what couldn't be carried over
+pub fn import(path: Option<PathBuf>) -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let path = match path {
+        Some(path) => path,
+        None => {
+            let found: Vec<PathBuf> = Format::ALL
+                .iter()
+                .map(|format| project.root.join(format.file_name()))
+                .filter(|path| path.is_file())
+                .collect();
+            match found.as_slice() {
+                [path] => path.clone(),
+                [] => {
+                    return Err(PyForgeError::Usage {
+                        message: "No poetry.lock, uv.lock, Pipfile.lock or pylock.toml next to pyproject.toml; pass the path to one"
+                            .to_string(),
+                    });
+                }
+                _ => {
+                    return Err(PyForgeError::Usage {
+                        message: format!(
+                            "Found {}; pass the one to import",
+                            found.iter().map(|path| path.display().to_string()).collect::<Vec<_>>().join(" and ")
+                        ),
+                    });
+                }
+            }
+        }
+    };
+    let format = Format::detect(&path).ok_or_else(|| PyForgeError::Usage {
+        message: format!(
+            "Can't tell the format of {}; the file must be named poetry.lock, uv.lock, Pipfile.lock or pylock[.NAME].toml",
+            path.display()
+        ),
+    })?;
+
+    println!("{} Importing {}", "📥".cyan(), path.display().to_string().cyan());
+    let import = lockimport::import(&project, &config, &path, format)?;
+    if let Some(policy) = Policy::load()? {
+        policy.enforce(policy.check_lock(&import.lockfile))?;
+    }
+    import.lockfile.write(&project)?;
+    println!(
+        "{} Wrote {} with {} package(s) from {}",
+        "✅".green(),
+        LOCKFILE.cyan(),
+        import.lockfile.packages.len(),
+        format
+    );
+
+    if !import.skipped.is_empty() {
+        println!("{} Skipped {} package(s) that can't be imported:", "⚠️".yellow(), import.skipped.len());
+        for skipped in &import.skipped {
+            let name = format!("{} {}", skipped.name, skipped.version);
+            println!("   {} {}: {}", "•".bright_black(), name.trim_end(), skipped.reason);
+        }
+    }
+    if !import.unsatisfied.is_empty() {
+        println!("{} The imported pins don't satisfy:", "⚠️".yellow());
+        for requirement in &import.unsatisfied {
+            println!("   {} {}", "•".bright_black(), requirement);
+        }
+        println!("   The next {} or {} resolves again", "pyforge lock".cyan(), "pyforge sync".cyan());
+    }
+    Ok(())
+}
+
+/// Write the lock in the PEP 751 format
+pub fn export(output: Option<PathBuf>, locked: bool, frozen_toolchain: bool) -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let flags = LockFlags {
+        locked,
+        frozen_toolchain,
+        ..Default::default()
+    };
+    let lockfile = ensure(&project, &config, &flags)?;
+
+    let output = output.unwrap_or_else(|| project.root.join(PYLOCK));
+    fs::write(&output, Pylock::from_lockfile(&lockfile).render()?)
+        .map_err(|e| PyForgeError::file_error(format!("Could not write {}", output.display()), e))?;
+    println!(
+        "{} Exported {} package(s) to {}",
+        "✅".green(),
+        lockfile.packages.len(),
+        output.display().to_string().cyan()
+    );
+    println!(
+        "   Locked for Python {} on {} only, like {}",
+        lockfile.environment.python_version, lockfile.environment.sys_platform, LOCKFILE
+    );
+    Ok(())
+}
+
+/// The project's lockfile, honoring `--locked` / `--frozen`: re-locks a missing
+/// or outdated lockfile unless either flag forbids it
+pub fn ensure(project: &Project, config: &Config, flags: &LockFlags) -> Result<Lockfile> {
+    let existing = Lockfile::load(project)?;
+    let lockfile = match existing {
+        Some(lockfile) if lockfile.is_fresh(project) => lockfile,
+        Some(_) if flags.locked || flags.frozen => {
+            return Err(PyForgeError::LockfileOutdated {
+                path: Lockfile::path(project).display().to_string(),
+            });
+        }
+        None if flags.locked || flags.frozen => {
+            return Err(PyForgeError::LockfileNotFound {
+                path: Lockfile::path(project).display().to_string(),
+            });
+        }
+        Some(_) | None => {
+            let lockfile = lock(project, config, flags.frozen_toolchain, None)?;
+            println!("{} Updated {}", "🔒".green(), LOCKFILE.cyan());
+            return Ok(lockfile);
+        }
+    };
+
+    toolchain::check(&lockfile, flags.frozen_toolchain || config.frozen_toolchain)?;
+    Ok(lockfile)
+}
+
+/// Check the toolchain when frozen, then re-lock, printing the resolver's
+/// decisions about `explain`. The `locked` strategy keeps the previous pins.
+pub fn lock(project: &Project, config: &Config, frozen_toolchain: bool, explain: Option<&str>) -> Result<Lockfile> {
+    let previous = Lockfile::load(project)?;
+    // A frozen toolchain must not be replaced by re-locking with different tools
+    if let Some(previous) = &previous
+        && (frozen_toolchain || config.frozen_toolchain)
+    {
+        toolchain::check(previous, true)?;
+    }
+    let preferred = match previous {
+        Some(previous) if config.resolver.strategy == ResolutionStrategy::Locked => previous.packages,
+        _ => Vec::new(),
+    };
+    write_lock(project, config, &preferred, explain)
+}
+
+/// Resolve preferring the `preferred` pins, check against the policy and write the lockfile
+pub fn write_lock(project: &Project, config: &Config, preferred: &[LockedPackage], explain: Option<&str>) -> Result<Lockfile> {
+    let policy = Policy::load()?;
+    if let Some(policy) = &policy {
+        let violations = config
+            .indexes()
+            .iter()
+            .flat_map(|index| index.urls().filter_map(|url| policy.check_index(url)).collect::<Vec<_>>())
+            .collect();
+        policy.enforce(violations)?;
+    }
+
+    let lockfile = resolve(project, config, preferred, explain)?;
+
+    if let Some(policy) = &policy {
+        policy.enforce(policy.check_lock(&lockfile))?;
+    }
+
+    lockfile.write(project)?;
+    Ok(lockfile)
+}
+
+/// Resolve the project's dependencies (including every optional group) for the current
+/// interpreter, keeping the versions of `preferred` packages where the constraints allow
+pub fn resolve(project: &Project, config: &Config, preferred: &[LockedPackage], explain: Option<&str>) -> Result<Lockfile> {
+    resolve_requirements(project, config, lockfile::requirements(project)?, preferred, explain)
+}
+
+/// Like [`resolve`], for `requirements` instead of the project's own
+pub fn resolve_requirements(
+    project: &Project,
+    config: &Config,
+    requirements: Vec<Requirement>,
+    preferred: &[LockedPackage],
+    explain: Option<&str>,
+) -> Result<Lockfile> {
+    let environment = Environment::current()?;
+    if let Some(requires_python) = project.requires_python()
+        && let Ok(specifier) = requires_python.parse::<SpecifierSet>()
+        && let Ok(python) = Version::parse(&environment.python_full_version)
+        && !specifier.contains(&python, true)
+    {
+        return Err(PyForgeError::UnsupportedPythonVersion {
+            version: format!("{} (project requires {})", environment.python_full_version, requires_python),
+        });
+    }
+
+    println!(
+        "{} Resolving {} requirement(s) for Python {} on {}",
+        "🔒".green(),
+        requirements.len(),
+        environment.python_version,
+        environment.sys_platform
+    );
+    let mut resolver = Resolver::new(config, &environment)?;
+    resolver.set_find_links(config.install.find_links.iter().map(|dir| project.root.join(dir)).collect());
+    if let Some(cutoff) = &config.resolver.exclude_newer {
+        println!("   {} Ignoring files uploaded from {} on", "•".bright_black(), cutoff);
+    }
+    resolver.set_members(workspace::members(project)?);
+    for package in preferred {
+        if let Ok(version) = Version::parse(&package.version) {
+            resolver.prefer(&package.name, version);
+        }
+    }
+    if let Some(name) = explain {
+        resolver.explain(name);
+    }
+    // Print the trace before a failure, since that is when it is needed most
+    let packages = resolver.resolve(&requirements, &|name| log::debug!("resolving {}", name));
+    if let Some(name) = explain {
+        print_trace(name, resolver.trace());
+    }
+    let packages = packages?;
+
+    let build_requirements = project
+        .build_requires()
+        .iter()
+        .map(|requirement| Requirement::parse(requirement))
+        .collect::<Result<Vec<_>>>()?;
+    let mut toolchain = Toolchain::current(&environment);
+    toolchain.build_requires = resolver
+        .resolve(&build_requirements, &|name| log::debug!("resolving build requirement {}", name))?
+        .into_iter()
+        .map(|package| format!("{}=={}", package.name, package.version))
+        .collect();
+
+    Ok(Lockfile {
+        version: LOCKFILE_VERSION,
+        requires_python: project.requires_python().map(str::to_string),
+        input_hash: lockfile::input_hash(project),
+        environment,
+        toolchain: Some(toolchain),
+        packages,
+        migrated_from: None,
+    })
+}
+
+fn print_trace(name: &str, trace: &[String]) {
+    println!("{} Why {} resolved the way it did:", "🔍".cyan(), name.cyan());
+    if trace.is_empty() {
+        println!("   Nothing requires {} in this environment", name);
+    }
+    for (step, decision) in trace.iter().enumerate() {
+        println!("   {} {}", format!("{:>3}.", step + 1).bright_black(), decision);
+    }
+}
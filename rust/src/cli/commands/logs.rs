@@ -0,0 +1,76 @@
+use crate::cli::args::LogsCommand;
+use crate::core::capture;
+use crate::core::distdiff;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::project::Project;
+use crate::core::utils::file_name;
+use colored::*;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+pub fn run(command: LogsCommand) -> Result<()> {
+    match command {
+        LogsCommand::List => list(),
+        LogsCommand::Show { log, .. } => show(log.as_deref()),
+    }
+}
+
+fn list() -> Result<()> {
+    let project = Project::require()?;
+    let logs = capture::logs(&project)?;
+    if logs.is_empty() {
+        println!("{} No captured logs; pass --capture-logs or set [tool.pyforge.logs] capture = true", "📭".cyan());
+        return Ok(());
+    }
+    for path in &logs {
+        let content = read(path)?;
+        let size = distdiff::size(content.len() as u64);
+        match outcome(&content) {
+            Some(outcome) if outcome.starts_with("exit 0 ") => {
+                println!("{} {} ({}, {})", "✅".green(), file_name(path), size, outcome)
+            }
+            Some(outcome) => println!("{} {} ({}, {})", "❌".red(), file_name(path), size, outcome),
+            None => println!("{} {} ({}, unfinished)", "⏳".yellow(), file_name(path), size),
+        }
+    }
+    Ok(())
+}
+
+fn show(log: Option<&str>) -> Result<()> {
+    let project = Project::require()?;
+    let logs = capture::logs(&project)?;
+    let found = match log {
+        None => logs.last(),
+        Some(log) => logs.iter().rev().find(|path| {
+            let name = file_name(path);
+            name == log || name.strip_suffix(".log") == Some(log) || kind(&name) == Some(log)
+        }),
+    };
+    let Some(path) = found else {
+        return Err(PyForgeError::Usage {
+            message: match log {
+                Some(log) => format!("No captured log '{}'; see pyforge logs list", log),
+                None => format!("No captured logs in {}", capture::log_dir(&project).display()),
+            },
+        });
+    };
+    println!("{} {}", "📄".cyan(), path.strip_prefix(&project.root).unwrap_or(path).display());
+    std::io::stdout().write_all(read(path)?.as_bytes())?;
+    Ok(())
+}
+
+/// The final `exit 1 after 2.0s` line of a finished log
+fn outcome(content: &str) -> Option<&str> {
+    content.lines().next_back()?.strip_prefix("# ").filter(|line| line.contains(" after "))
+}
+
+/// `test` in `20261015T120000.123Z-test.log`
+fn kind(name: &str) -> Option<&str> {
+    name.strip_suffix(".log")?.split_once('-').map(|(_, kind)| kind)
+}
+
+fn read(path: &Path) -> Result<String> {
+    let bytes = fs::read(path).map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
@@ -1,13 +1,138 @@
 
 pub mod init;
+pub mod add;
+pub mod remove;
+pub mod show;
 pub mod build;
+pub mod doctor;
+pub mod debug;
+pub mod ui;
+pub mod dev;
+pub mod help;
+pub mod generate_man;
+pub mod policy;
+pub mod lock;
+pub mod vendor;
+pub mod sync;
+pub mod install;
+pub mod setup;
+pub mod upgrade_self;
+pub mod verify;
+pub mod template;
+pub mod logs;
+pub mod queue;
+pub mod python;
+pub mod workon;
+pub mod bump;
+pub mod pin;
+pub mod bisect;
+pub mod report;
+pub mod publish;
+pub mod auth;
+pub mod test;
+pub mod devcontainer;
+pub mod editor;
+pub mod env;
+pub mod task;
+pub mod version;
+pub mod feature;
+pub mod yank;
+pub mod deprecate;
+pub mod owner;
+pub mod internal;
+pub mod check;
+pub mod trust;
+pub mod config;
 
-use crate::cli::args::Commands;
+use crate::cli::args::{BisectCommand, Commands, LockCommand, TaskCommand};
+use crate::core::build::Distribution;
 use crate::core::error::PyForgeError;
 
 pub fn execute_command(command: Commands) -> Result<(), PyForgeError> {
     match command {
-        Commands::Init { name, template } => init::run(&name, &template),
-        Commands::Build => build::run(),
+        Commands::Init { name, template, chmod, vars, preset, org, python } => {
+            init::run(&name, &template, &chmod, &vars, preset.as_deref(), &org, python.as_deref())
+        }
+        Commands::Add { requirements, optional, dev, groups, .. } => {
+            add::run(&requirements, optional.as_deref().or(dev.then_some("dev")), &groups)
+        }
+        Commands::Remove { packages, optional, dev } => remove::run(&packages, optional.as_deref().or(dev.then_some("dev"))),
+        Commands::Show { package } => show::run(&package),
+        Commands::Build { lock, allow_network, no_cache, diff_last, strict, sdist, wheel, out_dir } => {
+            build::run(&lock, allow_network, no_cache, diff_last, strict, Distribution::selected(sdist, wheel), out_dir.as_deref())
+        }
+        Commands::Doctor { network } => doctor::run(network),
+        Commands::Policy(command) => policy::run(command),
+        Commands::Owner(command) => owner::run(command),
+        Commands::Version { resolved } => version::run(resolved),
+        Commands::Task(TaskCommand::Run { name, on, isolation, lock, args }) => {
+            task::run(&name, &on, &isolation, &lock, &args)
+        }
+        Commands::Template(command) => template::run(command),
+        Commands::Logs(command) => logs::run(command),
+        Commands::Queue(command) => queue::run(command),
+        Commands::Auth(command) => auth::run(command),
+        Commands::Feature(command) => feature::run(command),
+        Commands::Devcontainer(command) => devcontainer::run(command),
+        Commands::Editor(command) => editor::run(command),
+        Commands::Env(command) => env::run(command),
+        Commands::Python(command) => python::run(command),
+        Commands::Workon { query, shell, add, forget } => workon::run(query.as_deref(), shell, add.as_deref(), forget.as_deref()),
+        Commands::Publish { github_release, remote, lock, allow_network } => {
+            publish::run(&github_release, &remote, &lock, allow_network)
+        }
+        Commands::Yank { version, reason } => yank::run(&version, reason.as_deref()),
+        Commands::Deprecate { message, successor, yes, lock } => {
+            deprecate::run(&message, successor.as_deref(), yes, &lock)
+        }
+        Commands::Debug(command) => debug::run(command),
+        Commands::Internal(command) => internal::run(command),
+        Commands::Setup { defaults } => setup::run(defaults),
+        Commands::Config(command) => config::run(command),
+        Commands::UpgradeSelf { channel, version, allow_downgrade, check } => {
+            upgrade_self::run(channel, version.as_deref(), allow_downgrade, check)
+        }
+        Commands::Install { lock, sync } => install::run(&lock, &sync),
+        Commands::Sync { lock, sync } => sync::run(&lock, &sync),
+        Commands::Check(command) => check::run(command),
+        Commands::Trust { revoke, list } => trust::run(revoke, list),
+        Commands::Verify { jobs } => verify::run(jobs),
+        Commands::Test {
+            in_docker,
+            profile,
+            no_cache,
+            reports,
+            lock,
+            args,
+        } => test::run(in_docker, profile.as_deref(), no_cache, &reports, &lock, &args),
+        Commands::Bump {
+            interactive,
+            create_pr,
+            no_test,
+            changelog,
+            remote,
+        } => bump::run(interactive, create_pr, no_test, changelog, &remote),
+        Commands::Pin { compatible } => pin::run(compatible),
+        Commands::Report { no_test, html, ci } => report::run(no_test, html.as_deref(), ci),
+        Commands::Bisect(command) => match command {
+            BisectCommand::Versions { package, good, bad, command } => {
+                bisect::versions(&package, &good, bad.as_deref(), &command)
+            }
+            BisectCommand::Lock { good, bad, command } => bisect::lock(&good, &bad, &command),
+        },
+        Commands::Lock { frozen_toolchain, explain, exclude_newer, find_links, command } => match command {
+            None => lock::run(frozen_toolchain, explain.as_deref(), exclude_newer, &find_links),
+            Some(LockCommand::Merge) => lock::merge(frozen_toolchain),
+            Some(LockCommand::Migrate) => lock::migrate(),
+            Some(LockCommand::Import { path }) => lock::import(path),
+            Some(LockCommand::Export { output, locked }) => lock::export(output, locked, frozen_toolchain),
+        },
+        Commands::Vendor { dir, inline, frozen_toolchain, jobs } => {
+            vendor::run(dir.as_deref(), inline, frozen_toolchain, jobs)
+        }
+        Commands::Ui => ui::run(),
+        Commands::Dev => dev::run(),
+        Commands::Help { topic } => help::run(topic),
+        Commands::GenerateMan { out_dir } => generate_man::run(out_dir.as_deref()),
     }
 }
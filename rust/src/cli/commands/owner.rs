@@ -0,0 +1,82 @@
+use crate::cli::args::{OwnerCommand, OwnerRole};
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::project::Project;
+use crate::core::pypi::{self, Role};
+use colored::*;
+
+pub fn run(command: OwnerCommand) -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let name = project
+        .name()
+        .ok_or_else(|| PyForgeError::Usage {
+            message: "[project] has no name".to_string(),
+        })?
+        .to_string();
+    let roles = pypi::package_roles(&config, &name)?;
+
+    match command {
+        OwnerCommand::List => list(&name, &roles),
+        OwnerCommand::Add { user, role } => add(&config, &name, &roles, &user, role),
+        OwnerCommand::Remove { user } => remove(&config, &name, &roles, &user),
+    }
+}
+
+fn list(name: &str, roles: &[Role]) -> Result<()> {
+    if roles.is_empty() {
+        println!("{} {} has no collaborators on the index (is it published?)", "ℹ️".blue(), name);
+        return Ok(());
+    }
+    println!("{} Collaborators of {}:", "👥".cyan(), name.cyan());
+    let width = roles.iter().map(|r| r.role.len()).max().unwrap_or(0);
+    for role in roles {
+        println!("   {:width$}  {}", role.role, role.user.bold(), width = width);
+    }
+    Ok(())
+}
+
+fn add(config: &Config, name: &str, roles: &[Role], user: &str, role: OwnerRole) -> Result<()> {
+    let role = role.to_string();
+    if let Some(existing) = find(roles, user) {
+        if existing.role == role {
+            println!("{} {} is already {} of {}", "ℹ️".blue(), user, role, name);
+            return Ok(());
+        }
+        println!("{} {} is {} of {}; remove them first to change the role", "⚠️".yellow(), user, existing.role, name);
+    }
+    guide(config, name, &format!("invite {} as {}", user.bold(), role.bold()));
+    println!("   {} receives an email and becomes a collaborator after accepting.", user);
+    Ok(())
+}
+
+fn remove(config: &Config, name: &str, roles: &[Role], user: &str) -> Result<()> {
+    let Some(existing) = find(roles, user) else {
+        return Err(PyForgeError::Usage {
+            message: format!("{} is not a collaborator of {}", user, name),
+        });
+    };
+    let owners = roles.iter().filter(|r| r.role == "Owner").count();
+    if existing.role == "Owner" && owners == 1 {
+        return Err(PyForgeError::Usage {
+            message: format!("{} is the only owner of {}; add another owner first", user, name),
+        });
+    }
+    guide(config, name, &format!("remove {} ({})", user.bold(), existing.role));
+    Ok(())
+}
+
+/// Index user names are case-insensitive
+fn find<'a>(roles: &'a [Role], user: &str) -> Option<&'a Role> {
+    roles.iter().find(|r| r.user.eq_ignore_ascii_case(user))
+}
+
+/// The index API cannot change roles; point at the page that can
+fn guide(config: &Config, name: &str, action: &str) {
+    println!(
+        "{} The index only lets owners change collaborators on its website. Open\n   {}\n   and {}.",
+        "👉".yellow(),
+        pypi::manage_collaboration_url(config, name).cyan(),
+        action
+    );
+}
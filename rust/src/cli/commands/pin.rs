@@ -0,0 +1,74 @@
+use crate::cli::args::LockFlags;
+use crate::cli::commands::lock;
+use crate::core::config::Config;
+use crate::core::error::Result;
+use crate::core::lockfile::{self, LOCKFILE, Lockfile};
+use crate::core::manifest;
+use crate::core::pep440::{SpecifierSet, Version};
+use crate::core::pep508::Requirement;
+use crate::core::project::Project;
+use colored::*;
+use std::fs;
+
+pub fn run(compatible: bool) -> Result<()> {
+    let mut project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let lockfile = lock::ensure(&project, &config, &LockFlags::default())?;
+
+    for requirement in lockfile::requirements(&project)? {
+        if requirement.url.is_none() && lockfile.package(&requirement.name).is_none() {
+            println!(
+                "{} {} is not locked for this platform; left as {}",
+                "ℹ️".cyan(),
+                requirement.name.cyan(),
+                requirement
+            );
+        }
+    }
+
+    let pyproject = project.pyproject_path();
+    let original = fs::read_to_string(&pyproject)?;
+    let mut pinned = Vec::new();
+    project.update_pyproject(|document| {
+        pinned = manifest::rewrite_dependencies(document, |requirement| pin(requirement, &lockfile, compatible));
+    })?;
+    if pinned.is_empty() {
+        println!("{} Every locked dependency is already pinned", "✅".green());
+        return Ok(());
+    }
+
+    // The pins are the locked versions, so re-locking only records the new input hash
+    if let Err(error) = lock::write_lock(&project, &config, &lockfile.packages, None) {
+        fs::write(&pyproject, original)?;
+        return Err(error);
+    }
+    println!("{} Pinned {} dependencies to the versions in {}", "📌".green(), pinned.len(), LOCKFILE.cyan());
+    for entry in pinned {
+        println!("   {} {}", "•".bright_black(), entry);
+    }
+    Ok(())
+}
+
+/// `requirement` constrained to its locked version, or `None` when it can't
+/// be pinned or already is
+fn pin(requirement: &Requirement, lockfile: &Lockfile, compatible: bool) -> Option<Requirement> {
+    if requirement.url.is_some() {
+        return None;
+    }
+    let locked = lockfile.package(&requirement.name).filter(|package| package.path.is_none())?;
+    let specifier: SpecifierSet = if compatible {
+        // `~=` needs at least two release segments
+        let version = Version::parse(&locked.version).ok()?;
+        let padded = if version.release.len() < 2 { format!("{}.0", locked.version) } else { locked.version.clone() };
+        format!("~={}", padded).parse().ok()?
+    } else {
+        format!("=={}", locked.version).parse().ok()?
+    };
+    if specifier.to_string() == requirement.specifier.to_string() {
+        return None;
+    }
+    Some(Requirement {
+        specifier,
+        ..requirement.clone()
+    })
+}
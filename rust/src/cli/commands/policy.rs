@@ -0,0 +1,48 @@
+use crate::cli::args::PolicyCommand;
+use crate::core::config::Config;
+use crate::core::error::Result;
+use crate::core::policy::Policy;
+use crate::core::project::Project;
+use colored::*;
+
+pub fn run(command: PolicyCommand) -> Result<()> {
+    let Some(policy) = Policy::load()? else {
+        println!("{} No organization policy is configured", "ℹ️".blue());
+        println!("   See 'pyforge help policy' to set one up.");
+        return Ok(());
+    };
+
+    match command {
+        PolicyCommand::Show => show(&policy),
+        PolicyCommand::Check => check(&policy),
+    }
+}
+
+fn show(policy: &Policy) -> Result<()> {
+    println!("{} {}", "📜".green(), policy.name.bold());
+    println!("   source: {}", policy.source.cyan());
+
+    let rules: [(&str, Vec<String>); 4] = [
+        ("allowed indexes", policy.allowed_indexes.clone()),
+        ("banned packages", policy.banned_packages.clone()),
+        ("requires-python", policy.requires_python.iter().cloned().collect()),
+        ("allowed licenses", policy.allowed_licenses.clone()),
+    ];
+    for (label, values) in rules {
+        if values.is_empty() {
+            println!("   {}: {}", label, "any".bright_black());
+        } else {
+            println!("   {}: {}", label, values.join(", "));
+        }
+    }
+    Ok(())
+}
+
+fn check(policy: &Policy) -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+
+    policy.enforce(policy.check_project(&project, &config))?;
+    println!("{} Project complies with {}", "✅".green(), policy.name.bold());
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use crate::cli::args::LockFlags;
+use crate::cli::commands::build;
+use crate::core::build::Distribution;
+use crate::core::config::{Config, ForgeKind};
+use crate::core::error::{PyForgeError, Result};
+use crate::core::forge::Forge;
+use crate::core::git;
+use crate::core::lockfile::Lockfile;
+use crate::core::pep440::Version;
+use crate::core::policy::Policy;
+use crate::core::project::Project;
+use crate::core::versioning;
+use colored::*;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Checksum file attached next to the distributions
+const CHECKSUMS_FILE: &str = "SHA256SUMS";
+
+pub fn run(tag: &str, remote: &str, flags: &LockFlags, allow_network: bool) -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let usage = |message: String| PyForgeError::Usage { message };
+
+    let version = versioning::resolve(&project, &config)?;
+    if tag.strip_prefix('v').unwrap_or(tag) != version {
+        return Err(usage(format!("Tag {} does not match the project version {}", tag, version)));
+    }
+
+    if let Some(policy) = Policy::load()? {
+        let mut violations = policy.check_project(&project, &config);
+        if let Some(lockfile) = Lockfile::load(&project)? {
+            violations.extend(policy.check_lock(&lockfile));
+        }
+        policy.enforce(violations)?;
+    }
+
+    // Resolve the repository and token before spending time on the build
+    let url = git::remote_url(&project.root, remote)
+        .ok_or_else(|| usage(format!("git remote '{}' is not configured", remote)))?;
+    let forge = Forge::from_remote(&url, &config)?;
+    if forge.kind != ForgeKind::Github {
+        return Err(usage(format!("Remote '{}' ({}) is not a GitHub repository", remote, url)));
+    }
+
+    let mut artifacts = build::build(&project, &config, flags, allow_network, false, Distribution::ALL, &project.root.join("dist"))?;
+    let (checksums, listing) = write_checksums(&project.root.join("dist"), &artifacts)?;
+    artifacts.push(checksums);
+
+    let prerelease = Version::parse(&version).is_ok_and(|v| v.is_prerelease());
+    let notes = format!(
+        "{} {}\n\nSHA-256 checksums:\n\n```\n{}```\n",
+        project.name().unwrap_or("project"),
+        version,
+        listing
+    );
+    println!("{} Creating GitHub release {}", "🚀".cyan(), tag.cyan());
+    let release = forge.create_release(tag, &notes, prerelease)?;
+    for path in &artifacts {
+        forge.upload_asset(&release, path)?;
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        println!("   {} {}", "↑".green(), name);
+    }
+
+    println!("{} Published {}: {}", "✅".green(), tag, release.html_url.cyan());
+    Ok(())
+}
+
+/// Write `SHA256SUMS` for `artifacts` into `dir`, in `sha256sum` format
+fn write_checksums(dir: &Path, artifacts: &[PathBuf]) -> Result<(PathBuf, String)> {
+    let mut listing = String::new();
+    for path in artifacts {
+        let content =
+            fs::read(path).map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        listing.push_str(&format!("{:x}  {}\n", Sha256::digest(&content), name));
+    }
+    let path = dir.join(CHECKSUMS_FILE);
+    fs::write(&path, &listing).map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))?;
+    Ok((path, listing))
+}
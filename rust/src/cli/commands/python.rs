@@ -0,0 +1,91 @@
+use crate::cli::args::PythonCommand;
+use crate::core::config::Config;
+use crate::core::error::Result;
+use crate::core::pep440::{SpecifierSet, Version};
+use crate::core::project::Project;
+use crate::core::python::{self, PinSource};
+use crate::core::venv::Venv;
+use colored::*;
+use std::fs;
+
+pub fn run(command: PythonCommand) -> Result<()> {
+    match command {
+        PythonCommand::List => list(),
+        PythonCommand::Pin { version } => pin(&version),
+    }
+}
+
+fn list() -> Result<()> {
+    let project = Project::discover()?;
+    let config = Config::load(project.as_ref())?;
+    let pin = python::pin(project.as_ref(), config.python.as_deref());
+    let selected = python::find(pin.as_ref().map(|(request, _)| request)).ok();
+    let canonical = selected.as_ref().and_then(|path| fs::canonicalize(path).ok());
+
+    let found = python::discover();
+    if found.is_empty() {
+        println!("{} No Python interpreters found", "📭".cyan());
+    }
+    for interpreter in found {
+        let version = format!("{:<10}", interpreter.version.to_string());
+        if canonical.is_some() && fs::canonicalize(&interpreter.path).ok() == canonical {
+            println!("{} {} {}", "▶".green(), version.green(), interpreter.path.display().to_string().green());
+        } else {
+            println!("  {} {}", version, interpreter.path.display());
+        }
+    }
+    match pin {
+        Some((request, source)) => println!("{} Pinned to {} by {}", "📌".cyan(), request, source),
+        None => match &selected {
+            Some(path) => println!("{} No pin; using {} from PATH", "•".bright_black(), path.display()),
+            None => println!("{} No pin, and no python3 on PATH", "⚠️".yellow()),
+        },
+    }
+    Ok(())
+}
+
+fn pin(version: &str) -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let interpreter = python::installed(version)?;
+
+    if let Some(requires_python) = project.requires_python()
+        && let Ok(specifiers) = requires_python.parse::<SpecifierSet>()
+        && !specifiers.contains(&interpreter.version, true)
+    {
+        println!(
+            "{} Python {} doesn't satisfy requires-python {}",
+            "⚠️".yellow(),
+            interpreter.version,
+            requires_python.yellow()
+        );
+    }
+
+    let path = python::write_pin(&project.root, version)?;
+    println!(
+        "{} Pinned Python {} in {} ({})",
+        "📌".green(),
+        version.cyan(),
+        python::VERSION_FILE,
+        interpreter.path.display()
+    );
+    if let Some((_, PinSource::Pyproject)) = python::pin(Some(&project), None) {
+        println!(
+            "{} [tool.pyforge] python in pyproject.toml takes precedence over {}",
+            "⚠️".yellow(),
+            path.display()
+        );
+    }
+    if let Some(venv) = Venv::open(&project, &config)
+        && let Some(current) = venv.python_version()
+        && Version::parse(&current).is_ok_and(|current| !python::version_matches(&current, version))
+    {
+        println!(
+            "   {} the environment has Python {}; run {} to recreate it",
+            "•".bright_black(),
+            current,
+            "pyforge env create --force".cyan()
+        );
+    }
+    Ok(())
+}
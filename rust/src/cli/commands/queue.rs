@@ -0,0 +1,136 @@
+use crate::cli::Cli;
+use crate::cli::args::QueueCommand;
+use crate::cli::commands::trust;
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::project::Project;
+use crate::core::queue::{self, Job, State};
+use clap::Parser;
+use colored::*;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::thread;
+use std::time::Duration;
+
+pub fn run(command: QueueCommand) -> Result<()> {
+    match command {
+        QueueCommand::Run(args) => enqueue(&args),
+        QueueCommand::Status => status(),
+        QueueCommand::Logs { id, follow } => logs(id, follow),
+        QueueCommand::Clear => {
+            let project = Project::require()?;
+            let removed = queue::clear(&project)?;
+            println!("{} Removed {} finished job(s)", "🧹".cyan(), removed);
+            Ok(())
+        }
+        QueueCommand::Work => {
+            let project = Project::require()?;
+            let config = Config::load(Some(&project))?;
+            queue::work(&project, config.queue.jobs)
+        }
+    }
+}
+
+fn enqueue(args: &[String]) -> Result<()> {
+    if matches!(args[0].as_str(), "q" | "queue") {
+        return Err(PyForgeError::Usage {
+            message: "The queue can't queue itself".to_string(),
+        });
+    }
+    // Fail now on a typo, instead of in the background
+    if let Err(error) = Cli::try_parse_from(std::iter::once("pyforge".to_string()).chain(args.iter().cloned())) {
+        let message = error.to_string();
+        return Err(PyForgeError::Usage {
+            message: format!("Can't queue 'pyforge {}': {}", args.join(" "), message.trim().trim_start_matches("error: ")),
+        });
+    }
+    let project = Project::require()?;
+    // Jobs can't answer the trust prompt
+    trust::require(&project)?;
+
+    let job = queue::enqueue(&project, args, &std::env::current_dir()?)?;
+    let ahead = queue::jobs(&project)?
+        .iter()
+        .filter(|other| other.id < job.id && matches!(other.state(), State::Queued | State::Running))
+        .count();
+    println!("{} Queued job {}: {} ({} ahead)", "📥".cyan(), job.id, job.command().cyan(), ahead);
+    println!("   {} follow it with {}", "•".bright_black(), format!("pyforge q logs {} -f", job.id).cyan());
+    Ok(())
+}
+
+fn status() -> Result<()> {
+    let project = Project::require()?;
+    let jobs = queue::jobs(&project)?;
+    if jobs.is_empty() {
+        println!("{} The queue is empty", "📭".cyan());
+        return Ok(());
+    }
+    let worker = queue::worker_running(&project)?;
+    for job in &jobs {
+        let state = match job.state() {
+            // Its worker is gone, so it won't finish
+            State::Running if !worker => State::Interrupted,
+            state => state,
+        };
+        let seconds = job.seconds.map(|seconds| format!(", {:.1}s", seconds)).unwrap_or_default();
+        match state {
+            State::Queued => println!("{} {:>3} {} {}", "⏳".cyan(), job.id, job.command(), "(queued)".bright_black()),
+            State::Running => println!("{} {:>3} {} {}", "▶️".cyan(), job.id, job.command().cyan(), "(running)".bright_black()),
+            State::Done(0) => println!("{} {:>3} {} {}", "✅".green(), job.id, job.command(), format!("(exit 0{})", seconds).bright_black()),
+            State::Done(code) => println!("{} {:>3} {} {}", "❌".red(), job.id, job.command(), format!("(exit {}{})", code, seconds).red()),
+            State::Interrupted => println!("{} {:>3} {} {}", "⚠️".yellow(), job.id, job.command(), "(interrupted)".yellow()),
+        }
+    }
+    let waiting = jobs.iter().any(|job| job.state() == State::Queued);
+    if waiting && !worker {
+        println!("{} No worker is running; queue another command to start one", "⚠️".yellow());
+    }
+    Ok(())
+}
+
+fn logs(id: Option<u64>, follow: bool) -> Result<()> {
+    let project = Project::require()?;
+    let jobs = queue::jobs(&project)?;
+    let job = match id {
+        Some(id) => jobs.into_iter().find(|job| job.id == id).ok_or_else(|| PyForgeError::Usage {
+            message: format!("No job {}; see pyforge q status", id),
+        })?,
+        None => jobs.into_iter().rev().find(|job| job.started.is_some()).ok_or_else(|| PyForgeError::Usage {
+            message: "No job has started yet; see pyforge q status".to_string(),
+        })?,
+    };
+    println!("{} Job {}: {}", "📄".cyan(), job.id, job.command().cyan());
+
+    let path = queue::log_path(&project, job.id);
+    let mut offset = 0;
+    loop {
+        let finished = !follow || !matches!(current(&project, &job)?.state(), State::Queued | State::Running);
+        // Read after checking, so output written just before the job finished is printed
+        if let Ok(mut file) = File::open(&path) {
+            file.seek(SeekFrom::Start(offset))?;
+            let mut chunk = Vec::new();
+            file.read_to_end(&mut chunk)?;
+            offset += chunk.len() as u64;
+            std::io::stdout().write_all(&chunk)?;
+            std::io::stdout().flush()?;
+        }
+        if finished {
+            break;
+        }
+        thread::sleep(Duration::from_millis(200));
+    }
+
+    let job = current(&project, &job)?;
+    match job.state() {
+        State::Done(0) => println!("{} Job {} finished", "✅".green(), job.id),
+        State::Done(code) => println!("{} Job {} failed (exit {})", "❌".red(), job.id, code),
+        State::Interrupted => println!("{} Job {} was interrupted", "⚠️".yellow(), job.id),
+        State::Queued | State::Running => {}
+    }
+    Ok(())
+}
+
+/// `job` as last written by the worker
+fn current(project: &Project, job: &Job) -> Result<Job> {
+    Ok(queue::jobs(project)?.into_iter().find(|other| other.id == job.id).unwrap_or_else(|| job.clone()))
+}
@@ -0,0 +1,59 @@
+use crate::cli::args::LockFlags;
+use crate::cli::commands::add::describe;
+use crate::cli::commands::lock;
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::manifest;
+use crate::core::pep508::Requirement;
+use crate::core::project::Project;
+use colored::*;
+use std::fs;
+
+pub fn run(packages: &[String], group: Option<&str>) -> Result<()> {
+    let mut project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    // Accept requirements too, so `pyforge remove "httpx[http2]>=0.27"` works after an add
+    let names = packages
+        .iter()
+        .map(|package| Requirement::parse(package).map(|requirement| requirement.name))
+        .collect::<Result<Vec<_>>>()?;
+
+    let pyproject = project.pyproject_path();
+    let original = fs::read_to_string(&pyproject)?;
+    let mut removed = Vec::new();
+    let mut missing = Vec::new();
+    project.try_update_pyproject(|document| {
+        for name in &names {
+            match manifest::remove_dependency(document, name, group)? {
+                Some(entry) => removed.push(entry),
+                None => missing.push((name.clone(), manifest::declared_in(document, name))),
+            }
+        }
+        Ok(())
+    })?;
+
+    if let Some((name, declared)) = missing.first() {
+        fs::write(&pyproject, &original)?;
+        let hint = match declared.as_slice() {
+            [] => String::new(),
+            lists => format!(
+                "; it is in {}",
+                lists.iter().map(|group| describe(group.as_deref())).collect::<Vec<_>>().join(" and ")
+            ),
+        };
+        return Err(PyForgeError::Usage {
+            message: format!("'{}' is not in {}{}", name, describe(group), hint),
+        });
+    }
+
+    // Undo the edit if locking fails so pyproject.toml and the lockfile stay consistent
+    if let Err(error) = lock::ensure(&project, &config, &LockFlags::default()) {
+        fs::write(&pyproject, original)?;
+        return Err(error);
+    }
+
+    for entry in &removed {
+        println!("{} Removed {} from {}", "✅".green(), entry.cyan(), describe(group));
+    }
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use crate::cli::args::{LockFlags, SyncFlags};
+use crate::cli::commands::{lock, sync, trust};
+use crate::core::ci::{self, Annotation, Level};
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::health::{self, Check, Kind, Report, Status};
+use crate::core::htmlreport;
+use crate::core::lockfile::Lockfile;
+use crate::core::pep440::Version;
+use crate::core::process::{self, shell_quote};
+use crate::core::project::Project;
+use crate::core::venv::Venv;
+use colored::*;
+use std::fs;
+use std::path::Path;
+
+/// Details listed under a check in the terminal; the HTML report has them all
+const SHOWN_DETAILS: usize = 5;
+
+/// Output lines of a failed test run shown with the report
+const FAILURE_LINES: usize = 5;
+
+pub fn run(no_test: bool, html: Option<&Path>, ci: bool) -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let lockfile = lock::ensure(&project, &config, &LockFlags::default())?;
+    let venv = Venv::ensure(&project, &config)?;
+    sync::sync_into(&project, &config, &venv, &lockfile, &LockFlags::default(), &SyncFlags::default())?;
+
+    let mut checks = Vec::new();
+    if no_test {
+        checks.push(Check::skipped(Kind::Tests, "--no-test"));
+        checks.push(Check::skipped(Kind::Coverage, "--no-test"));
+    } else {
+        checks.extend(tests(&project, &config, &venv, &lockfile)?);
+    }
+    checks.push(vulnerabilities(&config, &lockfile));
+    checks.push(outdated(&project, &config, &lockfile));
+    checks.push(lint(&project, &venv, &lockfile, ci || ci::github_actions()));
+
+    let report = Report {
+        project: project.name().unwrap_or("project").to_string(),
+        checks,
+    };
+    print(&report);
+
+    if let Some(path) = html {
+        health::write_html(&report, path)?;
+        println!("{} Wrote {}", "📄".green(), path.display().to_string().cyan());
+    }
+    Ok(())
+}
+
+/// Run the test command, with coverage when pytest-cov is locked and the command runs pytest
+fn tests(project: &Project, config: &Config, venv: &Venv, lockfile: &Lockfile) -> Result<[Check; 2]> {
+    trust::require(project)?;
+    let mut command = config.test_command();
+    let coverage_file = tempfile::Builder::new().prefix("pyforge-coverage-").suffix(".json").tempfile()?;
+    let with_coverage = lockfile.package("pytest-cov").is_some() && command.contains("pytest");
+    if with_coverage {
+        let report = format!("--cov-report=json:{}", coverage_file.path().display());
+        command.push_str(&format!(" --cov {}", shell_quote(&report)));
+    }
+
+    println!("{} Running {}", "🧪".cyan(), command.cyan());
+    let mut shell = process::shell_command(&command);
+    shell.current_dir(&project.root);
+    venv.activate(&mut shell)?;
+    let output = shell
+        .output()
+        .map_err(|e| PyForgeError::file_error(format!("Could not run '{}'", command), e))?;
+
+    let tests = if output.status.success() {
+        Check::scored(Kind::Tests, 1.0, "The test suite passes")
+    } else {
+        let text = String::from_utf8_lossy(&output.stdout).to_string() + &String::from_utf8_lossy(&output.stderr);
+        let lines: Vec<&str> = text.lines().filter(|line| !line.trim().is_empty()).collect();
+        let tail = lines[lines.len().saturating_sub(FAILURE_LINES)..].iter().map(|line| line.to_string()).collect();
+        Check::scored(Kind::Tests, 0.0, format!("{} failed ({})", command, output.status)).with_details(tail)
+    };
+
+    let coverage = if !with_coverage {
+        Check::skipped(Kind::Coverage, "pytest-cov is not a dependency")
+    } else {
+        match fs::read(coverage_file.path()).map_err(PyForgeError::from).and_then(|json| htmlreport::parse_coverage(&json)) {
+            Ok(coverage) => Check::scored(
+                Kind::Coverage,
+                coverage.percent / 100.0,
+                format!("{:.1}% of lines covered", coverage.percent),
+            ),
+            Err(e) => Check::skipped(Kind::Coverage, format!("no coverage report: {}", e)),
+        }
+    };
+    Ok([tests, coverage])
+}
+
+fn vulnerabilities(config: &Config, lockfile: &Lockfile) -> Check {
+    if config.network.is_metered() {
+        return Check::skipped(Kind::Vulnerabilities, "not queried on a metered connection");
+    }
+    match health::vulnerabilities(config, lockfile) {
+        Ok((_, 0)) => Check::skipped(Kind::Vulnerabilities, "no packages locked from PyPI"),
+        Ok((vulnerable, asked)) if vulnerable.is_empty() => {
+            Check::scored(Kind::Vulnerabilities, 1.0, format!("No known vulnerabilities in {} package(s)", asked))
+        }
+        Ok((vulnerable, _)) => {
+            let details = vulnerable
+                .iter()
+                .map(|package| format!("{} {}: {}", package.name, package.version, package.advisories.join(", ")))
+                .collect();
+            Check::scored(Kind::Vulnerabilities, 0.0, format!("{} package(s) with known vulnerabilities", vulnerable.len()))
+                .with_details(details)
+        }
+        Err(e) => Check::skipped(Kind::Vulnerabilities, format!("could not query the vulnerability database: {}", e)),
+    }
+}
+
+/// Compare the lockfile with a fresh resolution, without writing it
+fn outdated(project: &Project, config: &Config, lockfile: &Lockfile) -> Check {
+    let latest = match lock::resolve(project, config, &[], None) {
+        Ok(latest) => latest,
+        Err(e) => return Check::skipped(Kind::Outdated, format!("could not resolve the newest versions: {}", e)),
+    };
+    let newer = |old: &str, new: &str| match (Version::parse(old), Version::parse(new)) {
+        (Ok(old), Ok(new)) => new > old,
+        _ => false,
+    };
+    let outdated: Vec<String> = latest
+        .changes_since(lockfile)
+        .into_iter()
+        .filter_map(|change| {
+            let (old, new) = (change.old?, change.new?);
+            newer(&old, &new).then(|| format!("{} {} → {}", change.name, old, new))
+        })
+        .collect();
+    let total = lockfile.packages.len().max(1);
+    let score = 1.0 - outdated.len() as f64 / total as f64;
+    let summary = match outdated.len() {
+        0 => "Every locked package is at the newest allowed version".to_string(),
+        count => format!("{} of {} locked package(s) have newer versions; run 'pyforge bump'", count, lockfile.packages.len()),
+    };
+    Check::scored(Kind::Outdated, score, summary).with_details(outdated)
+}
+
+/// Count ruff's findings when ruff is a dependency, annotating each for CI when `annotate`
+fn lint(project: &Project, venv: &Venv, lockfile: &Lockfile, annotate: bool) -> Check {
+    if lockfile.package("ruff").is_none() {
+        return Check::skipped(Kind::Lint, "ruff is not a dependency");
+    }
+    let output = venv
+        .command()
+        .args(["-m", "ruff", "check", "--output-format", "json", "--exit-zero", "."])
+        .current_dir(&project.root)
+        .output();
+    let issues = match output {
+        Ok(output) if output.status.success() => health::lint_issues(&output.stdout),
+        Ok(output) => {
+            return Check::skipped(Kind::Lint, format!("ruff failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        Err(e) => return Check::skipped(Kind::Lint, format!("could not run ruff: {}", e)),
+    };
+    let issues = match issues {
+        Ok(issues) => issues,
+        Err(e) => return Check::skipped(Kind::Lint, e.to_string()),
+    };
+
+    if annotate {
+        let annotations: Vec<Annotation> = issues
+            .iter()
+            .map(|issue| Annotation {
+                level: Level::Warning,
+                file: Some(ci::repository_path(&project.root, &issue.filename)),
+                line: Some(issue.location.row),
+                column: Some(issue.location.column),
+                title: format!("ruff {}", issue.code.as_deref().unwrap_or("syntax")),
+                message: issue.message.clone(),
+            })
+            .collect();
+        ci::annotate(&annotations);
+    }
+    let details = health::lint_counts(&issues).iter().map(|(code, count)| format!("{} × {}", count, code)).collect();
+    let summary = match issues.len() {
+        0 => "ruff found no issues".to_string(),
+        count => format!("ruff found {} issue(s)", count),
+    };
+    Check::scored(Kind::Lint, health::lint_score(issues.len()), summary).with_details(details)
+}
+
+fn print(report: &Report) {
+    println!();
+    println!("{} {}", "📋 Health report for".bold(), report.project.cyan().bold());
+    for check in &report.checks {
+        let (icon, score) = match (check.status(), check.score) {
+            (Status::Pass, Some(score)) => ("✅", format!("{:>3.0}%", score * 100.0).green()),
+            (Status::Warn, Some(score)) => ("⚠️", format!("{:>3.0}%", score * 100.0).yellow()),
+            (Status::Fail, Some(score)) => ("❌", format!("{:>3.0}%", score * 100.0).red()),
+            _ => ("⏭️", "   –".bright_black()),
+        };
+        let summary = if check.status() == Status::Skipped {
+            check.summary.bright_black()
+        } else {
+            check.summary.normal()
+        };
+        println!("{} {:<16} {}  {}", icon, check.kind.to_string(), score, summary);
+        for detail in check.details.iter().take(SHOWN_DETAILS) {
+            println!("   {} {}", "•".bright_black(), detail);
+        }
+        if check.details.len() > SHOWN_DETAILS {
+            println!("   {} and {} more", "•".bright_black(), check.details.len() - SHOWN_DETAILS);
+        }
+    }
+
+    match (report.score(), report.grade()) {
+        (Some(score), Some(grade)) => {
+            let grade = match grade {
+                'A' | 'B' => grade.to_string().green(),
+                'C' | 'D' => grade.to_string().yellow(),
+                _ => grade.to_string().red(),
+            };
+            println!("{} Score {}/100, grade {}", "🏁".cyan(), score, grade.bold());
+        }
+        _ => println!("{} No check could run, so there is no score", "🏁".cyan()),
+    }
+}
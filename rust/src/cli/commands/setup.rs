@@ -0,0 +1,176 @@
+use crate::cli::args::Commands;
+use crate::cli::prompt;
+use crate::core::config::{self, Config, DEFAULT_INDEX_URL, global_config_path};
+use crate::core::error::{PyForgeError, Result};
+use crate::core::git;
+use colored::*;
+use std::fs;
+use std::io::{self, IsTerminal};
+use toml_edit::{ArrayOfTables, DocumentMut, InlineTable, Item, Table, value};
+
+/// What the setup asks for, starting from the current or detected values
+struct Answers {
+    python: String,
+    author_name: Option<String>,
+    author_email: Option<String>,
+    index: String,
+    color: String,
+}
+
+pub fn run(defaults: bool) -> Result<()> {
+    let mut answers = Answers::detect()?;
+    if !defaults {
+        if !io::stdin().is_terminal() {
+            return Err(PyForgeError::Usage {
+                message: "pyforge setup asks questions on a terminal; pass --defaults to write the detected settings".to_string(),
+            });
+        }
+        answers.ask()?;
+    }
+    answers.write()
+}
+
+/// Offer the setup the first time pyforge runs on a terminal without a global config
+pub fn offer_on_first_run(command: &Commands) -> Result<()> {
+    let path = global_config_path();
+    if path.exists()
+        || matches!(
+            command,
+            Commands::Setup { .. } | Commands::Help { .. } | Commands::GenerateMan { .. } | Commands::Internal(_)
+        )
+        || !io::stdin().is_terminal()
+        || !io::stdout().is_terminal()
+        || std::env::var_os("CI").is_some()
+    {
+        return Ok(());
+    }
+
+    println!("{} Welcome to pyforge! No settings found at {}.", "👋".cyan(), path.display());
+    let answer = prompt("Set up your defaults now? [Y/n]", "")?;
+    if answer.is_empty() || answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case("yes") {
+        let mut answers = Answers::detect()?;
+        answers.ask()?;
+        answers.write()?;
+    } else {
+        // An empty config, so this is only asked once
+        write_config(DocumentMut::new())?;
+        println!("   Skipped; run {} any time", "pyforge setup".cyan());
+    }
+    println!();
+    Ok(())
+}
+
+impl Answers {
+    /// Current settings, falling back to the interpreter on PATH and git's user
+    fn detect() -> Result<Self> {
+        let config = Config::load(None)?;
+        let author = config.init.author.clone();
+        let python = config.python.clone().unwrap_or_else(|| {
+            ["python3", "python"]
+                .into_iter()
+                .find(|name| which::which(name).is_ok())
+                .unwrap_or("python3")
+                .to_string()
+        });
+        Ok(Self {
+            python,
+            author_name: author.as_ref().and_then(|a| a.name.clone()).or_else(|| git::global_config("user.name")),
+            author_email: author.and_then(|a| a.email).or_else(|| git::global_config("user.email")),
+            index: config.indexes()[0].url.clone(),
+            color: format!("{:?}", config.color).to_lowercase(),
+        })
+    }
+
+    fn ask(&mut self) -> Result<()> {
+        println!("Press Enter to keep the value in brackets.");
+        self.python = prompt("Python interpreter for new environments", &self.python)?;
+        self.author_name = optional(prompt("Author name", self.author_name.as_deref().unwrap_or_default())?);
+        self.author_email = optional(prompt("Author email", self.author_email.as_deref().unwrap_or_default())?);
+        self.index = prompt("Package index", &self.index)?;
+        loop {
+            self.color = prompt("Colored output (auto, always, never)", &self.color)?.to_lowercase();
+            if ["auto", "always", "never"].contains(&self.color.as_str()) {
+                break;
+            }
+            println!("   {} Answer auto, always or never", "⚠️".yellow());
+        }
+        Ok(())
+    }
+
+    /// Merge the answers into the global config, keeping everything else in it
+    fn write(&self) -> Result<()> {
+        let path = global_config_path();
+        let mut document = match fs::read_to_string(&path) {
+            Ok(content) => content.parse::<DocumentMut>().map_err(|e| PyForgeError::InvalidToml {
+                file: path.display().to_string(),
+                message: e.to_string(),
+            })?,
+            Err(_) => DocumentMut::new(),
+        };
+
+        document["python"] = value(self.python.as_str());
+        document["color"] = value(self.color.as_str());
+
+        if self.author_name.is_some() || self.author_email.is_some() {
+            let mut author = InlineTable::new();
+            if let Some(name) = &self.author_name {
+                author.insert("name", name.as_str().into());
+            }
+            if let Some(email) = &self.author_email {
+                author.insert("email", email.as_str().into());
+            }
+            if document.get("init").and_then(Item::as_table).is_none() {
+                document["init"] = Item::Table(Table::new());
+            }
+            document["init"]["author"] = value(author);
+        }
+
+        let indexes = document.get_mut("index").and_then(Item::as_array_of_tables_mut);
+        match indexes.and_then(|indexes| indexes.get_mut(0)) {
+            Some(first) => first["url"] = value(self.index.as_str()),
+            None if self.index.trim_end_matches('/') != DEFAULT_INDEX_URL => {
+                let mut index = Table::new();
+                index["name"] = value("default");
+                index["url"] = value(self.index.as_str());
+                let mut indexes = ArrayOfTables::new();
+                indexes.push(index);
+                document["index"] = Item::ArrayOfTables(indexes);
+            }
+            None => {}
+        }
+
+        write_config(document)?;
+        println!("{} Saved settings to {}", "✅".green(), path.display().to_string().cyan());
+        println!("   Python {}, index {}, color {}", self.python, self.index, self.color);
+        if let Some(name) = &self.author_name {
+            println!("   New projects are authored by {}", name);
+        }
+        Ok(())
+    }
+}
+
+fn write_config(document: DocumentMut) -> Result<()> {
+    let path = global_config_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| PyForgeError::file_error(format!("Could not create {}", dir.display()), e))?;
+    }
+    fs::write(&path, document.to_string())
+        .map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))?;
+    // Check the result parses as settings, so a bad answer can't break every command
+    config::read_toml(&path)?;
+    Ok(())
+}
+
+/// Ask `question`, returning `default` for an empty answer
+fn prompt(question: &str, default: &str) -> Result<String> {
+    let answer = if default.is_empty() {
+        prompt::ask(&format!("{}:", question))?
+    } else {
+        prompt::ask(&format!("{} [{}]:", question, default.cyan()))?
+    };
+    Ok(if answer.is_empty() { default.to_string() } else { answer })
+}
+
+fn optional(answer: String) -> Option<String> {
+    (!answer.is_empty()).then_some(answer)
+}
@@ -0,0 +1,90 @@
+use crate::cli::commands::add;
+use crate::core::config::Config;
+use crate::core::error::Result;
+use crate::core::index::IndexClient;
+use crate::core::pep508::normalize_name;
+use crate::core::project::Project;
+use crate::core::stats;
+use crate::core::vetting;
+use colored::*;
+
+pub fn run(package: &str) -> Result<()> {
+    let project = Project::discover()?;
+    let config = Config::load(project.as_ref())?;
+    let client = IndexClient::new(&config)?;
+    let name = normalize_name(package);
+    let (index, files) = add::find_project(&client, &config, &name)?;
+    let releases = stats::releases(&files, &name);
+
+    let latest = add::latest_release(&files, &name);
+    println!(
+        "{} {} {} {}",
+        "📦".cyan(),
+        name.bold(),
+        latest.map(|version| version.to_string()).unwrap_or_else(|| "(no final release)".to_string()).cyan(),
+        format!("from {}", index.name).bright_black()
+    );
+
+    let date = |uploaded: Option<time::OffsetDateTime>| {
+        uploaded.map_or_else(|| "(date unknown)".to_string(), |uploaded| uploaded.date().to_string())
+    };
+    let yanked = releases.iter().filter(|release| release.yanked).count();
+    match (releases.first(), releases.last()) {
+        (Some(first), Some(last)) => println!(
+            "   {} {} release(s){}, first {} {}, newest {} {}",
+            "•".bright_black(),
+            releases.len(),
+            if yanked > 0 { format!(" ({} yanked)", yanked) } else { String::new() },
+            first.version,
+            date(first.uploaded),
+            last.version,
+            date(last.uploaded)
+        ),
+        _ => println!("   {} no releases", "•".bright_black()),
+    }
+
+    match stats::cadence(&releases) {
+        Some((recent, Some(gap))) => println!(
+            "   {} {} release(s) in the last 12 months, typically {} day(s) apart",
+            "•".bright_black(),
+            recent,
+            gap
+        ),
+        Some((recent, None)) => println!("   {} {} release(s) in the last 12 months", "•".bright_black(), recent),
+        None => println!("   {} {}", "•".bright_black(), "the index does not report upload times".bright_black()),
+    }
+
+    if stats::publishes_downloads(&config, &index) {
+        match stats::downloads(&config, &name) {
+            Ok(downloads) => println!(
+                "   {} {} downloads last month, {} last week, {} yesterday",
+                "•".bright_black(),
+                thousands(downloads.last_month),
+                thousands(downloads.last_week),
+                thousands(downloads.last_day)
+            ),
+            Err(error) => {
+                log::debug!("download statistics of {}: {}", name, error);
+                println!("   {} {}", "•".bright_black(), "download statistics unavailable".bright_black());
+            }
+        }
+    }
+
+    for concern in vetting::vet(&name, &files) {
+        println!("{} {} {}", "⚠️".yellow(), concern.message, format!("[{}]", concern.kind).bright_black());
+    }
+    Ok(())
+}
+
+/// `1234567` as `1,234,567`
+fn thousands(count: u64) -> String {
+    let digits = count.to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
@@ -0,0 +1,165 @@
+use crate::cli::args::{LockFlags, SyncFlags};
+use crate::cli::commands::{lock, trust};
+use crate::cli::prompt;
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::git::{self, Worktree};
+use crate::core::installer;
+use crate::core::overrides::DEV_OVERRIDES;
+use crate::core::lockfile::{self, LOCKFILE, Lockfile};
+use crate::core::parallel;
+use crate::core::project::Project;
+use crate::core::registry;
+use crate::core::venv::{SyncState, Venv};
+use colored::*;
+use std::io::{self, IsTerminal};
+
+pub fn run(flags: &LockFlags, sync_flags: &SyncFlags) -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    trust::require_build_env(&project)?;
+    let lockfile = lock::ensure(&project, &config, flags)?;
+    let venv = Venv::ensure(&project, &config)?;
+    // For `pyforge workon`; failing to record it shouldn't fail the sync
+    if let Err(error) = registry::remember(&project) {
+        log::debug!("could not register {}: {}", project.root.display(), error);
+    }
+    sync_into(&project, &config, &venv, &lockfile, flags, sync_flags)
+}
+
+/// Install `lockfile` into `venv`, reporting what changed
+pub fn sync_into(
+    project: &Project,
+    config: &Config,
+    venv: &Venv,
+    lockfile: &Lockfile,
+    flags: &LockFlags,
+    sync_flags: &SyncFlags,
+) -> Result<()> {
+    trust::require_build_env(project)?;
+    let state = SyncState::current(project, lockfile);
+    if let Some(previous) = venv.sync_state()
+        && previous.input_hash != state.input_hash
+        && (previous.worktree != state.worktree || previous.branch != state.branch)
+    {
+        println!(
+            "{} {} was last synced from {}{}; switching it to this checkout's lockfile",
+            "ℹ️".cyan(),
+            venv.display(project),
+            previous.worktree.display(),
+            previous.branch.map(|branch| format!(" (branch {})", branch)).unwrap_or_default()
+        );
+    }
+
+    let unused: Vec<String> = lockfile
+        .unreachable(&lockfile::requirements(project)?)
+        .iter()
+        .map(|package| package.name.clone())
+        .collect();
+    let pruned;
+    let lockfile = if sync_flags.prune && !unused.is_empty() {
+        if flags.locked || flags.frozen {
+            return Err(PyForgeError::Usage {
+                message: format!("--prune would drop unused entries from {}, which --locked and --frozen forbid", LOCKFILE),
+            });
+        }
+        let mut kept = lockfile.clone();
+        kept.packages.retain(|package| !unused.contains(&package.name));
+        kept.write(project)?;
+        println!("{} Dropped {} unused entr(ies) from {}", "🧹".green(), unused.len(), LOCKFILE.cyan());
+        for name in &unused {
+            println!("   {} {}", "-".red(), name);
+        }
+        pruned = kept;
+        &pruned
+    } else {
+        lockfile
+    };
+
+    println!(
+        "{} Syncing {} locked package(s) into {}",
+        "📦".green(),
+        lockfile.packages.len(),
+        venv.display(project).cyan()
+    );
+    let jobs = parallel::jobs(config, sync_flags.jobs);
+    review_hooks(project, config, venv, lockfile, flags.frozen, sync_flags.reinstall, jobs)?;
+    let report = installer::sync(venv, lockfile, &project.root, config, flags.frozen, sync_flags.reinstall, jobs)?;
+    let removed = if sync_flags.prune { report.orphans.clone() } else { Vec::new() };
+    installer::remove(venv, &removed)?;
+    venv.record_sync(&state)?;
+
+    for package in &report.installed {
+        println!("   {} {}", "+".green(), package);
+    }
+    for package in &removed {
+        println!("   {} {}", "-".red(), package);
+    }
+    for package in &report.overridden {
+        println!("   {} {} {}", "~".yellow(), package, format!("[{}]", DEV_OVERRIDES).bright_black());
+    }
+    println!(
+        "{} Environment is in sync ({} installed, {} removed, {} unchanged)",
+        "✅".green(),
+        report.installed.len(),
+        removed.len(),
+        report.unchanged
+    );
+
+    if project.root.join(DEV_OVERRIDES).is_file()
+        && Worktree::discover(&project.root).is_some()
+        && !git::is_ignored(&project.root, DEV_OVERRIDES)
+    {
+        println!("{} {} is not git-ignored; add it to .gitignore so its overrides stay local", "⚠️".yellow(), DEV_OVERRIDES);
+    }
+    if !sync_flags.prune && !report.orphans.is_empty() {
+        println!("{} Installed but not in {}: {}", "⚠️".yellow(), LOCKFILE, report.orphans.join(", "));
+    }
+    if !sync_flags.prune && !unused.is_empty() {
+        println!("{} Locked but not required by any dependency: {}", "⚠️".yellow(), unused.join(", "));
+    }
+    if !sync_flags.prune && (!report.orphans.is_empty() || !unused.is_empty()) {
+        println!("   Run {} to remove them", "pyforge sync --prune".cyan());
+    }
+    Ok(())
+}
+
+/// Hold back packages that run code of their own when installed until they
+/// are approved: on a terminal by asking, elsewhere by warning, or by failing
+/// with `[tool.pyforge.install.hooks] strict`
+pub fn review_hooks(
+    project: &Project,
+    config: &Config,
+    venv: &Venv,
+    lockfile: &Lockfile,
+    offline: bool,
+    reinstall: bool,
+    jobs: usize,
+) -> Result<()> {
+    let held = installer::review(venv, lockfile, &project.root, config, offline, reinstall, jobs)?;
+    if held.is_empty() {
+        return Ok(());
+    }
+
+    println!("{} {} package(s) run code of their own when installed:", "🔍".yellow(), held.len());
+    for package in &held {
+        println!("   {} {} {}", "•".bright_black(), package.package.bold(), package.version);
+        for hook in &package.hooks {
+            println!("     {} {}", hook.message, format!("[{}]", hook.kind).bright_black());
+        }
+    }
+    let packages: Vec<String> = held.iter().map(|package| package.package.clone()).collect();
+    let allow = format!("[tool.pyforge.install.hooks] allow = [{}]", packages.iter().map(|name| format!("\"{}\"", name)).collect::<Vec<_>>().join(", "));
+
+    if io::stdin().is_terminal() {
+        if !prompt::confirm("Install them?")? {
+            return Err(PyForgeError::InstallHooksHeld { packages });
+        }
+        println!("   Once reviewed, add {} to stop being asked", allow.cyan());
+    } else if config.install.hooks.strict {
+        return Err(PyForgeError::InstallHooksHeld { packages });
+    } else {
+        println!("{} Installing them anyway; once reviewed, add {}", "⚠️".yellow(), allow.cyan());
+    }
+    Ok(())
+}
@@ -0,0 +1,209 @@
+use crate::cli::args::{IsolationFlags, LockFlags};
+use crate::cli::commands::{lock, trust};
+use crate::core::buildinfo;
+use crate::core::capture;
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::installer;
+use crate::core::isolation::Isolation;
+use crate::core::overrides::DEV_OVERRIDES;
+use crate::core::process::{self, shell_quote};
+use crate::core::project::Project;
+use crate::core::ssh;
+use crate::core::venv::Venv;
+use crate::core::timings;
+use colored::*;
+use std::io::{BufRead, BufReader, Read};
+use std::sync::mpsc::{self, Sender};
+use std::time::Instant;
+
+/// What a host's worker reports back
+enum Event {
+    Line { host: usize, text: String },
+    Finished { host: usize, stage: &'static str, code: i32, seconds: f64 },
+}
+
+pub fn run(name: &str, hosts: &[String], isolation: &IsolationFlags, flags: &LockFlags, args: &[String]) -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+
+    if config.scripts.is_empty() {
+        return Err(PyForgeError::MissingConfig {
+            key: "tool.pyforge.scripts".to_string(),
+            example: "[tool.pyforge.scripts]\ntest = \"pytest -q\"".to_string(),
+        });
+    }
+    let script = config.scripts.get(name).ok_or_else(|| PyForgeError::Usage {
+        message: format!(
+            "No script '{}' (configured: {})",
+            name,
+            config.scripts.keys().cloned().collect::<Vec<_>>().join(", ")
+        ),
+    })?;
+    trust::require(&project)?;
+    let mut line = script.clone();
+    for arg in args {
+        line.push(' ');
+        line.push_str(&shell_quote(arg));
+    }
+
+    buildinfo::write(&project, &config, &project.root, buildinfo::DEV)?;
+    if hosts.is_empty() {
+        let isolation = isolation
+            .isolated
+            .then(|| Isolation::new(config.isolation.allow_env.iter().chain(&isolation.allow_env).cloned()))
+            .transpose()?;
+        return run_local(&project, &config, &line, isolation.as_ref());
+    }
+    if isolation.isolated {
+        return Err(PyForgeError::Usage {
+            message: "--isolated runs locally; it can't be combined with --on".to_string(),
+        });
+    }
+
+    ssh::require_tools()?;
+    let dir = ssh::remote_dir(&project, &config)?;
+    lock::ensure(&project, &config, flags)?;
+    println!(
+        "{} Running {} on {} (experimental)",
+        "🛰️".cyan(),
+        line.cyan(),
+        hosts.join(", ").cyan()
+    );
+
+    let (sender, receiver) = mpsc::channel();
+    for (index, host) in hosts.iter().enumerate() {
+        let upload = ssh::upload(&project, &config, host, &dir);
+        let execute = ssh::execute(&config, host, &dir, &line);
+        let sender = sender.clone();
+        std::thread::spawn(move || run_host(index, upload, execute, sender));
+    }
+    drop(sender);
+
+    let width = hosts.iter().map(String::len).max().unwrap_or(0);
+    let mut results = vec![None; hosts.len()];
+    for event in receiver {
+        match event {
+            Event::Line { host, text } => println!("{} │ {}", label(&hosts[host], host, width), text),
+            Event::Finished { host, stage, code, seconds } => results[host] = Some((stage, code, seconds)),
+        }
+    }
+
+    println!();
+    let mut failure = None;
+    for (index, host) in hosts.iter().enumerate() {
+        match results[index] {
+            Some((_, 0, seconds)) => println!("{} {} finished in {:.1}s", "✅".green(), host, seconds),
+            Some((stage, code, seconds)) => {
+                println!("{} {} failed to {} (exit {}) after {:.1}s", "❌".red(), host, stage, code, seconds);
+                failure.get_or_insert(code);
+            }
+            None => {
+                println!("{} {} stopped without reporting a result", "❌".red(), host);
+                failure.get_or_insert(1);
+            }
+        }
+    }
+
+    match failure {
+        Some(code) => Err(PyForgeError::command_failed(format!("{} on {}", line, hosts.join(",")), code)),
+        None => Ok(()),
+    }
+}
+
+/// Run the script from the project root, inside the environment when there is one
+fn run_local(project: &Project, config: &Config, line: &str, isolation: Option<&Isolation>) -> Result<()> {
+    let mut command = process::shell_command(line);
+    command.current_dir(&project.root);
+    if let Some(isolation) = isolation {
+        isolation.apply(&mut command);
+    }
+    if let Some(venv) = Venv::open(project, config) {
+        for package in installer::apply_overrides(&venv, &project.root, config, false, false)? {
+            println!("   {} {} {}", "~".yellow(), package, format!("[{}]", DEV_OVERRIDES).bright_black());
+        }
+        venv.activate(&mut command)?;
+    }
+    match isolation {
+        Some(_) => println!("{} Running {} {}", "▶️".cyan(), line.cyan(), "(isolated)".bright_black()),
+        None => println!("{} Running {}", "▶️".cyan(), line.cyan()),
+    }
+    let capture = capture::open(project, config, "task", line)?;
+    let _span = timings::subprocess(&command);
+    let status = capture::status(capture, &mut command)?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(PyForgeError::command_failed(line, status.code().unwrap_or(1)))
+    }
+}
+
+/// Upload to one host, then run there, forwarding every output line
+fn run_host(host: usize, mut upload: std::process::Command, mut execute: std::process::Command, sender: Sender<Event>) {
+    let started = Instant::now();
+    let finish = |stage, code| Event::Finished {
+        host,
+        stage,
+        code,
+        seconds: started.elapsed().as_secs_f64(),
+    };
+
+    match upload.output() {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => {
+            forward(&output.stderr[..], host, &sender);
+            let _ = sender.send(finish("upload", output.status.code().unwrap_or(1)));
+            return;
+        }
+        Err(e) => {
+            let _ = sender.send(Event::Line { host, text: format!("rsync: {}", e) });
+            let _ = sender.send(finish("upload", 127));
+            return;
+        }
+    }
+
+    let spawned = execute
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => {
+            let _ = sender.send(Event::Line { host, text: format!("ssh: {}", e) });
+            let _ = sender.send(finish("run", 127));
+            return;
+        }
+    };
+    let stderr = child.stderr.take().map(|stream| {
+        let sender = sender.clone();
+        std::thread::spawn(move || forward(stream, host, &sender))
+    });
+    if let Some(stdout) = child.stdout.take() {
+        forward(stdout, host, &sender);
+    }
+    if let Some(thread) = stderr {
+        let _ = thread.join();
+    }
+    let code = child.wait().ok().and_then(|status| status.code()).unwrap_or(1);
+    let _ = sender.send(finish("run", code));
+}
+
+fn forward(stream: impl Read, host: usize, sender: &Sender<Event>) {
+    for text in BufReader::new(stream).lines().map_while(|l| l.ok()) {
+        if sender.send(Event::Line { host, text }).is_err() {
+            break;
+        }
+    }
+}
+
+/// Host name padded to `width`, in a color of its own
+fn label(name: &str, index: usize, width: usize) -> ColoredString {
+    let padded = format!("{:<width$}", name, width = width);
+    match index % 5 {
+        0 => padded.cyan(),
+        1 => padded.magenta(),
+        2 => padded.yellow(),
+        3 => padded.blue(),
+        _ => padded.green(),
+    }
+}
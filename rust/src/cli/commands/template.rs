@@ -0,0 +1,290 @@
+use crate::cli::args::TemplateCommand;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::permissions::ChmodRule;
+use crate::core::project::Project;
+use crate::core::template::{self, Change, MANIFEST_FILE, RECORD_FILE, Record, Template};
+use colored::*;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+pub fn run(command: TemplateCommand) -> Result<()> {
+    match command {
+        TemplateCommand::Update { to } => update(to.as_deref()),
+        TemplateCommand::Snapshot { templates, update } => snapshot(&templates, update),
+    }
+}
+
+fn update(to: Option<&str>) -> Result<()> {
+    let project = Project::require()?;
+    let record = Record::load(&project.root)?.ok_or_else(|| PyForgeError::Usage {
+        message: format!("This project was not generated from a template (no {})", RECORD_FILE),
+    })?;
+    let Some(commit) = &record.commit else {
+        return Err(PyForgeError::Usage {
+            message: format!(
+                "Template {} is not a git repository, so the revision this project came from can't be re-rendered",
+                record.source
+            ),
+        });
+    };
+
+    let old = Template::fetch_pinned(&record.source, Some(commit), &record.bases)?;
+    let new = Template::fetch(&record.source, to)?;
+    if new.commit == old.commit && new.bases() == old.bases() {
+        println!("{} Already up to date with {}", "✅".green(), describe(&new));
+        return Ok(());
+    }
+    println!("{} Updating template {} → {}", "📐".cyan(), describe(&old), describe(&new));
+    let old_bases = old.bases();
+    for (source, commit) in new.bases() {
+        let previous = old_bases.get(&source).map_or("none", |c| &c[..c.len().min(10)]);
+        if previous != &commit[..commit.len().min(10)] {
+            println!("   {} base {}: {} → {}", "•".bright_black(), source, previous, &commit[..commit.len().min(10)]);
+        }
+    }
+
+    // Variables added by the new revision start at their defaults
+    let mut variables = new.defaults();
+    variables.extend(record.variables.clone());
+    let rules = record.chmod.iter().map(|rule| ChmodRule::parse(rule)).collect::<Result<Vec<_>>>()?;
+    let changes = template::apply_update(
+        &project.root,
+        &old.rendered(&record.variables)?,
+        &new.rendered(&variables)?,
+        &new.modes(&variables, &rules)?,
+    )?;
+    new.record(variables, &rules).write(&project.root)?;
+
+    let mut conflicts = 0;
+    for (path, change) in &changes {
+        let (marker, label) = match change {
+            Change::Added => ("+".green(), "added"),
+            Change::Updated => ("~".cyan(), "updated"),
+            Change::Merged => ("~".cyan(), "merged with your changes"),
+            Change::Conflicted => ("!".red(), "conflict"),
+            Change::Removed => ("-".red(), "removed"),
+        };
+        if *change == Change::Conflicted {
+            conflicts += 1;
+        }
+        println!("   {} {} {}", marker, path.display(), label.bright_black());
+    }
+
+    if conflicts > 0 {
+        println!(
+            "{} {} file(s) have conflicts marked with <<<<<<< project / >>>>>>> new template; resolve them by hand",
+            "⚠️".yellow(),
+            conflicts
+        );
+    } else {
+        println!("{} Template changes applied ({} file(s))", "✅".green(), changes.len());
+    }
+    Ok(())
+}
+
+fn describe(template: &Template) -> String {
+    let commit = template.commit.as_deref().map(|c| &c[..c.len().min(10)]).unwrap_or("working tree");
+    match &template.manifest.version {
+        Some(version) => format!("{} ({})", version, commit),
+        None => commit.to_string(),
+    }
+}
+
+/// Render every snapshot case of `templates` and compare it with, or when
+/// `update` write it to, the template's snapshot directory
+fn snapshot(templates: &[String], update: bool) -> Result<()> {
+    let mut mismatched = Vec::new();
+    let mut cases = 0;
+    for dir in snapshot_targets(templates)? {
+        let template = Template::open(&dir)?;
+        let name = template
+            .manifest
+            .name
+            .clone()
+            .unwrap_or_else(|| dir.file_name().map_or_else(|| dir.display().to_string(), |name| name.to_string_lossy().to_string()));
+        println!("{} Snapshots of {}", "📸".cyan(), name.cyan());
+
+        let snapshot_cases = template.snapshot_cases();
+        for case in snapshot_cases.keys() {
+            let mut components = Path::new(case).components();
+            if !matches!((components.next(), components.next()), (Some(Component::Normal(_)), None)) {
+                return Err(PyForgeError::Usage {
+                    message: format!("Snapshot case '{}' of {} must be a plain directory name", case, name),
+                });
+            }
+        }
+        let snapshot_dir = template.snapshot_dir();
+        let stale = stale_cases(&snapshot_dir, &snapshot_cases)?;
+
+        for (case, variables) in &snapshot_cases {
+            cases += 1;
+            let rendered = template.rendered(variables)?;
+            let dir = snapshot_dir.join(case);
+            if update {
+                write_snapshot(&dir, &rendered)?;
+                println!("   {} {} {}", "✓".green(), case, format!("({} file(s))", rendered.len()).bright_black());
+                continue;
+            }
+            let differences = compare(&dir, &rendered)?;
+            if differences.is_empty() {
+                println!("   {} {}", "✓".green(), case);
+            } else {
+                println!("   {} {}", "✗".red(), case);
+                for difference in &differences {
+                    println!("      {} {}", "•".bright_black(), difference);
+                }
+                mismatched.push(format!("{}/{}", name, case));
+            }
+        }
+
+        for case in stale {
+            if update {
+                fs::remove_dir_all(snapshot_dir.join(&case))?;
+                println!("   {} {} {}", "-".red(), case, "removed, no longer a case".bright_black());
+            } else {
+                println!("   {} {} {}", "✗".red(), case, "is not a [snapshots] case".bright_black());
+                mismatched.push(format!("{}/{}", name, case));
+            }
+        }
+    }
+
+    if !mismatched.is_empty() {
+        return Err(PyForgeError::SnapshotMismatch { snapshots: mismatched });
+    }
+    if update {
+        println!("{} Wrote {} snapshot(s); commit them with the template", "✅".green(), cases);
+    } else {
+        println!("{} {} snapshot(s) match", "✅".green(), cases);
+    }
+    Ok(())
+}
+
+/// The templates named, or the one in the current directory, or every user template
+fn snapshot_targets(templates: &[String]) -> Result<Vec<PathBuf>> {
+    let user_templates = template::user_template_dir();
+    if !templates.is_empty() {
+        return templates
+            .iter()
+            .map(|name| {
+                [PathBuf::from(name), user_templates.join(name)]
+                    .into_iter()
+                    .find(|path| path.is_dir())
+                    .ok_or_else(|| PyForgeError::TemplateNotFound { template: name.clone() })
+            })
+            .collect();
+    }
+
+    let cwd = std::env::current_dir()?;
+    if cwd.join(MANIFEST_FILE).is_file() {
+        return Ok(vec![cwd]);
+    }
+    let mut found = Vec::new();
+    if user_templates.is_dir() {
+        for entry in fs::read_dir(&user_templates)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                found.push(path);
+            }
+        }
+    }
+    if found.is_empty() {
+        return Err(PyForgeError::Usage {
+            message: format!(
+                "No templates to snapshot: name one, run this in a template (with {}), or add templates to {}",
+                MANIFEST_FILE,
+                user_templates.display()
+            ),
+        });
+    }
+    found.sort();
+    Ok(found)
+}
+
+/// Snapshot directories left from cases the manifest no longer has
+fn stale_cases(snapshot_dir: &Path, cases: &BTreeMap<String, BTreeMap<String, String>>) -> Result<Vec<String>> {
+    let mut stale = Vec::new();
+    if snapshot_dir.is_dir() {
+        for entry in fs::read_dir(snapshot_dir)? {
+            let entry = entry?;
+            let case = entry.file_name().to_string_lossy().to_string();
+            if entry.path().is_dir() && !cases.contains_key(&case) {
+                stale.push(case);
+            }
+        }
+    }
+    stale.sort();
+    Ok(stale)
+}
+
+/// How the snapshot in `dir` differs from `rendered`, one line per file
+fn compare(dir: &Path, rendered: &BTreeMap<PathBuf, Vec<u8>>) -> Result<Vec<String>> {
+    if !dir.is_dir() {
+        return Ok(vec!["no snapshot yet".to_string()]);
+    }
+    let snapshot = read_snapshot(dir)?;
+    let mut differences = Vec::new();
+    for (path, content) in rendered {
+        match snapshot.get(path) {
+            None => differences.push(format!("{} {}", path.display(), "is new".green())),
+            Some(expected) if expected != content => differences.push(format!("{} {}", path.display(), first_difference(expected, content))),
+            Some(_) => {}
+        }
+    }
+    for path in snapshot.keys().filter(|path| !rendered.contains_key(*path)) {
+        differences.push(format!("{} {}", path.display(), "is no longer rendered".red()));
+    }
+    Ok(differences)
+}
+
+/// `changed at line N`, with the snapshot's line and the new one when both are text
+fn first_difference(expected: &[u8], actual: &[u8]) -> String {
+    let (Ok(expected), Ok(actual)) = (std::str::from_utf8(expected), std::str::from_utf8(actual)) else {
+        return "changed".yellow().to_string();
+    };
+    let (mut expected_lines, mut actual_lines) = (expected.lines(), actual.lines());
+    let mut line = 1;
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (Some(old), Some(new)) if old == new => line += 1,
+            (None, None) => return format!("{} {}", "changed".yellow(), "(line endings)".bright_black()),
+            (old, new) => {
+                return format!(
+                    "{} at line {}: {} → {}",
+                    "changed".yellow(),
+                    line,
+                    old.map_or_else(|| "end of file".bright_black().to_string(), |old| format!("{:?}", old).red().to_string()),
+                    new.map_or_else(|| "end of file".bright_black().to_string(), |new| format!("{:?}", new).green().to_string()),
+                );
+            }
+        }
+    }
+}
+
+fn read_snapshot(dir: &Path) -> Result<BTreeMap<PathBuf, Vec<u8>>> {
+    let mut files = BTreeMap::new();
+    for entry in walkdir::WalkDir::new(dir).min_depth(1) {
+        let entry = entry.map_err(|e| PyForgeError::internal(format!("Could not read the snapshot {}: {}", dir.display(), e)))?;
+        if entry.file_type().is_file() {
+            let relative = entry.path().strip_prefix(dir).expect("walked under dir");
+            files.insert(relative.to_path_buf(), fs::read(entry.path())?);
+        }
+    }
+    Ok(files)
+}
+
+/// Replace the snapshot in `dir` with `rendered`
+fn write_snapshot(dir: &Path, rendered: &BTreeMap<PathBuf, Vec<u8>>) -> Result<()> {
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    fs::create_dir_all(dir)?;
+    for (path, content) in rendered {
+        let target = dir.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(target, content)?;
+    }
+    Ok(())
+}
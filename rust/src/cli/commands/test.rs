@@ -0,0 +1,272 @@
+use crate::cli::args::{LockFlags, TestReportFlags};
+use crate::cli::commands::{lock, sync, trust};
+use crate::core::capture::{self, Capture};
+use crate::core::config::Config;
+use crate::core::container;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::ci::{self, Annotation, Level};
+use crate::core::htmlreport::{self, TestRun};
+use crate::core::junit::{self, Outcome, TestCase};
+use crate::core::installer;
+use crate::core::lockfile::Lockfile;
+use crate::core::process::{self, shell_quote};
+use crate::core::project::Project;
+use crate::core::testcache::TestCache;
+use crate::core::venv::{SyncState, Venv};
+use crate::core::timings;
+use crate::core::parallel;
+use colored::*;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+use std::time::Instant;
+
+/// Written by a run with reports, in the project root, and removed after it
+const JUNIT_FILE: &str = ".pyforge-junit.xml";
+const COVERAGE_FILE: &str = ".pyforge-coverage.json";
+
+pub fn run(
+    in_docker: bool,
+    profile: Option<&str>,
+    no_cache: bool,
+    reports: &TestReportFlags,
+    flags: &LockFlags,
+    args: &[String],
+) -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    trust::require(&project)?;
+    let lockfile = lock::ensure(&project, &config, flags)?;
+
+    let mut command = config.test_command();
+    for arg in args {
+        command.push(' ');
+        command.push_str(&shell_quote(arg));
+    }
+    let results = Results::new(reports, &lockfile, &command);
+    // Reports need a run, so a green run with the same inputs isn't skipped
+    let no_cache = no_cache || results.is_some();
+    let run_command = results.as_ref().map_or_else(|| command.clone(), |results| results.command(&command));
+
+    if !in_docker {
+        let cache = TestCache::new(&project, &command)?;
+        if !no_cache && skip(&cache) {
+            return Ok(());
+        }
+        let venv = sync_venv(&project, &config, &lockfile, flags.frozen)?;
+        println!("{} Running {}", "🧪".cyan(), run_command.cyan());
+        let mut shell = process::shell_command(&run_command);
+        shell.current_dir(&project.root);
+        venv.activate(&mut shell)?;
+        let capture = capture::open(&project, &config, "test", &run_command)?;
+        let (status, elapsed) = execute(&mut shell, capture)?;
+        if let Some(results) = results {
+            results.write(&project, &run_command, status, elapsed)?;
+        }
+        conclude(status, elapsed, &run_command)?;
+        return cache.record_green();
+    }
+
+    let image = match profile {
+        Some(name) => {
+            let profile = config.test.profiles.get(name).ok_or_else(|| PyForgeError::Usage {
+                message: format!(
+                    "No test profile '{}' (configured: {})",
+                    name,
+                    config.test.profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+                ),
+            })?;
+            profile.image.clone()
+        }
+        None => config.test.image.clone().unwrap_or_else(|| container::default_image(&lockfile)),
+    };
+    let cache = TestCache::new(&project, &format!("{} in {}", command, image))?;
+    if !no_cache && skip(&cache) {
+        return Ok(());
+    }
+    let mut run = container::prepare(&project, &config, &lockfile, &image, &run_command)?;
+    println!(
+        "{} Running {} in {} ({})",
+        "🧪".cyan(),
+        run_command.cyan(),
+        image.cyan(),
+        run.engine
+    );
+    let capture = capture::open(&project, &config, "test", &format!("{} in {}", run_command, image))?;
+    let (status, elapsed) = execute(&mut run.command, capture)?;
+    if let Some(results) = results {
+        results.write(&project, &format!("{} in {}", run_command, image), status, elapsed)?;
+    }
+    conclude(status, elapsed, &run_command)?;
+    cache.record_green()
+}
+
+/// Whether the last green run had the same inputs, saying so
+fn skip(cache: &TestCache) -> bool {
+    if !cache.is_green() {
+        return false;
+    }
+    println!(
+        "{} Tests passed before with identical inputs ({}); skipping. Pass --no-cache to run them anyway.",
+        "⏭️".cyan(),
+        &cache.inputs[..12]
+    );
+    true
+}
+
+/// Sync the lockfile into the project's environment and run `command` there
+pub fn run_in_venv(project: &Project, config: &Config, lockfile: &Lockfile, command: &str, offline: bool) -> Result<()> {
+    trust::require(project)?;
+    let venv = sync_venv(project, config, lockfile, offline)?;
+    println!("{} Running {}", "🧪".cyan(), command.cyan());
+    let mut shell = process::shell_command(command);
+    shell.current_dir(&project.root);
+    venv.activate(&mut shell)?;
+    let capture = capture::open(project, config, "test", command)?;
+    let (status, elapsed) = execute(&mut shell, capture)?;
+    conclude(status, elapsed, command)
+}
+
+fn sync_venv(project: &Project, config: &Config, lockfile: &Lockfile, offline: bool) -> Result<Venv> {
+    let venv = Venv::ensure(project, config)?;
+    let jobs = parallel::jobs(config, None);
+    sync::review_hooks(project, config, &venv, lockfile, offline, false, jobs)?;
+    installer::sync(&venv, lockfile, &project.root, config, offline, false, jobs)?;
+    venv.record_sync(&SyncState::current(project, lockfile))?;
+    Ok(venv)
+}
+
+/// Run the tests with output streamed through (and copied to `capture`),
+/// returning how they exited and the seconds taken
+fn execute(command: &mut Command, capture: Option<Capture>) -> Result<(ExitStatus, f64)> {
+    let started = Instant::now();
+    let _span = timings::subprocess(command);
+    let status: ExitStatus = capture::status(capture, command).map_err(|_| PyForgeError::CommandNotFound {
+        command: command.get_program().to_string_lossy().to_string(),
+    })?;
+    Ok((status, started.elapsed().as_secs_f64()))
+}
+
+/// Print the summary line, failing when the tests did
+fn conclude(status: ExitStatus, elapsed: f64, label: &str) -> Result<()> {
+    if status.success() {
+        println!("{} Tests passed in {:.1}s", "✅".green(), elapsed);
+        Ok(())
+    } else {
+        let code = status.code().unwrap_or(-1);
+        println!("{} Tests failed (exit {}) after {:.1}s", "❌".red(), code, elapsed);
+        Err(PyForgeError::command_failed(label, code))
+    }
+}
+
+/// The reports asked for, from the JUnit XML and coverage files a run
+/// writes into the project root, where a container sees them too
+struct Results<'a> {
+    html: Option<&'a Path>,
+    junit_xml: Option<&'a Path>,
+    annotate: bool,
+    /// The command runs pytest, which writes JUnit XML
+    pytest: bool,
+    coverage: bool,
+}
+
+impl<'a> Results<'a> {
+    /// `None` when no report was asked for
+    fn new(reports: &'a TestReportFlags, lockfile: &Lockfile, command: &str) -> Option<Self> {
+        let annotate = reports.ci || ci::github_actions();
+        if reports.report_html.is_none() && reports.junit_xml.is_none() && !annotate {
+            return None;
+        }
+        let pytest = command.contains("pytest");
+        Some(Self {
+            html: reports.report_html.as_deref(),
+            junit_xml: reports.junit_xml.as_deref(),
+            annotate,
+            pytest,
+            // Coverage only goes into the HTML report
+            coverage: pytest && reports.report_html.is_some() && lockfile.package("pytest-cov").is_some(),
+        })
+    }
+
+    fn command(&self, command: &str) -> String {
+        let mut command = command.to_string();
+        if self.pytest {
+            command.push_str(&format!(" --junitxml={}", JUNIT_FILE));
+        }
+        if self.coverage {
+            command.push_str(&format!(" --cov --cov-report=json:{}", COVERAGE_FILE));
+        }
+        command
+    }
+
+    /// Write the reports from the files the run left behind, then remove them
+    fn write(self, project: &Project, label: &str, status: ExitStatus, elapsed: f64) -> Result<()> {
+        let junit = project.root.join(JUNIT_FILE);
+        let coverage = project.root.join(COVERAGE_FILE);
+        let xml = fs::read_to_string(&junit).ok();
+        let measured = match fs::read(&coverage) {
+            Ok(json) => Some(htmlreport::parse_coverage(&json)?),
+            Err(_) => None,
+        };
+        let _ = fs::remove_file(&junit);
+        let _ = fs::remove_file(&coverage);
+        let cases = xml.as_deref().map(junit::parse).unwrap_or_default();
+        let name = project.name().unwrap_or("project").to_string();
+
+        if self.annotate {
+            ci::annotate(&annotations(&project.root, &cases, label, status));
+        }
+        if let Some(path) = self.junit_xml {
+            let xml = xml.unwrap_or_else(|| junit::single_case(&name, label, status, elapsed));
+            fs::write(path, xml).map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))?;
+            println!("{} Wrote {}", "📄".green(), path.display().to_string().cyan());
+        }
+        if let Some(dir) = self.html {
+            let run = TestRun {
+                project: name,
+                command: label.to_string(),
+                passed: status.success(),
+                exit_code: status.code().unwrap_or(-1),
+                seconds: elapsed,
+                cases,
+                coverage: measured,
+            };
+            let index = htmlreport::write_test_report(dir, &run)?;
+            println!("{} Wrote {}", "📄".green(), index.display().to_string().cyan());
+        }
+        Ok(())
+    }
+}
+
+/// An error annotation for each failing test, or one for the whole run when
+/// the command doesn't report tests one by one
+fn annotations(root: &Path, cases: &[TestCase], label: &str, status: ExitStatus) -> Vec<Annotation> {
+    if cases.is_empty() {
+        if status.success() {
+            return Vec::new();
+        }
+        return vec![Annotation {
+            level: Level::Error,
+            file: None,
+            line: None,
+            column: None,
+            title: "Tests failed".to_string(),
+            message: format!("{} exited with {}", label, status.code().unwrap_or(-1)),
+        }];
+    }
+    cases
+        .iter()
+        .filter(|case| matches!(case.outcome, Outcome::Failed | Outcome::Error))
+        .map(|case| {
+            let location = junit::location(case);
+            Annotation {
+                level: Level::Error,
+                file: location.as_ref().map(|(file, _)| ci::repository_path(root, file)),
+                line: location.map(|(_, line)| line),
+                column: None,
+                title: format!("{} {}", case.name, case.outcome.label()),
+                message: case.message.clone(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,75 @@
+use crate::cli::prompt;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::project::Project;
+use crate::core::trust;
+use colored::*;
+use std::io::{self, IsTerminal};
+
+pub fn run(revoke: bool, list: bool) -> Result<()> {
+    if list {
+        let directories = trust::trusted();
+        if directories.is_empty() {
+            println!("No trusted directories");
+        }
+        for dir in directories {
+            println!("{}", dir.display());
+        }
+        return Ok(());
+    }
+
+    let project = Project::require()?;
+    if revoke {
+        if trust::revoke(&project.root)? {
+            println!("{} {} is no longer trusted", "🔒".green(), project.root.display());
+        } else {
+            println!("{} {} was not trusted", "ℹ️".cyan(), project.root.display());
+        }
+        return Ok(());
+    }
+    print_commands(&project);
+    trust::trust(&project.root)?;
+    println!("{} Trusted {}", "✅".green(), project.root.display().to_string().cyan());
+    Ok(())
+}
+
+/// Make sure `project` may run the commands it defines, asking on a terminal
+/// the first time. CI jobs opt in with `PYFORGE_TRUST_ALL`: a pipeline may run
+/// on a fork's pull request, so `CI` alone trusts nothing.
+pub fn require(project: &Project) -> Result<()> {
+    if trust::is_trusted(project) {
+        return Ok(());
+    }
+    let untrusted = || PyForgeError::UntrustedProject {
+        path: project.root.display().to_string(),
+    };
+    if !io::stdin().is_terminal() {
+        return Err(untrusted());
+    }
+
+    println!("{} {} is not trusted yet", "⚠️".yellow(), project.root.display().to_string().bold());
+    print_commands(project);
+    if !prompt::confirm("Trust this directory and run them?")? {
+        return Err(untrusted());
+    }
+    trust::trust(&project.root)
+}
+
+/// Like `require`, before installing into the project's environment: asks
+/// only when the project sets build environment variables
+pub fn require_build_env(project: &Project) -> Result<()> {
+    if trust::build_env(project).is_empty() {
+        return Ok(());
+    }
+    require(project)
+}
+
+fn print_commands(project: &Project) {
+    let commands = trust::defined_commands(project);
+    if commands.is_empty() {
+        return;
+    }
+    println!("   Its pyproject.toml defines commands and build variables for pyforge to use:");
+    for command in commands {
+        println!("   {} {}", "•".bright_black(), command);
+    }
+}
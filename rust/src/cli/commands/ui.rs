@@ -0,0 +1,275 @@
+use crate::cli::args::Cli;
+use crate::cli::commands::trust;
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::git::Worktree;
+use crate::core::project::Project;
+use crate::core::venv::Venv;
+use crate::core::{fuzzy, history, process};
+use clap::CommandFactory;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::path::PathBuf;
+
+/// Number of history entries offered as recent tasks
+const RECENT_TASKS: usize = 15;
+
+/// What happens when a palette entry is selected
+#[derive(Clone)]
+enum Action {
+    /// Re-invoke pyforge with these arguments
+    Pyforge(Vec<String>),
+    /// Run a configured script through the shell
+    Shell(String),
+}
+
+#[derive(Clone)]
+struct Entry {
+    kind: &'static str,
+    label: String,
+    detail: String,
+    action: Action,
+}
+
+struct App {
+    status: Vec<(String, String)>,
+    dependencies: Vec<String>,
+    entries: Vec<Entry>,
+    query: String,
+    selected: ListState,
+}
+
+pub fn run() -> Result<()> {
+    let project = Project::discover()?;
+    let config = Config::load(project.as_ref())?;
+    let mut app = App::new(project.as_ref(), &config);
+
+    let mut terminal = ratatui::try_init()?;
+    let choice = app.event_loop(&mut terminal);
+    ratatui::restore();
+
+    match choice? {
+        Some(action) => execute(action, project.map(|p| p.root)),
+        None => Ok(()),
+    }
+}
+
+impl App {
+    fn new(project: Option<&Project>, config: &Config) -> Self {
+        let mut status = Vec::new();
+        let mut dependencies = Vec::new();
+
+        match project {
+            Some(project) => {
+                status.push(("Project".to_string(), project.name().unwrap_or("<unnamed>").to_string()));
+                status.push(("Version".to_string(), project.version().unwrap_or("dynamic").to_string()));
+                status.push(("Root".to_string(), project.root.display().to_string()));
+                if let Some(python) = project.requires_python() {
+                    status.push(("Python".to_string(), python.to_string()));
+                }
+                let venv = match Venv::open(project, config) {
+                    Some(venv) => format!("present ({})", venv.display(project)),
+                    None => "missing".to_string(),
+                };
+                status.push(("Venv".to_string(), venv));
+                if let Some(worktree) = Worktree::discover(&project.root) {
+                    if let Some(branch) = &worktree.branch {
+                        status.push(("Branch".to_string(), branch.clone()));
+                    }
+                    if worktree.is_linked() {
+                        status.push(("Worktree".to_string(), worktree.root.display().to_string()));
+                    }
+                }
+
+                dependencies.extend(project.dependencies());
+                for (group, deps) in project.optional_dependencies() {
+                    dependencies.extend(deps.into_iter().map(|dep| format!("[{}] {}", group, dep)));
+                }
+            }
+            None => status.push(("Project".to_string(), "none (not in a pyproject.toml tree)".to_string())),
+        }
+
+        let mut entries = Vec::new();
+
+        for (name, command) in &config.scripts {
+            entries.push(Entry {
+                kind: "script",
+                label: name.clone(),
+                detail: command.clone(),
+                action: Action::Shell(command.clone()),
+            });
+        }
+
+        let root = project.map(|p| p.root.display().to_string());
+        let mut seen = Vec::new();
+        for task in history::recent(RECENT_TASKS * 4) {
+            let in_project = root.as_ref().is_none_or(|root| task.cwd.starts_with(root.as_str()));
+            if task.args.is_empty() || task.args[0] == "ui" || !in_project || seen.contains(&task.args) {
+                continue;
+            }
+            seen.push(task.args.clone());
+            entries.push(Entry {
+                kind: "recent",
+                label: task.args.join(" "),
+                detail: format!("exit {} · {}", task.exit_code, task.timestamp),
+                action: Action::Pyforge(task.args),
+            });
+            if seen.len() == RECENT_TASKS {
+                break;
+            }
+        }
+
+        for command in Cli::command().get_subcommands() {
+            let name = command.get_name();
+            if command.is_hide_set() || name == "ui" || name == "help" {
+                continue;
+            }
+            entries.push(Entry {
+                kind: "command",
+                label: name.to_string(),
+                detail: command.get_about().map(|a| a.to_string()).unwrap_or_default(),
+                action: Action::Pyforge(vec![name.to_string()]),
+            });
+        }
+
+        let mut selected = ListState::default();
+        selected.select(Some(0));
+
+        Self {
+            status,
+            dependencies,
+            entries,
+            query: String::new(),
+            selected,
+        }
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<Option<Action>> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            let matches = self.matches().len();
+            match key.code {
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(None),
+                KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => self.set_query(String::new()),
+                KeyCode::Enter => {
+                    let choice = self
+                        .selected
+                        .selected()
+                        .and_then(|i| self.matches().get(i).map(|entry| entry.action.clone()));
+                    if choice.is_some() {
+                        return Ok(choice);
+                    }
+                }
+                KeyCode::Up => self.selected.select_previous(),
+                KeyCode::Down if self.selected.selected().is_some_and(|i| i + 1 < matches) => {
+                    self.selected.select_next()
+                }
+                KeyCode::Backspace => {
+                    let mut query = self.query.clone();
+                    query.pop();
+                    self.set_query(query);
+                }
+                KeyCode::Char(c) => self.set_query(format!("{}{}", self.query, c)),
+                _ => {}
+            }
+        }
+    }
+
+    fn set_query(&mut self, query: String) {
+        self.query = query;
+        self.selected.select(Some(0));
+    }
+
+    fn matches(&self) -> Vec<&Entry> {
+        fuzzy::rank(&self.query, &self.entries, |entry| entry.label.clone())
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let [main, footer] = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [left, right] = Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(main);
+        let status_height = self.status.len() as u16 + 2;
+        let [status_area, deps_area] =
+            Layout::vertical([Constraint::Length(status_height), Constraint::Min(0)]).areas(left);
+        let [search_area, palette_area] = Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(right);
+
+        let status: Vec<Line> = self
+            .status
+            .iter()
+            .map(|(key, value)| Line::from(vec![Span::raw(format!("{:<8}", key)).bold(), Span::raw(value.clone())]))
+            .collect();
+        frame.render_widget(Paragraph::new(status).block(Block::bordered().title(" Status ")), status_area);
+
+        let deps: Vec<ListItem> = if self.dependencies.is_empty() {
+            vec![ListItem::new("no dependencies declared".dark_gray())]
+        } else {
+            self.dependencies.iter().map(|d| ListItem::new(d.as_str())).collect()
+        };
+        let deps_title = format!(" Dependencies ({}) ", self.dependencies.len());
+        frame.render_widget(List::new(deps).block(Block::bordered().title(deps_title)), deps_area);
+
+        let search = Paragraph::new(format!("> {}", self.query)).block(Block::bordered().title(" Search "));
+        frame.render_widget(search, search_area);
+
+        let items: Vec<ListItem> = self
+            .matches()
+            .into_iter()
+            .map(|entry| {
+                let color = match entry.kind {
+                    "script" => Color::Green,
+                    "recent" => Color::Yellow,
+                    _ => Color::Cyan,
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{:<8}", entry.kind), Style::default().fg(color)),
+                    Span::raw(format!("{:<24} ", entry.label)),
+                    Span::raw(entry.detail.clone()).dark_gray(),
+                ]))
+            })
+            .collect();
+        let palette = List::new(items)
+            .block(Block::bordered().title(" Commands · scripts · recent tasks "))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("▶ ");
+        frame.render_stateful_widget(palette, palette_area, &mut self.selected);
+
+        let help = Line::from("type to search · ↑/↓ move · Enter run · Ctrl-U clear · Esc quit").dark_gray();
+        frame.render_widget(help, footer);
+    }
+}
+
+fn execute(action: Action, root: Option<PathBuf>) -> Result<()> {
+    let (description, status) = match action {
+        Action::Pyforge(args) => {
+            let exe = std::env::current_exe()?;
+            (format!("pyforge {}", args.join(" ")), std::process::Command::new(exe).args(&args).status()?)
+        }
+        Action::Shell(line) => {
+            let mut command = process::shell_command(&line);
+            if let Some(root) = root {
+                trust::require(&Project::load(&root)?)?;
+                command.current_dir(root);
+            }
+            (line, command.status()?)
+        }
+    };
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(PyForgeError::command_failed(description, status.code().unwrap_or(1)))
+    }
+}
+
@@ -0,0 +1,84 @@
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::project::Project;
+use crate::core::selfupdate::{self, Channel, Installed};
+use colored::*;
+
+pub fn run(channel: Option<Channel>, version: Option<&str>, allow_downgrade: bool, check: bool) -> Result<()> {
+    let project = Project::discover()?;
+    let config = Config::load(project.as_ref())?;
+    let installed = Installed::current();
+
+    // A project's pin keeps the whole team on one channel
+    let pinned = project
+        .as_ref()
+        .and_then(|project| project.tool_config())
+        .and_then(|tool| tool.get("self-update"))
+        .and_then(|table| table.get("channel"))
+        .and_then(|channel| channel.clone().try_into::<Channel>().ok());
+    if let (Some(pinned), Some(requested)) = (pinned, channel)
+        && pinned != requested
+    {
+        return Err(PyForgeError::Usage {
+            message: format!(
+                "This project pins the {} channel in [tool.pyforge.self-update]; run outside it to follow {}",
+                pinned, requested
+            ),
+        });
+    }
+    let channel = channel.or(pinned).or(config.self_update.channel).unwrap_or(installed.channel);
+
+    let releases = selfupdate::releases(&config)?;
+    let target = match version {
+        Some(version) => releases
+            .iter()
+            .find(|release| release.tag == version || release.tag.strip_prefix('v') == Some(version))
+            .ok_or_else(|| PyForgeError::Usage {
+                message: format!("No release {} with a binary for this platform", version),
+            })?,
+        None => releases.iter().find(|release| release.channel == channel).ok_or_else(|| PyForgeError::Usage {
+            message: format!("No {} release with a binary for this platform", channel),
+        })?,
+    };
+
+    if target.tag == installed.tag {
+        println!("{} pyforge {} ({}) is up to date", "✅".green(), installed.tag.cyan(), installed.channel);
+        return Ok(());
+    }
+
+    // Nightlies are built ahead of the latest stable release
+    let downgrade = match (installed.channel, target.channel) {
+        (Channel::Nightly, Channel::Stable) => true,
+        _ => matches!((installed.version(), target.version()), (Some(current), Some(next)) if next < current),
+    };
+    println!(
+        "{} {} ({}) → {} ({})",
+        if downgrade { "⬇️" } else { "⬆️" },
+        installed.tag,
+        installed.channel,
+        target.tag.cyan(),
+        target.channel
+    );
+    if downgrade && !allow_downgrade {
+        return Err(PyForgeError::Usage {
+            message: format!(
+                "{} is older than the running {}; settings and lockfiles written since may not load in it. \
+                 Pass --allow-downgrade to install it anyway",
+                target.tag, installed.tag
+            ),
+        });
+    }
+    if check {
+        return Ok(());
+    }
+
+    let path = selfupdate::install(&config, target)?;
+    println!("{} Installed pyforge {} at {}", "✅".green(), target.tag.cyan(), path.display());
+    if target.channel == Channel::Nightly {
+        println!(
+            "   To go back: {}",
+            "pyforge upgrade-self --channel stable --allow-downgrade".cyan()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,238 @@
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::index::IndexClient;
+use crate::core::inline::{self, Provenance, VendoredPackage, WheelContents};
+use crate::core::lockfile::Lockfile;
+use crate::core::parallel;
+use crate::core::project::Project;
+use crate::core::toolchain;
+use colored::*;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use toml_edit::{Array, Item, Table, value};
+
+pub fn run(dir: Option<&str>, inline: bool, frozen_toolchain: bool, jobs: Option<usize>) -> Result<()> {
+    let mut project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let lockfile = Lockfile::require(&project)?;
+    toolchain::check(&lockfile, frozen_toolchain || config.frozen_toolchain)?;
+    if !lockfile.is_fresh(&project) {
+        println!(
+            "{} pyproject.toml changed since the last lock; run 'pyforge lock' to vendor the current dependencies",
+            "⚠️".yellow()
+        );
+    }
+
+    let jobs = parallel::jobs(&config, jobs);
+    if inline {
+        return run_inline(&project, &config, &lockfile, dir, jobs);
+    }
+    let dir = dir.unwrap_or("vendor");
+
+    let vendor_dir = project.root.join(dir);
+    fs::create_dir_all(&vendor_dir)
+        .map_err(|e| PyForgeError::file_error(format!("Could not create {}", vendor_dir.display()), e))?;
+
+    let client = IndexClient::new(&config)?;
+    let mut kept = BTreeSet::new();
+    let mut files = Vec::new();
+
+    // Path dependencies live in the repository already
+    for package in lockfile.packages.iter().filter(|p| p.path.is_none()) {
+        let Some(file) = package.files.first() else {
+            return Err(PyForgeError::ResolutionFailed {
+                package: package.name.clone(),
+                reason: "the lockfile lists no installable file".to_string(),
+            });
+        };
+        kept.insert(file.name.clone());
+        files.push((package, file));
+    }
+
+    // Present files are re-hashed and missing ones downloaded, `jobs` at a time
+    let fetched = parallel::map(jobs, &files, |(package, file)| {
+        let path = vendor_dir.join(&file.name);
+        if path.is_file() && verify(&path, file.sha256()).is_ok() {
+            return Ok(false);
+        }
+        println!("  {} {} {}", "⬇".cyan(), package.name, package.version.bright_black());
+        client.download_to(&file.url, &path, file.sha256())?;
+        Ok(true)
+    })
+    .into_iter()
+    .collect::<Result<Vec<bool>>>()?;
+    let downloaded = fetched.iter().filter(|fetched| **fetched).count();
+    let reused = fetched.len() - downloaded;
+
+    let pruned = prune(&vendor_dir, &kept)?;
+
+    project.update_pyproject(|document| {
+        let tool = document.entry("tool").or_insert_with(implicit_table);
+        let Some(tool) = tool.as_table_like_mut() else {
+            return;
+        };
+        let pyforge = tool.entry("pyforge").or_insert_with(implicit_table);
+        let Some(pyforge) = pyforge.as_table_like_mut() else {
+            return;
+        };
+        let install = pyforge.entry("install").or_insert(Item::Table(Table::new()));
+        install["no-index"] = value(true);
+        install["find-links"] = value(Array::from_iter([dir]));
+    })?;
+
+    println!(
+        "{} Vendored {} package(s) into {}/ ({} downloaded, {} already present{})",
+        "✅".green(),
+        lockfile.packages.len(),
+        dir.cyan(),
+        downloaded,
+        reused,
+        if pruned > 0 { format!(", {} stale removed", pruned) } else { String::new() }
+    );
+    println!("   Installs now use {}/ only ([tool.pyforge.install] in pyproject.toml).", dir);
+    Ok(())
+}
+
+/// Copy pure-Python dependencies into the project's `_vendor` package, rewriting their imports
+fn run_inline(project: &Project, config: &Config, lockfile: &Lockfile, dir: Option<&str>, jobs: usize) -> Result<()> {
+    let dir = match dir {
+        Some(dir) => dir.to_string(),
+        None => default_inline_dir(project)?,
+    };
+    let vendor_dir = project.root.join(&dir);
+    let prefix = import_prefix(&dir);
+    fs::create_dir_all(&vendor_dir)
+        .map_err(|e| PyForgeError::file_error(format!("Could not create {}", vendor_dir.display()), e))?;
+
+    let init = vendor_dir.join("__init__.py");
+    if !init.exists() {
+        fs::write(&init, "")?;
+    }
+
+    // Drop what the previous run copied so removed or upgraded packages don't linger
+    let previous = Provenance::load(&vendor_dir)?;
+    for package in &previous.packages {
+        inline::remove_modules(&vendor_dir, package)?;
+    }
+
+    let client = IndexClient::new(config)?;
+    let mut provenance = Provenance::default();
+    let mut skipped = Vec::new();
+
+    let mut wheels = Vec::new();
+    for package in lockfile.packages.iter().filter(|p| p.path.is_none()) {
+        match package.files.iter().find(|f| f.name.ends_with("-none-any.whl")) {
+            Some(file) => wheels.push((package, file)),
+            None => skipped.push(package.name.clone()),
+        }
+    }
+    let downloads = parallel::map(jobs, &wheels, |(_, file)| client.download_temp(&file.url, file.sha256()));
+
+    for ((package, file), download) in wheels.into_iter().zip(downloads) {
+        let mut wheel = WheelContents::open(download?)?;
+        if !wheel.pure {
+            skipped.push(package.name.clone());
+            continue;
+        }
+
+        wheel.extract(&vendor_dir, &package.name)?;
+        println!(
+            "  {} {} {} → {}",
+            "📥".cyan(),
+            package.name,
+            package.version.bright_black(),
+            wheel.modules.iter().map(|m| format!("{}.{}", prefix, m)).collect::<Vec<_>>().join(", ")
+        );
+        provenance.packages.push(VendoredPackage {
+            name: package.name.clone(),
+            version: package.version.clone(),
+            wheel: file.name.clone(),
+            url: file.url.clone(),
+            sha256: file.sha256().map(str::to_string),
+            license: package.license.clone(),
+            modules: wheel.modules.clone(),
+        });
+    }
+
+    let rewritten = inline::rewrite_tree(&vendor_dir, &provenance.modules(), &prefix)?;
+    provenance.write(&vendor_dir)?;
+
+    println!(
+        "{} Inlined {} package(s) into {}/ ({} file(s) with rewritten imports)",
+        "✅".green(),
+        provenance.packages.len(),
+        dir.cyan(),
+        rewritten
+    );
+    println!("   Import them as {}.<module>; provenance is recorded in {}.", prefix, inline::PROVENANCE_FILE);
+    if !skipped.is_empty() {
+        println!(
+            "{} Not pure Python, still installed normally: {}",
+            "⚠️".yellow(),
+            skipped.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// `<package>/_vendor` inside the project's import package (`src/` layout or flat)
+fn default_inline_dir(project: &Project) -> Result<String> {
+    let import_name = project.name().unwrap_or_default().replace(['-', '.'], "_").to_lowercase();
+    ["src", ""]
+        .iter()
+        .map(|base| Path::new(base).join(&import_name))
+        .find(|candidate| project.root.join(candidate).join("__init__.py").is_file())
+        .map(|package: PathBuf| package.join("_vendor").to_string_lossy().replace('\\', "/"))
+        .ok_or_else(|| PyForgeError::MissingConfig {
+            key: format!("import package '{}'", import_name),
+            example: format!("pyforge vendor --inline --dir src/{}/_vendor", import_name),
+        })
+}
+
+/// Dotted import path of a vendor directory, e.g. `src/app/_vendor` → `app._vendor`
+fn import_prefix(dir: &str) -> String {
+    let dir = dir.trim_matches('/');
+    dir.strip_prefix("src/").unwrap_or(dir).replace('/', ".")
+}
+
+fn implicit_table() -> Item {
+    let mut table = Table::new();
+    table.set_implicit(true);
+    Item::Table(table)
+}
+
+/// Check a file against its locked hash
+fn verify(path: &Path, sha256: Option<&str>) -> Result<()> {
+    let Some(expected) = sha256 else {
+        return Ok(());
+    };
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    let actual = format!("{:x}", hasher.finalize());
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(PyForgeError::HashMismatch {
+            file: path.display().to_string(),
+            expected: expected.to_string(),
+            actual,
+        })
+    }
+}
+
+/// Remove distribution files no longer in the lockfile, returning how many were removed
+fn prune(vendor_dir: &Path, kept: &BTreeSet<String>) -> Result<usize> {
+    let mut removed = 0;
+    for entry in fs::read_dir(vendor_dir)?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let is_dist = name.ends_with(".whl") || name.ends_with(".tar.gz") || name.ends_with(".zip");
+        if is_dist && !kept.contains(&name) {
+            fs::remove_file(entry.path())?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
@@ -0,0 +1,63 @@
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::lockfile::Lockfile;
+use crate::core::parallel;
+use crate::core::project::Project;
+use crate::core::venv::Venv;
+use crate::core::verify;
+use colored::*;
+
+pub fn run(jobs: Option<usize>) -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let venv = Venv::open(&project, &config).ok_or_else(|| PyForgeError::DirectoryNotFound {
+        path: Venv::location(&project, &config).display().to_string(),
+    })?;
+    let lockfile = Lockfile::load(&project)?;
+    match (&lockfile, venv.sync_state()) {
+        (None, _) => println!("{} No pyforge.lock; checking installed files against RECORD only", "⚠️".yellow()),
+        (Some(lockfile), Some(state)) if state.input_hash != lockfile.input_hash => println!(
+            "{} {} was last synced from a different lockfile ({}{}); run 'pyforge sync' first",
+            "⚠️".yellow(),
+            venv.display(&project),
+            state.worktree.display(),
+            state.branch.map(|branch| format!(", branch {}", branch)).unwrap_or_default()
+        ),
+        _ => {}
+    }
+
+    let checks = verify::verify(&venv.site_packages()?, lockfile.as_ref(), parallel::jobs(&config, jobs))?;
+
+    let width = checks.iter().map(|c| c.name.len()).max().unwrap_or(7).max(7);
+    println!(
+        "{:<width$}  {:<12}  {:>5}  {}",
+        "PACKAGE".bold(),
+        "VERSION".bold(),
+        "FILES".bold(),
+        "RESULT".bold(),
+        width = width
+    );
+    for check in &checks {
+        let result = if check.passed() {
+            "✅ pass".green().to_string()
+        } else {
+            format!("{} {}", "❌ fail:".red(), check.problems.join(", "))
+        };
+        println!(
+            "{:<width$}  {:<12}  {:>5}  {}",
+            check.name,
+            check.version.as_deref().unwrap_or("-"),
+            check.checked,
+            result,
+            width = width
+        );
+    }
+
+    let failed: Vec<String> = checks.iter().filter(|c| !c.passed()).map(|c| c.name.clone()).collect();
+    println!();
+    if !failed.is_empty() {
+        return Err(PyForgeError::VerificationFailed { packages: failed });
+    }
+    println!("{} {} package(s) verified", "✅".green(), checks.len());
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use crate::core::config::Config;
+use crate::core::error::Result;
+use crate::core::project::Project;
+use crate::core::versioning;
+use colored::*;
+
+pub fn run(resolved: bool) -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let version = versioning::resolve(&project, &config)?;
+
+    if resolved {
+        println!("{}", version);
+    } else if versioning::is_dynamic(&project) {
+        println!(
+            "{} {} {}",
+            project.name().unwrap_or("<unnamed>"),
+            version.cyan(),
+            "(dynamic, from git tags)".bright_black()
+        );
+    } else {
+        println!("{} {}", project.name().unwrap_or("<unnamed>"), version.cyan());
+    }
+    Ok(())
+}
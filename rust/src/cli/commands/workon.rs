@@ -0,0 +1,107 @@
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::process::shell_quote;
+use crate::core::project::Project;
+use crate::core::registry::{self, Entry};
+use crate::core::venv::Venv;
+use colored::*;
+use std::path::Path;
+use std::process::Command;
+
+pub fn run(query: Option<&str>, shell: bool, add: Option<&Path>, forget: Option<&str>) -> Result<()> {
+    if let Some(dir) = add {
+        return register(dir);
+    }
+    if let Some(name) = forget {
+        let removed = registry::forget(name)?;
+        if removed.is_empty() {
+            return Err(PyForgeError::Usage {
+                message: format!("No registered project is named '{}'; see pyforge workon", name),
+            });
+        }
+        for entry in removed {
+            eprintln!("{} Forgot {} ({})", "🗑️".cyan(), entry.name.cyan(), entry.path.display());
+        }
+        return Ok(());
+    }
+    match query {
+        Some(query) => switch(&registry::find(query)?, shell),
+        None => list(),
+    }
+}
+
+fn register(dir: &Path) -> Result<()> {
+    let dir = dir
+        .canonicalize()
+        .map_err(|e| PyForgeError::file_error(format!("Could not read {}", dir.display()), e))?;
+    let added = registry::add(&dir)?;
+    if added.is_empty() {
+        return Err(PyForgeError::Usage {
+            message: format!("No pyproject.toml in {} or the directories directly below it", dir.display()),
+        });
+    }
+    for entry in added {
+        eprintln!("{} Registered {} ({})", "📌".green(), entry.name.cyan(), entry.path.display());
+    }
+    Ok(())
+}
+
+fn list() -> Result<()> {
+    let entries = registry::entries();
+    if entries.is_empty() {
+        println!("{} No registered projects; add one with {}", "📭".cyan(), "pyforge workon --add <dir>".cyan());
+        return Ok(());
+    }
+    let current = Project::discover()?.and_then(|project| project.root.canonicalize().ok());
+    let width = entries.iter().map(|entry| entry.name.len()).max().unwrap_or(0);
+    for entry in entries {
+        let name = format!("{:<width$}", entry.name, width = width);
+        if !entry.path.is_dir() {
+            println!("  {} {} {}", name.bright_black(), entry.path.display().to_string().bright_black(), "(missing)".yellow());
+        } else if current.as_ref() == Some(&entry.path) {
+            println!("{} {} {}", "▶".green(), name.green(), entry.path.display().to_string().green());
+        } else {
+            println!("  {} {}", name, entry.path.display());
+        }
+    }
+    Ok(())
+}
+
+fn switch(entry: &Entry, shell: bool) -> Result<()> {
+    let project = Project::load(&entry.path)?;
+    let config = Config::load(Some(&project))?;
+    let venv = Venv::open(&project, &config);
+    if venv.is_none() {
+        eprintln!("{} {} has no environment yet; run {} there", "⚠️".yellow(), entry.name, "pyforge sync".cyan());
+    }
+
+    if shell {
+        let program = std::env::var_os(if cfg!(windows) { "COMSPEC" } else { "SHELL" })
+            .unwrap_or_else(|| if cfg!(windows) { "cmd.exe".into() } else { "/bin/sh".into() });
+        let mut command = Command::new(&program);
+        command.current_dir(&entry.path);
+        if let Some(venv) = &venv {
+            venv.activate(&mut command)?;
+        }
+        eprintln!("{} Working on {} in {}; exit the shell to leave", "🚀".cyan(), entry.name.cyan(), entry.path.display());
+        // The shell's exit status is whatever its last command returned, not a failure of ours
+        command.status().map_err(|e| PyForgeError::file_error(format!("Could not start {}", program.to_string_lossy()), e))?;
+        return Ok(());
+    }
+
+    // stdout is for `eval`; everything else goes to stderr
+    let root = entry.path.display().to_string();
+    if cfg!(windows) {
+        println!("cd /d \"{}\"", root);
+        if let Some(venv) = &venv {
+            println!("call \"{}\"", venv.bin_dir().join("activate.bat").display());
+        }
+    } else {
+        println!("cd {}", shell_quote(&root));
+        if let Some(venv) = &venv {
+            println!(". {}", shell_quote(&venv.bin_dir().join("activate").display().to_string()));
+        }
+    }
+    eprintln!("{} Working on {} ({})", "🚀".cyan(), entry.name.cyan(), root);
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use crate::core::config::Config;
+use crate::core::dist::DistFilename;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::index::IndexClient;
+use crate::core::pep440::Version;
+use crate::core::project::Project;
+use crate::core::pypi;
+use colored::*;
+
+pub fn run(version: &str, reason: Option<&str>) -> Result<()> {
+    let project = Project::require()?;
+    let config = Config::load(Some(&project))?;
+    let name = project.name().ok_or_else(|| PyForgeError::MissingConfig {
+        key: "project.name".to_string(),
+        example: "[project]\nname = \"my-package\"".to_string(),
+    })?;
+    let wanted = Version::parse(version)?;
+
+    // The release must exist before there is anything to yank
+    let index = config.indexes().remove(0);
+    let client = IndexClient::new(&config)?;
+    let files: Vec<_> = client
+        .project_files(&index, name)?
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|file| DistFilename::parse(&file.filename, name).is_some_and(|dist| dist.version == wanted))
+        .collect();
+    if files.is_empty() {
+        return Err(PyForgeError::Usage {
+            message: format!("{} {} is not on {} ({})", name, version, index.name, index.url),
+        });
+    }
+    if files.iter().all(|file| file.yanked) {
+        println!("{} {} {} is already yanked on {}", "ℹ️".blue(), name, version, index.name);
+        return Ok(());
+    }
+
+    println!(
+        "{} {} {} has {} file(s) on {}",
+        "🔎".cyan(),
+        name,
+        version.cyan(),
+        files.len(),
+        index.name
+    );
+    // Warehouse has no API for yanking; it is only offered to owners on the website
+    println!(
+        "{} PyPI only lets project owners yank from its website. Open\n   {}\n   choose {} and confirm with the version number.",
+        "👉".yellow(),
+        pypi::manage_release_url(&config, name, version).cyan(),
+        "Options → Yank".bold()
+    );
+    if let Some(reason) = reason {
+        println!("   Reason to enter: {}", reason);
+    }
+    println!(
+        "   Installers then skip {} unless it is pinned with =={}.",
+        version, version
+    );
+    Ok(())
+}
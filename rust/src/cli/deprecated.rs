@@ -0,0 +1,114 @@
+//! Commands and flags that were renamed, kept working for a while.
+//!
+//! An old spelling is rewritten to its replacement before clap parses the
+//! arguments, so it never shows up in `--help`, with a warning naming the
+//! release that removes it. From that release on, the old spelling is an
+//! error pointing at the new one. `pyforge debug deprecations --json` lists
+//! the table for scripts that want to check themselves.
+
+use crate::cli::alias;
+use crate::cli::args::Cli;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::pep440::Version;
+use clap::CommandFactory;
+use colored::*;
+use serde::Serialize;
+
+/// A renamed command or flag
+///
+/// `old` and `new` are written as on the command line after `pyforge`: a
+/// subcommand path such as `env path`, optionally followed by one flag of that
+/// subcommand, such as `build --no-cache`.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Deprecation {
+    pub old: &'static str,
+    pub new: &'static str,
+    /// First release warning about `old`
+    pub since: &'static str,
+    /// First release rejecting `old`
+    pub removal: &'static str,
+}
+
+/// Every rename, oldest first. Entries stay after their removal release so
+/// the old spelling keeps a helpful error.
+pub const DEPRECATIONS: &[Deprecation] = &[];
+
+impl Deprecation {
+    fn command(spelling: &str) -> Vec<&str> {
+        spelling.split_whitespace().take_while(|token| !token.starts_with('-')).collect()
+    }
+
+    fn flag(spelling: &str) -> Option<&str> {
+        spelling.split_whitespace().find(|token| token.starts_with('-'))
+    }
+
+    /// Whether this release no longer accepts the old spelling
+    pub fn removed(&self) -> bool {
+        match (Version::parse(env!("CARGO_PKG_VERSION")), Version::parse(self.removal)) {
+            (Ok(current), Ok(removal)) => current >= removal,
+            _ => false,
+        }
+    }
+}
+
+/// Replace deprecated spellings in `args` (including `argv[0]`), warning on stderr
+pub fn rewrite(mut args: Vec<String>) -> Result<Vec<String>> {
+    if DEPRECATIONS.is_empty() {
+        return Ok(args);
+    }
+    let Some(position) = alias::subcommand_position(&Cli::command(), &args) else {
+        return Ok(args);
+    };
+
+    for deprecation in DEPRECATIONS {
+        let old = Deprecation::command(deprecation.old);
+        let end = position + old.len();
+        if args.len() < end || args[position..end].iter().zip(&old).any(|(arg, token)| arg != token) {
+            continue;
+        }
+
+        let replaced = match Deprecation::flag(deprecation.old) {
+            None => Some(position..end),
+            Some(flag) => args[end..]
+                .iter()
+                .take_while(|arg| *arg != "--")
+                .position(|arg| arg == flag || arg.strip_prefix(flag).is_some_and(|rest| rest.starts_with('=')))
+                .map(|offset| end + offset..end + offset + 1),
+        };
+        let Some(range) = replaced else {
+            continue;
+        };
+
+        if deprecation.removed() {
+            return Err(PyForgeError::Usage {
+                message: format!(
+                    "`pyforge {}` was removed in {}; use `pyforge {}`",
+                    deprecation.old, deprecation.removal, deprecation.new
+                ),
+            });
+        }
+        eprintln!(
+            "{} `pyforge {}` is deprecated since {} and will be removed in {}; use `pyforge {}`",
+            "⚠️".yellow(),
+            deprecation.old,
+            deprecation.since,
+            deprecation.removal,
+            deprecation.new.cyan()
+        );
+
+        let replacement: Vec<String> = match Deprecation::flag(deprecation.new) {
+            // Keep a value given as `--old=value`
+            Some(flag) if Deprecation::flag(deprecation.old).is_some() => {
+                let value = args[range.start].split_once('=').map(|(_, value)| value.to_string());
+                vec![match value {
+                    Some(value) => format!("{}={}", flag, value),
+                    None => flag.to_string(),
+                }]
+            }
+            _ => deprecation.new.split_whitespace().map(str::to_string).collect(),
+        };
+        args.splice(range, replacement);
+    }
+
+    Ok(args)
+}
@@ -0,0 +1,87 @@
+use colored::*;
+
+/// A long-form help page shown by `pyforge help <topic>`
+pub struct Topic {
+    pub name: &'static str,
+    pub summary: &'static str,
+    pub body: &'static str,
+}
+
+pub const TOPICS: &[Topic] = &[
+    Topic {
+        name: "configuration",
+        summary: "Global and per-project settings, indexes and mirrors",
+        body: include_str!("help/configuration.md"),
+    },
+    Topic {
+        name: "presets",
+        summary: "Tooling presets for `pyforge init`, features for existing projects",
+        body: include_str!("help/presets.md"),
+    },
+    Topic {
+        name: "aliases",
+        summary: "Defining command shortcuts",
+        body: include_str!("help/aliases.md"),
+    },
+    Topic {
+        name: "lockfile",
+        summary: "pyforge.lock, how dependencies are resolved, and vendoring",
+        body: include_str!("help/lockfile.md"),
+    },
+    Topic {
+        name: "policy",
+        summary: "Organization policies: allowed indexes, banned packages, licenses",
+        body: include_str!("help/policy.md"),
+    },
+    Topic {
+        name: "exit-codes",
+        summary: "Exit codes and machine-readable errors for scripting",
+        body: include_str!("help/exit-codes.md"),
+    },
+    Topic {
+        name: "plumbing",
+        summary: "`pyforge internal`: stable JSON output for scripts and tools",
+        body: include_str!("help/plumbing.md"),
+    },
+];
+
+pub fn find(name: &str) -> Option<&'static Topic> {
+    TOPICS.iter().find(|topic| topic.name == name)
+}
+
+/// Render a topic's markdown for the terminal
+pub fn render(markdown: &str) -> String {
+    let mut out = String::new();
+    let mut in_code = false;
+
+    for line in markdown.lines() {
+        if line.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+
+        let rendered = if in_code {
+            format!("    {}", line.cyan())
+        } else if let Some(title) = line.strip_prefix("# ") {
+            title.to_uppercase().bold().underline().to_string()
+        } else if let Some(title) = line.strip_prefix("## ") {
+            title.yellow().bold().to_string()
+        } else if let Some(item) = line.strip_prefix("- ") {
+            format!("  • {}", inline_code(item))
+        } else {
+            inline_code(line)
+        };
+
+        out.push_str(&rendered);
+        out.push('\n');
+    }
+    out
+}
+
+/// Highlight `code` spans
+fn inline_code(line: &str) -> String {
+    line.split('`')
+        .enumerate()
+        .map(|(i, part)| if i % 2 == 1 { part.cyan().to_string() } else { part.to_string() })
+        .collect()
+}
@@ -1,5 +1,10 @@
 pub mod commands;
 pub mod args;
+pub mod alias;
+pub mod deprecated;
+pub mod help;
+pub mod picker;
+pub mod prompt;
 
 pub use args::Cli;
 pub use commands::*;
\ No newline at end of file
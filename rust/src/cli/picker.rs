@@ -0,0 +1,255 @@
+//! Checkbox list of available updates for `pyforge bump --interactive`,
+//! grouped by how much each one changes the version, with how far into the
+//! project each one reaches.
+
+use crate::core::error::Result;
+use crate::core::lockfile::VersionChange;
+use crate::core::pep440::Version;
+use crate::core::reach::{Analysis, Radius};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState};
+use ratatui::{DefaultTerminal, Frame};
+
+/// How far an update moves a package, by the first release segment that changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Impact {
+    Major,
+    Minor,
+    Patch,
+    /// Pre-releases, post-releases, downgrades and versions that don't parse
+    Other,
+}
+
+impl Impact {
+    pub fn of(old: &str, new: &str) -> Self {
+        let (Ok(old), Ok(new)) = (Version::parse(old), Version::parse(new)) else {
+            return Impact::Other;
+        };
+        if new <= old || new.is_prerelease() {
+            return Impact::Other;
+        }
+        if old.epoch != new.epoch || old.release_at(0) != new.release_at(0) {
+            Impact::Major
+        } else if old.release_at(1) != new.release_at(1) {
+            Impact::Minor
+        } else if old.release_at(2) != new.release_at(2) {
+            Impact::Patch
+        } else {
+            Impact::Other
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Impact::Major => "Major (may break)",
+            Impact::Minor => "Minor",
+            Impact::Patch => "Patch",
+            Impact::Other => "Other",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Impact::Major => Color::Red,
+            Impact::Minor => Color::Yellow,
+            Impact::Patch => Color::Green,
+            Impact::Other => Color::Cyan,
+        }
+    }
+}
+
+/// A list row: a group heading, or an update that can be ticked
+enum Row {
+    Heading(Impact),
+    Update { name: String, old: String, new: String, reach: String, checked: bool },
+}
+
+struct Picker {
+    rows: Vec<Row>,
+    state: ListState,
+}
+
+/// Let the user choose among the updated packages of `changes` (added and
+/// removed packages follow from the others and are not offered). Returns the
+/// chosen names, or `None` when cancelled. Patch and minor updates start
+/// ticked, major ones don't.
+pub fn pick(changes: &[VersionChange], analysis: &Analysis) -> Result<Option<Vec<String>>> {
+    let mut updates: Vec<(Impact, &VersionChange, &str, &str)> = changes
+        .iter()
+        .filter_map(|change| {
+            let (old, new) = (change.old.as_deref()?, change.new.as_deref()?);
+            Some((Impact::of(old, new), change, old, new))
+        })
+        .collect();
+    updates.sort_by(|a, b| (a.0, &a.1.name).cmp(&(b.0, &b.1.name)));
+
+    let mut rows = Vec::new();
+    for (impact, change, old, new) in updates {
+        if last_impact(&rows) != Some(impact) {
+            rows.push(Row::Heading(impact));
+        }
+        rows.push(Row::Update {
+            name: change.name.clone(),
+            old: old.to_string(),
+            new: new.to_string(),
+            reach: analysis.get(&change.name).map(|reach| reach_label(reach.radius, reach.modules.len())).unwrap_or_default(),
+            checked: matches!(impact, Impact::Minor | Impact::Patch),
+        });
+    }
+    let mut picker = Picker {
+        rows,
+        state: ListState::default(),
+    };
+    picker.state.select(picker.rows.iter().position(|row| matches!(row, Row::Update { .. })));
+
+    let mut terminal = ratatui::try_init()?;
+    let confirmed = picker.event_loop(&mut terminal);
+    ratatui::restore();
+
+    Ok(confirmed?.then(|| {
+        picker
+            .rows
+            .into_iter()
+            .filter_map(|row| match row {
+                Row::Update { name, checked: true, .. } => Some(name),
+                _ => None,
+            })
+            .collect()
+    }))
+}
+
+/// `high · 7 module(s)`
+fn reach_label(radius: Radius, modules: usize) -> String {
+    match modules {
+        0 => format!("{} · no module imports it", radius),
+        count => format!("{} · {} module(s)", radius, count),
+    }
+}
+
+fn last_impact(rows: &[Row]) -> Option<Impact> {
+    rows.iter().rev().find_map(|row| match row {
+        Row::Heading(impact) => Some(*impact),
+        Row::Update { .. } => None,
+    })
+}
+
+impl Picker {
+    /// Whether the selection was confirmed
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<bool> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(false),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(false),
+                KeyCode::Enter => return Ok(true),
+                KeyCode::Up | KeyCode::Char('k') => self.step(-1),
+                KeyCode::Down | KeyCode::Char('j') => self.step(1),
+                KeyCode::Char(' ') => {
+                    if let Some(Row::Update { checked, .. }) = self.state.selected().and_then(|i| self.rows.get_mut(i)) {
+                        *checked = !*checked;
+                    }
+                }
+                // Tick or untick the whole group of the selected update
+                KeyCode::Char('g') => self.toggle_group(),
+                KeyCode::Char('a') => self.set_all(true),
+                KeyCode::Char('n') => self.set_all(false),
+                _ => {}
+            }
+        }
+    }
+
+    /// Move the selection to the next update up or down, skipping headings
+    fn step(&mut self, direction: isize) {
+        let Some(mut i) = self.state.selected() else {
+            return;
+        };
+        loop {
+            let Some(next) = i.checked_add_signed(direction).filter(|next| *next < self.rows.len()) else {
+                return;
+            };
+            i = next;
+            if matches!(self.rows[i], Row::Update { .. }) {
+                self.state.select(Some(i));
+                return;
+            }
+        }
+    }
+
+    fn toggle_group(&mut self) {
+        let Some(selected) = self.state.selected() else {
+            return;
+        };
+        let start = self.rows[..selected].iter().rposition(|row| matches!(row, Row::Heading(_))).map_or(0, |i| i + 1);
+        let end = self.rows[selected..]
+            .iter()
+            .position(|row| matches!(row, Row::Heading(_)))
+            .map_or(self.rows.len(), |i| selected + i);
+        let all_checked = self.rows[start..end].iter().all(|row| matches!(row, Row::Update { checked: true, .. }));
+        for row in &mut self.rows[start..end] {
+            if let Row::Update { checked, .. } = row {
+                *checked = !all_checked;
+            }
+        }
+    }
+
+    fn set_all(&mut self, value: bool) {
+        for row in &mut self.rows {
+            if let Row::Update { checked, .. } = row {
+                *checked = value;
+            }
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let [list_area, footer] = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let width = self
+            .rows
+            .iter()
+            .filter_map(|row| match row {
+                Row::Update { name, .. } => Some(name.len()),
+                Row::Heading(_) => None,
+            })
+            .max()
+            .unwrap_or(0);
+
+        let mut impact = Impact::Other;
+        let items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .map(|row| match row {
+                Row::Heading(heading) => {
+                    impact = *heading;
+                    ListItem::new(Line::from(Span::styled(
+                        heading.title(),
+                        Style::default().fg(heading.color()).add_modifier(Modifier::BOLD),
+                    )))
+                }
+                Row::Update { name, old, new, reach, checked } => ListItem::new(Line::from(vec![
+                    Span::raw(if *checked { "  [x] " } else { "  [ ] " }),
+                    Span::raw(format!("{:<width$}  ", name, width = width)),
+                    Span::raw(format!("{} → ", old)).dark_gray(),
+                    Span::styled(new.clone(), Style::default().fg(impact.color())),
+                    Span::raw(format!("  {}", reach)).dark_gray(),
+                ])),
+            })
+            .collect();
+        let chosen = self.rows.iter().filter(|row| matches!(row, Row::Update { checked: true, .. })).count();
+        let list = List::new(items)
+            .block(Block::bordered().title(format!(" Updates · {} selected ", chosen)))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, list_area, &mut self.state);
+
+        let help = Line::from("↑/↓ move · Space tick · g group · a all · n none · Enter apply · Esc cancel").dark_gray();
+        frame.render_widget(help, footer);
+    }
+}
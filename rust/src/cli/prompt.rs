@@ -0,0 +1,20 @@
+//! Questions on the terminal. Callers check that stdin is a terminal first
+//! and decide what a missing answer means.
+
+use crate::core::error::Result;
+use std::io::{self, BufRead, Write};
+
+/// Print `question` and read one line, trimmed
+pub fn ask(question: &str) -> Result<String> {
+    print!("{} ", question);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(answer.trim().to_string())
+}
+
+/// Ask a yes/no `question`; anything but "y" or "yes" is no
+pub fn confirm(question: &str) -> Result<bool> {
+    let answer = ask(&format!("{} [y/N]", question))?;
+    Ok(matches!(answer.to_lowercase().as_str(), "y" | "yes"))
+}
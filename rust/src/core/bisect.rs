@@ -0,0 +1,72 @@
+//! Binary search for the candidate that broke something (`pyforge bisect`).
+//!
+//! Candidates are ordered oldest first; the first is known to be good and the
+//! last known to be bad. Like `git bisect run`, a step's command exits with 0
+//! when good and 125 when the candidate can't be tested; any other exit code
+//! means bad.
+
+use crate::core::error::Result;
+use std::collections::BTreeSet;
+use std::process::ExitStatus;
+
+/// Exit code that marks a candidate as untestable
+pub const SKIP_EXIT_CODE: i32 = 125;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Good,
+    Bad,
+    Skip,
+}
+
+impl Verdict {
+    pub fn from_status(status: ExitStatus) -> Self {
+        match status.code() {
+            Some(0) => Verdict::Good,
+            Some(SKIP_EXIT_CODE) => Verdict::Skip,
+            _ => Verdict::Bad,
+        }
+    }
+}
+
+/// Where the search ended, as indexes into the candidates
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The newest candidate known to be good
+    pub good: usize,
+    /// The oldest candidate known to be bad
+    pub bad: usize,
+    /// Skipped candidates between the two, any of which may be the first bad one
+    pub skipped: Vec<usize>,
+}
+
+/// Steps left at most when `untested` candidates remain
+pub fn steps_left(untested: usize) -> u32 {
+    (untested + 1).next_power_of_two().trailing_zeros()
+}
+
+/// Bisect `count` candidates, calling `test` with the index of each one to
+/// try and the steps left at most, counting this one
+pub fn search(count: usize, mut test: impl FnMut(usize, u32) -> Result<Verdict>) -> Result<Outcome> {
+    let mut good = 0;
+    let mut bad = count.saturating_sub(1);
+    let mut skipped = BTreeSet::new();
+    loop {
+        let untested: Vec<usize> = (good + 1..bad).filter(|index| !skipped.contains(index)).collect();
+        let Some(&next) = untested.get(untested.len() / 2) else {
+            break;
+        };
+        match test(next, steps_left(untested.len()))? {
+            Verdict::Good => good = next,
+            Verdict::Bad => bad = next,
+            Verdict::Skip => {
+                skipped.insert(next);
+            }
+        }
+    }
+    Ok(Outcome {
+        good,
+        bad,
+        skipped: skipped.into_iter().filter(|index| (good + 1..bad).contains(index)).collect(),
+    })
+}
@@ -0,0 +1,290 @@
+//! Building sdists and wheels through the project's PEP 517 backend.
+//!
+//! The source tree is copied to a temporary directory and the build
+//! requirements are installed into a fresh environment there. The backend
+//! hooks then run inside a [`Sandbox`] that may only write to that directory.
+//! A version from git and the build-info module are written into the copy.
+
+use crate::core::buildenv;
+use crate::core::buildinfo;
+use crate::core::capture;
+use crate::core::config::{Config, VenvConfig};
+use crate::core::error::{PyForgeError, Result};
+use crate::core::installer;
+use crate::core::manifest;
+use crate::core::lockfile::Lockfile;
+use crate::core::project::Project;
+use crate::core::sandbox::Sandbox;
+use crate::core::venv::Venv;
+use crate::core::versioning;
+use crate::core::workspace;
+use crate::core::timings;
+use colored::*;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Runs a single backend hook and prints its result as JSON
+const HOOKS: &str = include_str!("../scripts/pep517_hooks.py");
+
+/// Backend used when `[build-system]` is missing, as PEP 517 specifies
+const DEFAULT_BACKEND: &str = "setuptools.build_meta:__legacy__";
+const DEFAULT_REQUIRES: &[&str] = &["setuptools>=40.8.0"];
+
+/// Directories never copied into the build tree
+const SKIPPED_DIRS: &[&str] = &[".git", ".hg", ".venv", ".tox", ".nox", "__pycache__", "build", "dist", "node_modules"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distribution {
+    Sdist,
+    Wheel,
+}
+
+impl Distribution {
+    /// Both kinds, sdist first
+    pub const ALL: &'static [Distribution] = &[Distribution::Sdist, Distribution::Wheel];
+
+    /// The kinds `--sdist` and `--wheel` ask for: both when neither or both are given
+    pub fn selected(sdist: bool, wheel: bool) -> &'static [Distribution] {
+        match (sdist, wheel) {
+            (true, false) => &[Distribution::Sdist],
+            (false, true) => &[Distribution::Wheel],
+            _ => Self::ALL,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Distribution::Sdist => "sdist",
+            Distribution::Wheel => "wheel",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BuildOptions<'a> {
+    /// Where the built files are copied
+    pub out_dir: PathBuf,
+    /// Run hooks in a sandbox confined to the build tree
+    pub sandboxed: bool,
+    pub allow_network: bool,
+    /// Install build requirements from `find-links` directories only
+    pub offline: bool,
+    /// Pinned build requirements are taken from the lockfile's toolchain
+    pub lockfile: Option<&'a Lockfile>,
+}
+
+/// A temporary build tree with its own environment
+pub struct Builder<'a> {
+    project: &'a Project,
+    config: &'a Config,
+    options: BuildOptions<'a>,
+    backend: String,
+    sandbox: Sandbox,
+    pinned: Vec<String>,
+    stamped: Option<String>,
+    workspace: TempDir,
+    env: Venv,
+    /// `[tool.pyforge.build.env]` for the release profile
+    variables: BTreeMap<String, String>,
+}
+
+impl<'a> Builder<'a> {
+    /// Copy the sources and install the build requirements
+    pub fn prepare(project: &'a Project, config: &'a Config, options: BuildOptions<'a>) -> Result<Self> {
+        let _span = timings::span("build environment");
+        let workspace = tempfile::Builder::new().prefix("pyforge-build-").tempdir()?;
+        for dir in ["src", "home", "tmp", "out"] {
+            fs::create_dir_all(workspace.path().join(dir))?;
+        }
+        copy_sources(&project.root, &workspace.path().join("src"))?;
+        let pinned = pin_members(project, &workspace.path().join("src").join("pyproject.toml"))?;
+        let stamped = stamp_version(project, config, &workspace.path().join("src").join("pyproject.toml"))?;
+        buildinfo::write(project, config, &workspace.path().join("src"), buildinfo::RELEASE)?;
+        fs::write(workspace.path().join("pep517_hooks.py"), HOOKS)?;
+
+        // Build environments stay isolated whatever [tool.pyforge.venv] says
+        let env = Venv::create(&workspace.path().join("env"), &VenvConfig::default())?;
+        let sandbox = if options.sandboxed {
+            Sandbox::new(vec![workspace.path().to_path_buf()], options.allow_network)
+        } else {
+            Sandbox::disabled()
+        };
+        let variables = buildenv::variables(project, config, buildinfo::RELEASE)?;
+        let builder = Self {
+            project,
+            config,
+            backend: project.build_backend().unwrap_or(DEFAULT_BACKEND).to_string(),
+            sandbox,
+            pinned,
+            stamped,
+            options,
+            workspace,
+            env,
+            variables,
+        };
+        builder.install(&builder.build_requires())?;
+        Ok(builder)
+    }
+
+    /// The backend in use, e.g. `hatchling.build`
+    pub fn backend(&self) -> &str {
+        &self.backend
+    }
+
+    pub fn sandbox(&self) -> &Sandbox {
+        &self.sandbox
+    }
+
+    /// Path dependencies given a version requirement in the built metadata
+    pub fn pinned(&self) -> &[String] {
+        &self.pinned
+    }
+
+    /// Version derived from git and written into the built metadata
+    pub fn stamped_version(&self) -> Option<&str> {
+        self.stamped.as_deref()
+    }
+
+    /// Build `distribution`, returning the path of the copied artifact
+    pub fn build(&self, distribution: Distribution) -> Result<PathBuf> {
+        let _span = timings::span("build");
+        let requires = self.hook(&format!("get_requires_for_build_{}", distribution.name()))?;
+        let extra: Vec<String> = serde_json::from_value(requires).unwrap_or_default();
+        self.install(&extra)?;
+
+        let result = self.hook(&format!("build_{}", distribution.name()))?;
+        let filename = result.as_str().ok_or_else(|| {
+            PyForgeError::internal(format!("{} returned no {} filename", self.backend, distribution.name()))
+        })?;
+
+        fs::create_dir_all(&self.options.out_dir)?;
+        let built = self.workspace.path().join("out").join(filename);
+        let target = self.options.out_dir.join(filename);
+        fs::copy(&built, &target)
+            .map_err(|e| PyForgeError::file_error(format!("Could not copy {}", built.display()), e))?;
+        Ok(target)
+    }
+
+    /// Locked pins when available, else `[build-system].requires`
+    fn build_requires(&self) -> Vec<String> {
+        let locked = self
+            .options
+            .lockfile
+            .and_then(|lockfile| lockfile.toolchain.as_ref())
+            .map(|toolchain| toolchain.build_requires.clone())
+            .filter(|pins| !pins.is_empty());
+        if let Some(pins) = locked {
+            return pins;
+        }
+        match self.project.pyproject.get("build-system") {
+            Some(_) => self.project.build_requires(),
+            None => DEFAULT_REQUIRES.iter().map(|r| r.to_string()).collect(),
+        }
+    }
+
+    /// Installing runs outside the sandbox, since it needs the indexes
+    fn install(&self, requirements: &[String]) -> Result<()> {
+        let index_args = installer::index_args(self.config, &self.project.root, self.options.offline);
+        installer::install_requirements(&self.env, requirements, &index_args, &self.variables)
+    }
+
+    /// Run a backend hook in the sandbox and parse its JSON result
+    fn hook(&self, hook: &str) -> Result<serde_json::Value> {
+        log::info!("calling {} {}", self.backend, hook);
+        let workspace = self.workspace.path();
+        let backend_path = serde_json::to_string(&self.project.backend_path())?;
+        let mut command = self.sandbox.command(&self.env.python());
+        command
+            .arg(workspace.join("pep517_hooks.py"))
+            .args([hook, &self.backend, &backend_path])
+            .arg(workspace.join("out"))
+            .current_dir(workspace.join("src"))
+            .envs(&self.variables)
+            .env("HOME", workspace.join("home"))
+            .env("TMPDIR", workspace.join("tmp"))
+            .env_remove("PYTHONPATH");
+        let _span = timings::subprocess(&command);
+        let output = command.output()?;
+        if let Some(capture) = capture::open(self.project, self.config, "build", &format!("{} {}", self.backend, hook))? {
+            capture.record(&output)?;
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        log::debug!("{} output:\n{}{}", hook, stdout, stderr);
+        if !output.status.success() {
+            eprintln!("{} {} failed:", "❌".red(), hook);
+            for line in stdout.lines().chain(stderr.lines()).rev().take(30).collect::<Vec<_>>().into_iter().rev() {
+                eprintln!("   {}", line.bright_black());
+            }
+            return Err(PyForgeError::command_failed(
+                format!("{} {}", self.backend, hook),
+                output.status.code().unwrap_or(-1),
+            ));
+        }
+
+        let last = stdout.lines().rev().find(|line| !line.trim().is_empty()).unwrap_or("null");
+        Ok(serde_json::from_str(last)?)
+    }
+}
+
+/// Replace path dependencies in the copied `pyproject.toml` with requirements on published versions
+fn pin_members(project: &Project, pyproject: &Path) -> Result<Vec<String>> {
+    let members = workspace::members(project)?;
+    if members.is_empty() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(pyproject)?;
+    let mut document = content.parse::<toml_edit::DocumentMut>().map_err(|e| PyForgeError::InvalidToml {
+        file: pyproject.display().to_string(),
+        message: e.to_string(),
+    })?;
+    let pinned = manifest::rewrite_dependencies(&mut document, |requirement| workspace::publishable(requirement, &members));
+    fs::write(pyproject, document.to_string())?;
+    Ok(pinned)
+}
+
+/// Write a version derived from git into the copied `pyproject.toml`, so any
+/// backend (and any wheel later built from the sdist) sees a static version
+fn stamp_version(project: &Project, config: &Config, pyproject: &Path) -> Result<Option<String>> {
+    if config.version.source.is_none() || !versioning::is_dynamic(project) {
+        return Ok(None);
+    }
+    let version = versioning::resolve(project, config)?;
+    let content = fs::read_to_string(pyproject)?;
+    let mut document = content.parse::<toml_edit::DocumentMut>().map_err(|e| PyForgeError::InvalidToml {
+        file: pyproject.display().to_string(),
+        message: e.to_string(),
+    })?;
+    if let Some(table) = document.get_mut("project").and_then(|p| p.as_table_like_mut()) {
+        if let Some(dynamic) = table.get_mut("dynamic").and_then(|d| d.as_array_mut()) {
+            dynamic.retain(|field| field.as_str() != Some("version"));
+            if dynamic.is_empty() {
+                table.remove("dynamic");
+            }
+        }
+        table.insert("version", toml_edit::value(version.clone()));
+    }
+    fs::write(pyproject, document.to_string())?;
+    Ok(Some(version))
+}
+
+fn copy_sources(from: &Path, to: &Path) -> Result<()> {
+    let walker = walkdir::WalkDir::new(from).min_depth(1).into_iter().filter_entry(|entry| {
+        let name = entry.file_name().to_string_lossy();
+        !(entry.file_type().is_dir() && (SKIPPED_DIRS.contains(&name.as_ref()) || name.ends_with(".egg-info")))
+    });
+    for entry in walker {
+        let entry = entry.map_err(|e| PyForgeError::internal(format!("Could not read the source tree: {}", e)))?;
+        let relative = entry.path().strip_prefix(from).expect("walked under root");
+        let target = to.join(relative);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if entry.file_type().is_file() {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,62 @@
+//! Environment variables for build and install subprocesses
+//! (`[tool.pyforge.build.env]`), so compiler flags, pip settings and
+//! `SOURCE_DATE_EPOCH` are the same on every machine.
+//!
+//! `pyforge build` uses the `release` profile, and syncing an environment
+//! the `dev` profile; `[tool.pyforge.build.profiles.<profile>.env]` overrides
+//! the shared table. Values may use `{{ project_name }}`, `{{ version }}`,
+//! `{{ profile }}`, `{{ git_commit }}`, `{{ git_commit_time }}` and
+//! `{{ env.NAME }}` placeholders.
+
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::git;
+use crate::core::project::Project;
+use crate::core::template;
+use crate::core::trust;
+use crate::core::versioning;
+use std::collections::BTreeMap;
+
+/// The variables to set for `profile`, with placeholders filled in. Fails
+/// when an untrusted project sets some itself.
+pub fn variables(project: &Project, config: &Config, profile: &str) -> Result<BTreeMap<String, String>> {
+    trust::check_build_env(project)?;
+    let mut raw = config.build.env.clone();
+    if let Some(overrides) = config.build.profiles.get(profile) {
+        raw.extend(overrides.env.clone());
+    }
+    if raw.is_empty() {
+        return Ok(raw);
+    }
+
+    let mut context = template::environment(raw.values().map(String::as_str));
+    context.insert("profile".to_string(), profile.to_string());
+    if let Some(name) = project.name() {
+        context.insert("project_name".to_string(), name.to_string());
+    }
+    if let Ok(version) = versioning::resolve(project, config) {
+        context.insert("version".to_string(), version);
+    }
+    if let Some(commit) = git::head_commit(&project.root) {
+        context.insert("git_commit".to_string(), commit);
+    }
+    if let Some(time) = git::head_commit_time(&project.root) {
+        context.insert("git_commit_time".to_string(), time.to_string());
+    }
+
+    raw.into_iter()
+        .map(|(name, value)| {
+            let rendered = template::substitute(&value, &context);
+            if rendered.contains("{{") {
+                return Err(PyForgeError::Usage {
+                    message: format!(
+                        "[tool.pyforge.build.env] {} = \"{}\" refers to a value that isn't available here",
+                        name, value
+                    ),
+                });
+            }
+            Ok((name, rendered))
+        })
+        .collect()
+}
+
@@ -0,0 +1,90 @@
+//! The generated build-info module (`[tool.pyforge.build.info]`): a Python file
+//! with the version, commit, build date and profile an app can report.
+//!
+//! `pyforge build` writes it into the build tree only, with the `release`
+//! profile. `pyforge task run` writes it into the project with the `dev`
+//! profile. `BUILD_DATE` honors `SOURCE_DATE_EPOCH`, from `[tool.pyforge.build.env]`
+//! or the environment, for reproducible builds.
+
+use crate::core::buildenv;
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::git;
+use crate::core::project::Project;
+use crate::core::versioning;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+pub const RELEASE: &str = "release";
+pub const DEV: &str = "dev";
+
+/// Write the module under `root` (the project or a copy of it), when configured;
+/// returns the path written
+pub fn write(project: &Project, config: &Config, root: &Path, profile: &str) -> Result<Option<PathBuf>> {
+    let Some(relative) = &config.build.info.path else {
+        return Ok(None);
+    };
+    if Path::new(relative)
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return Err(PyForgeError::Usage {
+            message: format!(
+                "[tool.pyforge.build.info] path '{}' must be relative to the project root",
+                relative
+            ),
+        });
+    }
+
+    let path = root.join(relative);
+    let content = render(project, config, profile)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| PyForgeError::file_error(format!("Could not create {}", dir.display()), e))?;
+    }
+    fs::write(&path, content).map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))?;
+    Ok(Some(path))
+}
+
+fn render(project: &Project, config: &Config, profile: &str) -> Result<String> {
+    let date = buildenv::variables(project, config, profile)?
+        .remove("SOURCE_DATE_EPOCH")
+        .or_else(|| std::env::var("SOURCE_DATE_EPOCH").ok())
+        .and_then(|epoch| epoch.trim().parse::<i64>().ok())
+        .and_then(|epoch| OffsetDateTime::from_unix_timestamp(epoch).ok())
+        .unwrap_or_else(OffsetDateTime::now_utc);
+    let date = date.replace_nanosecond(0).unwrap_or(date);
+    let commit = git::head_commit(&project.root);
+
+    let mut lines = vec![
+        "# Generated by pyforge; do not edit.".to_string(),
+        format!("VERSION = {}", python_str(versioning::resolve(project, config).ok().as_deref())),
+        format!("GIT_COMMIT = {}", python_str(commit.as_deref())),
+        format!(
+            "GIT_DIRTY = {}",
+            if commit.is_some() && git::is_dirty(&project.root) { "True" } else { "False" }
+        ),
+        format!("BUILD_DATE = {}", python_str(date.format(&Rfc3339).ok().as_deref())),
+        format!("PROFILE = {}", python_str(Some(profile))),
+    ];
+    for (name, value) in &config.build.info.constants {
+        let identifier = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !identifier {
+            return Err(PyForgeError::Usage {
+                message: format!("[tool.pyforge.build.info.constants] key '{}' is not a Python identifier", name),
+            });
+        }
+        lines.push(format!("{} = {}", name, python_str(Some(value))));
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+/// A Python string literal (JSON string syntax is valid Python), or `None`
+fn python_str(value: Option<&str>) -> String {
+    match value {
+        Some(value) => serde_json::to_string(value).unwrap_or_else(|_| "None".to_string()),
+        None => "None".to_string(),
+    }
+}
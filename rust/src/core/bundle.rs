@@ -0,0 +1,182 @@
+use crate::core::config::{global_config_path, read_toml};
+use crate::core::crash::crash_dir;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::logging::{last_error_path, log_dir};
+use crate::core::project::Project;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Number of most recent crash reports included in a bundle
+const MAX_CRASH_REPORTS: usize = 5;
+
+/// Placeholder written in place of secret values
+const REDACTED: &str = "***REDACTED***";
+
+/// Key fragments that mark a config value or environment variable as secret
+const SECRET_MARKERS: [&str; 7] = ["password", "passwd", "token", "secret", "key", "auth", "credential"];
+
+/// Collect logs, redacted config, environment info and the last error into a
+/// gzipped tarball at `output`
+pub fn create_bundle(output: &Path) -> Result<PathBuf> {
+    let file = File::create(output)
+        .map_err(|e| PyForgeError::file_error(format!("Could not create {}", output.display()), e))?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    // Logs written before they were redacted on write, crash reports and the
+    // last error all carry URLs and command lines verbatim
+    if let Ok(entries) = fs::read_dir(log_dir()) {
+        for entry in entries.flatten() {
+            let name = format!("logs/{}", entry.file_name().to_string_lossy());
+            append_redacted(&mut archive, &name, &entry.path())?;
+        }
+    }
+
+    if let Ok(entries) = fs::read_dir(crash_dir()) {
+        let mut reports: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+        reports.sort();
+        for report in reports.iter().rev().take(MAX_CRASH_REPORTS) {
+            let name = format!("crashes/{}", report.file_name().unwrap_or_default().to_string_lossy());
+            append_redacted(&mut archive, &name, report)?;
+        }
+    }
+
+    if let Some(config) = read_toml(&global_config_path())? {
+        append_text(&mut archive, "config/global.toml", &redacted_toml(config))?;
+    }
+
+    if let Some(project) = Project::discover()? {
+        append_text(&mut archive, "config/pyproject.toml", &redacted_toml(project.pyproject.clone()))?;
+    }
+
+    append_text(&mut archive, "environment.txt", &environment_report())?;
+
+    let last_error = last_error_path();
+    if last_error.is_file() {
+        append_redacted(&mut archive, "last-error.json", &last_error)?;
+    }
+
+    archive.into_inner()?.finish()?;
+    Ok(output.to_path_buf())
+}
+
+/// Whether a key name looks like it holds a secret
+pub fn is_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    SECRET_MARKERS.iter().any(|marker| key.contains(marker))
+}
+
+/// Strip `user:password@` credentials embedded in a URL
+pub fn redact_url(value: &str) -> String {
+    let Some(scheme_end) = value.find("://") else {
+        return value.to_string();
+    };
+    let host = scheme_end + 3;
+    match credentials_end(&value[host..], |c| matches!(c, '/' | '?' | '#')) {
+        Some(at) => format!("{}{}{}", &value[..host], REDACTED, &value[host + at..]),
+        None => value.to_string(),
+    }
+}
+
+/// Strip credentials from every URL in free text such as a log line
+pub fn redact_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(scheme_end) = rest.find("://") {
+        let host = scheme_end + 3;
+        out.push_str(&rest[..host]);
+        rest = &rest[host..];
+        let ends_url = |c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '<' | '>' | '`');
+        if let Some(at) = credentials_end(rest, |c| ends_url(c) || matches!(c, '/' | '?' | '#')) {
+            out.push_str(REDACTED);
+            rest = &rest[at..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Position of the `@` ending the credentials in `authority`, which runs up to
+/// the first character matching `ends`; a password may itself contain `@`
+fn credentials_end(authority: &str, ends: impl Fn(char) -> bool) -> Option<usize> {
+    let end = authority.find(ends).unwrap_or(authority.len());
+    authority[..end].rfind('@')
+}
+
+/// Replace secret values in a TOML document and serialize it
+fn redacted_toml(mut value: toml::Value) -> String {
+    redact_value(&mut value);
+    toml::to_string_pretty(&value).unwrap_or_default()
+}
+
+fn redact_value(value: &mut toml::Value) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table.iter_mut() {
+                if is_secret_key(key) && !value.is_table() {
+                    *value = toml::Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(value);
+                }
+            }
+        }
+        toml::Value::Array(items) => items.iter_mut().for_each(redact_value),
+        toml::Value::String(s) => *s = redact_url(s),
+        _ => {}
+    }
+}
+
+fn environment_report() -> String {
+    let mut report = String::new();
+    report.push_str(&format!("pyforge: {}\n", env!("CARGO_PKG_VERSION")));
+    report.push_str(&format!("os: {} ({})\n", std::env::consts::OS, std::env::consts::ARCH));
+
+    if let Ok(cwd) = std::env::current_dir() {
+        report.push_str(&format!("cwd: {}\n", cwd.display()));
+    }
+
+    let python = Command::new("python3")
+        .arg("--version")
+        .output()
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        .unwrap_or_else(|_| "not found".to_string());
+    report.push_str(&format!("python: {}\n", python));
+
+    report.push_str("\nenvironment:\n");
+    let mut vars: Vec<_> = std::env::vars()
+        .filter(|(name, _)| {
+            ["PYFORGE_", "PIP_", "UV_", "PYTHON", "VIRTUAL_ENV", "CONDA_"]
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+        })
+        .collect();
+    vars.sort();
+    for (name, value) in vars {
+        let value = if is_secret_key(&name) { REDACTED.to_string() } else { redact_url(&value) };
+        report.push_str(&format!("  {}={}\n", name, value));
+    }
+
+    report
+}
+
+fn append_redacted<W: std::io::Write>(archive: &mut tar::Builder<W>, name: &str, path: &Path) -> Result<()> {
+    let content = fs::read(path).map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+    append_text(archive, name, &redact_text(&String::from_utf8_lossy(&content)))
+}
+
+fn append_text<W: std::io::Write>(archive: &mut tar::Builder<W>, name: &str, content: &str) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    );
+    header.set_cksum();
+    archive.append_data(&mut header, name, content.as_bytes())?;
+    Ok(())
+}
@@ -0,0 +1,171 @@
+//! Log artifacts (`--capture-logs`, `[tool.pyforge.logs] capture = true`).
+//!
+//! The output of tests, tasks and builds is streamed through as usual and
+//! also written to a timestamped file in `.pyforge/logs`, so the full output
+//! survives a CI log that was truncated. Only the newest `keep` files stay.
+
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::project::Project;
+use crate::core::utils::timestamp;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Capture logs for the rest of the run (`--capture-logs`)
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Directory holding the project's captured logs
+pub fn log_dir(project: &Project) -> PathBuf {
+    project.root.join(".pyforge").join("logs")
+}
+
+/// Captured logs of the project, oldest first
+pub fn logs(project: &Project) -> Result<Vec<PathBuf>> {
+    list(&log_dir(project))
+}
+
+fn list(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut logs = Vec::new();
+    for entry in fs::read_dir(dir).map_err(|e| PyForgeError::file_error(format!("Could not read {}", dir.display()), e))? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|extension| extension == "log") {
+            logs.push(path);
+        }
+    }
+    // Names start with the time, so they sort oldest first
+    logs.sort();
+    Ok(logs)
+}
+
+/// An open log file for one command
+pub struct Capture {
+    file: File,
+    started: Instant,
+}
+
+/// Start a log of `kind` (`test`, `task`, `build`) for `label` when capturing
+/// is on, removing the oldest logs beyond `[tool.pyforge.logs] keep`
+pub fn open(project: &Project, config: &Config, kind: &str, label: &str) -> Result<Option<Capture>> {
+    if !ENABLED.load(Ordering::Relaxed) && !config.logs.capture {
+        return Ok(None);
+    }
+    let dir = log_dir(project);
+    fs::create_dir_all(&dir).map_err(|e| PyForgeError::file_error(format!("Could not create {}", dir.display()), e))?;
+    let ignore = project.root.join(".pyforge").join(".gitignore");
+    if !ignore.exists() {
+        fs::write(&ignore, "*\n").map_err(|e| PyForgeError::file_error(format!("Could not write {}", ignore.display()), e))?;
+    }
+
+    let path = dir.join(format!("{}-{}.log", file_stamp(), kind));
+    let mut file =
+        File::create(&path).map_err(|e| PyForgeError::file_error(format!("Could not create {}", path.display()), e))?;
+    writeln!(file, "# pyforge {}: {}", kind, label)?;
+    writeln!(file, "# started {}", timestamp())?;
+    rotate(&dir, config.logs.keep.max(1))?;
+    Ok(Some(Capture {
+        file,
+        started: Instant::now(),
+    }))
+}
+
+/// Run `command`, teeing its output to `capture` when there is one
+pub fn status(capture: Option<Capture>, command: &mut Command) -> io::Result<ExitStatus> {
+    match capture {
+        Some(capture) => capture.status(command),
+        None => command.status(),
+    }
+}
+
+impl Capture {
+    /// Run `command` with its output streamed through and copied to the log.
+    ///
+    /// The command's stdout and stderr become pipes, so tools that only
+    /// color a terminal print plain text.
+    pub fn status(self, command: &mut Command) -> io::Result<ExitStatus> {
+        let mut child = command.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+        let file = Arc::new(Mutex::new(self.file));
+        let copies = [
+            child.stdout.take().map(|out| tee(out, io::stdout(), Arc::clone(&file))),
+            child.stderr.take().map(|err| tee(err, io::stderr(), Arc::clone(&file))),
+        ];
+        let status = child.wait();
+        for copy in copies.into_iter().flatten() {
+            let _ = copy.join();
+        }
+        let mut file = file.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Ok(status) = &status {
+            finish(&mut file, *status, self.started)?;
+        }
+        status
+    }
+
+    /// Write output collected by `Command::output` to the log
+    pub fn record(mut self, output: &Output) -> io::Result<()> {
+        self.file.write_all(&output.stdout)?;
+        self.file.write_all(&output.stderr)?;
+        finish(&mut self.file, output.status, self.started)
+    }
+}
+
+/// Copy `from` to `to` and the log as it arrives, so progress output shows up live
+fn tee(mut from: impl Read + Send + 'static, mut to: impl Write + Send + 'static, log: Arc<Mutex<File>>) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let mut buffer = [0u8; 8192];
+        loop {
+            let read = match from.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => read,
+            };
+            let _ = to.write_all(&buffer[..read]);
+            let _ = to.flush();
+            if let Ok(mut log) = log.lock() {
+                let _ = log.write_all(&buffer[..read]);
+            }
+        }
+    })
+}
+
+fn finish(file: &mut File, status: ExitStatus, started: Instant) -> io::Result<()> {
+    let outcome = match status.code() {
+        Some(code) => format!("exit {}", code),
+        None => "killed by a signal".to_string(),
+    };
+    writeln!(file, "\n# {} after {:.1}s", outcome, started.elapsed().as_secs_f64())
+}
+
+/// Remove the oldest logs so at most `keep` remain
+fn rotate(dir: &Path, keep: usize) -> Result<()> {
+    let logs = list(dir)?;
+    for old in &logs[..logs.len().saturating_sub(keep)] {
+        fs::remove_file(old).map_err(|e| PyForgeError::file_error(format!("Could not remove {}", old.display()), e))?;
+    }
+    Ok(())
+}
+
+/// UTC time for file names, to the millisecond so logs of one run don't collide
+fn file_stamp() -> String {
+    let now = time::OffsetDateTime::now_utc();
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}.{:03}Z",
+        now.year(),
+        now.month() as u8,
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second(),
+        now.millisecond()
+    )
+}
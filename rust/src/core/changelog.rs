@@ -0,0 +1,179 @@
+//! Release notes for dependency updates (`pyforge bump --changelog`).
+//!
+//! A package's project URLs come from the PyPI JSON API. When one of them is
+//! a GitHub repository, the notes of its releases between the old and the new
+//! version are summarized; otherwise the changelog URL is shown. Only packages
+//! locked from PyPI are looked up, so private package names stay private.
+
+use crate::core::auth;
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::index::{self, IndexClient};
+use crate::core::pep440::Version;
+use crate::core::pep508::normalize_name;
+use reqwest::Url;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+const PYPI_HOST: &str = "pypi.org";
+
+const GITHUB_API: &str = "https://api.github.com";
+
+/// Lines of each release's notes kept in a summary
+const SUMMARY_LINES: usize = 6;
+
+/// Project URL labels that point at release notes, compared without case or separators
+const CHANGELOG_LABELS: [&str; 6] = ["changelog", "changes", "releasenotes", "history", "news", "whatsnew"];
+
+/// What is known about the releases between two versions of a package
+#[derive(Debug, Clone, Default)]
+pub struct Notes {
+    /// Release notes from the forge, oldest first
+    pub releases: Vec<ReleaseNotes>,
+    /// Where to read more: the changelog, else the repository's releases page
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReleaseNotes {
+    pub version: Version,
+    pub summary: Vec<String>,
+    /// Lines left out of the summary
+    pub truncated: usize,
+}
+
+#[derive(Deserialize)]
+struct PypiProject {
+    info: PypiInfo,
+}
+
+#[derive(Deserialize)]
+struct PypiInfo {
+    #[serde(default)]
+    project_urls: Option<BTreeMap<String, String>>,
+    #[serde(default)]
+    home_page: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    draft: bool,
+}
+
+/// Whether the lockfile's `index` is PyPI, whose packages may be looked up
+pub fn is_public(index: &str) -> bool {
+    Url::parse(index).is_ok_and(|url| url.host_str() == Some(PYPI_HOST))
+}
+
+/// Release notes of `name` after `old`, up to and including `new`
+pub fn notes(client: &IndexClient, config: &Config, name: &str, old: &Version, new: &Version) -> Result<Notes> {
+    let url = format!("https://{}/pypi/{}/json", PYPI_HOST, normalize_name(name));
+    let project: PypiProject = serde_json::from_slice(&client.download(&url)?).map_err(|e| PyForgeError::ParseError {
+        file_type: "PyPI project".to_string(),
+        message: format!("{}: {}", url, e),
+    })?;
+    let urls: Vec<(String, String)> = project
+        .info
+        .project_urls
+        .unwrap_or_default()
+        .into_iter()
+        .chain(project.info.home_page.map(|home| ("Homepage".to_string(), home)))
+        .collect();
+
+    let changelog = urls
+        .iter()
+        .find(|(label, _)| {
+            let label: String = label.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase();
+            CHANGELOG_LABELS.contains(&label.as_str())
+        })
+        .map(|(_, url)| url.clone());
+    let Some(repository) = urls.iter().find_map(|(_, url)| github_repository(url)) else {
+        return Ok(Notes {
+            releases: Vec::new(),
+            url: changelog,
+        });
+    };
+
+    let releases = github_releases(config, &repository, name, old, new)?;
+    Ok(Notes {
+        releases,
+        url: changelog.or_else(|| Some(format!("https://github.com/{}/releases", repository))),
+    })
+}
+
+/// `owner/repo` of a github.com URL
+fn github_repository(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    if url.host_str() != Some("github.com") && url.host_str() != Some("www.github.com") {
+        return None;
+    }
+    let mut segments = url.path_segments()?.filter(|segment| !segment.is_empty());
+    let owner = segments.next()?;
+    let repo = segments.next()?.trim_end_matches(".git");
+    Some(format!("{}/{}", owner, repo))
+}
+
+/// Notes of the repository's releases whose tag names a version in `(old, new]`
+fn github_releases(config: &Config, repository: &str, name: &str, old: &Version, new: &Version) -> Result<Vec<ReleaseNotes>> {
+    let client = index::client_builder(&config.network)?
+        .build()
+        .map_err(|e| PyForgeError::network_error("Could not create HTTP client", Some(e)))?;
+    let url = format!("{}/repos/{}/releases?per_page=100", GITHUB_API, repository);
+    let mut request = client.get(&url).header("Accept", "application/vnd.github+json");
+    // Unauthenticated requests are limited to 60 an hour
+    if let Some(token) = auth::token("github")? {
+        request = request.bearer_auth(token.secret);
+    }
+    let response = request
+        .send()
+        .map_err(|e| PyForgeError::network_error(format!("Request to '{}' failed", url), Some(e)))?;
+    if !response.status().is_success() {
+        return Err(PyForgeError::DownloadFailed {
+            url,
+            status: response.status().to_string(),
+        });
+    }
+    let releases: Vec<GithubRelease> = response
+        .json()
+        .map_err(|e| PyForgeError::network_error(format!("Could not read '{}'", url), Some(e)))?;
+
+    let mut notes: Vec<ReleaseNotes> = releases
+        .into_iter()
+        .filter(|release| !release.draft)
+        .filter_map(|release| {
+            let version = tag_version(&release.tag_name, name)?;
+            (version > *old && version <= *new).then(|| summarize(version, release.body.as_deref().unwrap_or_default()))
+        })
+        .collect();
+    notes.sort_by(|a, b| a.version.cmp(&b.version));
+    Ok(notes)
+}
+
+/// The version a tag names: `1.2.0`, `v1.2.0`, `<name>-1.2.0` or `<name>@1.2.0`
+fn tag_version(tag: &str, name: &str) -> Option<Version> {
+    let bare = tag
+        .rsplit_once(['-', '@', '/'])
+        .filter(|(prefix, _)| normalize_name(prefix) == normalize_name(name))
+        .map_or(tag, |(_, version)| version);
+    Version::parse(bare.trim_start_matches(['v', 'V'])).ok()
+}
+
+/// The first non-empty lines of `body`, without markdown headings' markup
+fn summarize(version: Version, body: &str) -> ReleaseNotes {
+    let lines: Vec<String> = body
+        .lines()
+        .map(|line| line.trim().trim_start_matches('#').trim())
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+    let truncated = lines.len().saturating_sub(SUMMARY_LINES);
+    ReleaseNotes {
+        version,
+        summary: lines.into_iter().take(SUMMARY_LINES).collect(),
+        truncated,
+    }
+}
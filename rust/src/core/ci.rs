@@ -0,0 +1,77 @@
+//! Output for CI systems: GitHub Actions workflow commands, which show up
+//! as annotations on the run and on the lines of a pull request's diff.
+
+use crate::core::git::Worktree;
+use std::path::Path;
+
+/// Whether the process runs in a GitHub Actions job
+pub fn github_actions() -> bool {
+    std::env::var("GITHUB_ACTIONS").is_ok_and(|value| value == "true")
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Level {
+    Error,
+    Warning,
+}
+
+/// An annotation pointing at `file` (relative to the repository) and `line` when known
+#[derive(Debug, Clone)]
+pub struct Annotation {
+    pub level: Level,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub title: String,
+    pub message: String,
+}
+
+impl Annotation {
+    /// The `::error file=...::message` workflow command
+    pub fn command(&self) -> String {
+        let mut properties = Vec::new();
+        if let Some(file) = &self.file {
+            properties.push(format!("file={}", escape_property(file)));
+        }
+        if let Some(line) = self.line {
+            properties.push(format!("line={}", line));
+        }
+        if let Some(column) = self.column {
+            properties.push(format!("col={}", column));
+        }
+        properties.push(format!("title={}", escape_property(&self.title)));
+        let level = match self.level {
+            Level::Error => "error",
+            Level::Warning => "warning",
+        };
+        format!("::{} {}::{}", level, properties.join(","), escape_data(&self.message))
+    }
+}
+
+/// `path` (absolute, or relative to `root`) relative to the repository
+/// holding `root`, which is how annotations name files
+pub fn repository_path(root: &Path, path: &str) -> String {
+    let absolute = root.join(path);
+    let base = Worktree::discover(root).map_or_else(|| root.to_path_buf(), |worktree| worktree.root);
+    let base = base.canonicalize().unwrap_or(base);
+    let absolute = absolute.canonicalize().unwrap_or(absolute);
+    match absolute.strip_prefix(&base) {
+        Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+        Err(_) => path.to_string(),
+    }
+}
+
+/// Print `annotations` as workflow commands
+pub fn annotate(annotations: &[Annotation]) {
+    for annotation in annotations {
+        println!("{}", annotation.command());
+    }
+}
+
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
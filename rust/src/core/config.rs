@@ -0,0 +1,842 @@
+use crate::core::error::{PyForgeError, Result};
+use crate::core::project::Project;
+use crate::core::dirs;
+use crate::core::distcheck::parse_size;
+use crate::core::org::Author;
+use crate::core::schema::{self, Scope};
+use crate::core::selfupdate::Channel;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Index used when none is configured
+pub const DEFAULT_INDEX_URL: &str = "https://pypi.org/simple";
+
+/// Effective PyForge configuration.
+///
+/// Built from the global config file (`config.toml` in the config directory) overlaid with the
+/// project's `[tool.pyforge]` table, so project settings win over user settings.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Config {
+    /// Package indexes in priority order (`[[index]]` entries)
+    #[serde(rename = "index")]
+    pub indexes: Vec<IndexConfig>,
+
+    /// HTTP client settings
+    pub network: NetworkConfig,
+
+    /// User-defined command shortcuts (`[alias]` / `[tool.pyforge.alias]`)
+    pub alias: BTreeMap<String, AliasValue>,
+
+    /// Named shell commands runnable from the project root (`[tool.pyforge.scripts]`)
+    pub scripts: BTreeMap<String, String>,
+
+    /// `pyforge dev` settings
+    pub dev: DevConfig,
+
+    /// Where packages are installed from
+    pub install: InstallConfig,
+
+    /// Fail instead of warning when the toolchain differs from the lockfile's
+    pub frozen_toolchain: bool,
+
+    /// `pyforge build` settings
+    pub build: BuildConfig,
+
+    /// Where the project's virtual environment lives when using git worktrees
+    pub venv_strategy: VenvStrategy,
+
+    /// Directory holding every project's environment, e.g. `~/.venvs`, instead
+    /// of inside the project; for projects on network filesystems
+    pub venv_dir: Option<String>,
+
+    /// How the project's virtual environment is created (`[tool.pyforge.venv]`)
+    pub venv: VenvConfig,
+
+    /// `pyforge init` settings
+    pub init: InitConfig,
+
+    /// `pyforge test` settings, also used by `pyforge bump`
+    pub test: TestConfig,
+
+    /// Where the repository is hosted, for pull requests and releases
+    pub forge: ForgeConfig,
+
+    /// `pyforge task run --on` settings
+    pub remote: RemoteConfig,
+
+    /// Where a dynamic `[project].version` comes from
+    pub version: VersionConfig,
+
+    /// Where distributions are uploaded
+    pub publish: PublishConfig,
+
+    /// Interpreter to lock for and create environments with: an installed
+    /// version such as `3.12`, a command on PATH or a path (default: `python3`)
+    pub python: Option<String>,
+
+    /// Colored output; `NO_COLOR` and `CLICOLOR_FORCE` still apply with `auto`
+    pub color: ColorChoice,
+
+    /// `pyforge upgrade-self` settings
+    pub self_update: SelfUpdateConfig,
+
+    /// Limits and strategy of dependency resolution (`[tool.pyforge.resolver]`)
+    pub resolver: ResolverConfig,
+
+    /// `pyforge show` settings
+    pub show: ShowConfig,
+
+    /// `pyforge report` settings
+    pub report: ReportConfig,
+
+    /// Notifications when a long command finishes
+    pub notify: NotifyConfig,
+
+    /// `--isolated` runs (`[tool.pyforge.isolation]`)
+    pub isolation: IsolationConfig,
+
+    /// Copies of subprocess output under `.pyforge/logs` (`[tool.pyforge.logs]`)
+    pub logs: LogsConfig,
+
+    /// `pyforge q` (`[tool.pyforge.queue]`)
+    pub queue: QueueConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct QueueConfig {
+    /// Jobs run at the same time; more than one only suits commands that
+    /// don't share the environment
+    pub jobs: usize,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self { jobs: 1 }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct LogsConfig {
+    /// Tee the output of tests, tasks and builds to log files, like `--capture-logs`
+    pub capture: bool,
+
+    /// How many log files to keep; older ones are removed
+    pub keep: usize,
+}
+
+impl Default for LogsConfig {
+    fn default() -> Self {
+        Self { capture: false, keep: 20 }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct IsolationConfig {
+    /// Environment variables passed through to isolated runs, besides PATH and the locale
+    pub allow_env: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ShowConfig {
+    /// Look up recent download counts of packages found on PyPI
+    pub download_stats: bool,
+
+    /// Download statistics API, for mirrors; defaults to pypistats.org
+    pub stats_url: Option<String>,
+}
+
+impl Default for ShowConfig {
+    fn default() -> Self {
+        Self {
+            download_stats: true,
+            stats_url: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct NotifyConfig {
+    /// Notify when a command took at least this many seconds; unset disables notifications
+    pub after: Option<u64>,
+
+    /// Show a desktop notification (notify-send on Linux, Notification Center on macOS)
+    pub desktop: bool,
+
+    /// POST a JSON summary of the command to this URL
+    pub webhook: Option<String>,
+
+    /// Post a message to this Slack incoming webhook
+    pub slack_webhook: Option<String>,
+
+    /// Commands never notified about, e.g. ones that run until interrupted
+    pub ignore: Vec<String>,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            after: None,
+            desktop: true,
+            webhook: None,
+            slack_webhook: None,
+            ignore: vec!["dev".to_string(), "ui".to_string()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ReportConfig {
+    /// OSV vulnerability API, for mirrors; defaults to api.osv.dev
+    pub vulnerability_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct SelfUpdateConfig {
+    /// Release channel to follow; set in a project, it pins the channel for everyone working on it
+    pub channel: Option<Channel>,
+
+    /// Release feed, for mirrors; defaults to the upstream GitHub releases. Global config only
+    pub releases_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct PublishConfig {
+    /// Upload endpoint; defaults to PyPI's
+    pub repository_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct VersionConfig {
+    pub source: Option<VersionSource>,
+
+    /// Only tags starting with this are versions, e.g. `app-v` in a monorepo
+    pub tag_prefix: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VersionSource {
+    /// The nearest tag, as setuptools-scm derives it
+    Git,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct RemoteConfig {
+    /// Directory the project is copied to on each host, relative to the remote home
+    pub dir: Option<String>,
+
+    /// pyforge executable on the hosts
+    pub pyforge: String,
+
+    /// Extra rsync exclude patterns, on top of `.gitignore` and environments
+    pub exclude: Vec<String>,
+}
+
+impl Default for RemoteConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            pyforge: "pyforge".to_string(),
+            exclude: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct TestConfig {
+    /// Test command; defaults to the `test` script, then `python -m pytest`
+    pub command: Option<String>,
+
+    /// Container image for `pyforge test --in-docker`; defaults to the lockfile's Python
+    pub image: Option<String>,
+
+    /// Named alternatives to `image`, selected with `--profile`
+    pub profiles: BTreeMap<String, TestProfile>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TestProfile {
+    pub image: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ForgeConfig {
+    /// Hosting service of the remote, when it cannot be told from the URL
+    pub kind: Option<ForgeKind>,
+
+    /// API base URL, for self-hosted GitHub Enterprise or GitLab instances
+    pub api_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ForgeKind {
+    Github,
+    Gitlab,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct InitConfig {
+    /// Path or http(s) URL of the organization defaults applied to new projects
+    pub org_defaults: Option<String>,
+
+    /// Expected SHA-256 of the organization defaults file
+    pub org_defaults_sha256: Option<String>,
+
+    /// `[project].authors` for new projects the organization defaults don't cover
+    /// (default: git's `user.name` and `user.email`)
+    pub author: Option<Author>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct VenvConfig {
+    /// Link the interpreter into the environment or copy it; defaults to the platform's choice
+    pub interpreter: Option<VenvInterpreter>,
+
+    /// Let the environment import packages installed in the base interpreter
+    pub system_site_packages: bool,
+
+    /// Shown in the shell prompt when activated; defaults to the directory name
+    pub prompt: Option<String>,
+
+    /// Upgrade pip and the other seed packages to their latest releases when creating
+    pub upgrade_deps: bool,
+
+    /// Requirements seeded into new environments, e.g. `["pip==24.0"]`; `[]` for
+    /// none. Unset: whatever `python -m venv` installs.
+    pub seed: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VenvInterpreter {
+    Symlink,
+    Copy,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VenvStrategy {
+    /// `.venv` inside each worktree
+    #[default]
+    PerWorktree,
+    /// One `.venv` in the main worktree, used by every worktree of the repository
+    Shared,
+    /// `.venvs/<branch>` inside each worktree, so switching branches switches environments
+    PerBranch,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct BuildConfig {
+    /// Run the build backend in a sandbox where the platform supports it
+    pub sandbox: bool,
+
+    /// Let the build backend access the network
+    pub allow_network: bool,
+
+    /// Commands run in order before the backend (`[[tool.pyforge.build.steps]]`)
+    pub steps: Vec<BuildStep>,
+
+    /// Data files added to the distributions: glob pattern -> directory in the wheel
+    pub include: BTreeMap<String, String>,
+
+    /// Generated module with version, commit, build date and profile
+    pub info: BuildInfoConfig,
+
+    /// Checks on the built distributions
+    pub checks: BuildChecksConfig,
+
+    /// Environment variables for build and install subprocesses, e.g.
+    /// `SOURCE_DATE_EPOCH = "{{ git_commit_time }}"`
+    pub env: BTreeMap<String, String>,
+
+    /// Settings of the `release` (build) and `dev` (sync, task) profiles
+    pub profiles: BTreeMap<String, BuildProfile>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct BuildProfile {
+    /// Overrides `[tool.pyforge.build.env]` for this profile
+    pub env: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct BuildInfoConfig {
+    /// Where the module is written, relative to the project root, e.g. `src/app/_build_info.py`
+    pub path: Option<String>,
+
+    /// Extra string constants added to the module
+    pub constants: BTreeMap<String, String>,
+}
+
+/// `[tool.pyforge.build.checks]`; a glob without `/` matches any file or
+/// directory name, one with `/` the whole path inside the distribution
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct BuildChecksConfig {
+    /// Largest allowed distribution, e.g. `"10 MB"`
+    pub max_size: Option<String>,
+
+    /// Files that must not be packaged
+    pub forbid: Vec<String>,
+
+    /// Files every distribution must contain, e.g. `py.typed` or `LICENSE*`
+    pub require: Vec<String>,
+
+    /// Fail the build when a check fails instead of warning (`pyforge build --strict`)
+    pub strict: bool,
+}
+
+impl Default for BuildConfig {
+    fn default() -> Self {
+        Self {
+            sandbox: true,
+            allow_network: false,
+            steps: Vec::new(),
+            include: BTreeMap::new(),
+            info: BuildInfoConfig::default(),
+            checks: BuildChecksConfig::default(),
+            env: BTreeMap::new(),
+            profiles: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for BuildChecksConfig {
+    fn default() -> Self {
+        Self {
+            max_size: None,
+            forbid: [".env", ".env.*", "*.key", "*.pem", "__pycache__"].map(String::from).to_vec(),
+            require: Vec::new(),
+            strict: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BuildStep {
+    pub name: String,
+
+    /// Shell command, run from the project root
+    pub command: String,
+
+    /// Glob patterns of the files the step reads; without them the step always runs
+    #[serde(default)]
+    pub inputs: Vec<String>,
+
+    /// Glob patterns of the files the step writes; each must match after a run
+    #[serde(default)]
+    pub outputs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct InstallConfig {
+    /// Never contact an index; install only from `find-links`
+    pub no_index: bool,
+
+    /// Local directories (relative to the project root) searched for distribution files
+    pub find_links: Vec<String>,
+
+    /// Parallel downloads and hashing; defaults to one per CPU (`--jobs`)
+    pub jobs: Option<usize>,
+
+    /// Review of packages that run code of their own when installed (`[tool.pyforge.install.hooks]`)
+    pub hooks: InstallHooksConfig,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct InstallHooksConfig {
+    /// Packages already reviewed, installed without asking
+    pub allow: Vec<String>,
+
+    /// Refuse to install unreviewed packages when there is no terminal to ask on, instead of warning
+    pub strict: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct DevConfig {
+    /// Long-running processes shown side by side (`[tool.pyforge.dev.watchers.<name>]`)
+    pub watchers: BTreeMap<String, WatcherConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct WatcherConfig {
+    /// Shell command to run from the project root
+    pub command: String,
+
+    /// Glob patterns; the watcher restarts when a matching file changes
+    #[serde(default)]
+    pub watch: Vec<String>,
+
+    /// Regex counted as an error when it matches an output line
+    #[serde(default = "default_error_pattern")]
+    pub error_pattern: String,
+}
+
+fn default_error_pattern() -> String {
+    r"(?i)\berror\b|Traceback|FAILED".to_string()
+}
+
+/// An alias expansion, written either as a command line or as a list of arguments
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum AliasValue {
+    Line(String),
+    Args(Vec<String>),
+}
+
+impl AliasValue {
+    pub fn args(&self) -> Vec<String> {
+        match self {
+            AliasValue::Line(line) => crate::core::utils::split_command_line(line),
+            AliasValue::Args(args) => args.clone(),
+        }
+    }
+}
+
+/// A package index and its mirrors
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct IndexConfig {
+    pub name: String,
+    pub url: String,
+
+    /// Fallback URLs serving the same content, tried in order when `url` fails
+    #[serde(default)]
+    pub mirrors: Vec<String>,
+
+    /// Certificates for this index and its mirrors (`[index.tls]`)
+    #[serde(default)]
+    pub tls: TlsConfig,
+
+    /// Which of `urls()` the global config declares, not only a project
+    #[serde(skip)]
+    pub global_urls: Vec<String>,
+}
+
+/// TLS settings of one index. Paths may start with `~`; relative paths are
+/// taken from the current directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct TlsConfig {
+    /// PEM files of certificate authorities trusted for the index, e.g. an internal CA
+    pub ca_certs: Vec<String>,
+
+    /// Trust only `ca-certs`, not the system's roots
+    pub pin: bool,
+
+    /// PEM client certificate (with its chain) for servers requiring mutual TLS
+    pub client_cert: Option<String>,
+
+    /// PEM PKCS#8 private key of `client-cert`
+    pub client_key: Option<String>,
+}
+
+impl IndexConfig {
+    /// Primary URL followed by its mirrors
+    pub fn urls(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.url.as_str()).chain(self.mirrors.iter().map(String::as_str))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct NetworkConfig {
+    /// Per-request timeout in seconds
+    pub timeout: u64,
+
+    /// Seconds to wait for a connection, separately from `timeout`
+    pub connect_timeout: Option<u64>,
+
+    /// Address family to connect over
+    pub ip_version: IpVersion,
+
+    /// Addresses to use for host names instead of DNS (`[network.hosts]`)
+    pub hosts: BTreeMap<String, HostAddresses>,
+
+    /// Attempts per URL before failing over to the next mirror
+    pub retries: u32,
+
+    /// Cap on package download speed per second, e.g. `"2 MB"` or `"500kB"`
+    pub max_download_rate: Option<String>,
+
+    /// Skip optional downloads such as release notes and vulnerability queries
+    pub metered: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IpVersion {
+    /// IPv6 and IPv4 addresses raced (happy eyeballs)
+    #[default]
+    Auto,
+    Ipv4,
+    Ipv6,
+}
+
+/// One address or several for a `[network.hosts]` entry
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum HostAddresses {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl HostAddresses {
+    pub fn addresses(&self) -> Vec<&str> {
+        match self {
+            HostAddresses::One(address) => vec![address.as_str()],
+            HostAddresses::Many(addresses) => addresses.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// The first configuration loaded in this run, for `notify` when the command ends
+static LOADED: OnceLock<Config> = OnceLock::new();
+
+/// Set by `--metered`, for this run only
+static METERED: AtomicBool = AtomicBool::new(false);
+
+/// Treat the connection as metered for the rest of the run (`--metered`)
+pub fn set_metered() {
+    METERED.store(true, Ordering::Relaxed);
+}
+
+impl NetworkConfig {
+    /// Whether optional downloads should be skipped: `--metered`, `PYFORGE_METERED=1`
+    /// or `[network] metered = true`
+    pub fn is_metered(&self) -> bool {
+        self.metered
+            || METERED.load(Ordering::Relaxed)
+            || std::env::var("PYFORGE_METERED").is_ok_and(|value| !value.is_empty() && value != "0")
+    }
+
+    /// `max-download-rate` in bytes per second
+    pub fn download_rate(&self) -> Result<Option<u64>> {
+        let Some(text) = &self.max_download_rate else {
+            return Ok(None);
+        };
+        let size = text.trim().strip_suffix("/s").unwrap_or(text);
+        match parse_size(size) {
+            Some(rate) if rate > 0 => Ok(Some(rate)),
+            _ => Err(PyForgeError::Usage {
+                message: format!("Invalid [network] max-download-rate '{}'; expected a size per second like \"2 MB\"", text),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ResolverConfig {
+    /// Re-pinning steps before giving up on constraints that don't converge
+    pub max_steps: usize,
+
+    /// Seconds resolution may take before failing with what it pinned so far
+    pub timeout: Option<u64>,
+
+    /// Which allowed version to pick
+    pub strategy: ResolutionStrategy,
+
+    /// Ignore files uploaded at or after this date (`2024-05-01`) or RFC 3339 timestamp
+    pub exclude_newer: Option<String>,
+}
+
+impl Default for ResolverConfig {
+    fn default() -> Self {
+        Self {
+            max_steps: 10_000,
+            timeout: None,
+            strategy: ResolutionStrategy::default(),
+            exclude_newer: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ResolutionStrategy {
+    /// The newest allowed version
+    #[default]
+    Newest,
+    /// The version in the existing lockfile while it is allowed, else the newest
+    Locked,
+    /// The oldest allowed version, to check lower bounds
+    Lowest,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            timeout: 30,
+            connect_timeout: None,
+            ip_version: IpVersion::default(),
+            hosts: BTreeMap::new(),
+            retries: 1,
+            max_download_rate: None,
+            metered: false,
+        }
+    }
+}
+
+impl Config {
+    /// Load the global config overlaid with the given project's `[tool.pyforge]`
+    pub fn load(project: Option<&Project>) -> Result<Self> {
+        let global = global_config_path();
+        let mut merged = match read_toml(&global)? {
+            Some(value) => value,
+            None => toml::Value::Table(Default::default()),
+        };
+        schema::validate(&global, Scope::Global)?;
+        let global_urls: Vec<String> = merged
+            .get("index")
+            .cloned()
+            .and_then(|indexes| indexes.try_into::<Vec<IndexConfig>>().ok())
+            .unwrap_or_default()
+            .iter()
+            .flat_map(|index| index.urls().map(str::to_string).collect::<Vec<_>>())
+            .collect();
+
+        if let Some(project) = project
+            && let Some(tool) = project.tool_config()
+        {
+            schema::validate(&project.pyproject_path(), Scope::Project)?;
+            let mut tool = tool.clone();
+            schema::strip_global_only(&mut tool);
+            merge_toml(&mut merged, tool);
+        }
+        schema::migrate(&mut merged);
+
+        let mut config: Self = merged.try_into().map_err(|e: toml::de::Error| PyForgeError::InvalidConfig {
+            file: "pyforge configuration".to_string(),
+            source: Box::new(e),
+        })?;
+        for index in &mut config.indexes {
+            index.global_urls = index.urls().filter(|url| global_urls.iter().any(|global| global == url)).map(str::to_string).collect();
+        }
+        let _ = LOADED.set(config.clone());
+        Ok(config)
+    }
+
+    /// The configuration this run loaded first, if the command loaded any
+    pub fn loaded() -> Option<&'static Config> {
+        LOADED.get()
+    }
+
+    /// Load configuration for the project in the current directory, if any
+    pub fn load_current() -> Result<Self> {
+        let project = Project::discover()?;
+        Self::load(project.as_ref())
+    }
+
+    /// The project's test command
+    pub fn test_command(&self) -> String {
+        self.test
+            .command
+            .clone()
+            .or_else(|| self.scripts.get("test").cloned())
+            .unwrap_or_else(|| "python -m pytest".to_string())
+    }
+
+    /// Configured indexes, or PyPI when none are configured
+    pub fn indexes(&self) -> Vec<IndexConfig> {
+        if self.indexes.is_empty() {
+            vec![IndexConfig {
+                name: "pypi".to_string(),
+                url: DEFAULT_INDEX_URL.to_string(),
+                mirrors: Vec::new(),
+                tls: TlsConfig::default(),
+                global_urls: vec![DEFAULT_INDEX_URL.to_string()],
+            }]
+        } else {
+            self.indexes.clone()
+        }
+    }
+}
+
+/// The `color` setting of the global config, read without loading the whole
+/// configuration so every command can apply it at startup
+pub fn color_preference() -> ColorChoice {
+    read_toml(&global_config_path())
+        .ok()
+        .flatten()
+        .and_then(|config| config.get("color").cloned())
+        .and_then(|color| color.try_into().ok())
+        .unwrap_or_default()
+}
+
+/// Path to the global config file
+pub fn global_config_path() -> PathBuf {
+    dirs::config_dir().join("config.toml")
+}
+
+/// Read a TOML file, returning `None` when it doesn't exist
+pub fn read_toml(path: &Path) -> Result<Option<toml::Value>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(path)
+        .map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+    content
+        .parse::<toml::Value>()
+        .map(Some)
+        .map_err(|e| PyForgeError::InvalidToml {
+            file: path.display().to_string(),
+            message: e.to_string(),
+        })
+}
+
+/// Recursively merge `overlay` into `base`; tables are merged, everything else is replaced
+pub fn merge_toml(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
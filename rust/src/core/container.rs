@@ -0,0 +1,133 @@
+//! Running the project's commands in a container (docker or podman).
+//!
+//! The project is mounted at `/workspace`, and the locked packages are
+//! installed into a virtual environment inside the container. They are
+//! pinned by version rather than by file, because the locked files were
+//! chosen for the host platform. A named volume keeps pip's cache between
+//! runs.
+
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::lockfile::Lockfile;
+use crate::core::process::shell_quote;
+use crate::core::project::Project;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+/// Where the project is mounted
+pub const WORKDIR: &str = "/workspace";
+
+/// Named volume holding pip's cache
+const CACHE_VOLUME: &str = "pyforge-pip-cache";
+
+/// Where the generated setup files are mounted
+const SETUP_DIR: &str = "/pyforge";
+
+/// Virtual environment created inside the container
+const VENV: &str = "/opt/pyforge-venv";
+
+/// A command prepared to run in a container, with the files it mounts
+pub struct ContainerRun {
+    pub command: Command,
+    pub engine: String,
+    /// Generated setup files; must outlive the command
+    _setup: TempDir,
+}
+
+/// The container engine to use: docker, else podman
+pub fn engine() -> Result<PathBuf> {
+    which::which("docker")
+        .or_else(|_| which::which("podman"))
+        .map_err(|_| PyForgeError::CommandNotFound {
+            command: "docker".to_string(),
+        })
+}
+
+/// Prepare `script` (a shell command line) to run in `image` with the locked
+/// packages installed and the project as the working directory
+pub fn prepare(project: &Project, config: &Config, lockfile: &Lockfile, image: &str, script: &str) -> Result<ContainerRun> {
+    let engine = engine()?;
+    let setup = tempfile::Builder::new().prefix("pyforge-container-").tempdir()?;
+    let root = project.root.canonicalize().unwrap_or_else(|_| project.root.clone());
+
+    let pins: Vec<String> = lockfile
+        .packages
+        .iter()
+        .filter(|package| package.path.is_none())
+        .map(|package| format!("{}=={}", package.name, package.version))
+        .collect();
+    write(setup.path(), "requirements.txt", &(pins.join("\n") + "\n"))?;
+    write(setup.path(), "command.sh", &format!("{}\n", script))?;
+
+    let mut command = Command::new(&engine);
+    command.args(["run", "--rm", "--init"]);
+    if std::io::stdout().is_terminal() {
+        command.arg("--tty");
+    }
+    command
+        .arg("--volume")
+        .arg(format!("{}:{}", root.display(), WORKDIR))
+        .arg("--volume")
+        .arg(format!("{}:{}:ro", setup.path().display(), SETUP_DIR))
+        .arg("--volume")
+        .arg(format!("{}:/root/.cache/pip", CACHE_VOLUME))
+        .args(["--workdir", WORKDIR, "--env", "PYTHONUNBUFFERED=1"]);
+
+    let pip = format!("{}/bin/pip install --disable-pip-version-check --quiet --no-deps", VENV);
+    let mut lines = vec![
+        "set -e".to_string(),
+        format!("python -m venv {}", VENV),
+        format!("{} {} -r {}/requirements.txt", pip, index_args(config).join(" "), SETUP_DIR),
+    ];
+
+    // Path dependencies are mounted next to the setup files and installed editable
+    for package in lockfile.packages.iter().filter(|package| package.path.is_some()) {
+        let dir = root.join(package.path.as_deref().unwrap_or_default());
+        let dir = dir.canonicalize().unwrap_or(dir);
+        let mounted = format!("/opt/pyforge-members/{}", package.name);
+        command.arg("--volume").arg(format!("{}:{}", dir.display(), mounted));
+        lines.push(format!("{} --editable {}", pip, mounted));
+    }
+
+    lines.push(format!("export VIRTUAL_ENV={} PATH={}/bin:$PATH", VENV, VENV));
+    lines.push(format!("exec sh {}/command.sh", SETUP_DIR));
+    write(setup.path(), "run.sh", &(lines.join("\n") + "\n"))?;
+
+    command.arg(image).args(["sh", &format!("{}/run.sh", SETUP_DIR)]);
+    Ok(ContainerRun {
+        command,
+        engine: engine.file_name().unwrap_or_default().to_string_lossy().to_string(),
+        _setup: setup,
+    })
+}
+
+/// The default image: the official Python image for the lockfile's Python version
+pub fn default_image(lockfile: &Lockfile) -> String {
+    format!("python:{}-slim", lockfile.environment.python_version)
+}
+
+/// pip index arguments as seen from inside the container
+fn index_args(config: &Config) -> Vec<String> {
+    let mut args = Vec::new();
+    if config.install.no_index {
+        args.push("--no-index".to_string());
+    } else {
+        for (position, index) in config.indexes().iter().enumerate() {
+            args.push(if position == 0 { "--index-url" } else { "--extra-index-url" }.to_string());
+            args.push(shell_quote(&index.url));
+        }
+    }
+    for dir in &config.install.find_links {
+        args.push("--find-links".to_string());
+        args.push(shell_quote(&format!("{}/{}", WORKDIR, dir)));
+    }
+    args
+}
+
+fn write(dir: &Path, name: &str, content: &str) -> Result<()> {
+    let path = dir.join(name);
+    fs::write(&path, content).map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))
+}
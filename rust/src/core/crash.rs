@@ -0,0 +1,97 @@
+use crate::core::bundle::redact_text;
+use crate::core::dirs::state_dir;
+use crate::core::utils::timestamp;
+use colored::*;
+use std::backtrace::Backtrace;
+use std::fs;
+use std::panic::PanicHookInfo;
+use std::path::PathBuf;
+
+/// Where crash reports are stored
+pub fn crash_dir() -> PathBuf {
+    state_dir().join("crashes")
+}
+
+/// Replace the default panic output with a friendly message and a saved crash report.
+///
+/// Set `PYFORGE_BACKTRACE=1` to also print the backtrace to the terminal.
+pub fn install_panic_hook() {
+    std::panic::set_hook(Box::new(|info| {
+        let report = build_report(info);
+        let saved = save_report(&report);
+
+        log::error!("pyforge panicked: {}", panic_message(info));
+
+        eprintln!();
+        eprintln!("{} This is a bug in PyForge, not in your project.", "💥 PyForge crashed unexpectedly.".red().bold());
+        match saved {
+            Some(path) => eprintln!("   A crash report was saved to {}", path.display().to_string().cyan()),
+            None => eprintln!("   The crash report could not be saved; details follow:\n{}", report),
+        }
+        eprintln!(
+            "💡 {}: open an issue at {} and attach the report (or run 'pyforge debug bundle')",
+            "Suggestion".yellow(),
+            env!("CARGO_PKG_REPOSITORY").cyan()
+        );
+
+        if std::env::var_os("PYFORGE_BACKTRACE").is_some() {
+            eprintln!("\n{}", report);
+        }
+    }));
+}
+
+fn build_report(info: &PanicHookInfo) -> String {
+    let location = info
+        .location()
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+        .unwrap_or_else(|| "unknown".to_string());
+    let thread = std::thread::current();
+    let cwd = std::env::current_dir()
+        .map(|p| p.display().to_string())
+        .unwrap_or_default();
+
+    format!(
+        "PyForge crash report\n\
+         ====================\n\
+         time:     {}\n\
+         version:  pyforge {}\n\
+         platform: {} ({})\n\
+         command:  {}\n\
+         cwd:      {}\n\
+         thread:   {}\n\
+         location: {}\n\
+         message:  {}\n\
+         \n\
+         backtrace:\n{}\n",
+        timestamp(),
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        redact_text(&std::env::args().collect::<Vec<_>>().join(" ")),
+        cwd,
+        thread.name().unwrap_or("<unnamed>"),
+        location,
+        panic_message(info),
+        Backtrace::force_capture(),
+    )
+}
+
+fn panic_message(info: &PanicHookInfo) -> String {
+    if let Some(message) = info.payload().downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = info.payload().downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+fn save_report(report: &str) -> Option<PathBuf> {
+    let dir = crash_dir();
+    fs::create_dir_all(&dir).ok()?;
+
+    let name = format!("crash-{}.txt", timestamp().replace(':', "-"));
+    let path = dir.join(name);
+    fs::write(&path, report).ok()?;
+    Some(path)
+}
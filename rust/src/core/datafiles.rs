@@ -0,0 +1,204 @@
+//! Data files declared in `[tool.pyforge.build.include]`, added to the built
+//! distributions whatever the backend's own package-data settings are.
+//!
+//! Each entry maps a glob, relative to the project root, to a directory inside
+//! the wheel. The part of a matched path after the glob's first wildcard
+//! component is kept, so `"assets/**/*.css" = "app/static"` puts
+//! `assets/css/site.css` at `app/static/css/site.css`.
+
+use crate::core::error::{PyForgeError, Result};
+use crate::core::permissions;
+use crate::core::project::Project;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// A project file and where it goes in the wheel
+#[derive(Debug, Clone)]
+pub struct DataFile {
+    pub source: PathBuf,
+    /// Path inside the wheel, with `/` separators
+    pub destination: String,
+}
+
+/// Files matched by the include table
+#[derive(Debug, Default)]
+pub struct DataFiles {
+    pub files: Vec<DataFile>,
+    /// Patterns that matched no file
+    pub unmatched: Vec<String>,
+}
+
+impl DataFiles {
+    pub fn collect(project: &Project, include: &BTreeMap<String, String>) -> Result<Self> {
+        let mut collected = Self::default();
+        for (pattern, destination) in include {
+            let destination = destination.trim_matches('/');
+            let escapes = Path::new(destination)
+                .components()
+                .any(|component| !matches!(component, Component::Normal(_)));
+            if escapes || destination.split('/').next().is_some_and(|top| top.ends_with(".dist-info")) {
+                return Err(PyForgeError::Usage {
+                    message: format!(
+                        "Invalid destination '{}' for '{}' in [tool.pyforge.build.include]: \
+                         use a relative directory inside the wheel, e.g. \"mypkg/data\"",
+                        destination, pattern
+                    ),
+                });
+            }
+
+            let base = project.root.join(static_prefix(pattern));
+            let paths = glob::glob(&project.root.join(pattern).to_string_lossy()).map_err(|e| PyForgeError::Usage {
+                message: format!("Invalid pattern '{}' in [tool.pyforge.build.include]: {}", pattern, e),
+            })?;
+            let mut matched = false;
+            for path in paths.flatten().filter(|path| path.is_file()) {
+                matched = true;
+                let relative = path.strip_prefix(&base).unwrap_or(&path);
+                let relative: Vec<String> = relative
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy().to_string())
+                    .collect();
+                let destination = if destination.is_empty() {
+                    relative.join("/")
+                } else {
+                    format!("{}/{}", destination, relative.join("/"))
+                };
+                collected.files.push(DataFile { source: path, destination });
+            }
+            if !matched {
+                collected.unmatched.push(pattern.clone());
+            }
+        }
+        Ok(collected)
+    }
+
+    /// Add the files the wheel lacks, updating its RECORD; returns how many were added
+    pub fn add_to_wheel(&self, wheel: &Path) -> Result<usize> {
+        let content = fs::read(wheel).map_err(|e| PyForgeError::file_error(format!("Could not read {}", wheel.display()), e))?;
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(content))?;
+        let names: BTreeSet<String> = archive.file_names().map(str::to_string).collect();
+        let missing: Vec<&DataFile> = self.files.iter().filter(|file| !names.contains(&file.destination)).collect();
+        if missing.is_empty() {
+            return Ok(0);
+        }
+        let record_name = names
+            .iter()
+            .find(|name| name.ends_with(".dist-info/RECORD") && name.matches('/').count() == 1)
+            .cloned()
+            .ok_or_else(|| PyForgeError::ParseError {
+                file_type: "wheel".to_string(),
+                message: format!("{} has no RECORD", wheel.display()),
+            })?;
+
+        let mut output = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        let mut record = String::new();
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index)?;
+            if entry.name() == record_name {
+                entry.read_to_string(&mut record)?;
+            } else {
+                output.raw_copy_file(entry)?;
+            }
+        }
+        if !record.is_empty() && !record.ends_with('\n') {
+            record.push('\n');
+        }
+        for file in &missing {
+            let data = fs::read(&file.source)
+                .map_err(|e| PyForgeError::file_error(format!("Could not read {}", file.source.display()), e))?;
+            let options = options.unix_permissions(permissions::archive_mode(&file.source));
+            output.start_file(file.destination.as_str(), options)?;
+            output.write_all(&data)?;
+            record.push_str(&format!(
+                "{},sha256={},{}\n",
+                file.destination,
+                URL_SAFE_NO_PAD.encode(Sha256::digest(&data)),
+                data.len()
+            ));
+        }
+        output.start_file(record_name.as_str(), options)?;
+        output.write_all(record.as_bytes())?;
+        let written = output.finish()?.into_inner();
+        fs::write(wheel, written).map_err(|e| PyForgeError::file_error(format!("Could not write {}", wheel.display()), e))?;
+        Ok(missing.len())
+    }
+
+    /// Add the source files the sdist lacks, at their place in the source tree;
+    /// returns how many were added
+    pub fn add_to_sdist(&self, sdist: &Path, project: &Project) -> Result<usize> {
+        let file = fs::File::open(sdist).map_err(|e| PyForgeError::file_error(format!("Could not read {}", sdist.display()), e))?;
+        let mut entries = Vec::new();
+        for entry in tar::Archive::new(GzDecoder::new(file)).entries()? {
+            let mut entry = entry?;
+            let header = entry.header().clone();
+            let path = entry.path()?.to_string_lossy().to_string();
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)?;
+            entries.push((header, path, data));
+        }
+        let Some(top) = entries.first().and_then(|(_, path, _)| path.split('/').next().map(str::to_string)) else {
+            return Ok(0);
+        };
+        let present: BTreeSet<&str> = entries.iter().map(|(_, path, _)| path.as_str()).collect();
+        let mut missing = Vec::new();
+        for file in &self.files {
+            let Ok(relative) = file.source.strip_prefix(&project.root) else {
+                continue;
+            };
+            let parts: Vec<String> = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().to_string())
+                .collect();
+            let path = format!("{}/{}", top, parts.join("/"));
+            if !present.contains(path.as_str()) && !missing.iter().any(|(p, _): &(String, &Path)| *p == path) {
+                missing.push((path, file.source.as_path()));
+            }
+        }
+        if missing.is_empty() {
+            return Ok(0);
+        }
+
+        let mut output = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        for (mut header, path, data) in entries {
+            output.append_data(&mut header, path, data.as_slice())?;
+        }
+        for (path, source) in &missing {
+            let data = fs::read(source).map_err(|e| PyForgeError::file_error(format!("Could not read {}", source.display()), e))?;
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(permissions::archive_mode(source));
+            header.set_mtime(
+                fs::metadata(source)
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+            );
+            header.set_cksum();
+            output.append_data(&mut header, path, data.as_slice())?;
+        }
+        let written = output.into_inner()?.finish()?;
+        fs::write(sdist, written).map_err(|e| PyForgeError::file_error(format!("Could not write {}", sdist.display()), e))?;
+        Ok(missing.len())
+    }
+}
+
+/// Leading path components of `pattern` without wildcards
+fn static_prefix(pattern: &str) -> PathBuf {
+    let parts: Vec<&str> = pattern.split('/').collect();
+    let literal = parts
+        .iter()
+        .position(|part| part.contains(['*', '?', '[']))
+        .unwrap_or(parts.len().saturating_sub(1));
+    parts[..literal].iter().collect()
+}
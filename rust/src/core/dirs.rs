@@ -0,0 +1,115 @@
+//! Where pyforge keeps its own files, following each platform's conventions:
+//!
+//! | kind   | Linux (XDG)                  | macOS                                  | Windows                       |
+//! |--------|------------------------------|----------------------------------------|-------------------------------|
+//! | config | `~/.config/pyforge`          | `~/Library/Application Support/pyforge`| `%APPDATA%\pyforge`           |
+//! | data   | `~/.local/share/pyforge`     | `~/Library/Application Support/pyforge`| `%APPDATA%\pyforge\data`      |
+//! | cache  | `~/.cache/pyforge`           | `~/Library/Caches/pyforge`             | `%LOCALAPPDATA%\pyforge\cache`|
+//! | state  | `~/.local/state/pyforge`     | `~/Library/Application Support/pyforge/state` | `%LOCALAPPDATA%\pyforge\state` |
+//!
+//! `PYFORGE_HOME` puts everything under one directory instead: config and data
+//! at its root, `cache/` and `state/` inside it. An existing `~/.pyforge` from
+//! older releases is used the same way, so upgrading moves nothing.
+
+use std::env;
+use std::path::PathBuf;
+
+/// User-written settings: `config.toml`, `credentials.toml`, presets and features
+pub fn config_dir() -> PathBuf {
+    match single_home() {
+        Some(home) => home,
+        None => platform(Kind::Config),
+    }
+}
+
+/// Files users install for pyforge to use, such as templates
+pub fn data_dir() -> PathBuf {
+    match single_home() {
+        Some(home) => home,
+        None => platform(Kind::Data),
+    }
+}
+
+/// Anything that can be deleted and fetched or computed again
+pub fn cache_dir() -> PathBuf {
+    match single_home() {
+        Some(home) => home.join("cache"),
+        None => platform(Kind::Cache),
+    }
+}
+
+/// Logs, history and crash reports
+pub fn state_dir() -> PathBuf {
+    match single_home() {
+        Some(home) => home.join("state"),
+        None => platform(Kind::State),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Config,
+    Data,
+    Cache,
+    State,
+}
+
+/// A configured path with a leading `~` expanded to the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix('~') {
+        Some("") => home_dir(),
+        Some(rest) if rest.starts_with(['/', '\\']) => home_dir().join(&rest[1..]),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn home_dir() -> PathBuf {
+    home::home_dir().unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// `PYFORGE_HOME`, else the pre-XDG `~/.pyforge` when it exists
+fn single_home() -> Option<PathBuf> {
+    if let Some(home) = env::var_os("PYFORGE_HOME").filter(|home| !home.is_empty()) {
+        return Some(PathBuf::from(home));
+    }
+    let legacy = home_dir().join(".pyforge");
+    legacy.is_dir().then_some(legacy)
+}
+
+/// An absolute directory from `variable`; relative values are ignored, as the XDG spec asks
+fn env_dir(variable: &str) -> Option<PathBuf> {
+    env::var_os(variable).map(PathBuf::from).filter(|path| path.is_absolute())
+}
+
+#[cfg(target_os = "macos")]
+fn platform(kind: Kind) -> PathBuf {
+    let library = home_dir().join("Library");
+    match kind {
+        Kind::Config | Kind::Data => library.join("Application Support").join("pyforge"),
+        Kind::Cache => library.join("Caches").join("pyforge"),
+        Kind::State => library.join("Application Support").join("pyforge").join("state"),
+    }
+}
+
+#[cfg(windows)]
+fn platform(kind: Kind) -> PathBuf {
+    let roaming = env_dir("APPDATA").unwrap_or_else(|| home_dir().join("AppData").join("Roaming"));
+    let local = env_dir("LOCALAPPDATA").unwrap_or_else(|| home_dir().join("AppData").join("Local"));
+    match kind {
+        Kind::Config => roaming.join("pyforge"),
+        Kind::Data => roaming.join("pyforge").join("data"),
+        Kind::Cache => local.join("pyforge").join("cache"),
+        Kind::State => local.join("pyforge").join("state"),
+    }
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn platform(kind: Kind) -> PathBuf {
+    let (variable, fallback) = match kind {
+        Kind::Config => ("XDG_CONFIG_HOME", ".config"),
+        Kind::Data => ("XDG_DATA_HOME", ".local/share"),
+        Kind::Cache => ("XDG_CACHE_HOME", ".cache"),
+        Kind::State => ("XDG_STATE_HOME", ".local/state"),
+    };
+    env_dir(variable).unwrap_or_else(|| home_dir().join(fallback)).join("pyforge")
+}
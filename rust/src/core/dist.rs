@@ -0,0 +1,251 @@
+//! Distribution files: wheel/sdist filenames, platform tags and core metadata.
+
+use crate::core::error::{PyForgeError, Result};
+use crate::core::markers::Environment;
+use crate::core::pep440::Version;
+use crate::core::pep508::{Requirement, normalize_name};
+use std::io::Read;
+
+/// Archive extensions recognized as source distributions
+const SDIST_EXTENSIONS: &[&str] = &[".tar.gz", ".zip", ".tar.bz2", ".tgz"];
+
+/// What kind of file a distribution filename denotes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DistKind {
+    /// `{name}-{version}(-{build})?-{python}-{abi}-{platform}.whl`
+    Wheel {
+        python: Vec<String>,
+        abi: Vec<String>,
+        platform: Vec<String>,
+    },
+    Sdist,
+}
+
+/// A parsed distribution filename
+#[derive(Debug, Clone)]
+pub struct DistFilename {
+    pub name: String,
+    pub version: Version,
+    pub kind: DistKind,
+}
+
+impl DistFilename {
+    /// Parse a wheel or sdist filename; `project` disambiguates sdist names containing dashes
+    pub fn parse(filename: &str, project: &str) -> Option<Self> {
+        if let Some(stem) = filename.strip_suffix(".whl") {
+            let parts: Vec<&str> = stem.split('-').collect();
+            if !(5..=6).contains(&parts.len()) {
+                return None;
+            }
+            let tags = &parts[parts.len() - 3..];
+            let split = |tag: &str| tag.split('.').map(str::to_string).collect();
+            return Some(Self {
+                name: normalize_name(parts[0]),
+                version: Version::parse(parts[1]).ok()?,
+                kind: DistKind::Wheel {
+                    python: split(tags[0]),
+                    abi: split(tags[1]),
+                    platform: split(tags[2]),
+                },
+            });
+        }
+
+        let stem = SDIST_EXTENSIONS.iter().find_map(|ext| filename.strip_suffix(ext))?;
+        // The name part may itself contain dashes, so match it against the project name
+        let project = normalize_name(project);
+        let (name, version) = stem
+            .match_indices('-')
+            .map(|(i, _)| (&stem[..i], &stem[i + 1..]))
+            .find(|(name, _)| normalize_name(name) == project)?;
+        Some(Self {
+            name: normalize_name(name),
+            version: Version::parse(version).ok()?,
+            kind: DistKind::Sdist,
+        })
+    }
+
+    pub fn is_wheel(&self) -> bool {
+        matches!(self.kind, DistKind::Wheel { .. })
+    }
+
+    /// How well a wheel fits `environment`: `None` if incompatible, higher is more specific.
+    /// Sdists are always compatible with the lowest priority.
+    pub fn compatibility(&self, environment: &Environment) -> Option<u32> {
+        let DistKind::Wheel { python, abi, platform } = &self.kind else {
+            return Some(0);
+        };
+
+        let minor = environment.python_version.replace('.', "");
+        let major = minor.get(..1).unwrap_or("3");
+        let cpython = environment.implementation_name == "cpython";
+
+        let python_score = python
+            .iter()
+            .filter_map(|tag| match tag.as_str() {
+                t if cpython && t == format!("cp{}", minor) => Some(3),
+                t if t == format!("py{}", minor) => Some(2),
+                t if t == format!("py{}", major) || t == "py2.py3" => Some(1),
+                // Stable ABI wheels built for an older CPython still work
+                t if cpython && t.starts_with("cp3") && abi.iter().any(|a| a == "abi3") => {
+                    let built = t[2..].parse::<u32>().ok()?;
+                    (built <= minor.parse::<u32>().ok()?).then_some(2)
+                }
+                _ => None,
+            })
+            .max()?;
+
+        let abi_ok = abi
+            .iter()
+            .any(|tag| tag == "none" || (cpython && (tag == "abi3" || tag.starts_with(&format!("cp{}", minor)))));
+        if !abi_ok {
+            return None;
+        }
+
+        let platform_score = platform.iter().filter_map(|tag| platform_score(tag, environment)).max()?;
+        Some(python_score * 10 + platform_score)
+    }
+}
+
+/// Whether a platform tag runs on the environment's OS and architecture
+fn platform_score(tag: &str, environment: &Environment) -> Option<u32> {
+    if tag == "any" {
+        return Some(1);
+    }
+
+    let machine = environment.platform_machine.to_lowercase();
+    let arch = match machine.as_str() {
+        "amd64" | "x86_64" => "x86_64",
+        "arm64" | "aarch64" => match environment.sys_platform.as_str() {
+            "darwin" => "arm64",
+            "win32" => "arm64",
+            _ => "aarch64",
+        },
+        other => other,
+    };
+
+    let matches = match environment.sys_platform.as_str() {
+        "linux" => {
+            (tag.starts_with("manylinux") || tag.starts_with("musllinux") || tag.starts_with("linux_"))
+                && tag.ends_with(&format!("_{}", arch))
+        }
+        "darwin" => tag.starts_with("macosx_") && (tag.ends_with(&format!("_{}", arch)) || tag.ends_with("_universal2")),
+        "win32" => match arch {
+            "x86_64" => tag == "win_amd64",
+            "arm64" => tag == "win_arm64",
+            _ => tag == "win32",
+        },
+        _ => false,
+    };
+    matches.then_some(2)
+}
+
+/// The parts of a distribution's core metadata (`METADATA` / `PKG-INFO`) pyforge uses
+#[derive(Debug, Clone, Default)]
+pub struct Metadata {
+    pub name: String,
+    pub version: String,
+    pub requires_dist: Vec<Requirement>,
+    pub requires_python: Option<String>,
+    pub license: Option<String>,
+    pub summary: Option<String>,
+}
+
+impl Metadata {
+    /// Parse RFC 822 style core metadata headers
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut metadata = Metadata::default();
+        let mut license_expression = None;
+        let mut classifier_license = None;
+
+        for (key, value) in headers(content) {
+            match key.to_ascii_lowercase().as_str() {
+                "name" => metadata.name = value,
+                "version" => metadata.version = value,
+                "requires-python" => metadata.requires_python = Some(value),
+                "summary" => metadata.summary = Some(value),
+                "license-expression" => license_expression = Some(value),
+                "license" if !value.is_empty() && !value.contains('\n') && value.len() <= 64 => {
+                    metadata.license = Some(value)
+                }
+                "classifier" if classifier_license.is_none() => {
+                    classifier_license = value.strip_prefix("License :: OSI Approved :: ").map(license_from_classifier);
+                }
+                "requires-dist" => metadata.requires_dist.push(Requirement::parse(&value)?),
+                _ => {}
+            }
+        }
+
+        metadata.license = license_expression.or(metadata.license).or(classifier_license.flatten());
+        Ok(metadata)
+    }
+
+    /// Read `*.dist-info/METADATA` from a wheel archive; only that entry is read
+    pub fn from_wheel(wheel: impl std::io::Read + std::io::Seek) -> Result<Self> {
+        let mut archive = zip::ZipArchive::new(wheel)?;
+        let name = archive
+            .file_names()
+            .find(|name| name.ends_with(".dist-info/METADATA") && name.matches('/').count() == 1)
+            .map(str::to_string)
+            .ok_or_else(|| PyForgeError::ParseError {
+                file_type: "wheel".to_string(),
+                message: "no .dist-info/METADATA".to_string(),
+            })?;
+
+        let mut content = String::new();
+        archive
+            .by_name(&name)
+            .map_err(|e| PyForgeError::ParseError { file_type: "wheel".to_string(), message: e.to_string() })?
+            .read_to_string(&mut content)?;
+        Self::parse(&content)
+    }
+}
+
+/// Header fields in order, with continuation lines folded; stops at the body
+pub fn headers(content: &str) -> Vec<(String, String)> {
+    let mut fields: Vec<(String, String)> = Vec::new();
+    for line in content.lines() {
+        if line.is_empty() {
+            break;
+        }
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = fields.last_mut() {
+                value.push('\n');
+                value.push_str(line.trim());
+            }
+        } else if let Some((key, value)) = line.split_once(':') {
+            fields.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    fields
+}
+
+/// The common trove license classifiers (after `License :: OSI Approved :: `)
+/// and their SPDX identifiers
+const LICENSE_CLASSIFIERS: &[(&str, &str)] = &[
+    ("MIT License", "MIT"),
+    ("Apache Software License", "Apache-2.0"),
+    ("BSD License", "BSD-3-Clause"),
+    ("ISC License (ISCL)", "ISC"),
+    ("Mozilla Public License 2.0 (MPL 2.0)", "MPL-2.0"),
+    ("GNU General Public License v3 (GPLv3)", "GPL-3.0-only"),
+    ("GNU General Public License v2 (GPLv2)", "GPL-2.0-only"),
+    ("GNU Lesser General Public License v3 (LGPLv3)", "LGPL-3.0-only"),
+    ("Python Software Foundation License", "PSF-2.0"),
+    ("The Unlicense (Unlicense)", "Unlicense"),
+];
+
+/// SPDX identifier for the common trove license classifiers
+fn license_from_classifier(classifier: &str) -> Option<String> {
+    LICENSE_CLASSIFIERS
+        .iter()
+        .find(|(name, _)| *name == classifier)
+        .map(|(_, spdx)| spdx.to_string())
+}
+
+/// Full trove classifier for a common SPDX license identifier
+pub fn license_classifier(spdx: &str) -> Option<String> {
+    LICENSE_CLASSIFIERS
+        .iter()
+        .find(|(_, id)| id.eq_ignore_ascii_case(spdx.trim()))
+        .map(|(name, _)| format!("License :: OSI Approved :: {}", name))
+}
@@ -0,0 +1,94 @@
+//! Checks on built distributions (`[tool.pyforge.build.checks]`): a size
+//! limit, files that must not be packaged, and files that must be. They run
+//! after every build and warn, or fail the build when strict.
+
+use crate::core::config::BuildChecksConfig;
+use crate::core::distdiff::{self, Contents};
+use crate::core::error::{PyForgeError, Result};
+
+/// `[tool.pyforge.build.checks]`, parsed
+pub struct Checks {
+    max_size: Option<(u64, String)>,
+    forbid: Vec<(String, glob::Pattern)>,
+    require: Vec<(String, glob::Pattern)>,
+}
+
+impl Checks {
+    /// Parse the settings, so mistakes show before anything is built
+    pub fn new(config: &BuildChecksConfig) -> Result<Self> {
+        let max_size = match &config.max_size {
+            Some(limit) => Some((
+                parse_size(limit).ok_or_else(|| PyForgeError::Usage {
+                    message: format!("Invalid [tool.pyforge.build.checks] max-size '{}': use a size such as \"10 MB\"", limit),
+                })?,
+                limit.clone(),
+            )),
+            None => None,
+        };
+        Ok(Self {
+            max_size,
+            forbid: patterns(&config.forbid, "forbid")?,
+            require: patterns(&config.require, "require")?,
+        })
+    }
+
+    /// What is wrong with one distribution, as messages naming `filename`
+    pub fn check(&self, filename: &str, contents: &Contents) -> Vec<String> {
+        let mut violations = Vec::new();
+        if let Some((max, limit)) = &self.max_size
+            && contents.size > *max
+        {
+            violations.push(format!("{} is {}, over the {} limit", filename, distdiff::size(contents.size), limit));
+        }
+        for path in contents.files.keys() {
+            if let Some((text, _)) = self.forbid.iter().find(|(_, pattern)| matches(pattern, path)) {
+                violations.push(format!("{} contains {}, which is forbidden by '{}'", filename, path, text));
+            }
+        }
+        for (text, pattern) in &self.require {
+            if !contents.files.keys().any(|path| matches(pattern, path)) {
+                violations.push(format!("{} has no file matching '{}'", filename, text));
+            }
+        }
+        violations
+    }
+}
+
+/// `"10 MB"`, `"500kB"`, `"2 MiB"` or a number of bytes
+pub fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let factor = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1.0,
+        "k" | "kb" => 1e3,
+        "m" | "mb" => 1e6,
+        "g" | "gb" => 1e9,
+        "kib" => 1024.0,
+        "mib" => 1024.0 * 1024.0,
+        "gib" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((number * factor) as u64)
+}
+
+fn patterns(patterns: &[String], setting: &str) -> Result<Vec<(String, glob::Pattern)>> {
+    patterns
+        .iter()
+        .map(|text| {
+            glob::Pattern::new(text).map(|pattern| (text.clone(), pattern)).map_err(|e| PyForgeError::Usage {
+                message: format!("Invalid [tool.pyforge.build.checks] {} pattern '{}': {}", setting, text, e),
+            })
+        })
+        .collect()
+}
+
+/// A pattern with `/` matches the whole path, one without any of its names
+fn matches(pattern: &glob::Pattern, path: &str) -> bool {
+    if pattern.as_str().contains('/') {
+        pattern.matches(path)
+    } else {
+        path.split('/').any(|name| pattern.matches(name))
+    }
+}
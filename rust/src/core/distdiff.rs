@@ -0,0 +1,198 @@
+//! Comparing a freshly built wheel or sdist with the previous build
+//! (`pyforge build --diff-last`): size, files and core metadata. Files that
+//! look like test data or secrets are flagged when they first appear, so they
+//! can be caught before publishing.
+
+use crate::core::dist;
+use crate::core::error::{PyForgeError, Result};
+use flate2::read::GzDecoder;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// File names that rarely belong in a distribution
+const SUSPICIOUS_NAMES: &[&str] = &[
+    ".env", ".env.*", ".pypirc", ".netrc", "*.pem", "*.key", "*.p12", "*.pfx", "id_rsa*", "id_ed25519*", "*.sqlite",
+    "*.sqlite3", "*.db", "*.log", "*.orig", "*.rej",
+];
+
+/// Directories whose files rarely belong in a distribution
+const SUSPICIOUS_DIRS: &[&str] = &[".git", ".venv", "venv", "node_modules", "__pycache__", ".pytest_cache", "tests"];
+
+/// What a distribution holds
+#[derive(Debug, Clone, Default)]
+pub struct Contents {
+    /// Size of the archive itself
+    pub size: u64,
+    /// Uncompressed file sizes, by path with the versioned top directory
+    /// (sdists) or `.dist-info`/`.data` directory (wheels) made generic
+    pub files: BTreeMap<String, u64>,
+    /// Core metadata fields, each with its values in order
+    pub metadata: BTreeMap<String, Vec<String>>,
+}
+
+impl Contents {
+    /// Read a `.whl` or `.tar.gz` distribution
+    pub fn read(path: &Path) -> Result<Self> {
+        let size = fs::metadata(path)
+            .map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?
+            .len();
+        let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let (files, metadata) = if name.ends_with(".whl") {
+            read_wheel(path)?
+        } else if name.ends_with(".tar.gz") {
+            read_sdist(path)?
+        } else {
+            return Err(PyForgeError::ParseError {
+                file_type: "distribution".to_string(),
+                message: format!("{} is neither a wheel nor a .tar.gz sdist", name),
+            });
+        };
+        let mut fields: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (key, value) in dist::headers(&metadata) {
+            fields.entry(key).or_default().push(value);
+        }
+        Ok(Self {
+            size,
+            files,
+            metadata: fields,
+        })
+    }
+}
+
+/// A metadata field whose values changed
+#[derive(Debug, Clone)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Vec<String>,
+    pub new: Vec<String>,
+}
+
+/// Differences between two builds
+#[derive(Debug, Clone, Default)]
+pub struct Diff {
+    pub old_size: u64,
+    pub new_size: u64,
+    /// New files and their sizes
+    pub added: Vec<(String, u64)>,
+    pub removed: Vec<(String, u64)>,
+    /// Files whose size changed, with the old and new size
+    pub resized: Vec<(String, u64, u64)>,
+    pub metadata: Vec<FieldChange>,
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.resized.is_empty() && self.metadata.is_empty()
+    }
+}
+
+pub fn diff(old: &Contents, new: &Contents) -> Diff {
+    let mut diff = Diff {
+        old_size: old.size,
+        new_size: new.size,
+        ..Diff::default()
+    };
+    for (path, &size) in &new.files {
+        match old.files.get(path) {
+            None => diff.added.push((path.clone(), size)),
+            Some(&old_size) if old_size != size => diff.resized.push((path.clone(), old_size, size)),
+            Some(_) => {}
+        }
+    }
+    for (path, &size) in &old.files {
+        if !new.files.contains_key(path) {
+            diff.removed.push((path.clone(), size));
+        }
+    }
+    let none = Vec::new();
+    let fields: std::collections::BTreeSet<&String> = old.metadata.keys().chain(new.metadata.keys()).collect();
+    for field in fields {
+        let (old_values, new_values) = (old.metadata.get(field).unwrap_or(&none), new.metadata.get(field).unwrap_or(&none));
+        if old_values != new_values {
+            diff.metadata.push(FieldChange {
+                field: field.clone(),
+                old: old_values.clone(),
+                new: new_values.clone(),
+            });
+        }
+    }
+    diff
+}
+
+/// Whether a path inside a distribution looks like test data or a secret
+pub fn suspicious(path: &str) -> bool {
+    let mut parts: Vec<&str> = path.split('/').collect();
+    let Some(name) = parts.pop() else {
+        return false;
+    };
+    parts.iter().any(|dir| SUSPICIOUS_DIRS.contains(dir))
+        || SUSPICIOUS_NAMES
+            .iter()
+            .any(|pattern| glob::Pattern::new(pattern).is_ok_and(|pattern| pattern.matches(name)))
+}
+
+/// `512 B`, `12.3 kB` or `4.5 MB`
+pub fn size(bytes: u64) -> String {
+    match bytes {
+        0..1_000 => format!("{} B", bytes),
+        1_000..1_000_000 => format!("{:.1} kB", bytes as f64 / 1e3),
+        _ => format!("{:.1} MB", bytes as f64 / 1e6),
+    }
+}
+
+/// `+1.2 kB` or `-300 B`
+pub fn delta(old: u64, new: u64) -> String {
+    if new >= old {
+        format!("+{}", size(new - old))
+    } else {
+        format!("-{}", size(old - new))
+    }
+}
+
+fn read_wheel(path: &Path) -> Result<(BTreeMap<String, u64>, String)> {
+    let file = fs::File::open(path).map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+    let mut archive = zip::ZipArchive::new(file)?;
+    let mut files = BTreeMap::new();
+    let mut metadata = String::new();
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        if name.ends_with(".dist-info/METADATA") && name.matches('/').count() == 1 {
+            entry.read_to_string(&mut metadata)?;
+        }
+        // `app-1.0.dist-info/RECORD` → `*.dist-info/RECORD`, so versions compare equal
+        let generic = match name.split_once('/') {
+            Some((top, rest)) if top.ends_with(".dist-info") || top.ends_with(".data") => {
+                format!("*{}/{}", &top[top.rfind('.').unwrap_or(0)..], rest)
+            }
+            _ => name,
+        };
+        files.insert(generic, entry.size());
+    }
+    Ok((files, metadata))
+}
+
+fn read_sdist(path: &Path) -> Result<(BTreeMap<String, u64>, String)> {
+    let file = fs::File::open(path).map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+    let mut files = BTreeMap::new();
+    let mut metadata = String::new();
+    for entry in tar::Archive::new(GzDecoder::new(file)).entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path()?.to_string_lossy().to_string();
+        // Drop the `app-1.0/` every path starts with
+        let relative = name.split_once('/').map_or(name.as_str(), |(_, rest)| rest).to_string();
+        if relative == "PKG-INFO" {
+            entry.read_to_string(&mut metadata)?;
+        }
+        files.insert(relative, entry.size());
+    }
+    Ok((files, metadata))
+}
@@ -0,0 +1,260 @@
+//! Editor settings pointing VS Code and PyCharm at the project's environment,
+//! test runner and formatter.
+//!
+//! In VS Code's `settings.json` only the keys pyforge manages are replaced.
+//! The PyCharm files pyforge writes (`misc.xml`, `modules.xml`, `<name>.iml`) are
+//! regenerated as a whole.
+
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::pep508::Requirement;
+use crate::core::project::Project;
+use crate::core::venv::Venv;
+use serde_json::{Map, Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const VSCODE_SETTINGS: &str = ".vscode/settings.json";
+pub const PYCHARM_DIR: &str = ".idea";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Editor {
+    Vscode,
+    Pycharm,
+}
+
+impl Editor {
+    pub fn name(self) -> &'static str {
+        match self {
+            Editor::Vscode => "VS Code",
+            Editor::Pycharm => "PyCharm",
+        }
+    }
+
+    /// Editors already set up in the project
+    pub fn configured(project: &Project) -> Vec<Editor> {
+        let mut editors = Vec::new();
+        if project.root.join(VSCODE_SETTINGS).is_file() {
+            editors.push(Editor::Vscode);
+        }
+        if project.root.join(PYCHARM_DIR).is_dir() {
+            editors.push(Editor::Pycharm);
+        }
+        editors
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestRunner {
+    Pytest,
+    Unittest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Formatter {
+    Ruff,
+    Black,
+}
+
+/// What the editor should be told about the project
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// Interpreter of the managed environment, relative to the project root when inside it
+    pub interpreter: PathBuf,
+    pub test_runner: Option<TestRunner>,
+    /// Arguments from the configured pytest command
+    pub pytest_args: Vec<String>,
+    pub formatter: Option<Formatter>,
+}
+
+impl Settings {
+    pub fn detect(project: &Project, config: &Config) -> Self {
+        let venv = Venv {
+            root: Venv::location(project, config),
+        };
+        let interpreter = venv.python();
+        let interpreter = interpreter
+            .strip_prefix(&project.root)
+            .map(Path::to_path_buf)
+            .unwrap_or(interpreter);
+
+        let command = config.test_command();
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let pytest = words.iter().position(|word| *word == "pytest" || word.ends_with("/pytest"));
+        let test_runner = if pytest.is_some() {
+            Some(TestRunner::Pytest)
+        } else if words.contains(&"unittest") {
+            Some(TestRunner::Unittest)
+        } else {
+            None
+        };
+        let pytest_args = pytest
+            .map(|position| words[position + 1..].iter().map(|word| word.to_string()).collect())
+            .unwrap_or_default();
+
+        Self {
+            interpreter,
+            test_runner,
+            pytest_args,
+            formatter: formatter(project),
+        }
+    }
+
+    /// Write the settings for `editor`, returning the files written
+    pub fn write(&self, project: &Project, editor: Editor) -> Result<Vec<PathBuf>> {
+        match editor {
+            Editor::Vscode => self.write_vscode(&project.root).map(|path| vec![path]),
+            Editor::Pycharm => self.write_pycharm(project),
+        }
+    }
+
+    fn write_vscode(&self, root: &Path) -> Result<PathBuf> {
+        let path = root.join(VSCODE_SETTINGS);
+        let mut settings = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str::<Map<String, Value>>(&content).map_err(|e| PyForgeError::InvalidConfig {
+                file: path.display().to_string(),
+                source: Box::new(e),
+            })?,
+            Err(_) => Map::new(),
+        };
+
+        let interpreter = if self.interpreter.is_relative() {
+            format!("${{workspaceFolder}}/{}", self.interpreter.display())
+        } else {
+            self.interpreter.display().to_string()
+        };
+        settings.insert("python.defaultInterpreterPath".to_string(), json!(interpreter));
+        settings.insert(
+            "python.testing.pytestEnabled".to_string(),
+            json!(self.test_runner == Some(TestRunner::Pytest)),
+        );
+        settings.insert(
+            "python.testing.unittestEnabled".to_string(),
+            json!(self.test_runner == Some(TestRunner::Unittest)),
+        );
+        if self.test_runner == Some(TestRunner::Pytest) {
+            settings.insert("python.testing.pytestArgs".to_string(), json!(self.pytest_args));
+        }
+        if let Some(formatter) = self.formatter {
+            let extension = match formatter {
+                Formatter::Ruff => "charliermarsh.ruff",
+                Formatter::Black => "ms-python.black-formatter",
+            };
+            let python = settings.entry("[python]").or_insert_with(|| json!({}));
+            if let Some(python) = python.as_object_mut() {
+                python.insert("editor.defaultFormatter".to_string(), json!(extension));
+            }
+        }
+
+        write(&path, &(serde_json::to_string_pretty(&settings)? + "\n"))?;
+        Ok(path)
+    }
+
+    /// PyCharm keeps interpreters in IDE-wide settings, so the project files only
+    /// name the SDK (PyCharm offers to create it from `.venv`), the test runner
+    /// and the excluded environment directory
+    fn write_pycharm(&self, project: &Project) -> Result<Vec<PathBuf>> {
+        let dir = project.root.join(PYCHARM_DIR);
+        let name = project.name().unwrap_or("project");
+        let sdk = format!("Python ({})", name);
+        let venv_dir = self
+            .interpreter
+            .parent()
+            .and_then(Path::parent)
+            .filter(|dir| dir.is_relative())
+            .map(|dir| dir.display().to_string());
+
+        let misc = dir.join("misc.xml");
+        write(
+            &misc,
+            &format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<project version=\"4\">\n  \
+                 <component name=\"ProjectRootManager\" version=\"2\" project-jdk-name=\"{}\" project-jdk-type=\"Python SDK\" />\n\
+                 </project>\n",
+                xml_escape(&sdk)
+            ),
+        )?;
+
+        let runner = match self.test_runner {
+            Some(TestRunner::Pytest) => "py.test",
+            Some(TestRunner::Unittest) | None => "Unittests",
+        };
+        let exclude = venv_dir
+            .map(|dir| format!("      <excludeFolder url=\"file://$MODULE_DIR$/{}\" />\n", xml_escape(&dir)))
+            .unwrap_or_default();
+        let module = dir.join(format!("{}.iml", name));
+        write(
+            &module,
+            &format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<module type=\"PYTHON_MODULE\" version=\"4\">\n  \
+                 <component name=\"NewModuleRootManager\">\n    <content url=\"file://$MODULE_DIR$\">\n{}    </content>\n    \
+                 <orderEntry type=\"jdk\" jdkName=\"{}\" jdkType=\"Python SDK\" />\n    \
+                 <orderEntry type=\"sourceFolder\" forTests=\"false\" />\n  </component>\n  \
+                 <component name=\"TestRunnerService\">\n    <option name=\"PROJECT_TEST_RUNNER\" value=\"{}\" />\n  </component>\n\
+                 </module>\n",
+                exclude,
+                xml_escape(&sdk),
+                runner
+            ),
+        )?;
+
+        let modules = dir.join("modules.xml");
+        let iml = format!("$PROJECT_DIR$/{}/{}.iml", PYCHARM_DIR, xml_escape(name));
+        write(
+            &modules,
+            &format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<project version=\"4\">\n  \
+                 <component name=\"ProjectModuleManager\">\n    <modules>\n      \
+                 <module fileurl=\"file://{}\" filepath=\"{}\" />\n    </modules>\n  </component>\n</project>\n",
+                iml, iml
+            ),
+        )?;
+
+        let mut written = vec![misc, modules, module];
+        if self.formatter == Some(Formatter::Black) {
+            let black = dir.join("black.xml");
+            write(
+                &black,
+                &format!(
+                    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<project version=\"4\">\n  \
+                     <component name=\"BlackFormatterSettings\">\n    <option name=\"enabledOnReformat\" value=\"true\" />\n    \
+                     <option name=\"sdkName\" value=\"{}\" />\n  </component>\n</project>\n",
+                    xml_escape(&sdk)
+                ),
+            )?;
+            written.push(black);
+        }
+        Ok(written)
+    }
+}
+
+/// The formatter the project depends on or configures in pyproject.toml
+fn formatter(project: &Project) -> Option<Formatter> {
+    let dependencies: Vec<String> = project
+        .dependencies()
+        .into_iter()
+        .chain(project.optional_dependencies().into_iter().flat_map(|(_, deps)| deps))
+        .filter_map(|dependency| Requirement::parse(&dependency).ok())
+        .map(|requirement| requirement.normalized_name())
+        .collect();
+    let tool = project.pyproject.get("tool");
+    let uses = |name: &str| dependencies.iter().any(|d| d == name) || tool.is_some_and(|t| t.get(name).is_some());
+    if uses("ruff") {
+        Some(Formatter::Ruff)
+    } else if uses("black") {
+        Some(Formatter::Black)
+    } else {
+        None
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn write(path: &Path, content: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| PyForgeError::file_error(format!("Could not create {}", dir.display()), e))?;
+    }
+    fs::write(path, content).map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))
+}
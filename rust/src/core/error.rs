@@ -1,4 +1,3 @@
-use std::fmt;
 use std::io;
 use thiserror::Error;
 use std::error::Error; 
@@ -35,6 +34,12 @@ pub enum PyForgeError {
         source: Box<dyn std::error::Error + Send + Sync>,
     },
     
+    #[error("Missing configuration: '{key}' is not set")]
+    MissingConfig { key: String, example: String },
+
+    #[error("pyforge doctor found {problems} problem(s)")]
+    HealthCheckFailed { problems: usize },
+    
     // === COMMAND ERRORS ===
     #[error("Command '{command}' failed with exit code {code}")]
     CommandFailed { command: String, code: i32 },
@@ -49,12 +54,25 @@ pub enum PyForgeError {
     #[error("Invalid project name: '{name}'. {reason}")]
     InvalidProjectName { name: String, reason: String },
     
-    #[error("Unsupported Python version: {version}")]
+    #[error("No Python {version} interpreter found; see pyforge python list")]
     UnsupportedPythonVersion { version: String },
     
     #[error("Template '{template}' not found")]
     TemplateNotFound { template: String },
     
+    #[error("Preset '{preset}' not found")]
+    PresetNotFound { preset: String },
+    
+    #[error("{message}")]
+    Usage { message: String },
+    
+    // === POLICY ERRORS ===
+    #[error("Blocked by {policy}: {} violation(s)", violations.len())]
+    PolicyViolation { policy: String, violations: Vec<String> },
+
+    #[error("{path} is not trusted to run the commands it defines")]
+    UntrustedProject { path: String },
+    
     // === NETWORK ERRORS ===
     #[error("Network error: {message}")]
     NetworkError { 
@@ -76,6 +94,40 @@ pub enum PyForgeError {
     #[error("Invalid TOML in '{file}': {message}")]
     InvalidToml { file: String, message: String },
     
+    // === DEPENDENCY ERRORS ===
+    #[error("No lockfile found at '{path}'")]
+    LockfileNotFound { path: String },
+    
+    #[error("Lockfile '{path}' is out of date with pyproject.toml")]
+    LockfileOutdated { path: String },
+    
+    #[error("Could not resolve '{package}': {reason}")]
+    ResolutionFailed { package: String, reason: String },
+
+    #[error("Resolution stopped: {limit}; pinned {} package(s) so far, {} still unsettled", pinned.len(), unsettled.len())]
+    ResolutionLimit { limit: String, pinned: Vec<String>, unsettled: Vec<String> },
+    
+    #[error("Hash mismatch for '{file}': expected {expected}, got {actual}")]
+    HashMismatch { file: String, expected: String, actual: String },
+    
+    #[error("Verification failed for {} package(s): {}", packages.len(), packages.join(", "))]
+    VerificationFailed { packages: Vec<String> },
+    
+    #[error("Toolchain differs from the one recorded in the lockfile")]
+    ToolchainMismatch { differences: Vec<String> },
+
+    #[error("Project metadata has {} issue(s) to fix", issues.len())]
+    MetadataIssues { issues: Vec<String> },
+
+    #[error("Built distributions failed {} check(s)", violations.len())]
+    ArtifactCheckFailed { violations: Vec<String> },
+
+    #[error("{} package(s) with install hooks were not approved: {}", packages.len(), packages.join(", "))]
+    InstallHooksHeld { packages: Vec<String> },
+
+    #[error("Rendered templates differ from {} snapshot(s): {}", snapshots.len(), snapshots.join(", "))]
+    SnapshotMismatch { snapshots: Vec<String> },
+    
     // === GENERIC ERRORS ===
     #[error("Internal error: {message}")]
     Internal { message: String },
@@ -129,19 +181,186 @@ impl PyForgeError {
         )
     }
     
-    /// Get appropriate exit code
+    /// Get appropriate exit code.
+    ///
+    /// Exit codes are grouped by category and are part of the scripting contract:
+    /// they only change in a major release.
+    ///
+    /// | Code | Category   | Variants                                                  |
+    /// |------|------------|-----------------------------------------------------------|
+    /// | 1    | internal   | `Internal`, `NotImplemented`                              |
+    /// | 2    | io         | `FileError`, `DirectoryNotFound`                          |
+    /// | 3    | command    | `CommandFailed`                                           |
+    /// | 64   | usage      | `Usage`, `InvalidProjectName`, `UnsupportedPythonVersion`, `TemplateNotFound`, `PresetNotFound` |
+    /// | 65   | project    | `NotAPythonProject`, `ParseError`, `InvalidJson`, `InvalidToml`, `LockfileNotFound`, `LockfileOutdated`, `ResolutionFailed`, `ResolutionLimit`, `HashMismatch`, `VerificationFailed`, `ToolchainMismatch`, `SnapshotMismatch`, `ArtifactCheckFailed`, `MetadataIssues`, `InstallHooksHeld` |
+    /// | 69   | network    | `NetworkError`, `DownloadFailed`                          |
+    /// | 73   | project    | `ProjectAlreadyExists`                                    |
+    /// | 75   | command    | `CommandTimeout`                                          |
+    /// | 77   | policy     | `PolicyViolation`, `UntrustedProject`                     |
+    /// | 78   | config     | `InvalidConfig`, `MissingConfig`, `HealthCheckFailed`     |
+    /// | 126  | io         | `PermissionDenied`                                        |
+    /// | 127  | command    | `CommandNotFound`                                         |
+    /// | 130  | cancelled  | `UserCancelled`                                           |
     pub fn exit_code(&self) -> i32 {
         match self {
-            PyForgeError::UserCancelled => 130,  // SIGINT
-            PyForgeError::CommandNotFound { .. } => 127,
+            PyForgeError::Internal { .. } | PyForgeError::NotImplemented { .. } => 1,
+            PyForgeError::FileError { .. } | PyForgeError::DirectoryNotFound { .. } => 2,
+            PyForgeError::CommandFailed { .. } => 3,
+            PyForgeError::Usage { .. }
+            | PyForgeError::InvalidProjectName { .. }
+            | PyForgeError::UnsupportedPythonVersion { .. }
+            | PyForgeError::TemplateNotFound { .. }
+            | PyForgeError::PresetNotFound { .. } => 64,
+            PyForgeError::NotAPythonProject
+            | PyForgeError::ParseError { .. }
+            | PyForgeError::InvalidJson { .. }
+            | PyForgeError::InvalidToml { .. }
+            | PyForgeError::LockfileNotFound { .. }
+            | PyForgeError::LockfileOutdated { .. }
+            | PyForgeError::ResolutionFailed { .. }
+            | PyForgeError::ResolutionLimit { .. }
+            | PyForgeError::HashMismatch { .. }
+            | PyForgeError::VerificationFailed { .. }
+            | PyForgeError::ToolchainMismatch { .. }
+            | PyForgeError::SnapshotMismatch { .. }
+            | PyForgeError::ArtifactCheckFailed { .. }
+            | PyForgeError::MetadataIssues { .. }
+            | PyForgeError::InstallHooksHeld { .. } => 65,
+            PyForgeError::NetworkError { .. } | PyForgeError::DownloadFailed { .. } => 69,
+            PyForgeError::ProjectAlreadyExists { .. } => 73,
+            PyForgeError::CommandTimeout { .. } => 75,
+            PyForgeError::PolicyViolation { .. } | PyForgeError::UntrustedProject { .. } => 77,
+            PyForgeError::InvalidConfig { .. } | PyForgeError::MissingConfig { .. } | PyForgeError::HealthCheckFailed { .. } => 78,
             PyForgeError::PermissionDenied { .. } => 126,
-            PyForgeError::FileError { .. } => 2,
-            PyForgeError::InvalidProjectName { .. } => 64,
-            PyForgeError::NotAPythonProject => 65,
-            _ => 1,
+            PyForgeError::CommandNotFound { .. } => 127,
+            PyForgeError::UserCancelled => 130,  // SIGINT
+        }
+    }
+    
+    /// Stable category name, see `exit_code` for the mapping
+    pub fn category(&self) -> &'static str {
+        match self.exit_code() {
+            1 => "internal",
+            2 | 126 => "io",
+            3 | 75 | 127 => "command",
+            64 => "usage",
+            65 | 73 => "project",
+            69 => "network",
+            77 => "policy",
+            78 => "config",
+            130 => "cancelled",
+            _ => "internal",
+        }
+    }
+    
+    /// Stable variant name for machine-readable output
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            PyForgeError::FileError { .. } => "FileError",
+            PyForgeError::DirectoryNotFound { .. } => "DirectoryNotFound",
+            PyForgeError::PermissionDenied { .. } => "PermissionDenied",
+            PyForgeError::ProjectAlreadyExists { .. } => "ProjectAlreadyExists",
+            PyForgeError::NotAPythonProject => "NotAPythonProject",
+            PyForgeError::InvalidConfig { .. } => "InvalidConfig",
+            PyForgeError::MissingConfig { .. } => "MissingConfig",
+            PyForgeError::HealthCheckFailed { .. } => "HealthCheckFailed",
+            PyForgeError::CommandFailed { .. } => "CommandFailed",
+            PyForgeError::CommandNotFound { .. } => "CommandNotFound",
+            PyForgeError::CommandTimeout { .. } => "CommandTimeout",
+            PyForgeError::InvalidProjectName { .. } => "InvalidProjectName",
+            PyForgeError::UnsupportedPythonVersion { .. } => "UnsupportedPythonVersion",
+            PyForgeError::TemplateNotFound { .. } => "TemplateNotFound",
+            PyForgeError::PresetNotFound { .. } => "PresetNotFound",
+            PyForgeError::Usage { .. } => "Usage",
+            PyForgeError::PolicyViolation { .. } => "PolicyViolation",
+            PyForgeError::UntrustedProject { .. } => "UntrustedProject",
+            PyForgeError::NetworkError { .. } => "NetworkError",
+            PyForgeError::DownloadFailed { .. } => "DownloadFailed",
+            PyForgeError::ParseError { .. } => "ParseError",
+            PyForgeError::InvalidJson { .. } => "InvalidJson",
+            PyForgeError::InvalidToml { .. } => "InvalidToml",
+            PyForgeError::LockfileNotFound { .. } => "LockfileNotFound",
+            PyForgeError::LockfileOutdated { .. } => "LockfileOutdated",
+            PyForgeError::ResolutionFailed { .. } => "ResolutionFailed",
+            PyForgeError::ResolutionLimit { .. } => "ResolutionLimit",
+            PyForgeError::HashMismatch { .. } => "HashMismatch",
+            PyForgeError::VerificationFailed { .. } => "VerificationFailed",
+            PyForgeError::ToolchainMismatch { .. } => "ToolchainMismatch",
+            PyForgeError::SnapshotMismatch { .. } => "SnapshotMismatch",
+            PyForgeError::ArtifactCheckFailed { .. } => "ArtifactCheckFailed",
+            PyForgeError::MetadataIssues { .. } => "MetadataIssues",
+            PyForgeError::InstallHooksHeld { .. } => "InstallHooksHeld",
+            PyForgeError::Internal { .. } => "Internal",
+            PyForgeError::UserCancelled => "UserCancelled",
+            PyForgeError::NotImplemented { .. } => "NotImplemented",
         }
     }
     
+    /// The variant's context fields as a JSON object
+    pub fn context(&self) -> serde_json::Value {
+        use serde_json::json;
+        
+        match self {
+            PyForgeError::FileError { message, .. } => json!({ "message": message }),
+            PyForgeError::DirectoryNotFound { path } => json!({ "path": path }),
+            PyForgeError::PermissionDenied { path, reason } => json!({ "path": path, "reason": reason }),
+            PyForgeError::ProjectAlreadyExists { name, path } => json!({ "name": name, "path": path }),
+            PyForgeError::InvalidConfig { file, .. } => json!({ "file": file }),
+            PyForgeError::MissingConfig { key, .. } => json!({ "key": key }),
+            PyForgeError::HealthCheckFailed { problems } => json!({ "problems": problems }),
+            PyForgeError::CommandFailed { command, code } => json!({ "command": command, "code": code }),
+            PyForgeError::CommandNotFound { command } => json!({ "command": command }),
+            PyForgeError::CommandTimeout { command, timeout } => json!({ "command": command, "timeout": timeout }),
+            PyForgeError::InvalidProjectName { name, reason } => json!({ "name": name, "reason": reason }),
+            PyForgeError::UnsupportedPythonVersion { version } => json!({ "version": version }),
+            PyForgeError::TemplateNotFound { template } => json!({ "template": template }),
+            PyForgeError::PresetNotFound { preset } => json!({ "preset": preset }),
+            PyForgeError::Usage { message } => json!({ "message": message }),
+            PyForgeError::PolicyViolation { policy, violations } => json!({ "policy": policy, "violations": violations }),
+            PyForgeError::UntrustedProject { path } => json!({ "path": path }),
+            PyForgeError::NetworkError { message, .. } => json!({ "message": message }),
+            PyForgeError::DownloadFailed { url, status } => json!({ "url": url, "status": status }),
+            PyForgeError::ParseError { file_type, message } => json!({ "file_type": file_type, "message": message }),
+            PyForgeError::InvalidJson { file, message } => json!({ "file": file, "message": message }),
+            PyForgeError::InvalidToml { file, message } => json!({ "file": file, "message": message }),
+            PyForgeError::LockfileNotFound { path } => json!({ "path": path }),
+            PyForgeError::LockfileOutdated { path } => json!({ "path": path }),
+            PyForgeError::ResolutionFailed { package, reason } => json!({ "package": package, "reason": reason }),
+            PyForgeError::ResolutionLimit { limit, pinned, unsettled } => {
+                json!({ "limit": limit, "pinned": pinned, "unsettled": unsettled })
+            }
+            PyForgeError::HashMismatch { file, expected, actual } => json!({ "file": file, "expected": expected, "actual": actual }),
+            PyForgeError::VerificationFailed { packages } => json!({ "packages": packages }),
+            PyForgeError::ToolchainMismatch { differences } => json!({ "differences": differences }),
+            PyForgeError::SnapshotMismatch { snapshots } => json!({ "snapshots": snapshots }),
+            PyForgeError::ArtifactCheckFailed { violations } => json!({ "violations": violations }),
+            PyForgeError::MetadataIssues { issues } => json!({ "issues": issues }),
+            PyForgeError::InstallHooksHeld { packages } => json!({ "packages": packages }),
+            PyForgeError::Internal { message } => json!({ "message": message }),
+            PyForgeError::NotImplemented { feature } => json!({ "feature": feature }),
+            PyForgeError::NotAPythonProject | PyForgeError::UserCancelled => json!({}),
+        }
+    }
+    
+    /// Full machine-readable description of the error (`--error-format json`)
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut causes = Vec::new();
+        let mut source = self.source();
+        while let Some(err) = source {
+            causes.push(err.to_string());
+            source = err.source();
+        }
+        
+        serde_json::json!({
+            "error": self.variant_name(),
+            "category": self.category(),
+            "code": self.exit_code(),
+            "message": self.to_string(),
+            "context": self.context(),
+            "causes": causes,
+        })
+    }
+    
     /// Display error with colors and formatting
     pub fn display_error(&self) {
         match self {
@@ -160,6 +379,13 @@ impl PyForgeError {
                     "Run 'pyforge init <name>' to create a new project".cyan()
                 );
             },
+            PyForgeError::MissingConfig { example, .. } => {
+                eprintln!("{} {}", "❌ Error:".red().bold(), self);
+                eprintln!("💡 {}: add it to pyproject.toml, for example:", "Suggestion".yellow());
+                for line in example.lines() {
+                    eprintln!("   {}", line.cyan());
+                }
+            },
             PyForgeError::CommandNotFound { command } => {
                 eprintln!("{} {}", "❌ Error:".red().bold(), self);
                 eprintln!("💡 {}: Install {} or make sure it's in your PATH", 
@@ -167,7 +393,108 @@ impl PyForgeError {
                     command.cyan()
                 );
             },
-            PyForgeError::InvalidProjectName { name, reason } => {
+            PyForgeError::ResolutionLimit { pinned, unsettled, .. } => {
+                eprintln!("{} {}", "❌ Error:".red().bold(), self);
+                if !unsettled.is_empty() {
+                    eprintln!("   Still unsettled: {}", unsettled.join(", "));
+                }
+                if !pinned.is_empty() {
+                    eprintln!("   Best effort so far: {}", pinned.join(", "));
+                }
+                eprintln!(
+                    "💡 {}: raise max-steps or timeout in [tool.pyforge.resolver], or tighten the constraints of the unsettled packages",
+                    "Suggestion".yellow()
+                );
+            },
+            PyForgeError::Usage { message } => {
+                // clap's rendered messages carry their own trailing newline
+                if message.ends_with('\n') {
+                    eprint!("{}", message);
+                } else {
+                    eprintln!("{} {}", "❌ Error:".red().bold(), message);
+                }
+            },
+            PyForgeError::PresetNotFound { .. } => {
+                eprintln!("{} {}", "❌ Error:".red().bold(), self);
+                eprintln!("💡 {}: {}", 
+                    "Available presets".yellow(),
+                    crate::core::preset::Preset::available().join(", ").cyan()
+                );
+            },
+            PyForgeError::LockfileNotFound { .. } | PyForgeError::LockfileOutdated { .. } => {
+                eprintln!("{} {}", "❌ Error:".red().bold(), self);
+                eprintln!("💡 {}: {}", 
+                    "Suggestion".yellow(), 
+                    "Run 'pyforge lock' to resolve and lock dependencies".cyan()
+                );
+            },
+            PyForgeError::VerificationFailed { .. } => {
+                eprintln!("{} {}", "❌ Error:".red().bold(), self);
+                eprintln!("💡 {}: {}", 
+                    "Suggestion".yellow(), 
+                    "Run 'pyforge sync --reinstall' to restore the locked packages".cyan()
+                );
+            },
+            PyForgeError::MetadataIssues { .. } => {
+                eprintln!("{} {}", "❌ Error:".red().bold(), self);
+                eprintln!("💡 {}: run 'pyforge check metadata --fix' to make the changes", 
+                    "Suggestion".yellow()
+                );
+            },
+            PyForgeError::InstallHooksHeld { .. } => {
+                eprintln!("{} {}", "❌ Error:".red().bold(), self);
+                eprintln!("💡 {}: review the packages, then list them in [tool.pyforge.install.hooks] allow", 
+                    "Suggestion".yellow()
+                );
+            },
+            PyForgeError::ArtifactCheckFailed { violations } => {
+                eprintln!("{} {}", "❌ Error:".red().bold(), self);
+                for violation in violations {
+                    eprintln!("   {} {}", "✗".red(), violation);
+                }
+                eprintln!("💡 {}: fix what gets packaged, or adjust [tool.pyforge.build.checks]", 
+                    "Suggestion".yellow()
+                );
+            },
+            PyForgeError::SnapshotMismatch { .. } => {
+                eprintln!("{} {}", "❌ Error:".red().bold(), self);
+                eprintln!("💡 {}: {}", 
+                    "Suggestion".yellow(), 
+                    "If the changes are intended, run 'pyforge template snapshot --update' and commit the snapshots".cyan()
+                );
+            },
+            PyForgeError::ToolchainMismatch { differences } => {
+                eprintln!("{} {}", "❌ Error:".red().bold(), self);
+                for difference in differences {
+                    eprintln!("   {} {}", "✗".red(), difference);
+                }
+                eprintln!("💡 {}: install the recorded versions, or re-lock with 'pyforge lock' to record yours", 
+                    "Suggestion".yellow()
+                );
+            },
+            PyForgeError::PolicyViolation { violations, .. } => {
+                eprintln!("{} {}", "❌ Error:".red().bold(), self);
+                for violation in violations {
+                    eprintln!("   {} {}", "✗".red(), violation);
+                }
+                eprintln!("💡 {}: run 'pyforge policy show' to see the active rules", 
+                    "Suggestion".yellow()
+                );
+            },
+            PyForgeError::UntrustedProject { path } => {
+                eprintln!("{} {}", "❌ Error:".red().bold(), self);
+                eprintln!("💡 {}: review its [tool.pyforge] commands, then run 'pyforge trust' in {}", 
+                    "Suggestion".yellow(),
+                    path
+                );
+                if std::env::var_os("CI").is_some() {
+                    eprintln!("💡 {}: in CI, set {}=1 if this checkout's commands may run",
+                        "Suggestion".yellow(),
+                        crate::core::trust::TRUST_ALL_ENV
+                    );
+                }
+            },
+            PyForgeError::InvalidProjectName { .. } => {
                 eprintln!("{} {}", "❌ Error:".red().bold(), self);
                 eprintln!("💡 {}: Names must be valid Python package names", 
                     "Suggestion".yellow()
@@ -231,6 +558,15 @@ impl From<serde_json::Error> for PyForgeError {
     }
 }
 
+impl From<zip::result::ZipError> for PyForgeError {
+    fn from(err: zip::result::ZipError) -> Self {
+        Self::ParseError {
+            file_type: "wheel".to_string(),
+            message: err.to_string(),
+        }
+    }
+}
+
 impl From<toml::de::Error> for PyForgeError {
     fn from(err: toml::de::Error) -> Self {
         Self::ParseError {
@@ -312,6 +648,14 @@ pub mod validation {
                 reason: format!("'{}' is a reserved word", name),
             });
         }
+
+        // The project directory must be creatable on Windows too
+        if let Some(reason) = crate::core::paths::windows_name_problem(name) {
+            return Err(PyForgeError::InvalidProjectName {
+                name: name.to_string(),
+                reason,
+            });
+        }
         
         Ok(())
     }
@@ -327,15 +671,8 @@ pub mod validation {
         }
     }
     
+    /// Check an interpreter of `version` (e.g. `3.12`) is installed
     pub fn validate_python_version(version: &str) -> Result<()> {
-        let valid_versions = ["3.8", "3.9", "3.10", "3.11", "3.12"];
-        
-        if !valid_versions.iter().any(|&v| version.starts_with(v)) {
-            return Err(PyForgeError::UnsupportedPythonVersion {
-                version: version.to_string(),
-            });
-        }
-        
-        Ok(())
+        crate::core::python::installed(version).map(|_| ())
     }
 }
\ No newline at end of file
@@ -0,0 +1,254 @@
+use crate::core::dirs::config_dir;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::preset::{PreCommitRepo, add_dependency_groups, normalize_layout, to_edit_table};
+use crate::core::project::Project;
+use crate::core::template::substitute;
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value as Yaml};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, Table};
+
+/// Features shipped with PyForge
+const BUILTIN_FEATURES: &[(&str, &str)] = &[
+    ("ci", include_str!("../features/ci.toml")),
+    ("docker", include_str!("../features/docker.toml")),
+    ("docs", include_str!("../features/docs.toml")),
+    ("pre-commit", include_str!("../features/pre-commit.toml")),
+];
+
+/// A piece of project setup that can be added to an existing project.
+///
+/// Features are TOML files like presets: `[[file]]` entries are rendered with
+/// the template placeholders (`{{ project_name }}`, `{{ module_name }}`,
+/// `{{ python_version }}`), `[dependency-groups]` and `[tool.*]` are merged
+/// into `pyproject.toml`, and `[[pre-commit]]` repos are added to
+/// `.pre-commit-config.yaml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Feature {
+    #[serde(skip)]
+    pub name: String,
+
+    #[serde(default)]
+    pub description: String,
+
+    #[serde(default, rename = "file")]
+    pub files: Vec<FeatureFile>,
+
+    #[serde(default)]
+    pub dependency_groups: BTreeMap<String, Vec<String>>,
+
+    #[serde(default)]
+    pub tool: toml::Table,
+
+    #[serde(default)]
+    pub pre_commit: Vec<PreCommitRepo>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeatureFile {
+    pub path: String,
+    pub content: String,
+}
+
+/// Files a feature writes, and what already differs in the project
+#[derive(Debug, Default)]
+pub struct Plan {
+    /// Relative path and new content of every file that changes
+    pub writes: Vec<(PathBuf, String)>,
+    /// Files that already have the feature's content
+    pub unchanged: Vec<PathBuf>,
+    /// Existing files and settings the feature would overwrite
+    pub conflicts: Vec<String>,
+}
+
+/// Directory searched for user and organization features
+pub fn user_feature_dir() -> PathBuf {
+    config_dir().join("features")
+}
+
+impl Feature {
+    /// Resolve a feature by path, then user feature directory, then built-ins
+    pub fn load(name: &str) -> Result<Self> {
+        let path = Path::new(name);
+        if path.extension().is_some_and(|ext| ext == "toml") && path.is_file() {
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            return Self::from_file(&stem, path);
+        }
+
+        let user = user_feature_dir().join(format!("{}.toml", name));
+        if user.is_file() {
+            return Self::from_file(name, &user);
+        }
+
+        BUILTIN_FEATURES
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|(_, content)| Self::parse(name, content, "built-in feature"))
+            .unwrap_or_else(|| {
+                Err(PyForgeError::Usage {
+                    message: format!("Unknown feature '{}' (available: {})", name, Self::available().join(", ")),
+                })
+            })
+    }
+
+    /// All features available by name: user features shadow built-ins
+    pub fn available() -> Vec<String> {
+        let mut names: Vec<String> = BUILTIN_FEATURES.iter().map(|(name, _)| name.to_string()).collect();
+        if let Ok(entries) = fs::read_dir(user_feature_dir()) {
+            for path in entries.flatten().map(|e| e.path()) {
+                if path.extension().is_some_and(|ext| ext == "toml")
+                    && let Some(stem) = path.file_stem()
+                {
+                    names.push(stem.to_string_lossy().to_string());
+                }
+            }
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    fn from_file(name: &str, path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| PyForgeError::file_error(format!("Could not read feature {}", path.display()), e))?;
+        Self::parse(name, &content, &path.display().to_string())
+    }
+
+    fn parse(name: &str, content: &str, origin: &str) -> Result<Self> {
+        let mut feature: Feature = toml::from_str(content).map_err(|e| PyForgeError::InvalidToml {
+            file: origin.to_string(),
+            message: e.to_string(),
+        })?;
+        feature.name = name.to_string();
+        Ok(feature)
+    }
+
+    /// Work out the changes to `project` without writing anything. With `force`,
+    /// conflicting files and settings are replaced instead of reported.
+    pub fn plan(&self, project: &Project, variables: &BTreeMap<String, String>, force: bool) -> Result<Plan> {
+        let mut plan = Plan::default();
+
+        for file in &self.files {
+            let relative = PathBuf::from(&file.path);
+            let content = substitute(&file.content, variables);
+            match fs::read_to_string(project.root.join(&relative)) {
+                Ok(existing) if existing == content => plan.unchanged.push(relative),
+                Ok(_) if !force => plan.conflicts.push(format!("{} already exists with other content", file.path)),
+                _ => plan.writes.push((relative, content)),
+            }
+        }
+
+        let pyproject = project.pyproject_path();
+        let original = fs::read_to_string(&pyproject)
+            .map_err(|e| PyForgeError::file_error(format!("Could not read {}", pyproject.display()), e))?;
+        let mut document = original.parse::<DocumentMut>().map_err(|e| PyForgeError::InvalidToml {
+            file: pyproject.display().to_string(),
+            message: e.to_string(),
+        })?;
+        add_dependency_groups(&mut document, &self.dependency_groups);
+        if !self.tool.is_empty() {
+            let incoming = to_edit_table(&toml::Table::from_iter([(
+                "tool".to_string(),
+                toml::Value::Table(self.tool.clone()),
+            )]))?;
+            fill(document.as_table_mut(), &incoming, "", force, &mut plan.conflicts);
+        }
+        normalize_layout(&mut document);
+        let updated = document.to_string();
+        if updated != original {
+            plan.writes.push((PathBuf::from("pyproject.toml"), updated));
+        }
+
+        if !self.pre_commit.is_empty() {
+            let relative = PathBuf::from(".pre-commit-config.yaml");
+            match self.pre_commit_config(&project.root.join(&relative))? {
+                Some(content) => plan.writes.push((relative, content)),
+                None => plan.unchanged.push(relative),
+            }
+        }
+        Ok(plan)
+    }
+
+    /// `.pre-commit-config.yaml` with the feature's repos added; `None` when all are there.
+    /// Repos already configured keep their pinned revision.
+    fn pre_commit_config(&self, path: &Path) -> Result<Option<String>> {
+        let mut config = match fs::read_to_string(path) {
+            Ok(content) => serde_yaml::from_str::<Yaml>(&content).map_err(|e| PyForgeError::InvalidConfig {
+                file: path.display().to_string(),
+                source: Box::new(e),
+            })?,
+            Err(_) => Yaml::Mapping(Mapping::new()),
+        };
+        let Some(mapping) = config.as_mapping_mut() else {
+            return Err(PyForgeError::Usage {
+                message: format!("{} is not a YAML mapping", path.display()),
+            });
+        };
+        let repos = mapping
+            .entry(Yaml::from("repos"))
+            .or_insert_with(|| Yaml::Sequence(Vec::new()));
+        let Some(repos) = repos.as_sequence_mut() else {
+            return Err(PyForgeError::Usage {
+                message: format!("`repos` in {} is not a list", path.display()),
+            });
+        };
+
+        let mut added = false;
+        for repo in &self.pre_commit {
+            let present = repos
+                .iter()
+                .any(|existing| existing.get("repo").and_then(Yaml::as_str) == Some(repo.repo.as_str()));
+            if present {
+                continue;
+            }
+            let mut entry = Mapping::new();
+            entry.insert("repo".into(), repo.repo.as_str().into());
+            entry.insert("rev".into(), repo.rev.as_str().into());
+            let hooks = repo
+                .hooks
+                .iter()
+                .map(|id| Yaml::Mapping(Mapping::from_iter([(Yaml::from("id"), Yaml::from(id.as_str()))])))
+                .collect();
+            entry.insert("hooks".into(), Yaml::Sequence(hooks));
+            repos.push(Yaml::Mapping(entry));
+            added = true;
+        }
+        if !added {
+            return Ok(None);
+        }
+        serde_yaml::to_string(&config)
+            .map(Some)
+            .map_err(|e| PyForgeError::internal(format!("Could not render pre-commit config: {}", e)))
+    }
+}
+
+/// Add the keys of `source` missing from `target`, recording keys whose values
+/// differ as conflicts (or replacing them with `force`)
+fn fill(target: &mut Table, source: &Table, prefix: &str, force: bool, conflicts: &mut Vec<String>) {
+    for (key, item) in source.iter() {
+        let path = if prefix.is_empty() { key.to_string() } else { format!("{}.{}", prefix, key) };
+        match (target.get_mut(key), item) {
+            (None, _) => {
+                target.insert(key, item.clone());
+            }
+            (Some(Item::Table(existing)), Item::Table(incoming)) => fill(existing, incoming, &path, force, conflicts),
+            (Some(existing), _) => {
+                let same = match (existing.as_value(), item.as_value()) {
+                    (Some(a), Some(b)) => a.to_string().trim() == b.to_string().trim(),
+                    _ => false,
+                };
+                if same {
+                    continue;
+                }
+                if force {
+                    target.insert(key, item.clone());
+                } else {
+                    conflicts.push(format!("pyproject.toml sets {} differently", path));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Pull requests, merge requests and releases through the GitHub and GitLab
+//! REST APIs, for the repository behind a git remote.
+
+use crate::core::auth;
+use crate::core::config::{Config, ForgeKind, NetworkConfig};
+use crate::core::error::{PyForgeError, Result};
+use crate::core::index;
+use reqwest::blocking::{Client, RequestBuilder};
+use std::fs;
+use std::path::Path;
+
+/// The hosting service of a repository and credentials for its API
+#[derive(Debug, Clone)]
+pub struct Forge {
+    pub kind: ForgeKind,
+    api_url: String,
+    /// `owner/repo` (GitHub) or the full project path (GitLab)
+    repository: String,
+    token: String,
+    network: NetworkConfig,
+}
+
+impl Forge {
+    /// The forge hosting the repository at `remote_url`.
+    ///
+    /// The token is the one stored for the API host (see `auth::forge_service`):
+    /// `pyforge auth login github|gitlab` for github.com and gitlab.com,
+    /// `pyforge auth login forge:<host>` for a self-hosted instance.
+    pub fn from_remote(remote_url: &str, config: &Config) -> Result<Self> {
+        let (host, repository) = parse_remote(remote_url).ok_or_else(|| PyForgeError::Usage {
+            message: format!("Cannot tell the repository from remote URL '{}'", remote_url),
+        })?;
+        let kind = config.forge.kind.unwrap_or(if host.contains("gitlab") {
+            ForgeKind::Gitlab
+        } else {
+            ForgeKind::Github
+        });
+        let api_url = match (&config.forge.api_url, kind) {
+            (Some(url), _) => url.trim_end_matches('/').to_string(),
+            (None, ForgeKind::Github) if host == "github.com" => "https://api.github.com".to_string(),
+            (None, ForgeKind::Github) => format!("https://{}/api/v3", host),
+            (None, ForgeKind::Gitlab) => format!("https://{}/api/v4", host),
+        };
+        let service = auth::forge_service(&api_url).ok_or_else(|| PyForgeError::Usage {
+            message: format!("'{}' is not an https API URL", api_url),
+        })?;
+        let token = auth::require_token(&service, &format!("access {}", repository))?.secret;
+
+        Ok(Self {
+            kind,
+            api_url,
+            repository,
+            token,
+            network: config.network.clone(),
+        })
+    }
+
+    /// Open a pull/merge request from `head` into `base`, returning its web URL
+    pub fn open_request(&self, head: &str, base: &str, title: &str, body: &str) -> Result<String> {
+        let client = self.client()?;
+        let (request, url_field) = match self.kind {
+            ForgeKind::Github => (
+                client
+                    .post(format!("{}/repos/{}/pulls", self.api_url, self.repository))
+                    .bearer_auth(&self.token)
+                    .header("Accept", "application/vnd.github+json")
+                    .json(&serde_json::json!({ "title": title, "head": head, "base": base, "body": body })),
+                "html_url",
+            ),
+            ForgeKind::Gitlab => (
+                client
+                    .post(format!(
+                        "{}/projects/{}/merge_requests",
+                        self.api_url,
+                        self.repository.replace('/', "%2F")
+                    ))
+                    .header("PRIVATE-TOKEN", &self.token)
+                    .json(&serde_json::json!({
+                        "title": title,
+                        "source_branch": head,
+                        "target_branch": base,
+                        "description": body,
+                        "remove_source_branch": true,
+                    })),
+                "web_url",
+            ),
+        };
+
+        let reply = send(request, &format!("open a {} for {}", self.kind.request_name(), self.repository))?;
+        Ok(reply.get(url_field).and_then(|url| url.as_str()).unwrap_or_default().to_string())
+    }
+
+    /// Create a GitHub release for `tag` (created from the default branch if it doesn't exist)
+    pub fn create_release(&self, tag: &str, body: &str, prerelease: bool) -> Result<Release> {
+        if self.kind != ForgeKind::Github {
+            return Err(PyForgeError::Usage {
+                message: format!("{} is not hosted on GitHub", self.repository),
+            });
+        }
+        let request = self
+            .client()?
+            .post(format!("{}/repos/{}/releases", self.api_url, self.repository))
+            .bearer_auth(&self.token)
+            .header("Accept", "application/vnd.github+json")
+            .json(&serde_json::json!({ "tag_name": tag, "name": tag, "body": body, "prerelease": prerelease }));
+        let reply = send(request, &format!("create release {} for {}", tag, self.repository))?;
+
+        let field = |name: &str| reply.get(name).and_then(|value| value.as_str()).unwrap_or_default().to_string();
+        // `upload_url` is a URI template: ".../assets{?name,label}"
+        let upload_url = field("upload_url");
+        let upload_url = upload_url.split('{').next().unwrap_or_default().to_string();
+        Ok(Release {
+            html_url: field("html_url"),
+            upload_url,
+        })
+    }
+
+    /// Attach a file to a release under its file name
+    pub fn upload_asset(&self, release: &Release, path: &Path) -> Result<()> {
+        let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+        let content =
+            fs::read(path).map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+        let request = self
+            .client()?
+            .post(&release.upload_url)
+            .query(&[("name", name)])
+            .bearer_auth(&self.token)
+            .header("Accept", "application/vnd.github+json")
+            .header("Content-Type", "application/octet-stream")
+            .body(content);
+        send(request, &format!("upload {}", name))?;
+        Ok(())
+    }
+
+    fn client(&self) -> Result<Client> {
+        index::client_builder(&self.network)?
+            .build()
+            .map_err(|e| PyForgeError::network_error("Could not create HTTP client", Some(e)))
+    }
+}
+
+/// A GitHub release
+#[derive(Debug, Clone)]
+pub struct Release {
+    pub html_url: String,
+    /// Where assets are posted
+    upload_url: String,
+}
+
+/// Send an API request, turning error statuses into errors carrying the API's message
+fn send(request: RequestBuilder, action: &str) -> Result<serde_json::Value> {
+    let response = request
+        .send()
+        .map_err(|e| PyForgeError::network_error(format!("Could not {}", action), Some(e)))?;
+    let status = response.status();
+    let reply: serde_json::Value = response.json().unwrap_or_default();
+    if !status.is_success() {
+        let message = reply.get("message").map(|m| m.to_string()).unwrap_or_default();
+        return Err(PyForgeError::network_error(
+            format!("Could not {}: HTTP {} {}", action, status.as_u16(), message),
+            None,
+        ));
+    }
+    Ok(reply)
+}
+
+impl ForgeKind {
+    /// What the forge calls a proposed change
+    pub fn request_name(self) -> &'static str {
+        match self {
+            ForgeKind::Github => "pull request",
+            ForgeKind::Gitlab => "merge request",
+        }
+    }
+}
+
+/// Host and repository path of `https://host/owner/repo.git`,
+/// `ssh://git@host[:port]/owner/repo.git` or `git@host:owner/repo.git`
+fn parse_remote(url: &str) -> Option<(String, String)> {
+    let (host, path) = match url.split_once("://") {
+        Some((_, rest)) => {
+            let (authority, path) = rest.split_once('/')?;
+            let host = authority.rsplit('@').next()?;
+            (host.split(':').next()?, path)
+        }
+        None => {
+            let (authority, path) = url.split_once(':')?;
+            (authority.rsplit('@').next()?, path)
+        }
+    };
+    let path = path.trim_matches('/').trim_end_matches(".git");
+    (!host.is_empty() && path.contains('/')).then(|| (host.to_string(), path.to_string()))
+}
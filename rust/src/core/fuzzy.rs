@@ -0,0 +1,50 @@
+/// Score `candidate` against a fuzzy `pattern`.
+///
+/// Every pattern character must appear in order (case-insensitive). Consecutive
+/// matches and matches at word boundaries score higher; `None` means no match.
+pub fn score(pattern: &str, candidate: &str) -> Option<i64> {
+    if pattern.is_empty() {
+        return Some(0);
+    }
+
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous_matched = false;
+    let mut previous_char = None;
+
+    for c in candidate.chars() {
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        if next < pattern.len() && lower == pattern[next] {
+            score += 1;
+            if previous_matched {
+                score += 5;
+            }
+            if previous_char.is_none_or(|p: char| !p.is_alphanumeric()) {
+                score += 8;
+            }
+            next += 1;
+            previous_matched = true;
+        } else {
+            previous_matched = false;
+        }
+        previous_char = Some(c);
+    }
+
+    if next == pattern.len() {
+        // Prefer shorter candidates among equal matches
+        Some(score * 100 - candidate.chars().count() as i64)
+    } else {
+        None
+    }
+}
+
+/// Filter and rank `items` by their fuzzy score against `pattern`, best first
+pub fn rank<'a, T>(pattern: &str, items: &'a [T], key: impl Fn(&T) -> String) -> Vec<&'a T> {
+    let mut scored: Vec<_> = items
+        .iter()
+        .filter_map(|item| score(pattern, &key(item)).map(|s| (s, item)))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, item)| item).collect()
+}
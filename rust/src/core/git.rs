@@ -0,0 +1,174 @@
+//! Just enough git to locate worktrees, read merge conflicts and earlier
+//! lockfiles, push update branches and describe HEAD for versioning;
+//! everything goes through the `git` CLI.
+
+use crate::core::error::{PyForgeError, Result};
+use crate::core::timings;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The git worktree containing a directory
+#[derive(Debug, Clone)]
+pub struct Worktree {
+    /// Top-level directory of this worktree
+    pub root: PathBuf,
+    /// The repository's git directory shared by all its worktrees
+    pub common_dir: PathBuf,
+    /// Checked-out branch, `None` when HEAD is detached
+    pub branch: Option<String>,
+}
+
+impl Worktree {
+    /// The worktree containing `dir`, or `None` outside a repository (or without git)
+    pub fn discover(dir: &Path) -> Option<Self> {
+        let output = git(dir, &["rev-parse", "--path-format=absolute", "--show-toplevel", "--git-common-dir"])?;
+        let mut lines = output.lines();
+        let root = PathBuf::from(lines.next()?);
+        let common_dir = PathBuf::from(lines.next()?);
+        let branch = git(dir, &["symbolic-ref", "--quiet", "--short", "HEAD"]).map(|b| b.trim().to_string());
+        Some(Self { root, common_dir, branch })
+    }
+
+    /// The main worktree's top-level directory; `None` for bare repositories
+    pub fn main_root(&self) -> Option<PathBuf> {
+        (self.common_dir.file_name()? == ".git").then(|| self.common_dir.parent().map(Path::to_path_buf))?
+    }
+
+    /// Whether this is a linked worktree created by `git worktree add`
+    pub fn is_linked(&self) -> bool {
+        self.main_root().is_none_or(|main| main != self.root)
+    }
+
+    /// Where `dir` (inside this worktree) is in the main worktree
+    pub fn in_main(&self, dir: &Path) -> Option<PathBuf> {
+        let dir = dir.canonicalize().ok()?;
+        let relative = dir.strip_prefix(&self.root).ok()?;
+        Some(self.main_root()?.join(relative))
+    }
+}
+
+/// The base, ours and theirs versions of a conflicted `file` in `dir`, from git's index
+pub fn conflict_stages(dir: &Path, file: &str) -> Option<crate::core::lockmerge::Sides> {
+    let stage = |number: u8| git(dir, &["show", &format!(":{}:./{}", number, file)]);
+    Some(crate::core::lockmerge::Sides {
+        base: stage(1),
+        ours: stage(2)?,
+        theirs: stage(3)?,
+    })
+}
+
+/// URL of a configured remote, e.g. `origin`
+pub fn remote_url(dir: &Path, remote: &str) -> Option<String> {
+    git(dir, &["remote", "get-url", remote]).map(|url| url.trim().to_string())
+}
+
+/// A value from the user's git config, e.g. `user.name`
+pub fn global_config(key: &str) -> Option<String> {
+    git(Path::new("."), &["config", "--global", "--get", key])
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Whether `file` in `dir` has uncommitted changes
+pub fn is_modified(dir: &Path, file: &str) -> bool {
+    git(dir, &["status", "--porcelain", "--", file]).is_some_and(|status| !status.trim().is_empty())
+}
+
+/// Whether `.gitignore` (or another exclude file) ignores `file` in `dir`
+pub fn is_ignored(dir: &Path, file: &str) -> bool {
+    git(dir, &["check-ignore", "--quiet", "--", file]).is_some()
+}
+
+/// Run `git` in `dir`, failing with its exit code; output goes to the terminal
+pub fn run(dir: &Path, args: &[&str]) -> Result<()> {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(dir).args(args);
+    let _span = timings::subprocess(&command);
+    let status = command
+        .status()
+        .map_err(|_| PyForgeError::CommandNotFound {
+            command: "git".to_string(),
+        })?;
+    if !status.success() {
+        return Err(PyForgeError::command_failed(
+            format!("git {}", args.join(" ")),
+            status.code().unwrap_or(-1),
+        ));
+    }
+    Ok(())
+}
+
+/// Commits in `range` (e.g. `v1.0..HEAD`) that changed `file`, oldest first,
+/// as full hash and subject; `None` when git fails, e.g. on an unknown revision
+pub fn file_history(dir: &Path, range: &str, file: &str) -> Option<Vec<(String, String)>> {
+    let log = git(dir, &["log", "--reverse", "--format=%H%x09%s", range, "--", file])?;
+    Some(
+        log.lines()
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(hash, subject)| (hash.to_string(), subject.to_string()))
+            .collect(),
+    )
+}
+
+/// `file` (relative to `dir`) as of `revision`
+pub fn show_file(dir: &Path, revision: &str, file: &str) -> Option<String> {
+    git(dir, &["show", &format!("{}:./{}", revision, file)])
+}
+
+/// Full commit hash of HEAD
+pub fn head_commit(dir: &Path) -> Option<String> {
+    git(dir, &["rev-parse", "HEAD"]).map(|commit| commit.trim().to_string())
+}
+
+/// Commit time of HEAD, in seconds since the Unix epoch
+pub fn head_commit_time(dir: &Path) -> Option<i64> {
+    git(dir, &["log", "-1", "--format=%ct", "HEAD"])?.trim().parse().ok()
+}
+
+/// Whether tracked files have uncommitted changes
+pub fn is_dirty(dir: &Path) -> bool {
+    git(dir, &["status", "--porcelain", "--untracked-files=no"]).is_some_and(|status| !status.trim().is_empty())
+}
+
+/// Where HEAD is relative to the nearest tag
+#[derive(Debug, Clone)]
+pub struct Describe {
+    /// Nearest tag matching the pattern, `None` when there is none
+    pub tag: Option<String>,
+    /// Commits since the tag, or since the root commit without one
+    pub distance: u64,
+    /// Abbreviated commit hash of HEAD
+    pub commit: String,
+    /// Tracked files have uncommitted changes
+    pub dirty: bool,
+}
+
+/// Describe HEAD against the nearest tag matching the glob `pattern`;
+/// `None` outside a repository or before the first commit
+pub fn describe(dir: &Path, pattern: &str) -> Option<Describe> {
+    let commit = git(dir, &["rev-parse", "--short", "HEAD"])?.trim().to_string();
+    let dirty = is_dirty(dir);
+    let described = git(dir, &["describe", "--tags", "--long", "--match", pattern, "HEAD"]);
+    // `<tag>-<distance>-g<hash>`, where the tag itself may contain dashes
+    let parsed = described.as_deref().map(str::trim).and_then(|line| {
+        let (rest, _hash) = line.rsplit_once("-g")?;
+        let (tag, distance) = rest.rsplit_once('-')?;
+        Some((tag.to_string(), distance.parse().ok()?))
+    });
+    let (tag, distance) = match parsed {
+        Some((tag, distance)) => (Some(tag), distance),
+        None => (None, git(dir, &["rev-list", "--count", "HEAD"])?.trim().parse().ok()?),
+    };
+    Some(Describe { tag, distance, commit, dirty })
+}
+
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(dir).args(args);
+    let _span = timings::subprocess(&command);
+    let output = command.output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
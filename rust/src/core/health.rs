@@ -0,0 +1,307 @@
+//! Project health report card (`pyforge report`).
+//!
+//! Each check scores between 0 and 1 and carries a weight; checks that could
+//! not run (no coverage plugin, no linter, no network) are left out of the
+//! total rather than counted as failures. Known vulnerabilities come from the
+//! OSV database, asked only about packages locked from PyPI so private
+//! package names stay private.
+
+use crate::core::changelog;
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::htmlreport::{self, escape};
+use crate::core::index;
+use crate::core::lockfile::Lockfile;
+use serde::Deserialize;
+use std::fmt;
+use std::path::Path;
+
+/// Vulnerability API of the OSV project
+pub const DEFAULT_VULNERABILITY_URL: &str = "https://api.osv.dev";
+
+/// Share of the score each check is worth
+const WEIGHTS: [(Kind, u32); 5] = [
+    (Kind::Tests, 30),
+    (Kind::Vulnerabilities, 25),
+    (Kind::Coverage, 20),
+    (Kind::Outdated, 15),
+    (Kind::Lint, 10),
+];
+
+/// Lint issues at which the lint check scores nothing
+const LINT_ISSUES_FOR_ZERO: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Tests,
+    Vulnerabilities,
+    Coverage,
+    Outdated,
+    Lint,
+}
+
+impl Kind {
+    fn weight(self) -> u32 {
+        WEIGHTS.iter().find(|(kind, _)| *kind == self).map_or(0, |(_, weight)| *weight)
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Kind::Tests => "Tests",
+            Kind::Vulnerabilities => "Vulnerabilities",
+            Kind::Coverage => "Coverage",
+            Kind::Outdated => "Dependencies",
+            Kind::Lint => "Lint",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Warn,
+    Fail,
+    /// The check could not run and does not count towards the score
+    Skipped,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Status::Pass => "pass",
+            Status::Warn => "warn",
+            Status::Fail => "fail",
+            Status::Skipped => "skipped",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The outcome of one check
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub kind: Kind,
+    /// Between 0 and 1; `None` when skipped
+    pub score: Option<f64>,
+    /// One-line summary
+    pub summary: String,
+    /// Items behind the summary: failing packages, lint rules, ...
+    pub details: Vec<String>,
+}
+
+impl Check {
+    pub fn scored(kind: Kind, score: f64, summary: impl Into<String>) -> Self {
+        Self {
+            kind,
+            score: Some(score.clamp(0.0, 1.0)),
+            summary: summary.into(),
+            details: Vec::new(),
+        }
+    }
+
+    pub fn skipped(kind: Kind, reason: impl Into<String>) -> Self {
+        Self {
+            kind,
+            score: None,
+            summary: reason.into(),
+            details: Vec::new(),
+        }
+    }
+
+    pub fn with_details(mut self, details: Vec<String>) -> Self {
+        self.details = details;
+        self
+    }
+
+    pub fn status(&self) -> Status {
+        match self.score {
+            None => Status::Skipped,
+            Some(score) if score >= 0.9 => Status::Pass,
+            Some(score) if score >= 0.5 => Status::Warn,
+            Some(_) => Status::Fail,
+        }
+    }
+
+    pub fn weight(&self) -> u32 {
+        self.kind.weight()
+    }
+}
+
+/// The checks of a report and their weighted total
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub project: String,
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    /// Weighted score out of 100 over the checks that ran
+    pub fn score(&self) -> Option<u32> {
+        let ran: Vec<(f64, u32)> = self.checks.iter().filter_map(|check| Some((check.score?, check.weight()))).collect();
+        let total: u32 = ran.iter().map(|(_, weight)| weight).sum();
+        if total == 0 {
+            return None;
+        }
+        let earned: f64 = ran.iter().map(|(score, weight)| score * f64::from(*weight)).sum();
+        Some((earned * 100.0 / f64::from(total)).round() as u32)
+    }
+
+    pub fn grade(&self) -> Option<char> {
+        let grade = match self.score()? {
+            90.. => 'A',
+            80..=89 => 'B',
+            70..=79 => 'C',
+            60..=69 => 'D',
+            _ => 'F',
+        };
+        Some(grade)
+    }
+}
+
+/// Score of `issues` lint findings
+pub fn lint_score(issues: usize) -> f64 {
+    1.0 - issues.min(LINT_ISSUES_FOR_ZERO) as f64 / LINT_ISSUES_FOR_ZERO as f64
+}
+
+/// One finding of `ruff check --output-format json`
+#[derive(Debug, Clone, Deserialize)]
+pub struct LintIssue {
+    /// Rule code; `None` for syntax errors
+    pub code: Option<String>,
+    pub message: String,
+    pub filename: String,
+    pub location: LintLocation,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LintLocation {
+    pub row: u32,
+    pub column: u32,
+}
+
+pub fn lint_issues(json: &[u8]) -> Result<Vec<LintIssue>> {
+    serde_json::from_slice(json).map_err(|e| PyForgeError::ParseError {
+        file_type: "ruff output".to_string(),
+        message: e.to_string(),
+    })
+}
+
+/// Number of issues per rule, most frequent first
+pub fn lint_counts(issues: &[LintIssue]) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for issue in issues {
+        let code = issue.code.clone().unwrap_or_else(|| "syntax".to_string());
+        match counts.iter_mut().find(|(known, _)| *known == code) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((code, 1)),
+        }
+    }
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// A locked package with known vulnerabilities
+#[derive(Debug, Clone)]
+pub struct Vulnerable {
+    pub name: String,
+    pub version: String,
+    /// Advisory IDs, e.g. `GHSA-...` or `PYSEC-...`
+    pub advisories: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct BatchResponse {
+    results: Vec<BatchResult>,
+}
+
+#[derive(Deserialize)]
+struct BatchResult {
+    #[serde(default)]
+    vulns: Vec<Advisory>,
+}
+
+#[derive(Deserialize)]
+struct Advisory {
+    id: String,
+}
+
+/// Locked packages from PyPI that have known vulnerabilities, and how many were asked about
+pub fn vulnerabilities(config: &Config, lockfile: &Lockfile) -> Result<(Vec<Vulnerable>, usize)> {
+    let public: Vec<_> = lockfile.packages.iter().filter(|package| changelog::is_public(&package.index)).collect();
+    if public.is_empty() {
+        return Ok((Vec::new(), 0));
+    }
+    let base = config.report.vulnerability_url.as_deref().unwrap_or(DEFAULT_VULNERABILITY_URL);
+    let url = format!("{}/v1/querybatch", base.trim_end_matches('/'));
+    let queries: Vec<serde_json::Value> = public
+        .iter()
+        .map(|package| {
+            serde_json::json!({
+                "package": { "name": package.name, "ecosystem": "PyPI" },
+                "version": package.version,
+            })
+        })
+        .collect();
+
+    let client = index::client_builder(&config.network)?
+        .build()
+        .map_err(|e| PyForgeError::network_error("Could not create HTTP client", Some(e)))?;
+    let response = client
+        .post(&url)
+        .json(&serde_json::json!({ "queries": queries }))
+        .send()
+        .map_err(|e| PyForgeError::network_error(format!("Request to '{}' failed", url), Some(e)))?;
+    if !response.status().is_success() {
+        return Err(PyForgeError::DownloadFailed {
+            url,
+            status: response.status().to_string(),
+        });
+    }
+    let batch: BatchResponse = response
+        .json()
+        .map_err(|e| PyForgeError::network_error(format!("Could not read '{}'", url), Some(e)))?;
+
+    let vulnerable = public
+        .iter()
+        .zip(batch.results)
+        .filter(|(_, result)| !result.vulns.is_empty())
+        .map(|(package, result)| Vulnerable {
+            name: package.name.clone(),
+            version: package.version.clone(),
+            advisories: result.vulns.into_iter().map(|advisory| advisory.id).collect(),
+        })
+        .collect();
+    Ok((vulnerable, public.len()))
+}
+
+/// Write `report` as an HTML page at `path`
+pub fn write_html(report: &Report, path: &Path) -> Result<()> {
+    let mut rows = String::new();
+    for check in &report.checks {
+        let score = check.score.map_or("–".to_string(), |score| format!("{:.0}%", score * 100.0));
+        let details: String = check.details.iter().map(|detail| format!("<li>{}</li>", escape(detail))).collect();
+        rows.push_str(&format!(
+            "<tr class=\"{status}\"><td>{kind}</td><td><span class=\"badge\">{status}</span></td>\
+             <td class=\"number\">{score}</td><td class=\"number\">{weight}</td><td>{summary}{details}</td></tr>\n",
+            status = check.status(),
+            kind = check.kind,
+            score = score,
+            weight = check.weight(),
+            summary = escape(&check.summary),
+            details = if details.is_empty() { String::new() } else { format!("<ul>{}</ul>", details) },
+        ));
+    }
+    let headline = match (report.score(), report.grade()) {
+        (Some(score), Some(grade)) => format!("<span class=\"grade\">{}</span>{} / 100", grade, score),
+        _ => "No check could run, so there is no score".to_string(),
+    };
+    let content = format!(
+        "<p class=\"headline\">{}</p>\n<table>\n<tr><th>Check</th><th>Status</th><th class=\"number\">Score</th>\
+         <th class=\"number\">Weight</th><th>Findings</th></tr>\n{}</table>\n",
+        headline, rows
+    );
+    htmlreport::write_page(path, &format!("{} health report", report.project), "", &content)
+}
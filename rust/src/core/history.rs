@@ -0,0 +1,62 @@
+use crate::core::dirs::state_dir;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Entries kept when the history file is compacted
+const KEEP_ENTRIES: usize = 500;
+
+/// One recorded pyforge invocation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub timestamp: String,
+    pub cwd: String,
+    pub args: Vec<String>,
+    pub exit_code: i32,
+    pub duration_ms: u128,
+}
+
+pub fn history_path() -> PathBuf {
+    state_dir().join("history.jsonl")
+}
+
+/// Append an entry to the command history (best effort)
+pub fn record(entry: &HistoryEntry) {
+    let path = history_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+
+    if let Ok(line) = serde_json::to_string(entry)
+        && let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&path)
+    {
+        let _ = writeln!(file, "{}", line);
+    }
+
+    compact();
+}
+
+/// Most recent entries, newest first
+pub fn recent(limit: usize) -> Vec<HistoryEntry> {
+    let content = fs::read_to_string(history_path()).unwrap_or_default();
+    content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .take(limit)
+        .collect()
+}
+
+fn compact() {
+    let path = history_path();
+    let Ok(content) = fs::read_to_string(&path) else {
+        return;
+    };
+
+    let lines: Vec<&str> = content.lines().collect();
+    if lines.len() > KEEP_ENTRIES * 2 {
+        let kept = lines[lines.len() - KEEP_ENTRIES..].join("\n");
+        let _ = fs::write(&path, kept + "\n");
+    }
+}
@@ -0,0 +1,224 @@
+//! Static HTML reports CI can publish as artifacts (`pyforge test
+//! --report-html`, `pyforge report --html`).
+//!
+//! Every page is rendered into the built-in `reports/page.html` layout.
+//! Test cases come from pytest's JUnit XML and coverage from coverage.py's
+//! JSON report, both written next to the project while the tests run.
+
+use crate::core::error::{PyForgeError, Result};
+use crate::core::junit::{Outcome, TestCase};
+use crate::core::template::substitute;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+
+const PAGE: &str = include_str!("../reports/page.html");
+
+/// Coverage at or above this share of lines is shown as good
+const GOOD_COVERAGE: f64 = 80.0;
+
+/// Escape text for HTML content and attribute values
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Line coverage of the whole project and of each file
+#[derive(Debug, Clone)]
+pub struct Coverage {
+    pub percent: f64,
+    /// Path, covered percent, statements and missed lines, by path
+    pub files: Vec<FileCoverage>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileCoverage {
+    pub path: String,
+    pub percent: f64,
+    pub statements: u64,
+    pub missing: Vec<u64>,
+}
+
+#[derive(Deserialize)]
+struct CoverageJson {
+    totals: CoverageSummary,
+    #[serde(default)]
+    files: BTreeMap<String, CoverageFile>,
+}
+
+#[derive(Deserialize)]
+struct CoverageFile {
+    summary: CoverageSummary,
+    #[serde(default)]
+    missing_lines: Vec<u64>,
+}
+
+#[derive(Deserialize)]
+struct CoverageSummary {
+    percent_covered: f64,
+    #[serde(default)]
+    num_statements: u64,
+}
+
+/// Read a coverage.py JSON report (`coverage json`, `--cov-report=json`)
+pub fn parse_coverage(json: &[u8]) -> Result<Coverage> {
+    let report: CoverageJson = serde_json::from_slice(json).map_err(|e| PyForgeError::ParseError {
+        file_type: "coverage report".to_string(),
+        message: e.to_string(),
+    })?;
+    Ok(Coverage {
+        percent: report.totals.percent_covered,
+        files: report
+            .files
+            .into_iter()
+            .map(|(path, file)| FileCoverage {
+                path,
+                percent: file.summary.percent_covered,
+                statements: file.summary.num_statements,
+                missing: file.missing_lines,
+            })
+            .collect(),
+    })
+}
+
+/// A finished test run
+#[derive(Debug, Clone)]
+pub struct TestRun {
+    pub project: String,
+    pub command: String,
+    pub passed: bool,
+    pub exit_code: i32,
+    pub seconds: f64,
+    /// Empty when the test command doesn't write JUnit XML
+    pub cases: Vec<TestCase>,
+    pub coverage: Option<Coverage>,
+}
+
+/// Write `index.html` (and `coverage.html` when measured) for `run` into `dir`,
+/// returning the index page
+pub fn write_test_report(dir: &Path, run: &TestRun) -> Result<PathBuf> {
+    fs::create_dir_all(dir).map_err(|e| PyForgeError::file_error(format!("Could not create {}", dir.display()), e))?;
+    let mut content = String::new();
+
+    let status = if run.passed { "pass" } else { "fail" };
+    let verdict = if run.passed { "Tests passed".to_string() } else { format!("Tests failed (exit {})", run.exit_code) };
+    content.push_str(&format!(
+        "<p class=\"headline {status}\"><span class=\"badge\">{verdict}</span> in {seconds:.1}s</p>\n",
+        status = status,
+        verdict = verdict,
+        seconds = run.seconds
+    ));
+    if let Some(coverage) = &run.coverage {
+        content.push_str(&format!(
+            "<nav><a href=\"coverage.html\">Coverage: {:.1}%</a></nav>\n",
+            coverage.percent
+        ));
+    }
+
+    if run.cases.is_empty() {
+        content.push_str("<p>The test command did not write a JUnit XML report, so individual tests are not listed.</p>\n");
+    } else {
+        let mut counts: BTreeMap<Outcome, usize> = BTreeMap::new();
+        for case in &run.cases {
+            *counts.entry(case.outcome).or_default() += 1;
+        }
+        let totals: Vec<String> = counts.iter().map(|(outcome, count)| format!("{} {}", count, outcome.label())).collect();
+        content.push_str(&format!("<p>{} test(s): {}</p>\n", run.cases.len(), totals.join(", ")));
+
+        let mut cases: Vec<&TestCase> = run.cases.iter().collect();
+        cases.sort_by(|a, b| (a.outcome, &a.suite, &a.name).cmp(&(b.outcome, &b.suite, &b.name)));
+        content.push_str("<table>\n<tr><th>Test</th><th>Result</th><th class=\"number\">Time</th></tr>\n");
+        for case in cases {
+            let message = if case.message.is_empty() {
+                String::new()
+            } else {
+                format!("<pre>{}</pre>", escape(&case.message))
+            };
+            content.push_str(&format!(
+                "<tr class=\"{class}\"><td>{suite}{separator}<strong>{name}</strong>{message}</td>\
+                 <td><span class=\"badge\">{label}</span></td><td class=\"number\">{seconds:.3}s</td></tr>\n",
+                class = case.outcome.class(),
+                suite = escape(&case.suite),
+                separator = if case.suite.is_empty() { "" } else { "::" },
+                name = escape(&case.name),
+                message = message,
+                label = case.outcome.label(),
+                seconds = case.seconds,
+            ));
+        }
+        content.push_str("</table>\n");
+    }
+
+    let index = dir.join("index.html");
+    let subtitle = format!("<code>{}</code>", escape(&run.command));
+    write_page(&index, &format!("{} tests", run.project), &subtitle, &content)?;
+
+    if let Some(coverage) = &run.coverage {
+        let mut content = format!(
+            "<p class=\"headline\">{:.1}% of lines covered</p>\n<nav><a href=\"index.html\">Tests</a></nav>\n",
+            coverage.percent
+        );
+        let mut files: Vec<&FileCoverage> = coverage.files.iter().collect();
+        files.sort_by(|a, b| a.percent.total_cmp(&b.percent).then_with(|| a.path.cmp(&b.path)));
+        content.push_str(
+            "<table>\n<tr><th>File</th><th class=\"number\">Statements</th><th class=\"number\">Covered</th>\
+             <th></th><th>Missing lines</th></tr>\n",
+        );
+        for file in files {
+            content.push_str(&format!(
+                "<tr><td>{path}</td><td class=\"number\">{statements}</td><td class=\"number\">{percent:.1}%</td>\
+                 <td>{bar}</td><td>{missing}</td></tr>\n",
+                path = escape(&file.path),
+                statements = file.statements,
+                percent = file.percent,
+                bar = bar(file.percent),
+                missing = line_ranges(&file.missing),
+            ));
+        }
+        content.push_str("</table>\n");
+        write_page(&dir.join("coverage.html"), &format!("{} coverage", run.project), &subtitle, &content)?;
+    }
+    Ok(index)
+}
+
+/// Render `content` (HTML) into the page layout and write it to `path`
+pub fn write_page(path: &Path, heading: &str, subtitle: &str, content: &str) -> Result<()> {
+    let variables = BTreeMap::from([
+        ("title".to_string(), escape(heading)),
+        ("heading".to_string(), escape(heading)),
+        ("subtitle".to_string(), subtitle.to_string()),
+        ("content".to_string(), content.to_string()),
+        ("generated".to_string(), OffsetDateTime::now_utc().date().to_string()),
+    ]);
+    fs::write(path, substitute(PAGE, &variables))
+        .map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))
+}
+
+/// A horizontal bar filled to `percent`
+pub fn bar(percent: f64) -> String {
+    let class = if percent >= GOOD_COVERAGE {
+        ""
+    } else if percent >= GOOD_COVERAGE / 2.0 {
+        " warn"
+    } else {
+        " fail"
+    };
+    format!("<div class=\"bar{}\"><span style=\"width: {:.0}%\"></span></div>", class, percent.clamp(0.0, 100.0))
+}
+
+/// `1-3, 7, 9-10` for sorted line numbers
+fn line_ranges(lines: &[u64]) -> String {
+    let mut ranges: Vec<(u64, u64)> = Vec::new();
+    for &line in lines {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == line => *end = line,
+            _ => ranges.push((line, line)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|(start, end)| if start == end { start.to_string() } else { format!("{}-{}", start, end) })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
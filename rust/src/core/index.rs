@@ -0,0 +1,604 @@
+use crate::core::auth::{self, Credentials};
+use crate::core::config::{Config, IndexConfig, IpVersion, NetworkConfig, TlsConfig};
+use crate::core::dirs;
+use crate::core::dist::DistFilename;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::paths;
+use crate::core::pep508::normalize_name;
+use crate::core::timings;
+use regex::Regex;
+use reqwest::Url;
+use reqwest::blocking::{Client, ClientBuilder, RequestBuilder, Response};
+use reqwest::{Certificate, Identity, Method};
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use tempfile::NamedTempFile;
+use std::time::{Duration, Instant};
+
+/// Prefer the PEP 691 JSON project page, accept PEP 503 HTML from older indexes
+const SIMPLE_ACCEPT: &str = "application/vnd.pypi.simple.v1+json, text/html;q=0.1";
+
+static ANCHOR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?is)<a\s([^>]*)>([^<]*)</a>"#).expect("valid anchor regex"));
+
+static ATTRIBUTE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"([\w-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).expect("valid attribute regex"));
+
+/// HTTP client for package indexes with mirror failover
+pub struct IndexClient {
+    http: Client,
+    /// Clients of indexes with their own TLS settings, by the origins of their URLs
+    tls: Vec<(Vec<String>, Client)>,
+    /// Credentials of index URLs by origin, also sent for files on the same host
+    credentials: Vec<(String, Credentials)>,
+    retries: u32,
+    /// Shared by every download of this client, so parallel downloads stay under the cap together
+    throttle: Option<Throttle>,
+}
+
+/// Paces downloads to `[network] max-download-rate` bytes per second
+struct Throttle {
+    rate: u64,
+    /// When the bytes handed out so far have been paid for
+    until: Mutex<Instant>,
+}
+
+impl Throttle {
+    /// Wait until `bytes` more fit in the rate
+    fn take(&self, bytes: usize) {
+        let wait = Duration::from_secs_f64(bytes as f64 / self.rate as f64);
+        let until = {
+            let mut until = self.until.lock().unwrap_or_else(|e| e.into_inner());
+            *until = (*until).max(Instant::now()) + wait;
+            *until
+        };
+        std::thread::sleep(until.saturating_duration_since(Instant::now()));
+    }
+}
+
+/// Reads through a [`Throttle`]
+struct Throttled<'a, R> {
+    inner: R,
+    throttle: &'a Throttle,
+}
+
+impl<R: io::Read> io::Read for Throttled<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // Small reads keep the pace smooth instead of bursting a whole buffer
+        let limit = buf.len().min((self.throttle.rate as usize / 10).max(1024));
+        let read = self.inner.read(&mut buf[..limit])?;
+        self.throttle.take(read);
+        Ok(read)
+    }
+}
+
+/// Hashes everything written through it
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Result of probing a single index URL
+#[derive(Debug)]
+pub struct Probe {
+    pub url: String,
+    pub latency: Duration,
+    pub outcome: std::result::Result<u16, String>,
+}
+
+/// A distribution file listed on an index project page
+#[derive(Debug, Clone)]
+pub struct IndexFile {
+    pub filename: String,
+    /// Absolute download URL, without the hash fragment
+    pub url: String,
+    pub sha256: Option<String>,
+    pub requires_python: Option<String>,
+    pub yanked: bool,
+    /// Whether core metadata is served separately at `{url}.metadata` (PEP 658)
+    pub has_metadata: bool,
+    /// When the file was uploaded, if the index reports it (PEP 700)
+    pub upload_time: Option<time::OffsetDateTime>,
+}
+
+#[derive(Deserialize)]
+struct JsonProjectPage {
+    files: Vec<JsonFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct JsonFile {
+    filename: String,
+    url: String,
+    #[serde(default)]
+    hashes: std::collections::BTreeMap<String, String>,
+    requires_python: Option<String>,
+    #[serde(default)]
+    yanked: serde_json::Value,
+    #[serde(default, alias = "dist-info-metadata")]
+    core_metadata: serde_json::Value,
+    upload_time: Option<String>,
+}
+
+impl Probe {
+    pub fn is_healthy(&self) -> bool {
+        matches!(self.outcome, Ok(status) if status < 500)
+    }
+}
+
+impl IndexClient {
+    pub fn new(config: &Config) -> Result<Self> {
+        let builder = || client_builder(&config.network);
+        let http = builder()?
+            .build()
+            .map_err(|e| PyForgeError::network_error("Could not create HTTP client", Some(e)))?;
+
+        let mut tls = Vec::new();
+        let mut credentials = Vec::new();
+        for index in config.indexes() {
+            if index.tls != TlsConfig::default() {
+                let client = with_tls(builder()?, &index)?.build().map_err(|e| {
+                    PyForgeError::network_error(format!("Could not create HTTP client for index '{}'", index.name), Some(e))
+                })?;
+                tls.push((index.urls().filter_map(origin).collect(), client));
+            }
+            for url in index.urls() {
+                if let (Some(origin), Some(found)) = (origin(url), auth::index_credentials(&index, url)?) {
+                    log::debug!("credentials for {} from {}", origin, found.source);
+                    credentials.push((origin, found));
+                }
+            }
+        }
+
+        let throttle = config.network.download_rate()?.map(|rate| Throttle {
+            rate,
+            until: Mutex::new(Instant::now()),
+        });
+
+        Ok(Self {
+            http,
+            tls,
+            credentials,
+            retries: config.network.retries.max(1),
+            throttle,
+        })
+    }
+
+    /// A request to `url` with its index's client and credentials
+    fn request(&self, method: Method, url: &str) -> RequestBuilder {
+        let request = self.client(url).request(method, url);
+        // reqwest sends the credentials of a URL that has them itself
+        if Url::parse(url).is_ok_and(|parsed| !parsed.username().is_empty()) {
+            return request;
+        }
+        let origin = origin(url);
+        match self.credentials.iter().find(|(known, _)| Some(known) == origin.as_ref()) {
+            Some((_, credentials)) => request.basic_auth(&credentials.username, credentials.password.as_ref()),
+            None => request,
+        }
+    }
+
+    /// The client for `url`: its index's when that has TLS settings. Files
+    /// served from another host than the index use the default client.
+    fn client(&self, url: &str) -> &Client {
+        let Some(origin) = origin(url) else {
+            return &self.http;
+        };
+        self.tls
+            .iter()
+            .find(|(origins, _)| origins.contains(&origin))
+            .map_or(&self.http, |(_, client)| client)
+    }
+
+    /// GET `path` from the index, failing over to each mirror in turn.
+    ///
+    /// Connection errors, timeouts and 5xx responses move on to the next URL;
+    /// any other response (including 404) is returned to the caller as-is.
+    pub fn get(&self, index: &IndexConfig, path: &str) -> Result<Response> {
+        self.get_with(index, path, |request| request)
+    }
+
+    fn get_with(
+        &self,
+        index: &IndexConfig,
+        path: &str,
+        customize: impl Fn(RequestBuilder) -> RequestBuilder,
+    ) -> Result<Response> {
+        let _span = timings::span("index");
+        let mut last_error = None;
+
+        for base in index.urls() {
+            let url = join_url(base, path);
+
+            for _ in 0..self.retries {
+                match customize(self.request(Method::GET, &url)).send() {
+                    Ok(response) if response.status().is_server_error() => {
+                        log::warn!("{} returned {}, failing over", url, response.status());
+                        last_error = Some(PyForgeError::DownloadFailed {
+                            url: url.clone(),
+                            status: response.status().to_string(),
+                        });
+                    }
+                    Ok(response) => return Ok(response),
+                    Err(e) => {
+                        log::warn!("{} failed ({}), failing over", url, describe_request_error(&e));
+                        last_error = Some(PyForgeError::network_error(
+                            format!("Request to '{}' failed", url),
+                            Some(e),
+                        ));
+                    }
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            PyForgeError::network_error(format!("Index '{}' has no URLs", index.name), None)
+        }))
+    }
+
+    /// Files of a project on the index's simple API, or `None` if the index doesn't have it
+    pub fn project_files(&self, index: &IndexConfig, name: &str) -> Result<Option<Vec<IndexFile>>> {
+        if let Some(dir) = index.urls().find_map(local_path) {
+            return local_project_files(&dir, name);
+        }
+        let path = format!("{}/", crate::core::pep508::normalize_name(name));
+        let response = self.get_with(index, &path, |request| request.header(ACCEPT, SIMPLE_ACCEPT))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let url = response.url().clone();
+        let response = response.error_for_status().map_err(|e| PyForgeError::DownloadFailed {
+            url: url.to_string(),
+            status: e.status().map(|s| s.to_string()).unwrap_or_default(),
+        })?;
+
+        let is_json = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.contains("json"));
+        let body = response
+            .text()
+            .map_err(|e| PyForgeError::network_error(format!("Could not read '{}'", url), Some(e)))?;
+
+        let files = if is_json {
+            parse_json_page(&url, &body)?
+        } else {
+            parse_html_page(&url, &body)
+        };
+        Ok(Some(files))
+    }
+
+    /// Download a small file (such as PEP 658 metadata) by absolute URL into memory
+    pub fn download(&self, url: &str) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.stream(url, &mut bytes, None)?;
+        Ok(bytes)
+    }
+
+    /// Download a file to `path`, checking `sha256` as it streams in. The file is
+    /// written next to `path` first, so an interrupted download leaves nothing behind.
+    pub fn download_to(&self, url: &str, path: &Path, sha256: Option<&str>) -> Result<()> {
+        let target = paths::long(path);
+        let dir = target.parent().unwrap_or(Path::new("."));
+        let mut file = NamedTempFile::new_in(dir)
+            .map_err(|e| PyForgeError::file_error(format!("Could not create a file in {}", dir.display()), e))?;
+        self.stream(url, &mut BufWriter::new(file.as_file_mut()), sha256)?;
+        file.persist(&target)
+            .map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e.error))?;
+        Ok(())
+    }
+
+    /// Download a file into an anonymous temporary file, rewound for reading.
+    /// Wheels can be hundreds of megabytes, more than CI runners can hold in memory.
+    pub fn download_temp(&self, url: &str, sha256: Option<&str>) -> Result<File> {
+        let mut file = tempfile::tempfile()?;
+        self.stream(url, &mut BufWriter::new(&mut file), sha256)?;
+        file.seek(SeekFrom::Start(0))?;
+        Ok(file)
+    }
+
+    /// Copy the body of `url` into `writer` without buffering it whole
+    fn stream(&self, url: &str, writer: &mut impl Write, sha256: Option<&str>) -> Result<()> {
+        let _span = timings::span("download");
+        let mut body: Box<dyn io::Read> = match local_path(url) {
+            Some(path) => Box::new(
+                File::open(&path).map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?,
+            ),
+            None => {
+                let response = self
+                    .request(Method::GET, url)
+                    .send()
+                    .map_err(|e| PyForgeError::network_error(format!("Request to '{}' failed", url), Some(e)))?;
+                if !response.status().is_success() {
+                    return Err(PyForgeError::DownloadFailed {
+                        url: url.to_string(),
+                        status: response.status().to_string(),
+                    });
+                }
+                match &self.throttle {
+                    Some(throttle) => Box::new(Throttled { inner: response, throttle }),
+                    None => Box::new(response),
+                }
+            }
+        };
+
+        let mut hashing = HashingWriter { inner: writer, hasher: Sha256::new() };
+        io::copy(&mut body, &mut hashing).map_err(|e| PyForgeError::FileError {
+            message: format!("Could not download '{}'", url),
+            source: Some(e),
+        })?;
+        hashing.flush()?;
+
+        let actual = format!("{:x}", hashing.hasher.finalize());
+        match sha256 {
+            Some(expected) if !actual.eq_ignore_ascii_case(expected) => Err(PyForgeError::HashMismatch {
+                file: url.rsplit('/').next().unwrap_or(url).to_string(),
+                expected: expected.to_string(),
+                actual,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Measure time to first response from `url`
+    pub fn probe(&self, url: &str) -> Probe {
+        let start = Instant::now();
+        let outcome = self
+            .request(Method::HEAD, url)
+            .send()
+            .map(|response| response.status().as_u16())
+            .map_err(|e| describe_request_error(&e));
+
+        Probe {
+            url: url.to_string(),
+            latency: start.elapsed(),
+            outcome,
+        }
+    }
+}
+
+/// An HTTP client with the `[network]` settings: timeouts, address family and host overrides
+pub fn client_builder(network: &NetworkConfig) -> Result<ClientBuilder> {
+    let mut builder = Client::builder()
+        .timeout(Duration::from_secs(network.timeout))
+        .user_agent(concat!("pyforge/", env!("CARGO_PKG_VERSION")));
+    if let Some(seconds) = network.connect_timeout {
+        builder = builder.connect_timeout(Duration::from_secs(seconds));
+    }
+    // Binding to the unspecified address of one family only connects to
+    // addresses of that family
+    builder = match network.ip_version {
+        IpVersion::Auto => builder,
+        IpVersion::Ipv4 => builder.local_address(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+        IpVersion::Ipv6 => builder.local_address(IpAddr::V6(Ipv6Addr::UNSPECIFIED)),
+    };
+    for (host, addresses) in &network.hosts {
+        let addresses = addresses
+            .addresses()
+            .into_iter()
+            .map(|address| {
+                // The port of the URL is used, so any port here is ignored
+                address
+                    .parse::<IpAddr>()
+                    .map(|ip| SocketAddr::new(ip, 0))
+                    .or_else(|_| address.parse::<SocketAddr>())
+                    .map_err(|_| PyForgeError::Usage {
+                        message: format!("Invalid [network.hosts] address '{}' for {}; expected an IP address", address, host),
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        builder = builder.resolve_to_addrs(host, &addresses);
+    }
+    Ok(builder)
+}
+
+/// Apply the TLS settings of `index` to `builder`
+fn with_tls(mut builder: ClientBuilder, index: &IndexConfig) -> Result<ClientBuilder> {
+    let read = |path: &str| {
+        let path = dirs::expand_home(path);
+        fs::read(&path).map_err(|e| {
+            PyForgeError::file_error(format!("Could not read {} for index '{}'", path.display(), index.name), e)
+        })
+    };
+    let invalid = |what: &str, e: reqwest::Error| {
+        PyForgeError::network_error(format!("Invalid {} for index '{}'", what, index.name), Some(e))
+    };
+
+    for path in &index.tls.ca_certs {
+        for certificate in Certificate::from_pem_bundle(&read(path)?).map_err(|e| invalid("ca-certs", e))? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    if index.tls.pin {
+        if index.tls.ca_certs.is_empty() {
+            return Err(PyForgeError::Usage {
+                message: format!("Index '{}' sets tls.pin without tls.ca-certs to trust instead", index.name),
+            });
+        }
+        builder = builder.tls_built_in_root_certs(false);
+    }
+    match (&index.tls.client_cert, &index.tls.client_key) {
+        (Some(cert), Some(key)) => {
+            let identity = Identity::from_pkcs8_pem(&read(cert)?, &read(key)?).map_err(|e| invalid("client certificate", e))?;
+            builder = builder.identity(identity);
+        }
+        (None, None) => {}
+        _ => {
+            return Err(PyForgeError::Usage {
+                message: format!("Index '{}' needs both tls.client-cert and tls.client-key", index.name),
+            });
+        }
+    }
+    Ok(builder)
+}
+
+/// The path a `file://` URL points to
+pub fn local_path(url: &str) -> Option<PathBuf> {
+    Url::parse(url).ok().filter(|url| url.scheme() == "file")?.to_file_path().ok()
+}
+
+/// Files of `name` in a local index: a PEP 503 tree with a directory per
+/// project (its `index.html`, or the files themselves), or one flat directory
+fn local_project_files(dir: &Path, name: &str) -> Result<Option<Vec<IndexFile>>> {
+    let project = dir.join(normalize_name(name));
+    let page = project.join("index.html");
+    if page.is_file() {
+        let body = fs::read_to_string(&page)
+            .map_err(|e| PyForgeError::file_error(format!("Could not read {}", page.display()), e))?;
+        let url = Url::from_file_path(&page).map_err(|_| PyForgeError::Internal {
+            message: format!("{} is not an absolute path", page.display()),
+        })?;
+        return Ok(Some(parse_html_page(&url, &body)));
+    }
+    let files = directory_files(if project.is_dir() { &project } else { dir }, name)?;
+    Ok((!files.is_empty()).then_some(files))
+}
+
+/// Wheels and sdists of `name` in a `find-links` directory, hashed so the
+/// lockfile can pin them like index files
+pub fn directory_files(dir: &Path, name: &str) -> Result<Vec<IndexFile>> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+    let mut files = Vec::new();
+    for path in entries.flatten().map(|entry| entry.path()) {
+        let filename = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let matches = DistFilename::parse(&filename, name).is_some_and(|parsed| parsed.name == normalize_name(name));
+        if !path.is_file() || !matches {
+            continue;
+        }
+        let absolute = path.canonicalize().unwrap_or(path);
+        let Ok(url) = Url::from_file_path(&absolute) else {
+            continue;
+        };
+        let mut file = File::open(&absolute)
+            .map_err(|e| PyForgeError::file_error(format!("Could not read {}", absolute.display()), e))?;
+        let mut hasher = Sha256::new();
+        io::copy(&mut file, &mut hasher)?;
+        files.push(IndexFile {
+            filename,
+            url: url.to_string(),
+            sha256: Some(format!("{:x}", hasher.finalize())),
+            requires_python: None,
+            yanked: false,
+            has_metadata: false,
+            // When the file was put there stands in for the upload time, for `exclude-newer`
+            upload_time: file.metadata().and_then(|metadata| metadata.modified()).ok().map(time::OffsetDateTime::from),
+        });
+    }
+    files.sort_by(|a, b| a.filename.cmp(&b.filename));
+    Ok(files)
+}
+
+/// `scheme://host:port` of a URL, for matching downloads to their index
+fn origin(url: &str) -> Option<String> {
+    Url::parse(url).ok().map(|url| url.origin().ascii_serialization())
+}
+
+/// Join an index base URL and a relative path
+pub fn join_url(base: &str, path: &str) -> String {
+    format!("{}/{}", base.trim_end_matches('/'), path.trim_start_matches('/'))
+}
+
+fn parse_json_page(page: &Url, body: &str) -> Result<Vec<IndexFile>> {
+    let parsed: JsonProjectPage = serde_json::from_str(body).map_err(|e| PyForgeError::InvalidJson {
+        file: page.to_string(),
+        message: e.to_string(),
+    })?;
+
+    Ok(parsed
+        .files
+        .into_iter()
+        .map(|file| IndexFile {
+            url: resolve_url(page, &file.url).0,
+            sha256: file.hashes.get("sha256").cloned(),
+            requires_python: file.requires_python.filter(|r| !r.is_empty()),
+            yanked: file.yanked.as_bool().unwrap_or(false) || file.yanked.is_string(),
+            has_metadata: file.core_metadata.as_bool().unwrap_or(false) || file.core_metadata.is_object(),
+            upload_time: file.upload_time.as_deref().and_then(parse_upload_time),
+            filename: file.filename,
+        })
+        .collect())
+}
+
+fn parse_html_page(page: &Url, body: &str) -> Vec<IndexFile> {
+    ANCHOR_RE
+        .captures_iter(body)
+        .filter_map(|anchor| {
+            let attributes: std::collections::HashMap<String, String> = ATTRIBUTE_RE
+                .captures_iter(&anchor[1])
+                .map(|attr| {
+                    let value = attr.get(2).or_else(|| attr.get(3)).map_or("", |m| m.as_str());
+                    (attr[1].to_lowercase(), unescape_html(value))
+                })
+                .collect();
+            let (url, fragment) = resolve_url(page, attributes.get("href")?);
+            let sha256 = fragment.and_then(|f| f.strip_prefix("sha256=").map(str::to_string));
+            let filename = anchor[2].trim().to_string();
+
+            Some(IndexFile {
+                filename,
+                url,
+                sha256,
+                requires_python: attributes.get("data-requires-python").filter(|r| !r.is_empty()).cloned(),
+                yanked: attributes.contains_key("data-yanked"),
+                has_metadata: attributes
+                    .get("data-core-metadata")
+                    .or_else(|| attributes.get("data-dist-info-metadata"))
+                    .is_some_and(|value| value != "false"),
+                upload_time: attributes.get("data-upload-time").and_then(|t| parse_upload_time(t)),
+            })
+        })
+        .collect()
+}
+
+fn parse_upload_time(value: &str) -> Option<time::OffsetDateTime> {
+    time::OffsetDateTime::parse(value, &time::format_description::well_known::Rfc3339).ok()
+}
+
+/// Resolve a possibly relative file link, splitting off the `#hash` fragment
+fn resolve_url(page: &Url, href: &str) -> (String, Option<String>) {
+    let mut url = page.join(href).unwrap_or_else(|_| page.clone());
+    let fragment = url.fragment().map(str::to_string);
+    url.set_fragment(None);
+    (url.to_string(), fragment)
+}
+
+fn unescape_html(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+fn describe_request_error(error: &reqwest::Error) -> String {
+    if error.is_timeout() {
+        "timed out".to_string()
+    } else if error.is_connect() {
+        "connection failed".to_string()
+    } else {
+        error.to_string()
+    }
+}
@@ -0,0 +1,289 @@
+//! Copying pure-Python dependencies into a project's own `_vendor` package.
+
+use crate::core::error::{PyForgeError, Result};
+use crate::core::paths;
+use crate::core::permissions::{self, Mode};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// Provenance file kept in the `_vendor` directory
+pub const PROVENANCE_FILE: &str = "vendor.toml";
+
+const PROVENANCE_HEADER: &str = "# Generated by `pyforge vendor --inline`. Re-run it to update vendored packages.\n\n";
+
+static IMPORT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\s*)import\s+([^#;]+?)\s*(#.*)?$").expect("valid import regex"));
+
+static FROM_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\s*)from\s+([A-Za-z_][\w.]*)(\s+import\b.*)$").expect("valid from regex"));
+
+/// Where each vendored package came from, for later updates
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Provenance {
+    #[serde(default, rename = "package")]
+    pub packages: Vec<VendoredPackage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct VendoredPackage {
+    pub name: String,
+    pub version: String,
+    pub wheel: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// Top-level modules and packages copied from the wheel
+    pub modules: Vec<String>,
+}
+
+impl Provenance {
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(PROVENANCE_FILE);
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)
+            .map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+        toml::from_str(&content).map_err(|e| PyForgeError::InvalidToml {
+            file: path.display().to_string(),
+            message: e.to_string(),
+        })
+    }
+
+    pub fn write(&self, dir: &Path) -> Result<()> {
+        let body = toml::to_string(self).map_err(|e| PyForgeError::internal(format!("Could not render provenance: {}", e)))?;
+        fs::write(dir.join(PROVENANCE_FILE), format!("{}{}", PROVENANCE_HEADER, body))?;
+        Ok(())
+    }
+
+    /// Every top-level module name currently vendored
+    pub fn modules(&self) -> BTreeSet<String> {
+        self.packages.iter().flat_map(|p| p.modules.iter().cloned()).collect()
+    }
+}
+
+/// A wheel's contents, as needed for inlining
+pub struct WheelContents {
+    /// Whether the wheel installs into purelib and has no compiled extensions
+    pub pure: bool,
+    /// Top-level module and package names
+    pub modules: Vec<String>,
+    archive: zip::ZipArchive<File>,
+}
+
+impl WheelContents {
+    pub fn open(wheel: File) -> Result<Self> {
+        let mut archive = zip::ZipArchive::new(wheel)?;
+        let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+
+        let wheel_info = names
+            .iter()
+            .find(|name| name.ends_with(".dist-info/WHEEL") && name.matches('/').count() == 1)
+            .cloned();
+        let purelib = match wheel_info {
+            Some(path) => {
+                let mut content = String::new();
+                archive.by_name(&path)?.read_to_string(&mut content)?;
+                content
+                    .lines()
+                    .any(|line| line.replace(' ', "").eq_ignore_ascii_case("Root-Is-Purelib:true"))
+            }
+            None => false,
+        };
+        let compiled = names
+            .iter()
+            .any(|name| [".so", ".pyd", ".dylib", ".dll"].iter().any(|ext| name.ends_with(ext)));
+
+        let modules: BTreeSet<String> = names
+            .iter()
+            .filter_map(|name| {
+                let top = name.split('/').next()?;
+                if top.ends_with(".dist-info") || top.ends_with(".data") {
+                    None
+                } else if name.contains('/') {
+                    Some(top.to_string())
+                } else {
+                    top.strip_suffix(".py").map(str::to_string)
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            pure: purelib && !compiled,
+            modules: modules.into_iter().collect(),
+            archive,
+        })
+    }
+
+    /// Extract the importable files (and the license files) into `dir`
+    pub fn extract(&mut self, dir: &Path, package: &str) -> Result<()> {
+        for index in 0..self.archive.len() {
+            let mut entry = self.archive.by_index(index)?;
+            if entry.is_dir() {
+                continue;
+            }
+            let Some(relative) = entry.enclosed_name().map(Path::to_path_buf) else {
+                continue;
+            };
+            let top = relative.components().next().map(|c| c.as_os_str().to_string_lossy().to_string()).unwrap_or_default();
+
+            let target: PathBuf = if top.ends_with(".dist-info") {
+                // Keep licenses next to the code they cover
+                let file_name = relative.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+                if !file_name.to_uppercase().starts_with("LICENSE") && !file_name.to_uppercase().starts_with("COPYING") {
+                    continue;
+                }
+                dir.join(format!("{}.{}", package, file_name))
+            } else if top.ends_with(".data") {
+                continue;
+            } else {
+                dir.join(&relative)
+            };
+
+            let long = paths::long(&target);
+            if let Some(parent) = long.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            File::create(&long)
+                .and_then(|mut file| io::copy(&mut entry, &mut file))
+                .map_err(|e| PyForgeError::file_error(format!("Could not write {}", target.display()), e))?;
+            if entry.unix_mode().is_some_and(|mode| mode & 0o111 != 0) {
+                permissions::apply(&long, Mode::Executable)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Remove previously vendored modules (and their license files) from `dir`
+pub fn remove_modules(dir: &Path, package: &VendoredPackage) -> Result<()> {
+    for module in &package.modules {
+        let as_dir = dir.join(module);
+        let as_file = dir.join(format!("{}.py", module));
+        if as_dir.is_dir() {
+            fs::remove_dir_all(&as_dir)?;
+        }
+        if as_file.is_file() {
+            fs::remove_file(&as_file)?;
+        }
+    }
+    for entry in fs::read_dir(dir)?.flatten() {
+        if entry.file_name().to_string_lossy().starts_with(&format!("{}.", package.name))
+            && entry.path().is_file()
+            && !entry.file_name().to_string_lossy().ends_with(".py")
+        {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+/// Rewrite absolute imports of `modules` in every `.py` file under `dir` to go through `prefix`
+pub fn rewrite_tree(dir: &Path, modules: &BTreeSet<String>, prefix: &str) -> Result<usize> {
+    let mut changed = 0;
+    for entry in walkdir::WalkDir::new(dir).into_iter().flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|ext| ext != "py") {
+            continue;
+        }
+        let Ok(source) = fs::read_to_string(path) else {
+            continue;
+        };
+        let rewritten = rewrite_imports(&source, modules, prefix);
+        if rewritten != source {
+            fs::write(path, rewritten)?;
+            changed += 1;
+        }
+    }
+    Ok(changed)
+}
+
+/// Rewrite `import x` / `from x import y` statements for vendored top-level modules.
+///
+/// Relative imports and imports of other modules are left alone. Only
+/// statements starting a line are considered, which covers the way
+/// virtually all packages write imports.
+pub fn rewrite_imports(source: &str, modules: &BTreeSet<String>, prefix: &str) -> String {
+    let is_vendored = |dotted: &str| modules.contains(dotted.split('.').next().unwrap_or_default());
+    let mut out = String::with_capacity(source.len());
+
+    for line in source.split_inclusive('\n') {
+        let (body, newline) = match line.strip_suffix('\n') {
+            Some(body) => (body, "\n"),
+            None => (line, ""),
+        };
+
+        if let Some(caps) = FROM_RE.captures(body)
+            && is_vendored(&caps[2])
+        {
+            out.push_str(&format!("{}from {}.{}{}{}", &caps[1], prefix, &caps[2], &caps[3], newline));
+            continue;
+        }
+
+        if let Some(caps) = IMPORT_RE.captures(body)
+            && !caps[2].trim_end().ends_with(['(', '\\'])
+        {
+            let names: Vec<&str> = caps[2].split(',').map(str::trim).collect();
+            if names.iter().any(|name| is_vendored(name.split_whitespace().next().unwrap_or_default())) {
+                let statements: Vec<String> = names
+                    .iter()
+                    .map(|name| rewrite_import_clause(name, prefix, &is_vendored))
+                    .collect();
+                let comment = caps.get(3).map(|c| format!("  {}", c.as_str())).unwrap_or_default();
+                out.push_str(&format!("{}{}{}{}", &caps[1], statements.join("; "), comment, newline));
+                continue;
+            }
+        }
+
+        out.push_str(line);
+    }
+    out
+}
+
+/// Top-level modules `source` imports absolutely, read the same way as
+/// [`rewrite_imports`] reads them
+pub fn imported_modules(source: &str) -> BTreeSet<String> {
+    let mut modules = BTreeSet::new();
+    for line in source.lines() {
+        if let Some(caps) = FROM_RE.captures(line) {
+            modules.insert(caps[2].split('.').next().unwrap_or_default().to_string());
+        } else if let Some(caps) = IMPORT_RE.captures(line) {
+            for clause in caps[2].split(',') {
+                if let Some(module) = clause.split_whitespace().next() {
+                    modules.insert(module.split('.').next().unwrap_or_default().to_string());
+                }
+            }
+        }
+    }
+    modules.retain(|module| !module.is_empty() && !module.starts_with(['(', '\\']));
+    modules
+}
+
+/// Rewrite one `a.b [as c]` clause of an `import` statement
+fn rewrite_import_clause(clause: &str, prefix: &str, is_vendored: &dyn Fn(&str) -> bool) -> String {
+    let mut parts = clause.split_whitespace();
+    let module = parts.next().unwrap_or_default();
+    let alias = match (parts.next(), parts.next()) {
+        (Some("as"), Some(alias)) => Some(alias),
+        _ => None,
+    };
+
+    if !is_vendored(module) {
+        return format!("import {}", clause);
+    }
+    match (module.split_once('.'), alias) {
+        (None, Some(alias)) => format!("from {} import {} as {}", prefix, module, alias),
+        (None, None) => format!("from {} import {}", prefix, module),
+        (Some(_), Some(alias)) => format!("import {}.{} as {}", prefix, module, alias),
+        // `import a.b` binds `a`, so import the submodule and then bind the top-level name
+        (Some((top, _)), None) => format!("import {}.{}; from {} import {}", prefix, module, prefix, top),
+    }
+}
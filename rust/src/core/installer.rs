@@ -0,0 +1,491 @@
+//! Installing locked packages into a virtual environment with pip.
+
+use crate::core::buildenv;
+use crate::core::buildinfo;
+use crate::core::config::Config;
+use crate::core::dirs::cache_dir;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::index::{self, IndexClient};
+use crate::core::lockfile::{LockedFile, LockedPackage, Lockfile};
+use crate::core::overrides;
+use crate::core::pep440::Version;
+use crate::core::pep508::{Requirement, normalize_name};
+use crate::core::project::Project;
+use crate::core::quarantine::{self, Held};
+use crate::core::venv::Venv;
+use crate::core::workspace;
+use crate::core::timings;
+use crate::core::parallel;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Packages that belong to the environment itself and are never removed
+const SEED_PACKAGES: &[&str] = &["pip", "setuptools", "wheel"];
+
+/// What a sync changed
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    pub installed: Vec<String>,
+    /// Installed packages that are not in the lockfile, left in place
+    pub orphans: Vec<String>,
+    /// Packages (re)installed from `pyforge.dev.toml` overrides instead of the lockfile
+    pub overridden: Vec<String>,
+    pub unchanged: usize,
+}
+
+#[derive(Deserialize)]
+struct PipListEntry {
+    name: String,
+    version: String,
+}
+
+/// PEP 610 `direct_url.json` of a distribution installed from a directory
+#[derive(Deserialize)]
+struct DirectUrl {
+    url: String,
+    dir_info: Option<DirInfo>,
+}
+
+#[derive(Deserialize)]
+struct DirInfo {
+    #[serde(default)]
+    editable: bool,
+}
+
+/// Installed distributions, keyed by normalized name
+pub fn installed(venv: &Venv) -> Result<BTreeMap<String, String>> {
+    let mut command = venv.pip_command();
+    // --local leaves out the base interpreter's packages under system-site-packages
+    command.args(["list", "--local", "--format", "json", "--disable-pip-version-check"]);
+    let _span = timings::subprocess(&command);
+    let output = command.output()?;
+    if !output.status.success() {
+        return Err(PyForgeError::command_failed("pip list", output.status.code().unwrap_or(-1)));
+    }
+    let entries: Vec<PipListEntry> = serde_json::from_slice(&output.stdout)?;
+    Ok(entries
+        .into_iter()
+        .map(|entry| (normalize_name(&entry.name), entry.version))
+        .collect())
+}
+
+/// Make the environment match the lockfile exactly.
+///
+/// Every file is installed by its locked URL (or from a `find-links`
+/// directory) with its hash checked, so pip never consults an index.
+/// Path dependencies are installed from their directories, editable unless
+/// configured otherwise; only their build backends may come from an index.
+/// With `offline`, files must be available locally; with `reinstall`, every
+/// locked package is reinstalled even if the right version is present.
+/// Files are downloaded and hashed `jobs` at a time before pip runs.
+/// Packages overridden in `pyforge.dev.toml` are installed from there instead.
+pub fn sync(
+    venv: &Venv,
+    lockfile: &Lockfile,
+    root: &Path,
+    config: &Config,
+    offline: bool,
+    reinstall: bool,
+    jobs: usize,
+) -> Result<SyncReport> {
+    let _span = timings::span("install");
+    let install = &config.install;
+    let current = installed(venv)?;
+    let from_dirs = directory_installs(venv);
+    let overridden: Vec<String> = overrides::load(root)?.into_iter().map(|entry| entry.name).collect();
+    let project = Project::load(root)?;
+    let env = buildenv::variables(&project, config, buildinfo::DEV)?;
+    let mut report = SyncReport::default();
+
+    let pending: Vec<&LockedPackage> = pending(lockfile, &current, &from_dirs, reinstall)
+        .into_iter()
+        .filter(|package| !overridden.contains(&package.name))
+        .collect();
+    report.unchanged = lockfile.packages.iter().filter(|package| !overridden.contains(&package.name)).count() - pending.len();
+
+    let seeds = seed_names(config);
+    report.orphans = current
+        .keys()
+        .filter(|name| lockfile.package(name).is_none() && !seeds.contains(name) && !overridden.contains(name))
+        .cloned()
+        .collect();
+
+    let (local, pending): (Vec<&LockedPackage>, Vec<&LockedPackage>) =
+        pending.into_iter().partition(|package| package.path.is_some());
+
+    if !pending.is_empty() {
+        let links: Vec<PathBuf> = install.find_links.iter().map(|dir| root.join(dir)).collect();
+        let offline = offline || install.no_index;
+        let fetched = if offline { BTreeMap::new() } else { prefetch(config, &pending, &links, jobs)? };
+        let lines = pending
+            .iter()
+            .map(|package| requirement_line(package, &links, &fetched, offline))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut requirements = tempfile::Builder::new().prefix("pyforge-sync-").suffix(".txt").tempfile()?;
+        writeln!(requirements, "{}", lines.join("\n"))?;
+        let path = requirements.path().to_string_lossy().to_string();
+
+        let mut args = vec!["install", "--no-deps", "--no-index", "-r", &path];
+        if reinstall {
+            args.push("--force-reinstall");
+        }
+        if pending.iter().all(|p| p.files.first().is_some_and(|f| f.sha256().is_some())) {
+            args.push("--require-hashes");
+        }
+        // Packages left over from a dropped override may have the locked version already
+        let replaced: Vec<String> =
+            pending.iter().filter(|p| from_dirs.contains_key(&p.name)).map(|p| p.name.clone()).collect();
+        remove(venv, &replaced)?;
+        pip(venv, &env, &args)?;
+        report.installed = pending.iter().map(|p| format!("{} {}", p.name, p.version)).collect();
+    }
+
+    if !local.is_empty() {
+        let editable: BTreeMap<String, bool> = workspace::members(&project)?
+            .into_values()
+            .map(|member| (member.name, member.editable))
+            .collect();
+        let index_args = index_args(config, root, offline);
+        for package in local {
+            let dir = root.join(package.path.as_deref().unwrap_or_default()).display().to_string();
+            let mut args = vec!["install", "--no-deps"];
+            if reinstall {
+                args.push("--force-reinstall");
+            }
+            args.extend(index_args.iter().map(String::as_str));
+            if editable.get(&package.name).copied().unwrap_or(true) {
+                args.push("--editable");
+            }
+            args.push(&dir);
+            pip(venv, &env, &args)?;
+            report.installed.push(format!("{} {} ({})", package.name, package.version, package.path.as_deref().unwrap_or_default()));
+        }
+    }
+
+    report.overridden = install_overrides(venv, root, config, offline, reinstall, &env)?;
+    Ok(report)
+}
+
+/// Install the `pyforge.dev.toml` overrides that aren't installed from their
+/// directories yet (all of them with `reinstall`), as `name (path)`
+pub fn apply_overrides(venv: &Venv, root: &Path, config: &Config, offline: bool, reinstall: bool) -> Result<Vec<String>> {
+    let env = buildenv::variables(&Project::load(root)?, config, buildinfo::DEV)?;
+    install_overrides(venv, root, config, offline, reinstall, &env)
+}
+
+fn install_overrides(
+    venv: &Venv,
+    root: &Path,
+    config: &Config,
+    offline: bool,
+    reinstall: bool,
+    env: &BTreeMap<String, String>,
+) -> Result<Vec<String>> {
+    let entries = overrides::load(root)?;
+    if entries.is_empty() {
+        return Ok(Vec::new());
+    }
+    let from_dirs = directory_installs(venv);
+    let index_args = index_args(config, root, offline);
+    let mut applied = Vec::new();
+    for entry in entries {
+        if !reinstall && from_dirs.get(&entry.name) == Some(&(entry.path.clone(), entry.editable)) {
+            continue;
+        }
+        let dir = entry.path.display().to_string();
+        let mut args = vec!["install", "--no-deps"];
+        if reinstall {
+            args.push("--force-reinstall");
+        }
+        args.extend(index_args.iter().map(String::as_str));
+        if entry.editable {
+            args.push("--editable");
+        }
+        args.push(&dir);
+        pip(venv, env, &args)?;
+        applied.push(format!("{} ({})", entry.name, dir));
+    }
+    Ok(applied)
+}
+
+/// Distributions installed from a local directory, with the directory and
+/// whether the install is editable, keyed by normalized name
+fn directory_installs(venv: &Venv) -> BTreeMap<String, (PathBuf, bool)> {
+    let Ok(entries) = venv.site_packages().and_then(|dir| Ok(fs::read_dir(dir)?)) else {
+        return BTreeMap::new();
+    };
+    let mut installs = BTreeMap::new();
+    for dist_info in entries.flatten().map(|entry| entry.path()) {
+        let Some((name, _)) = dist_info
+            .file_name()
+            .and_then(|name| name.to_str()?.strip_suffix(".dist-info")?.rsplit_once('-'))
+        else {
+            continue;
+        };
+        let Some(direct) = fs::read_to_string(dist_info.join("direct_url.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<DirectUrl>(&content).ok())
+        else {
+            continue;
+        };
+        if let (Some(info), Some(path)) = (direct.dir_info, index::local_path(&direct.url)) {
+            installs.insert(normalize_name(name), (path, info.editable));
+        }
+    }
+    installs
+}
+
+/// Packages a sync would install that have hooks of their own and aren't in
+/// `[tool.pyforge.install.hooks] allow`.
+///
+/// Their files are downloaded into the file cache first, so the sync that
+/// follows doesn't fetch them again. Files locked without a hash are fetched by
+/// pip itself and can't be looked at beforehand.
+pub fn review(
+    venv: &Venv,
+    lockfile: &Lockfile,
+    root: &Path,
+    config: &Config,
+    offline: bool,
+    reinstall: bool,
+    jobs: usize,
+) -> Result<Vec<Held>> {
+    let allowed: Vec<String> = config.install.hooks.allow.iter().map(|name| normalize_name(name)).collect();
+    let current = installed(venv)?;
+    let overridden: Vec<String> = overrides::load(root)?.into_iter().map(|entry| entry.name).collect();
+    let pending: Vec<&LockedPackage> = pending(lockfile, &current, &directory_installs(venv), reinstall)
+        .into_iter()
+        .filter(|package| package.path.is_none() && !allowed.contains(&package.name) && !overridden.contains(&package.name))
+        .collect();
+    if pending.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let links: Vec<PathBuf> = config.install.find_links.iter().map(|dir| root.join(dir)).collect();
+    let offline = offline || config.install.no_index;
+    let fetched = if offline { BTreeMap::new() } else { prefetch(config, &pending, &links, jobs)? };
+    let mut held = Vec::new();
+    for package in pending {
+        let Some(file) = package.files.first() else {
+            continue;
+        };
+        let Some(path) = local_file(file, &links).or_else(|| fetched.get(&file.name).cloned()) else {
+            continue;
+        };
+        let hooks = quarantine::inspect(&path, &package.name)?;
+        if !hooks.is_empty() {
+            held.push(Held {
+                package: package.name.clone(),
+                version: package.version.clone(),
+                hooks,
+            });
+        }
+    }
+    Ok(held)
+}
+
+/// Locked packages missing from `current`, at another version, or installed
+/// from a directory they aren't locked from; all of them with `reinstall`
+fn pending<'a>(
+    lockfile: &'a Lockfile,
+    current: &BTreeMap<String, String>,
+    from_dirs: &BTreeMap<String, (PathBuf, bool)>,
+    reinstall: bool,
+) -> Vec<&'a LockedPackage> {
+    lockfile
+        .packages
+        .iter()
+        .filter(|package| {
+            let up_to_date = current.get(&package.name).is_some_and(|version| same_version(version, &package.version))
+                && (package.path.is_some() || !from_dirs.contains_key(&package.name));
+            reinstall || !up_to_date
+        })
+        .collect()
+}
+
+/// Shared cache of downloaded distribution files, by SHA-256
+pub fn file_cache_dir() -> PathBuf {
+    cache_dir().join("files")
+}
+
+/// Download the locked files that have a hash and aren't in a `find-links`
+/// directory into the file cache, `jobs` at a time; returns their paths by file name
+fn prefetch(
+    config: &Config,
+    packages: &[&LockedPackage],
+    links: &[PathBuf],
+    jobs: usize,
+) -> Result<BTreeMap<String, PathBuf>> {
+    let files: Vec<&LockedFile> = packages
+        .iter()
+        .filter_map(|package| package.files.first())
+        .filter(|file| file.sha256().is_some() && local_file(file, links).is_none())
+        .collect();
+    if files.is_empty() {
+        return Ok(BTreeMap::new());
+    }
+
+    let client = IndexClient::new(config)?;
+    parallel::map(jobs, &files, |file| {
+        let sha256 = file.sha256().unwrap_or_default().to_lowercase();
+        let dir = file_cache_dir().join(&sha256);
+        let path = dir.join(&file.name);
+        if !path.is_file() {
+            fs::create_dir_all(&dir)
+                .map_err(|e| PyForgeError::file_error(format!("Could not create {}", dir.display()), e))?;
+            client.download_to(&file.url, &path, Some(&sha256))?;
+        }
+        Ok((file.name.clone(), path))
+    })
+    .into_iter()
+    .collect()
+}
+
+/// A pip requirement pinning the exact locked file
+fn requirement_line(
+    package: &LockedPackage,
+    links: &[PathBuf],
+    fetched: &BTreeMap<String, PathBuf>,
+    offline: bool,
+) -> Result<String> {
+    let file = package.files.first().ok_or_else(|| PyForgeError::ResolutionFailed {
+        package: package.name.clone(),
+        reason: "the lockfile lists no installable file".to_string(),
+    })?;
+
+    let location = match local_file(file, links).or_else(|| fetched.get(&file.name).cloned()) {
+        Some(path) => file_url(&path),
+        None if offline => {
+            return Err(PyForgeError::FileError {
+                message: format!(
+                    "{} is not available offline; run 'pyforge vendor' while online to fetch it",
+                    file.name
+                ),
+                source: None,
+            });
+        }
+        None => file.url.clone(),
+    };
+
+    let hash = file
+        .sha256()
+        .map(|sha256| format!(" --hash=sha256:{}", sha256))
+        .unwrap_or_default();
+    Ok(format!("{} @ {}{}", package.name, location, hash))
+}
+
+/// The file in a `find-links` directory, or where its `file://` URL points
+fn local_file(file: &LockedFile, links: &[PathBuf]) -> Option<PathBuf> {
+    links
+        .iter()
+        .map(|dir| dir.join(&file.name))
+        .find(|path| path.is_file())
+        .or_else(|| index::local_path(&file.url).filter(|path| path.is_file()))
+}
+
+fn file_url(path: &Path) -> String {
+    let absolute = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let display = absolute.to_string_lossy().replace('\\', "/");
+    if display.starts_with('/') {
+        format!("file://{}", display)
+    } else {
+        format!("file:///{}", display)
+    }
+}
+
+/// pip arguments selecting where packages come from: the configured indexes,
+/// or only the `find-links` directories when offline or `no-index` is set
+pub fn index_args(config: &Config, root: &Path, offline: bool) -> Vec<String> {
+    let mut args = Vec::new();
+    if offline || config.install.no_index {
+        args.push("--no-index".to_string());
+    } else {
+        for (position, index) in config.indexes().iter().enumerate() {
+            args.push(if position == 0 { "--index-url" } else { "--extra-index-url" }.to_string());
+            args.push(index.url.clone());
+        }
+    }
+    for dir in &config.install.find_links {
+        args.push("--find-links".to_string());
+        args.push(root.join(dir).display().to_string());
+    }
+    args
+}
+
+/// Install requirements by name, letting pip pick versions from `index_args`,
+/// with the `[tool.pyforge.build.env]` variables in `env`
+pub fn install_requirements(
+    venv: &Venv,
+    requirements: &[String],
+    index_args: &[String],
+    env: &BTreeMap<String, String>,
+) -> Result<()> {
+    if requirements.is_empty() {
+        return Ok(());
+    }
+    let _span = timings::span("install");
+    let args: Vec<&str> = ["install"]
+        .into_iter()
+        .chain(index_args.iter().map(String::as_str))
+        .chain(requirements.iter().map(String::as_str))
+        .collect();
+    pip(venv, env, &args)
+}
+
+/// Install or upgrade the configured `[tool.pyforge.venv] seed` requirements
+pub fn seed(venv: &Venv, config: &Config, root: &Path) -> Result<()> {
+    let Some(seeds) = config.venv.seed.as_ref().filter(|seeds| !seeds.is_empty()) else {
+        return Ok(());
+    };
+    let _span = timings::span("install");
+    let env = buildenv::variables(&Project::load(root)?, config, buildinfo::DEV)?;
+    let index_args = index_args(config, root, false);
+    let args: Vec<&str> = ["install", "--upgrade"]
+        .into_iter()
+        .chain(index_args.iter().map(String::as_str))
+        .chain(seeds.iter().map(String::as_str))
+        .collect();
+    pip(venv, &env, &args)
+}
+
+/// Names of the packages that belong to the environment rather than the lockfile
+pub fn seed_names(config: &Config) -> Vec<String> {
+    let configured = config.venv.seed.iter().flatten().filter_map(|seed| Requirement::parse(seed).ok());
+    SEED_PACKAGES
+        .iter()
+        .map(|name| name.to_string())
+        .chain(configured.map(|requirement| requirement.normalized_name()))
+        .collect()
+}
+
+fn same_version(installed: &str, locked: &str) -> bool {
+    match (Version::parse(installed), Version::parse(locked)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => installed == locked,
+    }
+}
+
+/// Uninstall `names` from `venv`
+pub fn remove(venv: &Venv, names: &[String]) -> Result<()> {
+    if names.is_empty() {
+        return Ok(());
+    }
+    pip(venv, &BTreeMap::new(), &[&["uninstall", "--yes"], &names.iter().map(String::as_str).collect::<Vec<_>>()[..]].concat())
+}
+
+fn pip(venv: &Venv, env: &BTreeMap<String, String>, args: &[&str]) -> Result<()> {
+    log::info!("pip {}", args.join(" "));
+    let mut command = venv.pip_command();
+    command.args(["--disable-pip-version-check", "--quiet"]).args(args).envs(env);
+    let _span = timings::subprocess(&command);
+    let status = command.status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(PyForgeError::command_failed(format!("pip {}", args.join(" ")), status.code().unwrap_or(-1)))
+    }
+}
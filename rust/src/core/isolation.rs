@@ -0,0 +1,55 @@
+//! Hermetic runs (`--isolated`): the command gets only the environment
+//! variables it needs to start plus the ones allowed explicitly, a fresh
+//! empty home and temporary directory, no user site-packages and a fixed hash
+//! seed, so a failure that depends on the machine shows up (or goes away)
+//! the same way everywhere.
+
+use crate::core::error::Result;
+use std::process::Command;
+use tempfile::TempDir;
+
+/// Passed through always: finding programs, the locale and the terminal
+const BASELINE: &[&str] = &[
+    "PATH", "LANG", "LC_ALL", "LC_CTYPE", "TERM", "TZ",
+    // Windows can't start processes without these
+    "SYSTEMROOT", "SYSTEMDRIVE", "COMSPEC", "PATHEXT", "WINDIR",
+];
+
+/// A temporary home for one isolated run, removed when dropped
+pub struct Isolation {
+    dir: TempDir,
+    allowed: Vec<String>,
+}
+
+impl Isolation {
+    /// `allowed` names the variables to pass through besides the baseline
+    pub fn new(allowed: impl IntoIterator<Item = String>) -> Result<Self> {
+        let dir = tempfile::Builder::new().prefix("pyforge-isolated-").tempdir()?;
+        std::fs::create_dir_all(dir.path().join("home"))?;
+        std::fs::create_dir_all(dir.path().join("tmp"))?;
+        Ok(Self {
+            dir,
+            allowed: allowed.into_iter().collect(),
+        })
+    }
+
+    /// Replace the environment of `command`; activate the virtual environment afterwards
+    pub fn apply(&self, command: &mut Command) {
+        command.env_clear();
+        for name in BASELINE.iter().copied().chain(self.allowed.iter().map(String::as_str)) {
+            if let Some(value) = std::env::var_os(name) {
+                command.env(name, value);
+            }
+        }
+        let home = self.dir.path().join("home");
+        let tmp = self.dir.path().join("tmp");
+        command
+            .env("HOME", &home)
+            .env("USERPROFILE", &home)
+            .env("TMPDIR", &tmp)
+            .env("TEMP", &tmp)
+            .env("TMP", &tmp)
+            .env("PYTHONNOUSERSITE", "1")
+            .env("PYTHONHASHSEED", "0");
+    }
+}
@@ -0,0 +1,146 @@
+//! JUnit XML, the test result format CI systems read: parsed from pytest's
+//! `--junitxml` output, or written for test commands that don't produce it.
+
+use crate::core::htmlreport::escape;
+use std::process::ExitStatus;
+
+/// Outcome of one test case
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Outcome {
+    Failed,
+    Error,
+    Skipped,
+    Passed,
+}
+
+impl Outcome {
+    pub fn class(self) -> &'static str {
+        match self {
+            Outcome::Failed | Outcome::Error => "fail",
+            Outcome::Skipped => "skipped",
+            Outcome::Passed => "pass",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Outcome::Failed => "failed",
+            Outcome::Error => "error",
+            Outcome::Skipped => "skipped",
+            Outcome::Passed => "passed",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TestCase {
+    /// `classname` of the case, usually the module path
+    pub suite: String,
+    pub name: String,
+    pub seconds: f64,
+    pub outcome: Outcome,
+    /// Failure or skip message and traceback
+    pub message: String,
+}
+
+/// Test cases of a JUnit XML report, as written by `pytest --junitxml`
+pub fn parse(xml: &str) -> Vec<TestCase> {
+    let mut cases = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find("<testcase") {
+        rest = &rest[start..];
+        let Some(open_end) = rest.find('>') else {
+            break;
+        };
+        let open = &rest[..open_end];
+        let (body, next) = if open.ends_with('/') {
+            ("", &rest[open_end + 1..])
+        } else {
+            match rest.find("</testcase>") {
+                Some(close) => (&rest[open_end + 1..close], &rest[close + "</testcase>".len()..]),
+                None => ("", &rest[open_end + 1..]),
+            }
+        };
+        let (outcome, message) = [("<failure", Outcome::Failed), ("<error", Outcome::Error), ("<skipped", Outcome::Skipped)]
+            .into_iter()
+            .find_map(|(tag, outcome)| body.find(tag).map(|at| (outcome, element_text(&body[at..]))))
+            .unwrap_or((Outcome::Passed, String::new()));
+        cases.push(TestCase {
+            suite: attribute(open, "classname").unwrap_or_default(),
+            name: attribute(open, "name").unwrap_or_default(),
+            seconds: attribute(open, "time").and_then(|time| time.parse().ok()).unwrap_or(0.0),
+            outcome,
+            message,
+        });
+        rest = next;
+    }
+    cases
+}
+
+/// Value of `name="..."` in an opening tag
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let needle = format!(" {}=\"", name);
+    let start = tag.find(&needle)? + needle.len();
+    let end = tag[start..].find('"')? + start;
+    Some(unescape(&tag[start..end]))
+}
+
+/// The `message` attribute and text of the element `xml` starts with
+fn element_text(xml: &str) -> String {
+    let open_end = xml.find('>').unwrap_or(xml.len());
+    let open = &xml[..open_end];
+    let message = attribute(open, "message").unwrap_or_default();
+    let text = if open.ends_with('/') {
+        String::new()
+    } else {
+        let body = &xml[(open_end + 1).min(xml.len())..];
+        let body = body.find("</").map_or(body, |end| &body[..end]);
+        let body = body.trim().trim_start_matches("<![CDATA[").trim_end_matches("]]>");
+        unescape(body.trim())
+    };
+    match (message.is_empty(), text.is_empty()) {
+        (false, false) if !text.contains(&message) => format!("{}\n{}", message, text),
+        (_, false) => text,
+        _ => message,
+    }
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#10;", "\n")
+        .replace("&amp;", "&")
+}
+
+/// A JUnit XML report with a single case for a test command that doesn't
+/// report its tests one by one
+pub fn single_case(suite: &str, command: &str, status: ExitStatus, seconds: f64) -> String {
+    let failure = if status.success() {
+        String::new()
+    } else {
+        let message = format!("{} exited with {}", command, status.code().unwrap_or(-1));
+        format!("<failure message=\"{}\"/>", escape(&message))
+    };
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <testsuites><testsuite name=\"{suite}\" tests=\"1\" failures=\"{failures}\" errors=\"0\" skipped=\"0\" time=\"{seconds:.3}\">\
+         <testcase classname=\"{suite}\" name=\"{command}\" time=\"{seconds:.3}\">{failure}</testcase>\
+         </testsuite></testsuites>\n",
+        suite = escape(suite),
+        failures = u8::from(!status.success()),
+        seconds = seconds,
+        command = escape(command),
+        failure = failure,
+    )
+}
+
+/// Where a failing case failed: the last `path.py:line` its traceback names
+pub fn location(case: &TestCase) -> Option<(String, u32)> {
+    case.message.lines().rev().find_map(|line| {
+        let (path, rest) = line.trim().split_once(".py:")?;
+        let line: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        Some((format!("{}.py", path), line.parse().ok()?))
+    })
+}
@@ -0,0 +1,369 @@
+use crate::core::error::{PyForgeError, Result};
+use crate::core::markers::Environment;
+use crate::core::pep508::{Requirement, normalize_name};
+use crate::core::project::Project;
+use crate::core::toolchain::Toolchain;
+use crate::core::workspace;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+
+/// Lockfile name, next to `pyproject.toml`
+pub const LOCKFILE: &str = "pyforge.lock";
+
+/// Current lockfile format version
+pub const LOCKFILE_VERSION: u32 = 2;
+
+/// Upgrades of the lockfile format, one per version: the first turns a
+/// version 1 lockfile into version 2, and so on
+const MIGRATIONS: &[Migration] = &[Migration {
+    summary: "file hashes moved into a `hashes` table keyed by algorithm",
+    apply: migrate_file_hashes,
+}];
+
+struct Migration {
+    summary: &'static str,
+    apply: fn(&mut toml::Table),
+}
+
+const HEADER: &str = "# This file is generated by `pyforge lock`. Do not edit it by hand.\n\n";
+
+/// Exact versions and files of every dependency, resolved for one environment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Lockfile {
+    pub version: u32,
+
+    /// `requires-python` of the project when it was locked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requires_python: Option<String>,
+
+    /// Hash of the dependency declarations the lock was resolved from
+    pub input_hash: String,
+
+    /// Marker environment dependencies were resolved for
+    pub environment: Environment,
+
+    /// Tools used to produce the lock
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toolchain: Option<Toolchain>,
+
+    #[serde(default, rename = "package")]
+    pub packages: Vec<LockedPackage>,
+
+    /// Format version of the file this was read from, when older than the current one
+    #[serde(skip)]
+    pub migrated_from: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LockedPackage {
+    /// Normalized package name
+    pub name: String,
+    pub version: String,
+
+    /// Index the package was resolved from; empty for path dependencies
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub index: String,
+
+    /// Project directory of a path dependency, relative to the project root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// Normalized names of the locked packages this one requires
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+
+    /// License expression from the package metadata
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+
+    /// Installable files, best match first
+    #[serde(default)]
+    pub files: Vec<LockedFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LockedFile {
+    pub name: String,
+    pub url: String,
+    /// Digests by algorithm name, e.g. `sha256`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hashes: BTreeMap<String, String>,
+}
+
+impl LockedFile {
+    pub fn sha256(&self) -> Option<&str> {
+        self.hashes.get("sha256").map(String::as_str)
+    }
+}
+
+impl Lockfile {
+    pub fn path(project: &Project) -> PathBuf {
+        project.root.join(LOCKFILE)
+    }
+
+    /// Read the project's lockfile, if it has one
+    pub fn load(project: &Project) -> Result<Option<Self>> {
+        let path = Self::path(project);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path)
+            .map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+        Self::parse(&content, &path.display().to_string()).map(Some)
+    }
+
+    /// Parse lockfile `content`, migrating older formats; `file` names it in errors
+    pub fn parse(content: &str, file: &str) -> Result<Self> {
+        let invalid = |e: toml::de::Error| PyForgeError::InvalidToml {
+            file: file.to_string(),
+            message: e.to_string(),
+        };
+        let mut table: toml::Table = toml::from_str(content).map_err(invalid)?;
+
+        let version = match table.get("version").and_then(toml::Value::as_integer) {
+            Some(version) if (1..=i64::from(LOCKFILE_VERSION)).contains(&version) => version as u32,
+            Some(version) if version > 0 => {
+                return Err(PyForgeError::ParseError {
+                    file_type: LOCKFILE.to_string(),
+                    message: format!(
+                        "format version {} is newer than this pyforge supports ({}); upgrade pyforge",
+                        version, LOCKFILE_VERSION
+                    ),
+                });
+            }
+            _ => {
+                return Err(PyForgeError::ParseError {
+                    file_type: LOCKFILE.to_string(),
+                    message: format!("{} has no valid format `version`", file),
+                });
+            }
+        };
+        for migration in &MIGRATIONS[version as usize - 1..] {
+            log::info!("migrating {}: {}", file, migration.summary);
+            (migration.apply)(&mut table);
+        }
+        table.insert("version".to_string(), toml::Value::Integer(LOCKFILE_VERSION.into()));
+
+        let mut lockfile: Lockfile = table.try_into().map_err(invalid)?;
+        lockfile.migrated_from = (version < LOCKFILE_VERSION).then_some(version);
+        Ok(lockfile)
+    }
+
+    /// Read the project's lockfile, failing when it hasn't been locked yet
+    pub fn require(project: &Project) -> Result<Self> {
+        Self::load(project)?.ok_or_else(|| PyForgeError::LockfileNotFound {
+            path: Self::path(project).display().to_string(),
+        })
+    }
+
+    pub fn write(&self, project: &Project) -> Result<PathBuf> {
+        let path = Self::path(project);
+        let body = toml::to_string(self).map_err(|e| PyForgeError::internal(format!("Could not render lockfile: {}", e)))?;
+        fs::write(&path, format!("{}{}", HEADER, body))
+            .map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))?;
+        Ok(path)
+    }
+
+    /// Whether the project's dependency declarations changed since locking
+    pub fn is_fresh(&self, project: &Project) -> bool {
+        self.input_hash == input_hash(project)
+    }
+
+    pub fn package(&self, name: &str) -> Option<&LockedPackage> {
+        let name = normalize_name(name);
+        self.packages.iter().find(|p| p.name == name)
+    }
+
+    /// Packages that no requirement in `roots` reaches through the recorded
+    /// dependencies, e.g. left behind by a lockfile merge. Local packages
+    /// (workspace members) count as roots.
+    pub fn unreachable(&self, roots: &[Requirement]) -> Vec<&LockedPackage> {
+        let mut pending: Vec<String> = roots
+            .iter()
+            .map(Requirement::normalized_name)
+            .chain(self.packages.iter().filter(|package| package.path.is_some()).map(|package| package.name.clone()))
+            .collect();
+        let mut reached = BTreeSet::new();
+        while let Some(name) = pending.pop() {
+            if let Some(package) = self.package(&name)
+                && reached.insert(package.name.as_str())
+            {
+                pending.extend(package.dependencies.iter().cloned());
+            }
+        }
+        self.packages.iter().filter(|package| !reached.contains(package.name.as_str())).collect()
+    }
+
+    /// Packages added, removed or moved to another version since `previous`, by name
+    pub fn changes_since(&self, previous: &Lockfile) -> Vec<VersionChange> {
+        let versions = |lockfile: &Lockfile| -> BTreeMap<String, String> {
+            lockfile.packages.iter().map(|p| (p.name.clone(), p.version.clone())).collect()
+        };
+        let (old, new) = (versions(previous), versions(self));
+        let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+        names
+            .into_iter()
+            .filter(|name| old.get(*name) != new.get(*name))
+            .map(|name| VersionChange {
+                name: name.clone(),
+                old: old.get(name).cloned(),
+                new: new.get(name).cloned(),
+            })
+            .collect()
+    }
+}
+
+/// Descriptions of the format changes since `version`, oldest first
+pub fn changes_since_format(version: u32) -> Vec<&'static str> {
+    MIGRATIONS
+        .get(version.saturating_sub(1) as usize..)
+        .unwrap_or_default()
+        .iter()
+        .map(|migration| migration.summary)
+        .collect()
+}
+
+/// Version 2: `sha256 = "..."` on a file became `hashes = { sha256 = "..." }`
+fn migrate_file_hashes(lockfile: &mut toml::Table) {
+    let packages = lockfile.get_mut("package").and_then(toml::Value::as_array_mut).into_iter().flatten();
+    let files = packages
+        .filter_map(|package| package.get_mut("files").and_then(toml::Value::as_array_mut))
+        .flatten()
+        .filter_map(toml::Value::as_table_mut);
+    for file in files {
+        if let Some(sha256) = file.remove("sha256") {
+            let mut hashes = toml::Table::new();
+            hashes.insert("sha256".to_string(), sha256);
+            file.insert("hashes".to_string(), toml::Value::Table(hashes));
+        }
+    }
+}
+
+/// A package whose locked version differs between two lockfiles
+#[derive(Debug, Clone)]
+pub struct VersionChange {
+    pub name: String,
+    /// `None` when the package was added
+    pub old: Option<String>,
+    /// `None` when the package was removed
+    pub new: Option<String>,
+}
+
+/// The project's dependencies, including every optional group
+pub fn requirements(project: &Project) -> Result<Vec<Requirement>> {
+    project
+        .dependencies()
+        .into_iter()
+        .chain(project.optional_dependencies().into_iter().flat_map(|(_, deps)| deps))
+        .map(|dependency| Requirement::parse(&dependency))
+        .collect()
+}
+
+/// Hash of everything in `pyproject.toml` that affects resolution
+pub fn input_hash(project: &Project) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(project.requires_python().unwrap_or_default());
+    hasher.update("\0");
+    for dependency in project.dependencies() {
+        hasher.update(dependency);
+        hasher.update("\n");
+    }
+    for requirement in project.build_requires() {
+        hasher.update(format!("build:{}\n", requirement));
+    }
+    for (group, dependencies) in project.optional_dependencies() {
+        hasher.update(format!("[{}]\n", group));
+        for dependency in dependencies {
+            hasher.update(dependency);
+            hasher.update("\n");
+        }
+    }
+    // Path dependencies are resolved from their own pyproject.toml, so it is an input too
+    if let Ok(members) = workspace::members(project) {
+        for member in members.values() {
+            hasher.update(format!("path:{}={}@{}\n", member.name, member.path, member.version));
+            for dependency in member.project.dependencies() {
+                hasher.update(format!("{}:{}\n", member.name, dependency));
+            }
+        }
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORMAT_1: &str = r#"
+version = 1
+requires-python = ">=3.9"
+input-hash = "abc"
+
+[environment]
+python-version = "3.12"
+python-full-version = "3.12.4"
+implementation-name = "cpython"
+platform-python-implementation = "CPython"
+os-name = "posix"
+sys-platform = "linux"
+platform-system = "Linux"
+platform-machine = "x86_64"
+
+[[package]]
+name = "idna"
+version = "3.7"
+index = "https://pypi.org/simple"
+
+[[package.files]]
+name = "idna-3.7-py3-none-any.whl"
+url = "https://files.example/idna-3.7-py3-none-any.whl"
+sha256 = "aa"
+
+[[package.files]]
+name = "idna-3.7.tar.gz"
+url = "https://files.example/idna-3.7.tar.gz"
+"#;
+
+    #[test]
+    fn migrates_format_1_and_round_trips() {
+        let lockfile = Lockfile::parse(FORMAT_1, "pyforge.lock").unwrap();
+        assert_eq!(lockfile.version, LOCKFILE_VERSION);
+        assert_eq!(lockfile.migrated_from, Some(1));
+        let files = &lockfile.package("idna").unwrap().files;
+        assert_eq!(files[0].sha256(), Some("aa"));
+        assert!(files[1].hashes.is_empty());
+
+        let written = toml::to_string(&lockfile).unwrap();
+        let table: toml::Table = toml::from_str(&written).unwrap();
+        let file = &table["package"][0]["files"][0];
+        assert!(file.get("sha256").is_none(), "format 1 field left behind:\n{}", written);
+        assert_eq!(file["hashes"]["sha256"].as_str(), Some("aa"));
+        let reread = Lockfile::parse(&written, "pyforge.lock").unwrap();
+        assert_eq!(reread.migrated_from, None);
+        assert_eq!(reread.requires_python.as_deref(), Some(">=3.9"));
+        assert_eq!(reread.environment, lockfile.environment);
+        assert_eq!(toml::to_string(&reread).unwrap(), written);
+    }
+
+    #[test]
+    fn rejects_unknown_format_versions() {
+        let newer = FORMAT_1.replacen("version = 1", &format!("version = {}", LOCKFILE_VERSION + 1), 1);
+        let error = Lockfile::parse(&newer, "pyforge.lock").unwrap_err().to_string();
+        assert!(error.contains("newer than this pyforge supports"), "{}", error);
+        assert!(Lockfile::parse(&FORMAT_1.replacen("version = 1", "version = 0", 1), "pyforge.lock").is_err());
+        assert!(Lockfile::parse(&FORMAT_1.replacen("version = 1\n", "", 1), "pyforge.lock").is_err());
+    }
+
+    #[test]
+    fn lists_format_changes_since_a_version() {
+        assert_eq!(changes_since_format(1).len(), MIGRATIONS.len());
+        assert!(changes_since_format(LOCKFILE_VERSION).is_empty());
+    }
+}
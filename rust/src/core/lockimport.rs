@@ -0,0 +1,814 @@
+//! Converting the lockfiles of other tools (`poetry.lock`, `uv.lock`,
+//! `Pipfile.lock`, `pylock.toml`) into a pyforge lockfile, keeping their pins instead of
+//! resolving again.
+//!
+//! Those lockfiles cover every platform, while `pyforge.lock` is resolved for
+//! one environment: packages whose markers don't apply to the current
+//! interpreter are left out, along with packages only they require. Entries
+//! that can't be expressed, such as git or path dependencies, are reported
+//! instead of guessed at.
+
+use crate::core::config::{Config, IndexConfig};
+use crate::core::dist::DistFilename;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::index::{IndexClient, IndexFile};
+use crate::core::lockfile::{self, LOCKFILE_VERSION, LockedFile, LockedPackage, Lockfile};
+use crate::core::markers::Environment;
+use crate::core::pep440::Version;
+use crate::core::pep508::normalize_name;
+use crate::core::project::Project;
+use crate::core::pylock::{PYLOCK, Pylock};
+use crate::core::toolchain::Toolchain;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// A lockfile format that can be imported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Poetry,
+    Uv,
+    Pipenv,
+    Pylock,
+}
+
+impl Format {
+    pub const ALL: [Format; 4] = [Format::Poetry, Format::Uv, Format::Pipenv, Format::Pylock];
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Format::Poetry => "poetry.lock",
+            Format::Uv => "uv.lock",
+            Format::Pipenv => "Pipfile.lock",
+            Format::Pylock => PYLOCK,
+        }
+    }
+
+    /// The format of a lockfile, by its file name
+    pub fn detect(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        if name.starts_with("pylock.") && name.ends_with(".toml") {
+            return Some(Format::Pylock);
+        }
+        Self::ALL.into_iter().find(|format| format.file_name() == name)
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.file_name())
+    }
+}
+
+/// An entry of the imported lockfile that has no place in `pyforge.lock`
+#[derive(Debug, Clone)]
+pub struct Skipped {
+    pub name: String,
+    pub version: String,
+    pub reason: String,
+}
+
+#[derive(Debug)]
+pub struct Import {
+    pub lockfile: Lockfile,
+    pub skipped: Vec<Skipped>,
+    /// Project requirements the imported pins don't satisfy
+    pub unsatisfied: Vec<String>,
+}
+
+/// A package of any of the formats, before conversion
+struct Entry {
+    name: String,
+    version: String,
+    origin: Origin,
+    files: Vec<ImportedFile>,
+    /// Names of required packages, with the marker of the requirement
+    dependencies: Vec<(String, Option<String>)>,
+    /// Environments the entry itself is locked for
+    marker: Option<String>,
+}
+
+enum Origin {
+    /// From a package index; the URL when the lockfile records it
+    Index(Option<String>),
+    /// The project being imported itself
+    Project,
+    /// Anything else, described for the report
+    Other(String),
+}
+
+struct ImportedFile {
+    filename: Option<String>,
+    url: Option<String>,
+    hashes: BTreeMap<String, String>,
+}
+
+impl ImportedFile {
+    fn new(filename: Option<String>, url: Option<String>, hashes: impl IntoIterator<Item = String>) -> Self {
+        let hashes = hashes
+            .into_iter()
+            .filter_map(|hash| hash.split_once(':').map(|(algorithm, digest)| (algorithm.to_lowercase(), digest.to_lowercase())))
+            .collect();
+        Self { filename, url, hashes }
+    }
+
+    fn sha256(&self) -> Option<&str> {
+        self.hashes.get("sha256").map(String::as_str)
+    }
+}
+
+/// Read `path` and convert it for the project and current interpreter
+pub fn import(project: &Project, config: &Config, path: &Path, format: Format) -> Result<Import> {
+    let content = fs::read_to_string(path).map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+    let file = path.display().to_string();
+    let environment = Environment::current()?;
+    let entries = match format {
+        Format::Poetry => poetry(&content, &file)?,
+        Format::Uv => uv(&content, &file)?,
+        Format::Pipenv => pipenv(&content, &file)?,
+        Format::Pylock => pylock(&content, &file, &environment)?,
+    };
+
+    let kept = applicable(&entries, &environment);
+    let client = IndexClient::new(config)?;
+    let indexes = config.indexes();
+
+    let mut skipped = Vec::new();
+    let mut packages: BTreeMap<String, LockedPackage> = BTreeMap::new();
+    for entry in kept.into_iter().map(|i| &entries[i]) {
+        let skip = |reason: String| Skipped {
+            name: entry.name.clone(),
+            version: entry.version.clone(),
+            reason,
+        };
+        let index = match &entry.origin {
+            Origin::Project => continue,
+            Origin::Other(description) => {
+                skipped.push(skip(format!("{}; only index packages can be imported", description)));
+                continue;
+            }
+            Origin::Index(index) => index,
+        };
+        if let Some(other) = packages.get(&entry.name) {
+            skipped.push(skip(format!("locked in several versions; kept {}", other.version)));
+            continue;
+        }
+        let found = match index {
+            Some(index) if entry.files.iter().all(|file| file.url.is_some()) => Some((index.clone(), recorded_files(entry))),
+            _ => index_files(&client, &indexes, entry)?,
+        };
+        let Some((index, files)) = found else {
+            skipped.push(skip("none of its files were found on the configured indexes".to_string()));
+            continue;
+        };
+        let files = compatible(entry, files, &environment);
+        if files.is_empty() {
+            skipped.push(skip(format!("no file for {} on {}", environment.python_version, environment.sys_platform)));
+            continue;
+        }
+
+        packages.insert(
+            entry.name.clone(),
+            LockedPackage {
+                name: entry.name.clone(),
+                version: entry.version.clone(),
+                index,
+                path: None,
+                dependencies: Vec::new(),
+                license: None,
+                files,
+            },
+        );
+    }
+
+    // Only point at packages that made it into the lock
+    let names: BTreeSet<String> = packages.keys().cloned().collect();
+    for entry in &entries {
+        if let Some(package) = packages.get_mut(&entry.name)
+            && package.version == entry.version
+        {
+            let dependencies: BTreeSet<String> = entry
+                .dependencies
+                .iter()
+                .filter(|(name, marker)| names.contains(name) && applies(&environment, marker.as_deref()))
+                .map(|(name, _)| name.clone())
+                .collect();
+            package.dependencies = dependencies.into_iter().collect();
+        }
+    }
+
+    let unsatisfied = unsatisfied(project, &packages, &environment)?;
+    let lockfile = Lockfile {
+        version: LOCKFILE_VERSION,
+        requires_python: project.requires_python().map(str::to_string),
+        // An incomplete import must be re-locked by the next command that needs it
+        input_hash: if unsatisfied.is_empty() { lockfile::input_hash(project) } else { String::new() },
+        toolchain: Some(Toolchain::current(&environment)),
+        environment,
+        packages: packages.into_values().collect(),
+        migrated_from: None,
+    };
+    Ok(Import {
+        lockfile,
+        skipped,
+        unsatisfied,
+    })
+}
+
+/// Indexes of the entries needed on `environment`: their own marker applies,
+/// and unless nothing requires them, a needed package requires them here
+fn applicable(entries: &[Entry], environment: &Environment) -> BTreeSet<usize> {
+    let mut kept: BTreeSet<usize> = (0..entries.len())
+        .filter(|&i| applies(environment, entries[i].marker.as_deref()))
+        .collect();
+    let required: BTreeSet<&str> =
+        entries.iter().flat_map(|entry| entry.dependencies.iter().map(|(name, _)| name.as_str())).collect();
+
+    loop {
+        let needed: BTreeSet<&str> = kept
+            .iter()
+            .flat_map(|&i| &entries[i].dependencies)
+            .filter(|(_, marker)| applies(environment, marker.as_deref()))
+            .map(|(name, _)| name.as_str())
+            .collect();
+        let before = kept.len();
+        kept.retain(|&i| !required.contains(entries[i].name.as_str()) || needed.contains(entries[i].name.as_str()));
+        if kept.len() == before {
+            return kept;
+        }
+    }
+}
+
+/// Whether a marker holds on `environment`. Markers on extras are kept, since
+/// which extras the project uses isn't known here.
+fn applies(environment: &Environment, marker: Option<&str>) -> bool {
+    match marker {
+        Some(marker) if !marker.contains("extra") => environment.evaluate(marker, &BTreeSet::new()).unwrap_or(true),
+        _ => true,
+    }
+}
+
+/// Files with their file names, for checking compatibility
+type Files = Vec<(String, LockedFile)>;
+
+fn recorded_files(entry: &Entry) -> Files {
+    entry
+        .files
+        .iter()
+        .filter_map(|file| {
+            let url = file.url.clone()?;
+            let filename = file.filename.clone().or_else(|| url.rsplit('/').next().map(str::to_string))?;
+            let filename = filename.split(['#', '?']).next().unwrap_or_default().to_string();
+            Some((
+                filename.clone(),
+                LockedFile {
+                    name: filename,
+                    url,
+                    hashes: file.hashes.clone(),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Files of the entry on the first configured index that has them, matched
+/// by hash or name; every file of the version when the lockfile lists none
+fn index_files(client: &IndexClient, indexes: &[IndexConfig], entry: &Entry) -> Result<Option<(String, Files)>> {
+    let hashes: BTreeSet<&str> = entry.files.iter().filter_map(ImportedFile::sha256).collect();
+    let names: BTreeSet<&str> = entry.files.iter().filter_map(|file| file.filename.as_deref()).collect();
+    let version = Version::parse(&entry.version).ok();
+
+    for index in indexes {
+        let Some(files) = client.project_files(index, &entry.name)? else {
+            continue;
+        };
+        let matches = |file: &IndexFile| {
+            if entry.files.is_empty() {
+                return DistFilename::parse(&file.filename, &entry.name).is_some_and(|parsed| Some(parsed.version) == version);
+            }
+            file.sha256.as_deref().is_some_and(|sha256| hashes.contains(sha256.to_lowercase().as_str()))
+                || (hashes.is_empty() && names.contains(file.filename.as_str()))
+        };
+        let found: Files = files
+            .into_iter()
+            .filter(|file| matches(file))
+            .map(|file| {
+                let hashes = file.sha256.map(|sha256| BTreeMap::from([("sha256".to_string(), sha256)])).unwrap_or_default();
+                (
+                    file.filename.clone(),
+                    LockedFile {
+                        name: file.filename,
+                        url: file.url,
+                        hashes,
+                    },
+                )
+            })
+            .collect();
+        if !found.is_empty() {
+            return Ok(Some((index.url.clone(), found)));
+        }
+    }
+    Ok(None)
+}
+
+/// Files that install on `environment`, best match first
+fn compatible(entry: &Entry, files: Files, environment: &Environment) -> Vec<LockedFile> {
+    let mut scored: Vec<(u32, LockedFile)> = files
+        .into_iter()
+        .filter_map(|(filename, file)| {
+            let score = DistFilename::parse(&filename, &entry.name)?.compatibility(environment)?;
+            Some((score, file))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, file)| file).collect()
+}
+
+/// Requirements of the project that no imported pin satisfies
+fn unsatisfied(project: &Project, packages: &BTreeMap<String, LockedPackage>, environment: &Environment) -> Result<Vec<String>> {
+    Ok(lockfile::requirements(project)?
+        .into_iter()
+        .filter(|requirement| applies(environment, requirement.marker.as_deref()) && requirement.url.is_none())
+        .filter(|requirement| {
+            let locked = packages.get(&requirement.normalized_name());
+            !locked
+                .and_then(|package| Version::parse(&package.version).ok())
+                .is_some_and(|version| requirement.specifier.contains(&version, true))
+        })
+        .map(|requirement| requirement.to_string())
+        .collect())
+}
+
+fn invalid(file: &str, message: impl fmt::Display) -> PyForgeError {
+    PyForgeError::ParseError {
+        file_type: file.to_string(),
+        message: message.to_string(),
+    }
+}
+
+#[derive(Deserialize)]
+struct PoetryLock {
+    #[serde(default)]
+    package: Vec<PoetryPackage>,
+    #[serde(default)]
+    metadata: PoetryMetadata,
+}
+
+#[derive(Deserialize, Default)]
+struct PoetryMetadata {
+    /// Files by package name, in lock format 1
+    #[serde(default)]
+    files: BTreeMap<String, Vec<PoetryFile>>,
+}
+
+#[derive(Deserialize)]
+struct PoetryPackage {
+    name: String,
+    version: String,
+    #[serde(default)]
+    files: Vec<PoetryFile>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, toml::Value>,
+    source: Option<PoetrySource>,
+}
+
+#[derive(Deserialize)]
+struct PoetryFile {
+    file: String,
+    hash: String,
+}
+
+#[derive(Deserialize)]
+struct PoetrySource {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    url: String,
+}
+
+fn poetry(content: &str, file: &str) -> Result<Vec<Entry>> {
+    let mut lock: PoetryLock = toml::from_str(content).map_err(|e| invalid(file, e))?;
+    Ok(lock
+        .package
+        .into_iter()
+        .map(|package| {
+            let files = if package.files.is_empty() {
+                lock.metadata.files.remove(&package.name).unwrap_or_default()
+            } else {
+                package.files
+            };
+            let origin = match package.source {
+                None => Origin::Index(None),
+                Some(source) if source.kind == "legacy" => Origin::Index(None),
+                Some(source) => Origin::Other(format!("{} source {}", source.kind, source.url)),
+            };
+            Entry {
+                name: normalize_name(&package.name),
+                version: package.version,
+                origin,
+                files: files
+                    .into_iter()
+                    .map(|file| ImportedFile::new(Some(file.file), None, [file.hash]))
+                    .collect(),
+                dependencies: package
+                    .dependencies
+                    .iter()
+                    .map(|(name, constraint)| (normalize_name(name), poetry_marker(constraint)))
+                    .collect(),
+                marker: None,
+            }
+        })
+        .collect())
+}
+
+/// Marker of a Poetry dependency: a table's `markers`, or any of a list's
+fn poetry_marker(constraint: &toml::Value) -> Option<String> {
+    let marker = |value: &toml::Value| value.get("markers").and_then(toml::Value::as_str).map(str::to_string);
+    match constraint {
+        toml::Value::Array(alternatives) => {
+            let markers: Option<Vec<String>> = alternatives.iter().map(marker).collect();
+            markers.map(|markers| markers.iter().map(|m| format!("({})", m)).collect::<Vec<_>>().join(" or "))
+        }
+        other => marker(other),
+    }
+}
+
+#[derive(Deserialize)]
+struct UvLock {
+    #[serde(default)]
+    package: Vec<UvPackage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct UvPackage {
+    name: String,
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    source: BTreeMap<String, String>,
+    #[serde(default)]
+    dependencies: Vec<UvDependency>,
+    #[serde(default)]
+    optional_dependencies: BTreeMap<String, Vec<UvDependency>>,
+    #[serde(default)]
+    dev_dependencies: BTreeMap<String, Vec<UvDependency>>,
+    sdist: Option<UvFile>,
+    #[serde(default)]
+    wheels: Vec<UvFile>,
+    #[serde(default)]
+    resolution_markers: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct UvDependency {
+    name: String,
+    marker: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UvFile {
+    url: Option<String>,
+    hash: Option<String>,
+}
+
+fn uv(content: &str, file: &str) -> Result<Vec<Entry>> {
+    let lock: UvLock = toml::from_str(content).map_err(|e| invalid(file, e))?;
+    Ok(lock
+        .package
+        .into_iter()
+        .map(|package| {
+            let origin = match package.source.iter().next() {
+                Some((kind, _)) if kind == "registry" => Origin::Index(package.source.get("registry").cloned()),
+                Some((kind, path)) if (kind == "editable" || kind == "virtual") && path == "." => Origin::Project,
+                Some((kind, location)) => Origin::Other(format!("{} source {}", kind, location)),
+                None => Origin::Other("no source".to_string()),
+            };
+            let files = package
+                .sdist
+                .into_iter()
+                .chain(package.wheels)
+                .map(|file| ImportedFile::new(None, file.url, file.hash))
+                .collect();
+            let dependencies = package
+                .dependencies
+                .into_iter()
+                .chain(package.optional_dependencies.into_values().flatten())
+                .chain(package.dev_dependencies.into_values().flatten())
+                .map(|dependency| (normalize_name(&dependency.name), dependency.marker))
+                .collect();
+            let marker = (!package.resolution_markers.is_empty()).then(|| {
+                package.resolution_markers.iter().map(|m| format!("({})", m)).collect::<Vec<_>>().join(" or ")
+            });
+            Entry {
+                name: normalize_name(&package.name),
+                version: package.version,
+                origin,
+                files,
+                dependencies,
+                marker,
+            }
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct PipfileLock {
+    #[serde(default)]
+    default: BTreeMap<String, PipfileEntry>,
+    #[serde(default)]
+    develop: BTreeMap<String, PipfileEntry>,
+}
+
+#[derive(Deserialize)]
+struct PipfileEntry {
+    version: Option<String>,
+    #[serde(default)]
+    hashes: Vec<String>,
+    markers: Option<String>,
+    git: Option<String>,
+    path: Option<String>,
+    file: Option<String>,
+}
+
+fn pipenv(content: &str, file: &str) -> Result<Vec<Entry>> {
+    let lock: PipfileLock = serde_json::from_str(content).map_err(|e| invalid(file, e))?;
+    let mut seen = HashSet::new();
+    Ok(lock
+        .default
+        .into_iter()
+        .chain(lock.develop)
+        .filter(|(name, _)| seen.insert(normalize_name(name)))
+        .map(|(name, entry)| {
+            let origin = match (&entry.git, &entry.path, &entry.file) {
+                (Some(git), _, _) => Origin::Other(format!("git source {}", git)),
+                (_, Some(path), _) => Origin::Other(format!("path {}", path)),
+                (_, _, Some(url)) => Origin::Other(format!("file {}", url)),
+                _ => Origin::Index(None),
+            };
+            Entry {
+                name: normalize_name(&name),
+                version: entry.version.as_deref().unwrap_or_default().trim_start_matches("==").to_string(),
+                origin,
+                // Pipfile.lock lists hashes only; files are found by hash on the index
+                files: entry.hashes.into_iter().map(|hash| ImportedFile::new(None, None, [hash])).collect(),
+                dependencies: Vec::new(),
+                marker: entry.markers,
+            }
+        })
+        .collect())
+}
+
+fn pylock(content: &str, file: &str, environment: &Environment) -> Result<Vec<Entry>> {
+    let lock = Pylock::parse(content, file)?;
+    if !lock.environments.is_empty() && !lock.environments.iter().any(|marker| applies(environment, Some(marker))) {
+        return Err(PyForgeError::Usage {
+            message: format!(
+                "{} is locked for {}, not Python {} on {}",
+                file,
+                lock.environments.join(" or "),
+                environment.python_version,
+                environment.sys_platform
+            ),
+        });
+    }
+
+    Ok(lock
+        .packages
+        .into_iter()
+        .map(|package| {
+            let origin = match (&package.directory, &package.vcs, &package.archive) {
+                (Some(directory), _, _) => Origin::Other(format!("directory {}", directory.path)),
+                (_, Some(_), _) => Origin::Other("version control source".to_string()),
+                (_, _, Some(_)) => Origin::Other("archive".to_string()),
+                _ => Origin::Index(package.index),
+            };
+            let files = package
+                .sdist
+                .into_iter()
+                .chain(package.wheels)
+                .map(|file| ImportedFile {
+                    filename: file.name,
+                    url: file.url,
+                    hashes: file.hashes.into_iter().map(|(algorithm, digest)| (algorithm.to_lowercase(), digest.to_lowercase())).collect(),
+                })
+                .collect();
+            Entry {
+                name: normalize_name(&package.name),
+                version: package.version.unwrap_or_default(),
+                origin,
+                files,
+                dependencies: package.dependencies.into_iter().map(|d| (normalize_name(&d.name), None)).collect(),
+                marker: package.marker,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linux() -> Environment {
+        Environment {
+            python_version: "3.12".to_string(),
+            python_full_version: "3.12.4".to_string(),
+            implementation_name: "cpython".to_string(),
+            platform_python_implementation: "CPython".to_string(),
+            os_name: "posix".to_string(),
+            sys_platform: "linux".to_string(),
+            platform_system: "Linux".to_string(),
+            platform_machine: "x86_64".to_string(),
+        }
+    }
+
+    fn names(entries: &[Entry], kept: &BTreeSet<usize>) -> Vec<String> {
+        kept.iter().map(|&i| entries[i].name.clone()).collect()
+    }
+
+    fn describe(origin: &Origin) -> String {
+        match origin {
+            Origin::Index(url) => format!("index {}", url.as_deref().unwrap_or("-")),
+            Origin::Project => "project".to_string(),
+            Origin::Other(description) => description.clone(),
+        }
+    }
+
+    #[test]
+    fn detects_formats_by_file_name() {
+        assert_eq!(Format::detect(Path::new("sub/poetry.lock")), Some(Format::Poetry));
+        assert_eq!(Format::detect(Path::new("uv.lock")), Some(Format::Uv));
+        assert_eq!(Format::detect(Path::new("Pipfile.lock")), Some(Format::Pipenv));
+        assert_eq!(Format::detect(Path::new("pylock.toml")), Some(Format::Pylock));
+        assert_eq!(Format::detect(Path::new("pylock.dev.toml")), Some(Format::Pylock));
+        assert_eq!(Format::detect(Path::new("requirements.txt")), None);
+    }
+
+    #[test]
+    fn reads_poetry_files_from_either_lock_format() {
+        let content = r#"
+[[package]]
+name = "Requests"
+version = "2.32.3"
+files = [{ file = "requests-2.32.3-py3-none-any.whl", hash = "sha256:ABC" }]
+
+[package.dependencies]
+idna = ">=2.5"
+colorama = { version = "*", markers = "sys_platform == \"win32\"" }
+
+[[package]]
+name = "idna"
+version = "3.7"
+
+[[package]]
+name = "mylib"
+version = "0.1.0"
+
+[package.source]
+type = "git"
+url = "https://example.com/mylib.git"
+
+[metadata.files]
+idna = [{ file = "idna-3.7.tar.gz", hash = "sha256:def" }]
+"#;
+        let entries = poetry(content, "poetry.lock").unwrap();
+        let [requests, idna, mylib] = entries.as_slice() else {
+            panic!("expected three entries");
+        };
+        assert_eq!(requests.name, "requests");
+        assert_eq!(requests.files[0].sha256(), Some("abc"));
+        assert_eq!(
+            requests.dependencies,
+            [("colorama".to_string(), Some("sys_platform == \"win32\"".to_string())), ("idna".to_string(), None)]
+        );
+        assert_eq!(idna.files[0].filename.as_deref(), Some("idna-3.7.tar.gz"));
+        assert_eq!(describe(&idna.origin), "index -");
+        assert_eq!(describe(&mylib.origin), "git source https://example.com/mylib.git");
+    }
+
+    #[test]
+    fn joins_poetry_markers_of_alternatives() {
+        let constraint: toml::Value = toml::from_str::<toml::Table>(
+            r#"c = [{ version = "1", markers = "python_version < \"3.9\"" }, { version = "2", markers = "python_version >= \"3.9\"" }]"#,
+        )
+        .unwrap()["c"]
+            .clone();
+        assert_eq!(
+            poetry_marker(&constraint).as_deref(),
+            Some("(python_version < \"3.9\") or (python_version >= \"3.9\")")
+        );
+    }
+
+    #[test]
+    fn reads_uv_sources_and_markers() {
+        let content = r#"
+version = 1
+
+[[package]]
+name = "app"
+version = "0.1.0"
+source = { editable = "." }
+dependencies = [{ name = "httpx" }]
+
+[[package]]
+name = "httpx"
+version = "0.27.0"
+source = { registry = "https://pypi.org/simple" }
+sdist = { url = "https://files.example/httpx-0.27.0.tar.gz", hash = "sha256:aa" }
+wheels = [{ url = "https://files.example/httpx-0.27.0-py3-none-any.whl", hash = "sha256:bb" }]
+resolution-markers = ["python_version >= '3.10'"]
+
+[package.optional-dependencies]
+http2 = [{ name = "h2", marker = "extra == 'http2'" }]
+
+[[package]]
+name = "tool"
+version = "1.0"
+source = { git = "https://example.com/tool.git" }
+"#;
+        let entries = uv(content, "uv.lock").unwrap();
+        let [app, httpx, tool] = entries.as_slice() else {
+            panic!("expected three entries");
+        };
+        assert_eq!(describe(&app.origin), "project");
+        assert_eq!(describe(&httpx.origin), "index https://pypi.org/simple");
+        assert_eq!(httpx.files.len(), 2);
+        assert_eq!(httpx.marker.as_deref(), Some("(python_version >= '3.10')"));
+        assert_eq!(httpx.dependencies, [("h2".to_string(), Some("extra == 'http2'".to_string()))]);
+        assert_eq!(describe(&tool.origin), "git source https://example.com/tool.git");
+    }
+
+    #[test]
+    fn reads_pipfile_lock_once_per_package() {
+        let content = r#"{
+            "_meta": {},
+            "default": {
+                "requests": { "version": "==2.32.3", "hashes": ["sha256:AA"] },
+                "mylib": { "git": "https://example.com/mylib.git", "ref": "abc" }
+            },
+            "develop": {
+                "Requests": { "version": "==2.31.0" },
+                "pytest": { "version": "==8.2.0", "markers": "python_version >= '3.8'" }
+            }
+        }"#;
+        let entries = pipenv(content, "Pipfile.lock").unwrap();
+        let summary: Vec<(String, String)> = entries.iter().map(|entry| (entry.name.clone(), entry.version.clone())).collect();
+        assert_eq!(
+            summary,
+            [
+                ("mylib".to_string(), String::new()),
+                ("requests".to_string(), "2.32.3".to_string()),
+                ("pytest".to_string(), "8.2.0".to_string()),
+            ]
+        );
+        assert_eq!(describe(&entries[0].origin), "git source https://example.com/mylib.git");
+        assert_eq!(entries[1].files[0].sha256(), Some("aa"));
+        assert_eq!(entries[2].marker.as_deref(), Some("python_version >= '3.8'"));
+    }
+
+    #[test]
+    fn leaves_out_packages_only_other_platforms_need() {
+        let entry = |name: &str, dependencies: &[(&str, Option<&str>)], marker: Option<&str>| Entry {
+            name: name.to_string(),
+            version: "1.0".to_string(),
+            origin: Origin::Index(None),
+            files: Vec::new(),
+            dependencies: dependencies.iter().map(|(name, marker)| (name.to_string(), marker.map(str::to_string))).collect(),
+            marker: marker.map(str::to_string),
+        };
+        let entries = [
+            entry("app-dep", &[("colorama", Some("sys_platform == 'win32'")), ("idna", None)], None),
+            entry("colorama", &[("win-only", None)], None),
+            entry("win-only", &[], None),
+            entry("idna", &[], None),
+            entry("old-backport", &[], Some("python_version < '3.8'")),
+            entry("optional", &[], Some("extra == 'socks'")),
+        ];
+        let kept = applicable(&entries, &linux());
+        assert_eq!(names(&entries, &kept), ["app-dep", "idna", "optional"]);
+    }
+
+    #[test]
+    fn names_recorded_files_from_their_urls() {
+        let entry = Entry {
+            name: "idna".to_string(),
+            version: "3.7".to_string(),
+            origin: Origin::Index(Some("https://pypi.org/simple".to_string())),
+            files: vec![
+                ImportedFile::new(None, Some("https://files.example/idna-3.7-py3-none-any.whl#sha256=aa".to_string()), ["sha256:aa".to_string()]),
+                ImportedFile::new(None, None, ["sha256:bb".to_string()]),
+            ],
+            dependencies: Vec::new(),
+            marker: None,
+        };
+        let files = recorded_files(&entry);
+        let [(filename, file)] = files.as_slice() else {
+            panic!("expected one file, got {}", files.len());
+        };
+        assert_eq!(filename, "idna-3.7-py3-none-any.whl");
+        assert_eq!(file.sha256(), Some("aa"));
+        assert_eq!(compatible(&entry, files, &linux()).len(), 1);
+    }
+}
@@ -0,0 +1,229 @@
+//! Merging the two sides of a lockfile that git could not merge.
+//!
+//! Packages both sides agree on, or that only one side changed relative to
+//! the merge base, are kept as they are. The rest are conflicts, left for the
+//! resolver to pick again.
+
+use crate::core::lockfile::{LockedPackage, Lockfile};
+use std::collections::BTreeSet;
+
+/// The versions of a file involved in a merge
+#[derive(Debug, Clone)]
+pub struct Sides {
+    /// Common ancestor, when known (`git show :1:` or diff3-style markers)
+    pub base: Option<String>,
+    pub ours: String,
+    pub theirs: String,
+}
+
+/// A package the two sides changed differently
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub name: String,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Merge {
+    /// Packages taken from one side without conflict
+    pub agreed: Vec<LockedPackage>,
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Split `content` containing git conflict markers into its sides, or `None` without markers
+pub fn split_conflicts(content: &str) -> Option<Sides> {
+    enum Section {
+        Common,
+        Ours,
+        Base,
+        Theirs,
+    }
+
+    let (mut ours, mut base, mut theirs) = (String::new(), String::new(), String::new());
+    let mut section = Section::Common;
+    let (mut found, mut has_base) = (false, false);
+
+    for line in content.split_inclusive('\n') {
+        if line.starts_with("<<<<<<<") {
+            section = Section::Ours;
+            found = true;
+        } else if line.starts_with("|||||||") && matches!(section, Section::Ours) {
+            section = Section::Base;
+            has_base = true;
+        } else if line.starts_with("=======") && matches!(section, Section::Ours | Section::Base) {
+            section = Section::Theirs;
+        } else if line.starts_with(">>>>>>>") && matches!(section, Section::Theirs) {
+            section = Section::Common;
+        } else {
+            match section {
+                Section::Common => {
+                    ours.push_str(line);
+                    base.push_str(line);
+                    theirs.push_str(line);
+                }
+                Section::Ours => ours.push_str(line),
+                Section::Base => base.push_str(line),
+                Section::Theirs => theirs.push_str(line),
+            }
+        }
+    }
+
+    found.then(|| Sides {
+        base: has_base.then_some(base),
+        ours,
+        theirs,
+    })
+}
+
+/// Merge the package lists of `ours` and `theirs`, three-way when `base` is known
+pub fn merge(base: Option<&Lockfile>, ours: &Lockfile, theirs: &Lockfile) -> Merge {
+    let version = |lockfile: &Lockfile, name: &str| lockfile.package(name).map(|p| p.version.clone());
+    let names: BTreeSet<&str> = ours
+        .packages
+        .iter()
+        .chain(&theirs.packages)
+        .map(|package| package.name.as_str())
+        .collect();
+
+    let mut merge = Merge::default();
+    for name in names {
+        let (mine, other) = (version(ours, name), version(theirs, name));
+        let original = base.map(|base| version(base, name));
+
+        let take_ours = mine == other || original.as_ref().is_some_and(|original| *original == other);
+        let take_theirs = original.as_ref().is_some_and(|original| *original == mine);
+        let chosen = if take_ours {
+            ours.package(name)
+        } else if take_theirs {
+            theirs.package(name)
+        } else {
+            merge.conflicts.push(Conflict {
+                name: name.to_string(),
+                ours: mine,
+                theirs: other,
+            });
+            continue;
+        };
+        // `None` here means the side that changed removed the package
+        merge.agreed.extend(chosen.cloned());
+    }
+    merge
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENVIRONMENT: &str = r#"
+[environment]
+python-version = "3.12"
+python-full-version = "3.12.4"
+implementation-name = "cpython"
+platform-python-implementation = "CPython"
+os-name = "posix"
+sys-platform = "linux"
+platform-system = "Linux"
+platform-machine = "x86_64"
+"#;
+
+    fn lockfile(packages: &[(&str, &str)]) -> String {
+        let mut content = format!("version = 2\ninput-hash = \"abc\"\n{}", ENVIRONMENT);
+        for (name, version) in packages {
+            content.push_str(&format!("\n[[package]]\nname = \"{}\"\nversion = \"{}\"\n", name, version));
+        }
+        content
+    }
+
+    fn parse(content: &str) -> Lockfile {
+        Lockfile::parse(content, "pyforge.lock").unwrap()
+    }
+
+    fn agreed(merge: &Merge) -> Vec<(&str, &str)> {
+        merge.agreed.iter().map(|package| (package.name.as_str(), package.version.as_str())).collect()
+    }
+
+    #[test]
+    fn splits_two_way_markers() {
+        let content = "a\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> feature\nb\n";
+        let sides = split_conflicts(content).unwrap();
+        assert_eq!(sides.ours, "a\nours\nb\n");
+        assert_eq!(sides.theirs, "a\ntheirs\nb\n");
+        assert!(sides.base.is_none());
+    }
+
+    #[test]
+    fn splits_diff3_markers() {
+        let content = "a\n<<<<<<< HEAD\nours\n||||||| merged common ancestors\nbase\n=======\ntheirs\n>>>>>>> feature\nb\n";
+        let sides = split_conflicts(content).unwrap();
+        assert_eq!(sides.ours, "a\nours\nb\n");
+        assert_eq!(sides.base.as_deref(), Some("a\nbase\nb\n"));
+        assert_eq!(sides.theirs, "a\ntheirs\nb\n");
+    }
+
+    #[test]
+    fn ignores_content_without_markers() {
+        assert!(split_conflicts(&lockfile(&[("idna", "3.7")])).is_none());
+    }
+
+    #[test]
+    fn keeps_changes_made_on_one_side() {
+        let base = parse(&lockfile(&[("certifi", "2024.2.2"), ("idna", "3.6")]));
+        let ours = parse(&lockfile(&[("certifi", "2024.7.4"), ("idna", "3.6")]));
+        let theirs = parse(&lockfile(&[("certifi", "2024.2.2"), ("idna", "3.7")]));
+        let merge = merge(Some(&base), &ours, &theirs);
+        assert!(merge.conflicts.is_empty());
+        assert_eq!(agreed(&merge), [("certifi", "2024.7.4"), ("idna", "3.7")]);
+    }
+
+    #[test]
+    fn drops_a_package_one_side_removed() {
+        let base = parse(&lockfile(&[("idna", "3.6"), ("six", "1.16.0")]));
+        let ours = parse(&lockfile(&[("idna", "3.6"), ("six", "1.16.0")]));
+        let theirs = parse(&lockfile(&[("idna", "3.6")]));
+        let merge = merge(Some(&base), &ours, &theirs);
+        assert!(merge.conflicts.is_empty());
+        assert_eq!(agreed(&merge), [("idna", "3.6")]);
+
+        let merge = super::merge(Some(&base), &theirs, &ours);
+        assert!(merge.conflicts.is_empty());
+        assert_eq!(agreed(&merge), [("idna", "3.6")]);
+    }
+
+    #[test]
+    fn conflicts_when_one_side_removes_what_the_other_changed() {
+        let base = parse(&lockfile(&[("six", "1.16.0")]));
+        let ours = parse(&lockfile(&[]));
+        let theirs = parse(&lockfile(&[("six", "1.17.0")]));
+        let merge = merge(Some(&base), &ours, &theirs);
+        assert!(merge.agreed.is_empty());
+        let [conflict] = merge.conflicts.as_slice() else {
+            panic!("expected one conflict, got {:?}", merge.conflicts);
+        };
+        assert_eq!(conflict.name, "six");
+        assert_eq!(conflict.ours, None);
+        assert_eq!(conflict.theirs.as_deref(), Some("1.17.0"));
+    }
+
+    #[test]
+    fn without_a_base_only_identical_versions_agree() {
+        let ours = parse(&lockfile(&[("idna", "3.7"), ("six", "1.16.0")]));
+        let theirs = parse(&lockfile(&[("idna", "3.7"), ("six", "1.17.0")]));
+        let merge = merge(None, &ours, &theirs);
+        assert_eq!(agreed(&merge), [("idna", "3.7")]);
+        assert_eq!(merge.conflicts.iter().map(|conflict| conflict.name.as_str()).collect::<Vec<_>>(), ["six"]);
+    }
+
+    #[test]
+    fn merges_a_conflicted_lockfile() {
+        let content = format!(
+            "{}\n[[package]]\nname = \"idna\"\n<<<<<<< HEAD\nversion = \"3.7\"\n||||||| base\nversion = \"3.6\"\n=======\nversion = \"3.6\"\n>>>>>>> feature\n",
+            lockfile(&[])
+        );
+        let sides = split_conflicts(&content).unwrap();
+        let base = parse(sides.base.as_deref().unwrap());
+        let merge = merge(Some(&base), &parse(&sides.ours), &parse(&sides.theirs));
+        assert!(merge.conflicts.is_empty());
+        assert_eq!(agreed(&merge), [("idna", "3.7")]);
+    }
+}
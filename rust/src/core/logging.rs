@@ -0,0 +1,130 @@
+use crate::core::bundle::redact_text;
+use crate::core::dirs::state_dir;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::utils::timestamp;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde_json::json;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Size at which the recent-activity log is rotated
+const MAX_RECENT_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Directory holding PyForge's own logs
+pub fn log_dir() -> PathBuf {
+    state_dir().join("logs")
+}
+
+/// Rolling log of recent activity, always written so support bundles have context
+pub fn recent_log_path() -> PathBuf {
+    log_dir().join("pyforge.log")
+}
+
+/// Where the most recent failure is recorded
+pub fn last_error_path() -> PathBuf {
+    state_dir().join("last-error.json")
+}
+
+/// Logger writing one JSON object per record to every sink, with credentials
+/// in URLs redacted
+struct JsonLogger {
+    sinks: Mutex<Vec<File>>,
+    verbose: bool,
+}
+
+impl Log for JsonLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Debug
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        if self.verbose {
+            eprintln!("[{}] {}", record.level(), record.args());
+        }
+
+        let line = json!({
+            "ts": timestamp(),
+            "level": record.level().as_str(),
+            "target": record.target(),
+            "message": redact_text(&record.args().to_string()),
+        });
+
+        if let Ok(mut sinks) = self.sinks.lock() {
+            for sink in sinks.iter_mut() {
+                let _ = writeln!(sink, "{}", line);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut sinks) = self.sinks.lock() {
+            for sink in sinks.iter_mut() {
+                let _ = sink.flush();
+            }
+        }
+    }
+}
+
+/// Install the global logger.
+///
+/// Records always go to the rolling log in the state directory (best effort), and
+/// additionally to `log_file` when `--log-file` is given.
+pub fn init(log_file: Option<&Path>, verbose: bool) -> Result<()> {
+    let mut sinks = Vec::new();
+
+    if let Ok(file) = open_recent_log() {
+        sinks.push(file);
+    }
+
+    if let Some(path) = log_file {
+        let file = open_append(path)
+            .map_err(|e| PyForgeError::file_error(format!("Could not open log file {}", path.display()), e))?;
+        sinks.push(file);
+    }
+
+    let logger = JsonLogger {
+        sinks: Mutex::new(sinks),
+        verbose,
+    };
+
+    log::set_boxed_logger(Box::new(logger))
+        .map(|()| log::set_max_level(LevelFilter::Debug))
+        .map_err(|e| PyForgeError::internal(format!("Could not initialize logging: {}", e)))
+}
+
+/// Persist the details of a failed invocation for `pyforge debug bundle`
+pub fn record_last_error(error: &PyForgeError) {
+    let report = json!({
+        "timestamp": timestamp(),
+        "command": std::env::args().map(|arg| redact_text(&arg)).collect::<Vec<_>>(),
+        "error": error.to_json(),
+    });
+
+    log::error!("{}", error);
+    let path = last_error_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _ = fs::write(path, serde_json::to_string_pretty(&report).unwrap_or_default());
+}
+
+fn open_recent_log() -> std::io::Result<File> {
+    let path = recent_log_path();
+    if fs::metadata(&path).map(|m| m.len() > MAX_RECENT_LOG_BYTES).unwrap_or(false) {
+        fs::rename(&path, path.with_extension("log.1"))?;
+    }
+    open_append(&path)
+}
+
+fn open_append(path: &Path) -> std::io::Result<File> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new().create(true).append(true).open(path)
+}
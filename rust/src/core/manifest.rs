@@ -0,0 +1,194 @@
+//! Editing dependency declarations in `pyproject.toml`.
+
+use crate::core::error::{PyForgeError, Result};
+use crate::core::pep508::{Requirement, normalize_name};
+use toml_edit::{Array, DocumentMut, Item, Table, Value};
+
+/// Add `requirement` to `[project].dependencies`, or to the optional group `group`.
+///
+/// An existing entry for the same package is replaced in place. Returns the
+/// entry it replaced, if any. Fails when the list, or a table holding it, has
+/// another type.
+pub fn add_dependency(document: &mut DocumentMut, requirement: &Requirement, group: Option<&str>) -> Result<Option<String>> {
+    let array = dependency_array(document, group)?;
+    let name = requirement.normalized_name();
+    let existing = array.iter().position(|entry| {
+        entry
+            .as_str()
+            .and_then(|entry| Requirement::parse(entry).ok())
+            .is_some_and(|entry| entry.normalized_name() == name)
+    });
+
+    let entry = requirement.to_string();
+    match existing {
+        Some(position) => {
+            let previous = array.get(position).and_then(Value::as_str).map(str::to_string);
+            array.replace(position, entry);
+            Ok(previous)
+        }
+        None => {
+            array.push(entry);
+            if array.len() > 1 {
+                format_multiline(array);
+            }
+            Ok(None)
+        }
+    }
+}
+
+/// Remove the entry for package `name` from `[project].dependencies`, or from
+/// the optional group `group`, dropping the group when it becomes empty.
+/// Returns the removed entry, if there was one.
+pub fn remove_dependency(document: &mut DocumentMut, name: &str, group: Option<&str>) -> Result<Option<String>> {
+    let Some(project) = document.get_mut("project") else {
+        return Ok(None);
+    };
+    let project = project.as_table_like_mut().ok_or_else(|| malformed("project", "table"))?;
+    let item = match group {
+        Some(group) => match project.get_mut("optional-dependencies") {
+            Some(groups) => groups
+                .as_table_like_mut()
+                .ok_or_else(|| malformed("project.optional-dependencies", "table"))?
+                .get_mut(group)
+                .map(|item| (item, format!("project.optional-dependencies.{}", group))),
+            None => None,
+        },
+        None => project.get_mut("dependencies").map(|item| (item, "project.dependencies".to_string())),
+    };
+    let Some((item, key)) = item else {
+        return Ok(None);
+    };
+    let array = item.as_array_mut().ok_or_else(|| malformed(&key, "list"))?;
+    let name = normalize_name(name);
+    let Some(position) = array.iter().position(|entry| {
+        entry
+            .as_str()
+            .and_then(|entry| Requirement::parse(entry).ok())
+            .is_some_and(|entry| entry.normalized_name() == name)
+    }) else {
+        return Ok(None);
+    };
+    let removed = array.remove(position).as_str().map(str::to_string);
+    if array.is_empty() {
+        match group {
+            Some(group) => {
+                if let Some(groups) = project.get_mut("optional-dependencies").and_then(Item::as_table_like_mut) {
+                    groups.remove(group);
+                    if groups.is_empty() {
+                        project.remove("optional-dependencies");
+                    }
+                }
+            }
+            None => array.set_trailing(""),
+        }
+    }
+    Ok(removed)
+}
+
+/// The lists declaring package `name`: `None` for `dependencies`, else the optional group
+pub fn declared_in(document: &DocumentMut, name: &str) -> Vec<Option<String>> {
+    let name = normalize_name(name);
+    let declares = |array: &Array| {
+        array
+            .iter()
+            .filter_map(Value::as_str)
+            .filter_map(|entry| Requirement::parse(entry).ok())
+            .any(|entry| entry.normalized_name() == name)
+    };
+    let Some(project) = document.get("project").and_then(Item::as_table_like) else {
+        return Vec::new();
+    };
+    let mut found = Vec::new();
+    if project.get("dependencies").and_then(Item::as_array).is_some_and(declares) {
+        found.push(None);
+    }
+    if let Some(groups) = project.get("optional-dependencies").and_then(Item::as_table_like) {
+        for (group, item) in groups.iter() {
+            if item.as_array().is_some_and(declares) {
+                found.push(Some(group.to_string()));
+            }
+        }
+    }
+    found
+}
+
+/// Replace every dependency (including optional groups) for which `rewrite`
+/// returns a new requirement, returning the new entries
+pub fn rewrite_dependencies(
+    document: &mut DocumentMut,
+    rewrite: impl Fn(&Requirement) -> Option<Requirement>,
+) -> Vec<String> {
+    let Some(project) = document.get_mut("project").and_then(Item::as_table_like_mut) else {
+        return Vec::new();
+    };
+    let mut arrays: Vec<&mut Array> = Vec::new();
+    for (key, item) in project.iter_mut() {
+        match key.get() {
+            "dependencies" => arrays.extend(item.as_array_mut()),
+            "optional-dependencies" => {
+                if let Some(groups) = item.as_table_like_mut() {
+                    arrays.extend(groups.iter_mut().filter_map(|(_, group)| group.as_array_mut()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut rewritten = Vec::new();
+    for array in arrays {
+        for position in 0..array.len() {
+            let replacement = array
+                .get(position)
+                .and_then(Value::as_str)
+                .and_then(|entry| Requirement::parse(entry).ok())
+                .and_then(|requirement| rewrite(&requirement));
+            if let Some(replacement) = replacement {
+                let entry = replacement.to_string();
+                array.replace(position, entry.as_str());
+                rewritten.push(entry);
+            }
+        }
+    }
+    rewritten
+}
+
+/// The list `add_dependency` edits, created when missing
+fn dependency_array<'a>(document: &'a mut DocumentMut, group: Option<&str>) -> Result<&'a mut Array> {
+    let project = document
+        .entry("project")
+        .or_insert(Item::Table(Table::new()))
+        .as_table_like_mut()
+        .ok_or_else(|| malformed("project", "table"))?;
+    let (entry, key) = match group {
+        Some(group) => {
+            let groups = project
+                .entry("optional-dependencies")
+                .or_insert(Item::Table(Table::new()))
+                .as_table_like_mut()
+                .ok_or_else(|| malformed("project.optional-dependencies", "table"))?;
+            (groups.entry(group), format!("project.optional-dependencies.{}", group))
+        }
+        None => (project.entry("dependencies"), "project.dependencies".to_string()),
+    };
+    entry
+        .or_insert(Item::Value(Value::Array(Array::new())))
+        .as_array_mut()
+        .ok_or_else(|| malformed(&key, "list"))
+}
+
+fn malformed(key: &str, expected: &str) -> PyForgeError {
+    PyForgeError::ParseError {
+        file_type: "pyproject.toml".to_string(),
+        message: format!("`{}` must be a {}; fix it before pyforge edits the dependencies", key, expected),
+    }
+}
+
+/// One entry per line with a trailing comma, the way most projects write dependency lists
+pub fn format_multiline(array: &mut Array) {
+    for entry in array.iter_mut() {
+        entry.decor_mut().set_prefix("\n    ");
+        entry.decor_mut().set_suffix("");
+    }
+    array.set_trailing("\n");
+    array.set_trailing_comma(true);
+}
@@ -0,0 +1,299 @@
+//! PEP 508 environment markers, e.g. `python_version >= "3.9" and sys_platform != "win32"`.
+
+use crate::core::error::{PyForgeError, Result};
+use crate::core::pep440::{SpecifierSet, Version};
+use crate::core::timings;
+use crate::core::toolchain;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Marker variables describing the Python environment dependencies are resolved for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Environment {
+    pub python_version: String,
+    pub python_full_version: String,
+    pub implementation_name: String,
+    pub platform_python_implementation: String,
+    pub os_name: String,
+    pub sys_platform: String,
+    pub platform_system: String,
+    pub platform_machine: String,
+}
+
+const PROBE_SCRIPT: &str = r#"import json, os, platform, sys
+print(json.dumps({
+    "python-version": ".".join(platform.python_version_tuple()[:2]),
+    "python-full-version": platform.python_version(),
+    "implementation-name": sys.implementation.name,
+    "platform-python-implementation": platform.python_implementation(),
+    "os-name": os.name,
+    "sys-platform": sys.platform,
+    "platform-system": platform.system(),
+    "platform-machine": platform.machine(),
+}))"#;
+
+impl Environment {
+    /// Query the marker values of a Python interpreter
+    pub fn from_interpreter(python: &str) -> Result<Self> {
+        let _span = timings::span("inspect python");
+        let mut command = Command::new(python);
+        command.args(["-c", PROBE_SCRIPT]);
+        let _subprocess = timings::subprocess(&command);
+        let output = command
+            .output()
+            .map_err(|_| PyForgeError::CommandNotFound { command: python.to_string() })?;
+        if !output.status.success() {
+            return Err(PyForgeError::command_failed(python, output.status.code().unwrap_or(-1)));
+        }
+        serde_json::from_slice(&output.stdout).map_err(|e| PyForgeError::ParseError {
+            file_type: "interpreter environment".to_string(),
+            message: e.to_string(),
+        })
+    }
+
+    /// Marker values of the configured interpreter, probed once per run
+    pub fn current() -> Result<Self> {
+        static CURRENT: OnceLock<Environment> = OnceLock::new();
+        if let Some(environment) = CURRENT.get() {
+            return Ok(environment.clone());
+        }
+        let python = toolchain::python()?;
+        let environment = Self::from_interpreter(&python.to_string_lossy())?;
+        Ok(CURRENT.get_or_init(|| environment).clone())
+    }
+
+    fn get(&self, variable: &str) -> Option<&str> {
+        Some(match variable {
+            "python_version" => &self.python_version,
+            "python_full_version" => &self.python_full_version,
+            "implementation_name" => &self.implementation_name,
+            "platform_python_implementation" => &self.platform_python_implementation,
+            "os_name" => &self.os_name,
+            "sys_platform" => &self.sys_platform,
+            "platform_system" => &self.platform_system,
+            "platform_machine" => &self.platform_machine,
+            // Rarely used and not worth tracking; compare as empty
+            "implementation_version" => &self.python_full_version,
+            "platform_release" | "platform_version" => "",
+            _ => return None,
+        })
+    }
+
+    /// Evaluate a marker expression with the given extras active
+    pub fn evaluate(&self, marker: &str, extras: &BTreeSet<String>) -> Result<bool> {
+        let tokens = tokenize(marker)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            position: 0,
+            environment: self,
+            extras,
+            marker,
+        };
+        let value = parser.or()?;
+        if parser.position != tokens.len() {
+            return Err(invalid_marker(marker, "unexpected trailing input"));
+        }
+        Ok(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Variable(String),
+    Literal(String),
+    Operator(String),
+    And,
+    Or,
+    Open,
+    Close,
+}
+
+fn tokenize(marker: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = marker.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '(' || c == ')' {
+            tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            i += 1;
+        } else if c == '"' || c == '\'' {
+            let end = chars[i + 1..]
+                .iter()
+                .position(|&q| q == c)
+                .ok_or_else(|| invalid_marker(marker, "unterminated string"))?;
+            tokens.push(Token::Literal(chars[i + 1..i + 1 + end].iter().collect()));
+            i += end + 2;
+        } else if "<>=!~".contains(c) {
+            let start = i;
+            while i < chars.len() && "<>=!~".contains(chars[i]) {
+                i += 1;
+            }
+            tokens.push(Token::Operator(chars[start..i].iter().collect()));
+        } else if c.is_alphanumeric() || c == '_' || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            tokens.push(match word.as_str() {
+                "and" => Token::And,
+                "or" => Token::Or,
+                "in" => Token::Operator("in".to_string()),
+                "not" => Token::Operator("not".to_string()),
+                _ => Token::Variable(word),
+            });
+        } else {
+            return Err(invalid_marker(marker, &format!("unexpected character '{}'", c)));
+        }
+    }
+
+    // Merge `not in` into a single operator
+    let mut merged: Vec<Token> = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        if token == Token::Operator("in".to_string()) && merged.last() == Some(&Token::Operator("not".to_string())) {
+            merged.pop();
+            merged.push(Token::Operator("not in".to_string()));
+        } else {
+            merged.push(token);
+        }
+    }
+    Ok(merged)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    environment: &'a Environment,
+    extras: &'a BTreeSet<String>,
+    marker: &'a str,
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn or(&mut self) -> Result<bool> {
+        let mut value = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            let rhs = self.and()?;
+            value = value || rhs;
+        }
+        Ok(value)
+    }
+
+    fn and(&mut self) -> Result<bool> {
+        let mut value = self.atom()?;
+        while self.peek() == Some(&Token::And) {
+            self.position += 1;
+            let rhs = self.atom()?;
+            value = value && rhs;
+        }
+        Ok(value)
+    }
+
+    fn atom(&mut self) -> Result<bool> {
+        if self.peek() == Some(&Token::Open) {
+            self.position += 1;
+            let value = self.or()?;
+            if self.next() != Some(&Token::Close) {
+                return Err(invalid_marker(self.marker, "expected ')'"));
+            }
+            return Ok(value);
+        }
+
+        let lhs = self.next().cloned();
+        let operator = match self.next() {
+            Some(Token::Operator(op)) => op.clone(),
+            _ => return Err(invalid_marker(self.marker, "expected a comparison operator")),
+        };
+        let rhs = self.next().cloned();
+
+        match (lhs, rhs) {
+            (Some(Token::Variable(var)), Some(Token::Literal(value))) if var == "extra" => {
+                self.compare_extra(&operator, &value)
+            }
+            (Some(Token::Literal(value)), Some(Token::Variable(var))) if var == "extra" => {
+                self.compare_extra(&operator, &value)
+            }
+            (Some(Token::Variable(var)), Some(Token::Literal(value))) => {
+                let actual = self.variable(&var)?;
+                Ok(compare(&var, actual, &operator, &value))
+            }
+            (Some(Token::Literal(value)), Some(Token::Variable(var))) => {
+                // `"3.9" in python_version` style: swap to keep the variable on the left for version ops
+                let actual = self.variable(&var)?.to_string();
+                Ok(match operator.as_str() {
+                    "in" => actual.contains(&value),
+                    "not in" => !actual.contains(&value),
+                    _ => compare(&var, &value, &operator, &actual),
+                })
+            }
+            _ => Err(invalid_marker(self.marker, "expected a variable and a quoted value")),
+        }
+    }
+
+    fn variable(&self, name: &str) -> Result<&str> {
+        self.environment
+            .get(name)
+            .ok_or_else(|| invalid_marker(self.marker, &format!("unknown variable '{}'", name)))
+    }
+
+    fn compare_extra(&self, operator: &str, value: &str) -> Result<bool> {
+        let value = crate::core::pep508::normalize_name(value);
+        match operator {
+            "==" => Ok(self.extras.contains(&value)),
+            "!=" => Ok(!self.extras.contains(&value)),
+            _ => Err(invalid_marker(self.marker, "extra only supports == and !=")),
+        }
+    }
+}
+
+/// Compare a marker variable with a value, as versions when both sides parse as versions
+fn compare(variable: &str, actual: &str, operator: &str, value: &str) -> bool {
+    match operator {
+        "in" => return value.contains(actual),
+        "not in" => return !value.contains(actual),
+        "===" => return actual == value,
+        _ => {}
+    }
+
+    let is_version_variable = matches!(variable, "python_version" | "python_full_version" | "implementation_version");
+    if is_version_variable
+        && let Ok(version) = Version::parse(actual)
+        && let Ok(specifier) = format!("{}{}", operator, value).parse::<SpecifierSet>()
+    {
+        return specifier.contains(&version, true);
+    }
+
+    match operator {
+        "==" => actual == value,
+        "!=" => actual != value,
+        "<" => actual < value,
+        "<=" => actual <= value,
+        ">" => actual > value,
+        ">=" => actual >= value,
+        _ => false,
+    }
+}
+
+fn invalid_marker(marker: &str, reason: &str) -> PyForgeError {
+    PyForgeError::ParseError {
+        file_type: "environment marker".to_string(),
+        message: format!("'{}': {}", marker, reason),
+    }
+}
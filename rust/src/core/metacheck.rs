@@ -0,0 +1,380 @@
+//! Checking and fixing what a project publishes about itself (`pyforge check
+//! metadata`): the `py.typed` marker, trove classifiers that follow from
+//! `requires-python`, the license and typing, and `[project.urls]`.
+
+use crate::core::dist;
+use crate::core::error::Result;
+use crate::core::manifest;
+use crate::core::pep440::{SpecifierSet, Version};
+use crate::core::pep508::normalize_name;
+use crate::core::project::Project;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{Array, Item, value};
+
+/// Python 3 minor versions classifiers are derived for
+const PYTHON_MINORS: std::ops::RangeInclusive<u32> = 8..=14;
+
+const PYTHON_3: &str = "Programming Language :: Python :: 3";
+const TYPED: &str = "Typing :: Typed";
+
+/// Hosts that serve everything over https
+const HTTPS_HOSTS: &[&str] = &["github.com", "gitlab.com", "bitbucket.org", "pypi.org", "readthedocs.io", "readthedocs.org"];
+
+/// One change `--fix` makes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// An empty file, relative to the project root
+    CreateFile(PathBuf),
+    AddClassifier(String),
+    RemoveClassifier(String),
+    /// A `[project.urls]` entry, renamed and/or pointed elsewhere
+    Url { label: String, new_label: String, url: String, new_url: String },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::CreateFile(path) => write!(f, "create {}", path.display()),
+            Change::AddClassifier(classifier) => write!(f, "add classifier \"{}\"", classifier),
+            Change::RemoveClassifier(classifier) => write!(f, "remove classifier \"{}\"", classifier),
+            Change::Url { label, new_label, url, new_url } if label == new_label => {
+                write!(f, "URL \"{}\": {} → {}", label, url, new_url)
+            }
+            Change::Url { label, new_label, url, new_url } if url == new_url => {
+                write!(f, "rename URL \"{}\" to \"{}\"", label, new_label)
+            }
+            Change::Url { label, new_label, url, new_url } => {
+                write!(f, "URL \"{}\" → \"{}\": {} → {}", label, new_label, url, new_url)
+            }
+        }
+    }
+}
+
+/// A change and why it is needed
+#[derive(Debug, Clone)]
+pub struct Fix {
+    pub change: Change,
+    pub reason: String,
+}
+
+/// What `check metadata` found: fixes it can make, and notes it can't act on
+#[derive(Debug, Clone, Default)]
+pub struct Review {
+    pub fixes: Vec<Fix>,
+    pub notes: Vec<String>,
+}
+
+impl Review {
+    fn fix(&mut self, change: Change, reason: impl Into<String>) {
+        if !self.fixes.iter().any(|fix| fix.change == change) {
+            self.fixes.push(Fix {
+                change,
+                reason: reason.into(),
+            });
+        }
+    }
+}
+
+pub fn review(project: &Project) -> Result<Review> {
+    let mut review = Review::default();
+    let table = project.pyproject.get("project");
+    let dynamic: Vec<&str> = table
+        .and_then(|t| t.get("dynamic"))
+        .and_then(|d| d.as_array())
+        .map(|d| d.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+    let classifiers: Vec<&str> = table
+        .and_then(|t| t.get("classifiers"))
+        .and_then(|c| c.as_array())
+        .map(|c| c.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+    let has = |classifier: &str| classifiers.contains(&classifier);
+
+    if dynamic.contains(&"classifiers") {
+        review.notes.push("classifiers are dynamic, so only py.typed and urls are checked".to_string());
+    }
+    let static_classifiers = !dynamic.contains(&"classifiers");
+
+    match package_dir(project) {
+        Some(package) => {
+            let marker = package.join("py.typed");
+            let has_marker = project.root.join(&marker).is_file();
+            let reason = if has(TYPED) {
+                Some("the project declares \"Typing :: Typed\"")
+            } else if has_stubs(&project.root.join(&package)) {
+                Some("the package ships .pyi stubs")
+            } else if type_checked(project) {
+                Some("the project configures mypy or pyright")
+            } else {
+                None
+            };
+            if let Some(reason) = reason
+                && !has_marker
+            {
+                review.fix(Change::CreateFile(marker), format!("{}; type checkers ignore installed packages without it", reason));
+            }
+            if static_classifiers && (reason.is_some() || has_marker) && !has(TYPED) {
+                review.fix(Change::AddClassifier(TYPED.to_string()), "the package is typed");
+            }
+        }
+        None => review.notes.push(format!(
+            "no import package at src/{0}/ or {0}/, so py.typed isn't checked",
+            module_name(project)
+        )),
+    }
+
+    if static_classifiers {
+        python_classifiers(project, &classifiers, &mut review)?;
+        license_classifiers(project, &classifiers, &mut review);
+    }
+    urls(project, &mut review);
+    Ok(review)
+}
+
+/// Make the review's fixes
+pub fn apply(project: &mut Project, fixes: &[Fix]) -> Result<()> {
+    for fix in fixes {
+        if let Change::CreateFile(path) = &fix.change {
+            fs::write(project.root.join(path), "")?;
+        }
+    }
+    project.update_pyproject(|document| {
+        let table = &mut document["project"];
+        let mut sort = false;
+        for fix in fixes {
+            match &fix.change {
+                Change::AddClassifier(classifier) => {
+                    if table.get("classifiers").and_then(Item::as_array).is_none() {
+                        table["classifiers"] = value(Array::new());
+                    }
+                    if let Some(classifiers) = table["classifiers"].as_array_mut() {
+                        classifiers.push(classifier.as_str());
+                        sort = true;
+                    }
+                }
+                Change::RemoveClassifier(classifier) => {
+                    if let Some(classifiers) = table.get_mut("classifiers").and_then(Item::as_array_mut) {
+                        classifiers.retain(|c| c.as_str() != Some(classifier));
+                    }
+                }
+                Change::CreateFile(_) | Change::Url { .. } => {}
+            }
+        }
+        if let Some(classifiers) = table.get_mut("classifiers").and_then(Item::as_array_mut)
+            && sort
+            && classifiers.len() > 1
+        {
+            classifiers.sort_by_key(|c| c.as_str().unwrap_or_default().to_string());
+            manifest::format_multiline(classifiers);
+        }
+
+        // Rebuild the table so renamed entries keep their place
+        let url_fixes = fixes.iter().any(|fix| matches!(fix.change, Change::Url { .. }));
+        let Some(urls) = table.get_mut("urls").and_then(Item::as_table_like_mut).filter(|_| url_fixes) else {
+            return;
+        };
+        let entries: Vec<(String, Item)> = urls.iter().map(|(key, item)| (key.to_string(), item.clone())).collect();
+        urls.clear();
+        for (key, item) in entries {
+            let change = fixes.iter().find_map(|fix| match &fix.change {
+                Change::Url { label, new_label, new_url, .. } if *label == key => Some((new_label, new_url)),
+                _ => None,
+            });
+            match change {
+                Some((new_label, new_url)) => {
+                    urls.insert(new_label, value(new_url.as_str()));
+                }
+                None => {
+                    urls.insert(&key, item);
+                }
+            }
+        }
+    })
+}
+
+/// `Programming Language :: Python :: 3.N` for every minor version `requires-python` allows
+fn python_classifiers(project: &Project, classifiers: &[&str], review: &mut Review) -> Result<()> {
+    let Some(requires_python) = project.requires_python() else {
+        review.notes.push("requires-python is not set, so the Python classifiers can't be derived".to_string());
+        return Ok(());
+    };
+    let specifiers: SpecifierSet = requires_python.parse()?;
+    let allows = |minor: u32| {
+        [format!("3.{}", minor), format!("3.{}.99", minor)]
+            .iter()
+            .any(|version| Version::parse(version).is_ok_and(|version| specifiers.contains(&version, false)))
+    };
+    let wanted: Vec<String> = PYTHON_MINORS
+        .filter(|minor| allows(*minor))
+        .map(|minor| format!("{}.{}", PYTHON_3, minor))
+        .collect();
+    if wanted.is_empty() {
+        review.notes.push(format!("requires-python '{}' allows no Python 3 release pyforge knows", requires_python));
+        return Ok(());
+    }
+
+    let reason = format!("requires-python is '{}'", requires_python);
+    if !classifiers.contains(&PYTHON_3) {
+        review.fix(Change::AddClassifier(PYTHON_3.to_string()), reason.clone());
+    }
+    for classifier in &wanted {
+        if !classifiers.contains(&classifier.as_str()) {
+            review.fix(Change::AddClassifier(classifier.clone()), reason.clone());
+        }
+    }
+    for classifier in classifiers {
+        let minor = classifier.strip_prefix(PYTHON_3).and_then(|rest| rest.strip_prefix('.'));
+        if minor.is_some_and(|minor| minor.parse::<u32>().is_ok()) && !wanted.iter().any(|w| w == classifier) {
+            review.fix(Change::RemoveClassifier(classifier.to_string()), reason.clone());
+        }
+    }
+    Ok(())
+}
+
+/// A license classifier for `license = { text = "MIT" }`; none next to a
+/// license expression, which supersedes them (PEP 639)
+fn license_classifiers(project: &Project, classifiers: &[&str], review: &mut Review) {
+    let license = project.pyproject.get("project").and_then(|t| t.get("license"));
+    let existing: Vec<&str> = classifiers.iter().copied().filter(|c| c.starts_with("License ::")).collect();
+    match license {
+        Some(toml::Value::String(expression)) => {
+            for classifier in existing {
+                review.fix(
+                    Change::RemoveClassifier(classifier.to_string()),
+                    format!("license = \"{}\" supersedes license classifiers, and build backends reject both", expression),
+                );
+            }
+        }
+        Some(_) => {
+            let Some(text) = project.license() else {
+                return;
+            };
+            match dist::license_classifier(&text) {
+                Some(classifier) if existing.is_empty() => {
+                    review.fix(Change::AddClassifier(classifier), format!("the license is {}", text));
+                }
+                Some(_) => {}
+                None if existing.is_empty() => {
+                    review.notes.push(format!("no license classifier is known for '{}'", text));
+                }
+                None => {}
+            }
+        }
+        None => review.notes.push("[project].license is not set".to_string()),
+    }
+}
+
+/// Well-known labels (PEP 753) and canonical URLs for `[project.urls]`
+fn urls(project: &Project, review: &mut Review) {
+    let Some(urls) = project
+        .pyproject
+        .get("project")
+        .and_then(|t| t.get("urls"))
+        .and_then(|u| u.as_table())
+    else {
+        return;
+    };
+    for (label, url) in urls {
+        let Some(url) = url.as_str() else {
+            continue;
+        };
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            review.notes.push(format!("URL \"{}\" ({}) is not an http(s) URL", label, url));
+            continue;
+        }
+        let new_label = match well_known_label(label) {
+            Some(known) if known != label && !urls.contains_key(known) => known.to_string(),
+            _ => label.clone(),
+        };
+        let new_url = normalize_url(url);
+        if new_label != *label || new_url != url {
+            let reason = match (new_label != *label, new_url != url) {
+                (true, true) => "PyPI shows well-known labels with icons; the URL has a canonical form",
+                (true, false) => "PyPI shows well-known labels with icons",
+                _ => "the URL has a canonical form",
+            };
+            review.fix(
+                Change::Url {
+                    label: label.clone(),
+                    new_label,
+                    url: url.to_string(),
+                    new_url,
+                },
+                reason,
+            );
+        }
+    }
+}
+
+fn well_known_label(label: &str) -> Option<&'static str> {
+    let normalized: String = label.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase();
+    let known = match normalized.as_str() {
+        "homepage" | "home" | "website" => "Homepage",
+        "source" | "sourcecode" | "repository" | "repo" | "code" | "github" | "gitlab" => "Source",
+        "documentation" | "docs" => "Documentation",
+        "issues" | "issuetracker" | "bugtracker" | "bugs" | "tracker" => "Issues",
+        "changelog" | "changes" | "history" | "whatsnew" => "Changelog",
+        "releasenotes" => "Release Notes",
+        "download" => "Download",
+        "funding" | "sponsor" | "donate" => "Funding",
+        _ => return None,
+    };
+    Some(known)
+}
+
+/// https for hosts that serve it, no `www.` on code hosts, no trailing
+/// slash and no `.git` suffix
+fn normalize_url(url: &str) -> String {
+    let (scheme, rest) = url.split_once("://").unwrap_or(("https", url));
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let mut host = host.to_ascii_lowercase();
+    let code_host = |host: &str| ["github.com", "gitlab.com", "bitbucket.org"].contains(&host);
+    if let Some(bare) = host.strip_prefix("www.")
+        && code_host(bare)
+    {
+        host = bare.to_string();
+    }
+    let https = HTTPS_HOSTS.iter().any(|known| host == *known || host.ends_with(&format!(".{}", known)));
+    let scheme = if https { "https" } else { scheme };
+    let mut path = path.trim_end_matches('/').to_string();
+    if code_host(&host)
+        && let Some(stripped) = path.strip_suffix(".git")
+    {
+        path = stripped.to_string();
+    }
+    if path.is_empty() {
+        format!("{}://{}", scheme, host)
+    } else {
+        format!("{}://{}/{}", scheme, host, path)
+    }
+}
+
+fn module_name(project: &Project) -> String {
+    normalize_name(project.name().unwrap_or_default()).replace('-', "_")
+}
+
+/// The import package, `src/` layout or flat, relative to the project root
+fn package_dir(project: &Project) -> Option<PathBuf> {
+    let module = module_name(project);
+    [Path::new("src").join(&module), PathBuf::from(&module)]
+        .into_iter()
+        .find(|dir| project.root.join(dir).join("__init__.py").is_file())
+}
+
+fn has_stubs(dir: &Path) -> bool {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .any(|entry| entry.path().extension().is_some_and(|extension| extension == "pyi"))
+}
+
+/// Whether mypy or pyright is configured for the project
+fn type_checked(project: &Project) -> bool {
+    let tool = project.pyproject.get("tool");
+    tool.is_some_and(|tool| tool.get("mypy").is_some() || tool.get("pyright").is_some())
+        || ["mypy.ini", ".mypy.ini", "pyrightconfig.json"]
+            .iter()
+            .any(|file| project.root.join(file).is_file())
+}
@@ -1,2 +1,78 @@
 pub mod utils;
-pub mod error;
\ No newline at end of file
+pub mod error;
+pub mod config;
+pub mod dirs;
+pub mod paths;
+pub mod project;
+pub mod index;
+pub mod logging;
+pub mod bundle;
+pub mod crash;
+pub mod fuzzy;
+pub mod process;
+pub mod history;
+pub mod preset;
+pub mod pep440;
+pub mod pep508;
+pub mod policy;
+pub mod markers;
+pub mod dist;
+pub mod lockfile;
+pub mod resolver;
+pub mod inline;
+pub mod toolchain;
+pub mod venv;
+pub mod snapshot;
+pub mod installer;
+pub mod verify;
+pub mod sandbox;
+pub mod isolation;
+pub mod capture;
+pub mod queue;
+pub mod python;
+pub mod registry;
+pub mod build;
+pub mod vetting;
+pub mod stats;
+pub mod changelog;
+pub mod bisect;
+pub mod health;
+pub mod htmlreport;
+pub mod junit;
+pub mod ci;
+pub mod manifest;
+pub mod git;
+pub mod lockmerge;
+pub mod lockimport;
+pub mod pylock;
+pub mod workspace;
+pub mod template;
+pub mod permissions;
+pub mod remote;
+pub mod org;
+pub mod forge;
+pub mod auth;
+pub mod container;
+pub mod editor;
+pub mod testcache;
+pub mod ssh;
+pub mod pipeline;
+pub mod datafiles;
+pub mod distdiff;
+pub mod distcheck;
+pub mod metacheck;
+pub mod reach;
+pub mod scaffold;
+pub mod schema;
+pub mod trust;
+pub mod quarantine;
+pub mod overrides;
+pub mod versioning;
+pub mod buildenv;
+pub mod buildinfo;
+pub mod feature;
+pub mod pypi;
+pub mod timings;
+pub mod notify;
+pub mod parallel;
+pub mod selfupdate;
@@ -0,0 +1,123 @@
+//! Notifications when a long command finishes (`[notify]`, global config
+//! only), so a test suite or a big sync can run in the background. Failing to notify is logged and
+//! never changes the command's outcome.
+
+use crate::core::config::Config;
+use crate::core::index;
+use std::process::Command;
+use std::time::Duration;
+
+/// Quicker commands are never notified about, whatever `after` says, so most
+/// runs end without reading any settings
+pub const MIN_ELAPSED: Duration = Duration::from_secs(1);
+
+/// A finished invocation
+#[derive(Debug, Clone)]
+pub struct Finished<'a> {
+    /// Arguments after the program name
+    pub args: &'a [String],
+    pub exit_code: i32,
+    pub elapsed: Duration,
+}
+
+impl Finished<'_> {
+    /// The subcommand: the first argument that isn't an option
+    fn command(&self) -> Option<&str> {
+        self.args.iter().map(String::as_str).find(|arg| !arg.starts_with('-'))
+    }
+
+    fn title(&self) -> String {
+        let outcome = if self.exit_code == 0 { "finished" } else { "failed" };
+        format!("pyforge {} {}", self.command().unwrap_or_default(), outcome)
+    }
+
+    fn body(&self) -> String {
+        let cwd = std::env::current_dir().map(|dir| dir.display().to_string()).unwrap_or_default();
+        let status = match self.exit_code {
+            0 => String::new(),
+            code => format!(" with exit code {}", code),
+        };
+        format!("`pyforge {}` took {}{} in {}", self.args.join(" "), duration(self.elapsed), status, cwd)
+    }
+}
+
+/// Send the configured notifications when `finished` ran long enough
+pub fn notify(config: &Config, finished: &Finished) {
+    let settings = &config.notify;
+    let Some(after) = settings.after else {
+        return;
+    };
+    let Some(command) = finished.command() else {
+        return;
+    };
+    if finished.elapsed < Duration::from_secs(after) || settings.ignore.iter().any(|ignored| ignored == command) {
+        return;
+    }
+
+    if settings.desktop {
+        desktop(&finished.title(), &finished.body());
+    }
+    if let Some(url) = &settings.webhook {
+        let payload = serde_json::json!({
+            "command": command,
+            "args": finished.args,
+            "exit_code": finished.exit_code,
+            "success": finished.exit_code == 0,
+            "duration_seconds": finished.elapsed.as_secs_f64(),
+            "cwd": std::env::current_dir().map(|dir| dir.display().to_string()).unwrap_or_default(),
+        });
+        post(config, url, &payload);
+    }
+    if let Some(url) = &settings.slack_webhook {
+        let icon = if finished.exit_code == 0 { ":white_check_mark:" } else { ":x:" };
+        let payload = serde_json::json!({
+            "text": format!("{} *{}*\n{}", icon, finished.title(), finished.body()),
+        });
+        post(config, url, &payload);
+    }
+}
+
+/// `1h 02m`, `3m 20s` or `45s`
+fn duration(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    match (seconds / 3600, seconds / 60 % 60, seconds % 60) {
+        (0, 0, seconds) => format!("{}s", seconds),
+        (0, minutes, seconds) => format!("{}m {:02}s", minutes, seconds),
+        (hours, minutes, _) => format!("{}h {:02}m", hours, minutes),
+    }
+}
+
+fn desktop(title: &str, body: &str) {
+    let mut command = if cfg!(target_os = "macos") {
+        let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut command = Command::new("osascript");
+        command.args(["-e", &format!("display notification {} with title {}", quote(body), quote(title))]);
+        command
+    } else if cfg!(unix) {
+        let mut command = Command::new("notify-send");
+        command.args(["--app-name=pyforge", title, body]);
+        command
+    } else {
+        log::info!("desktop notifications are not supported on this platform");
+        return;
+    };
+    match command.output() {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => log::warn!("desktop notification failed: {}", String::from_utf8_lossy(&output.stderr).trim()),
+        Err(e) => log::warn!("could not show a desktop notification: {}", e),
+    }
+}
+
+fn post(config: &Config, url: &str, payload: &serde_json::Value) {
+    let client = match index::client_builder(&config.network) {
+        Ok(builder) => builder.build(),
+        Err(e) => {
+            log::warn!("notification to {} failed: {}", url, e);
+            return;
+        }
+    };
+    let result = client.and_then(|client| client.post(url).json(payload).send()?.error_for_status());
+    if let Err(e) = result {
+        log::warn!("notification to {} failed: {}", url, e);
+    }
+}
@@ -0,0 +1,174 @@
+//! Organization defaults for new projects (`pyforge init --org <URL>`).
+//!
+//! An organization publishes a TOML file with the authorship, license,
+//! indexes and tooling its projects should start from:
+//!
+//! ```toml
+//! name = "Example Corp"
+//! license = "LicenseRef-Proprietary"
+//! requires-python = ">=3.11"
+//! preset = "strict"
+//! authors = [{ name = "Example Corp", email = "python@example.com" }]
+//!
+//! [[index]]
+//! name = "corp"
+//! url = "https://pypi.example.com/simple"
+//! ```
+
+use crate::core::config::{Config, IndexConfig};
+use crate::core::dirs::cache_dir;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::project::Project;
+use crate::core::remote;
+use serde::Deserialize;
+use std::path::PathBuf;
+use toml_edit::{Array, ArrayOfTables, DocumentMut, InlineTable, Item, Table, value};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Author {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+/// Defaults an organization applies to every project created with `pyforge init`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct OrgDefaults {
+    pub name: String,
+
+    /// `[project].authors` for new projects
+    pub authors: Vec<Author>,
+
+    /// SPDX license expression for `[project].license`
+    pub license: Option<String>,
+
+    /// `[project].requires-python` for new projects
+    pub requires_python: Option<String>,
+
+    /// Preset applied when `--preset` is not given
+    pub preset: Option<String>,
+
+    /// Template rendered when `--template` is not given
+    pub template: Option<String>,
+
+    /// Indexes written to `[[tool.pyforge.index]]`
+    #[serde(rename = "index")]
+    pub indexes: Vec<IndexConfig>,
+
+    /// Where the defaults were loaded from
+    #[serde(skip)]
+    pub source: String,
+}
+
+impl OrgDefaults {
+    /// Load the defaults from `source` (a path or http(s) URL), or from the
+    /// global `[init] org-defaults` setting when no source is given
+    pub fn load(source: Option<&str>, config: &Config) -> Result<Option<Self>> {
+        let (source, sha256) = match source {
+            Some(source) => (source.to_string(), None),
+            None => match &config.init.org_defaults {
+                Some(source) => (source.clone(), config.init.org_defaults_sha256.as_deref()),
+                None => return Ok(None),
+            },
+        };
+
+        let content = remote::fetch(&source, "organization defaults", &cache_path())?;
+        remote::check_digest(&source, "organization defaults", &content, sha256)?;
+
+        let mut defaults: OrgDefaults = toml::from_str(&content).map_err(|e| PyForgeError::InvalidToml {
+            file: source.clone(),
+            message: e.to_string(),
+        })?;
+        if defaults.name.is_empty() {
+            defaults.name = "organization defaults".to_string();
+        }
+        defaults.source = source;
+        Ok(Some(defaults))
+    }
+
+    /// Fill in whatever the project's pyproject.toml does not set yet,
+    /// returning the keys that were written
+    pub fn apply(&self, project: &mut Project) -> Result<Vec<&'static str>> {
+        let mut written = Vec::new();
+        project.update_pyproject(|document| {
+            let Some(table) = document.get_mut("project").and_then(Item::as_table_mut) else {
+                return;
+            };
+            if !self.authors.is_empty() && !table.contains_key("authors") {
+                let mut authors = Array::new();
+                for author in &self.authors {
+                    let mut entry = InlineTable::new();
+                    if let Some(name) = &author.name {
+                        entry.insert("name", name.as_str().into());
+                    }
+                    if let Some(email) = &author.email {
+                        entry.insert("email", email.as_str().into());
+                    }
+                    authors.push(entry);
+                }
+                table.insert("authors", value(authors));
+                written.push("project.authors");
+            }
+            if let Some(license) = &self.license
+                && !table.contains_key("license")
+            {
+                table.insert("license", value(license.as_str()));
+                written.push("project.license");
+            }
+            if let Some(requires_python) = &self.requires_python
+                && !table.contains_key("requires-python")
+            {
+                table.insert("requires-python", value(requires_python.as_str()));
+                written.push("project.requires-python");
+            }
+            if self.add_indexes(document) {
+                written.push("tool.pyforge.index");
+            }
+        })?;
+        Ok(written)
+    }
+
+    fn add_indexes(&self, document: &mut DocumentMut) -> bool {
+        if self.indexes.is_empty() {
+            return false;
+        }
+        let tool = document.entry("tool").or_insert_with(|| {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            Item::Table(table)
+        });
+        let Some(tool) = tool.as_table_mut() else {
+            return false;
+        };
+        let pyforge = tool.entry("pyforge").or_insert_with(|| {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            Item::Table(table)
+        });
+        let Some(pyforge) = pyforge.as_table_mut() else {
+            return false;
+        };
+        if pyforge.contains_key("index") {
+            return false;
+        }
+
+        let mut indexes = ArrayOfTables::new();
+        for index in &self.indexes {
+            let mut table = Table::new();
+            table.insert("name", value(index.name.as_str()));
+            table.insert("url", value(index.url.as_str()));
+            if !index.mirrors.is_empty() {
+                table.insert("mirrors", value(index.mirrors.iter().map(String::as_str).collect::<Array>()));
+            }
+            indexes.push(table);
+        }
+        pyforge.insert("index", Item::ArrayOfTables(indexes));
+        true
+    }
+}
+
+/// Last successfully fetched remote defaults, used when the source is unreachable
+fn cache_path() -> PathBuf {
+    cache_dir().join("org-defaults-cache.toml")
+}
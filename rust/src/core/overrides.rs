@@ -0,0 +1,89 @@
+//! Local dependency overrides in `pyforge.dev.toml`.
+//!
+//! A developer patching a library next to the project points the dependency
+//! at their checkout, without touching `pyproject.toml`:
+//!
+//! ```toml
+//! [overrides]
+//! requests = { path = "../requests" }
+//! ```
+//!
+//! Syncs install the package from there instead of its locked file, editable
+//! unless `editable = false`. The file stays out of git and out of the
+//! lockfile; once an entry is removed, the next sync brings back the locked
+//! version.
+
+use crate::core::error::{PyForgeError, Result};
+use crate::core::pep508::normalize_name;
+use crate::core::project::Project;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Per-developer overrides, next to `pyproject.toml`
+pub const DEV_OVERRIDES: &str = "pyforge.dev.toml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct DevFile {
+    overrides: BTreeMap<String, Entry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Entry {
+    path: String,
+    #[serde(default = "default_editable")]
+    editable: bool,
+}
+
+fn default_editable() -> bool {
+    true
+}
+
+/// A dependency installed from a local directory instead of the lockfile
+#[derive(Debug, Clone)]
+pub struct Override {
+    /// Normalized name
+    pub name: String,
+    /// Absolute directory of the package's project
+    pub path: PathBuf,
+    pub editable: bool,
+}
+
+/// The overrides in `root`'s `pyforge.dev.toml`, if it has one
+pub fn load(root: &Path) -> Result<Vec<Override>> {
+    let file = root.join(DEV_OVERRIDES);
+    let Ok(content) = fs::read_to_string(&file) else {
+        return Ok(Vec::new());
+    };
+    let invalid = |source: Box<dyn std::error::Error + Send + Sync>| PyForgeError::InvalidConfig {
+        file: file.display().to_string(),
+        source,
+    };
+    let parsed: DevFile = toml::from_str(&content).map_err(|e| invalid(Box::new(e)))?;
+
+    let mut overrides = Vec::new();
+    for (name, entry) in parsed.overrides {
+        let name = normalize_name(&name);
+        let dir = root.join(&entry.path);
+        let path = dir
+            .canonicalize()
+            .ok()
+            .filter(|path| path.join("pyproject.toml").is_file() || path.join("setup.py").is_file())
+            .ok_or_else(|| invalid(format!("{} overrides {} with {}, which is not a Python project", DEV_OVERRIDES, name, dir.display()).into()))?;
+        if path.join("pyproject.toml").is_file()
+            && let Some(found) = Project::load(&path)?.name().map(normalize_name)
+            && found != name
+        {
+            return Err(invalid(format!("{} is the project '{}', not '{}'", path.display(), found, name).into()));
+        }
+        overrides.push(Override {
+            name,
+            path,
+            editable: entry.editable,
+        });
+    }
+    Ok(overrides)
+}
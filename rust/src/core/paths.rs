@@ -0,0 +1,71 @@
+//! Paths that work on Windows too: names Windows reserves for devices, and
+//! paths longer than the 260-character `MAX_PATH` limit.
+//!
+//! Reserved names are checked on every platform, since a project or template
+//! that uses one can't be checked out on Windows. Long paths only need the
+//! `\\?\` prefix on Windows; elsewhere [`long`] returns the path unchanged.
+
+use crate::core::error::{PyForgeError, Result};
+use std::path::{Component, Path, PathBuf};
+
+/// Device names Windows reserves in every directory, with any extension
+const RESERVED: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9", "LPT1", "LPT2",
+    "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Longest path Windows accepts without the `\\?\` prefix
+#[cfg_attr(not(windows), allow(dead_code))]
+const MAX_PATH: usize = 260;
+
+/// Why `name` can't be a file or directory name on Windows, if it can't
+pub fn windows_name_problem(name: &str) -> Option<String> {
+    let stem = name.split('.').next().unwrap_or(name).trim_end();
+    if RESERVED.iter().any(|reserved| stem.eq_ignore_ascii_case(reserved)) {
+        return Some(format!("'{}' is a device name reserved by Windows", stem));
+    }
+    if name.ends_with('.') || name.ends_with(' ') {
+        return Some("Windows drops trailing dots and spaces from names".to_string());
+    }
+    if let Some(c) = name.chars().find(|c| matches!(c, '<' | '>' | ':' | '"' | '|' | '?' | '*') || c.is_control()) {
+        return Some(format!("'{}' is not allowed in names on Windows", c.escape_default()));
+    }
+    None
+}
+
+/// Reject a relative path with a component Windows can't create
+pub fn check_portable(relative: &Path, what: &str) -> Result<()> {
+    for component in relative.components() {
+        if let Component::Normal(name) = component
+            && let Some(problem) = windows_name_problem(&name.to_string_lossy())
+        {
+            return Err(PyForgeError::Usage {
+                message: format!("{} {} can't be created on Windows: {}", what, relative.display(), problem),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// `path` in a form Windows can open even past `MAX_PATH`
+#[cfg(windows)]
+pub fn long(path: &Path) -> PathBuf {
+    let text = path.as_os_str().to_string_lossy();
+    if text.len() < MAX_PATH || text.starts_with(r"\\?\") {
+        return path.to_path_buf();
+    }
+    // The prefix turns off normalization, so the path must be absolute and clean
+    let Ok(absolute) = std::path::absolute(path) else {
+        return path.to_path_buf();
+    };
+    let absolute = absolute.to_string_lossy().into_owned();
+    match absolute.strip_prefix(r"\\") {
+        Some(unc) => PathBuf::from(format!(r"\\?\UNC\{}", unc)),
+        None => PathBuf::from(format!(r"\\?\{}", absolute)),
+    }
+}
+
+#[cfg(not(windows))]
+pub fn long(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
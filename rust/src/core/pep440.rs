@@ -0,0 +1,486 @@
+//! PEP 440 versions and version specifiers.
+
+use crate::core::error::{PyForgeError, Result};
+use regex::Regex;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+static VERSION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?xi)
+        ^\s*v?
+        (?:(?P<epoch>[0-9]+)!)?
+        (?P<release>[0-9]+(?:\.[0-9]+)*)
+        (?P<pre>[-_.]?(?P<pre_l>alpha|a|beta|b|preview|pre|c|rc)[-_.]?(?P<pre_n>[0-9]+)?)?
+        (?P<post>(?:-(?P<post_n1>[0-9]+))|(?:[-_.]?(?P<post_l>post|rev|r)[-_.]?(?P<post_n2>[0-9]+)?))?
+        (?P<dev>[-_.]?(?P<dev_l>dev)[-_.]?(?P<dev_n>[0-9]+)?)?
+        (?:\+(?P<local>[a-z0-9]+(?:[-_.][a-z0-9]+)*))?
+        \s*$",
+    )
+    .expect("valid version regex")
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PreKind {
+    Alpha,
+    Beta,
+    Rc,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LocalSegment {
+    Number(u64),
+    Text(String),
+}
+
+/// A parsed PEP 440 version
+#[derive(Debug, Clone, Eq)]
+pub struct Version {
+    pub epoch: u64,
+    pub release: Vec<u64>,
+    pub pre: Option<(PreKind, u64)>,
+    pub post: Option<u64>,
+    pub dev: Option<u64>,
+    pub local: Vec<LocalSegment>,
+}
+
+impl Version {
+    pub fn parse(s: &str) -> Result<Self> {
+        s.parse()
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some() || self.dev.is_some()
+    }
+
+    pub fn is_postrelease(&self) -> bool {
+        self.post.is_some()
+    }
+
+    /// The version without its local segment
+    pub fn public(&self) -> Version {
+        Version {
+            local: Vec::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Only epoch and release (`1.2.3rc1.post2` → `1.2.3`)
+    pub fn base(&self) -> Version {
+        Version {
+            epoch: self.epoch,
+            release: self.release.clone(),
+            pre: None,
+            post: None,
+            dev: None,
+            local: Vec::new(),
+        }
+    }
+
+    /// Release component at `index`, zero when absent
+    pub fn release_at(&self, index: usize) -> u64 {
+        self.release.get(index).copied().unwrap_or(0)
+    }
+
+    fn cmp_key(&self) -> (u64, Vec<u64>, PreKey, Option<u64>, DevKey, LocalKey<'_>) {
+        let mut release = self.release.clone();
+        while release.len() > 1 && release.last() == Some(&0) {
+            release.pop();
+        }
+
+        let pre = match (self.pre, self.post, self.dev) {
+            (None, None, Some(_)) => PreKey::Min,
+            (None, _, _) => PreKey::Max,
+            (Some(pre), _, _) => PreKey::Pre(pre),
+        };
+        let dev = match self.dev {
+            None => DevKey::Max,
+            Some(n) => DevKey::Dev(n),
+        };
+
+        (self.epoch, release, pre, self.post, dev, LocalKey(&self.local))
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum PreKey {
+    Min,
+    Pre((PreKind, u64)),
+    Max,
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum DevKey {
+    Dev(u64),
+    Max,
+}
+
+/// Local segments compare numerically or lexically; numbers sort after text
+#[derive(PartialEq, Eq)]
+struct LocalKey<'a>(&'a [LocalSegment]);
+
+impl PartialOrd for LocalKey<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LocalKey<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        for (a, b) in self.0.iter().zip(other.0.iter()) {
+            let ordering = match (a, b) {
+                (LocalSegment::Number(a), LocalSegment::Number(b)) => a.cmp(b),
+                (LocalSegment::Text(a), LocalSegment::Text(b)) => a.cmp(b),
+                (LocalSegment::Number(_), LocalSegment::Text(_)) => Ordering::Greater,
+                (LocalSegment::Text(_), LocalSegment::Number(_)) => Ordering::Less,
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        self.0.len().cmp(&other.0.len())
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Hash for Version {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Must agree with `Eq`, which ignores trailing zeros in the release
+        let (epoch, release, ..) = self.cmp_key();
+        epoch.hash(state);
+        release.hash(state);
+        self.pre.hash(state);
+        self.post.hash(state);
+        self.dev.hash(state);
+        self.local.hash(state);
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_key().cmp(&other.cmp_key())
+    }
+}
+
+impl FromStr for Version {
+    type Err = PyForgeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let caps = VERSION_RE.captures(s).ok_or_else(|| invalid_version(s))?;
+        let number = |name: &str| -> Result<Option<u64>> {
+            caps.name(name)
+                .map(|m| m.as_str().parse::<u64>().map_err(|_| invalid_version(s)))
+                .transpose()
+        };
+
+        let release = caps["release"]
+            .split('.')
+            .map(|part| part.parse::<u64>().map_err(|_| invalid_version(s)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let pre = match caps.name("pre_l") {
+            Some(label) => {
+                let kind = match label.as_str().to_lowercase().as_str() {
+                    "a" | "alpha" => PreKind::Alpha,
+                    "b" | "beta" => PreKind::Beta,
+                    _ => PreKind::Rc,
+                };
+                Some((kind, number("pre_n")?.unwrap_or(0)))
+            }
+            None => None,
+        };
+
+        let post = if caps.name("post").is_some() {
+            Some(number("post_n1")?.or(number("post_n2")?).unwrap_or(0))
+        } else {
+            None
+        };
+
+        let dev = if caps.name("dev").is_some() {
+            Some(number("dev_n")?.unwrap_or(0))
+        } else {
+            None
+        };
+
+        let local = caps
+            .name("local")
+            .map(|local| {
+                local
+                    .as_str()
+                    .split(['.', '-', '_'])
+                    .map(|part| match part.parse::<u64>() {
+                        Ok(n) => LocalSegment::Number(n),
+                        Err(_) => LocalSegment::Text(part.to_lowercase()),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Version {
+            epoch: number("epoch")?.unwrap_or(0),
+            release,
+            pre,
+            post,
+            dev,
+            local,
+        })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.epoch != 0 {
+            write!(f, "{}!", self.epoch)?;
+        }
+        let release: Vec<String> = self.release.iter().map(u64::to_string).collect();
+        write!(f, "{}", release.join("."))?;
+        if let Some((kind, n)) = self.pre {
+            let label = match kind {
+                PreKind::Alpha => "a",
+                PreKind::Beta => "b",
+                PreKind::Rc => "rc",
+            };
+            write!(f, "{}{}", label, n)?;
+        }
+        if let Some(post) = self.post {
+            write!(f, ".post{}", post)?;
+        }
+        if let Some(dev) = self.dev {
+            write!(f, ".dev{}", dev)?;
+        }
+        if !self.local.is_empty() {
+            let local: Vec<String> = self
+                .local
+                .iter()
+                .map(|segment| match segment {
+                    LocalSegment::Number(n) => n.to_string(),
+                    LocalSegment::Text(t) => t.clone(),
+                })
+                .collect();
+            write!(f, "+{}", local.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+fn invalid_version(s: &str) -> PyForgeError {
+    PyForgeError::ParseError {
+        file_type: "version".to_string(),
+        message: format!("'{}' is not a valid PEP 440 version", s),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operator {
+    Compatible,
+    Equal,
+    NotEqual,
+    LessEqual,
+    GreaterEqual,
+    Less,
+    Greater,
+    Arbitrary,
+}
+
+impl Operator {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operator::Compatible => "~=",
+            Operator::Equal => "==",
+            Operator::NotEqual => "!=",
+            Operator::LessEqual => "<=",
+            Operator::GreaterEqual => ">=",
+            Operator::Less => "<",
+            Operator::Greater => ">",
+            Operator::Arbitrary => "===",
+        }
+    }
+}
+
+/// A single version clause such as `>=1.2` or `==3.*`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Specifier {
+    pub operator: Operator,
+    /// The version as written (without the `.*` suffix for wildcards)
+    pub raw: String,
+    pub version: Option<Version>,
+    pub wildcard: bool,
+}
+
+impl Specifier {
+    /// Whether `version` satisfies this clause (pre-release filtering is done by `SpecifierSet`)
+    pub fn contains(&self, version: &Version) -> bool {
+        let Some(spec) = &self.version else {
+            return version.to_string() == self.raw;
+        };
+
+        match self.operator {
+            Operator::Arbitrary => version.to_string() == self.raw,
+            Operator::Equal if self.wildcard => release_prefix_matches(version, spec),
+            Operator::NotEqual if self.wildcard => !release_prefix_matches(version, spec),
+            Operator::Equal => equal_ignoring_local(version, spec),
+            Operator::NotEqual => !equal_ignoring_local(version, spec),
+            Operator::LessEqual => version.public() <= *spec,
+            Operator::GreaterEqual => version.public() >= *spec,
+            Operator::Less => {
+                version.public() < *spec
+                    && !(!spec.is_prerelease() && version.is_prerelease() && version.base() == spec.base())
+            }
+            Operator::Greater => {
+                version.public() > *spec
+                    && !(!spec.is_postrelease() && version.is_postrelease() && version.base() == spec.base())
+                    && (version.base() != spec.base() || version.local.is_empty())
+            }
+            Operator::Compatible => {
+                let mut prefix = spec.base();
+                prefix.release.pop();
+                version.public() >= *spec && release_prefix_matches(version, &prefix)
+            }
+        }
+    }
+
+    /// Whether this clause explicitly names a pre-release
+    pub fn mentions_prerelease(&self) -> bool {
+        self.version.as_ref().is_some_and(Version::is_prerelease)
+    }
+}
+
+fn equal_ignoring_local(version: &Version, spec: &Version) -> bool {
+    if spec.local.is_empty() {
+        version.public() == *spec
+    } else {
+        version == spec
+    }
+}
+
+fn release_prefix_matches(version: &Version, prefix: &Version) -> bool {
+    version.epoch == prefix.epoch
+        && prefix
+            .release
+            .iter()
+            .enumerate()
+            .all(|(i, part)| version.release_at(i) == *part)
+}
+
+impl FromStr for Specifier {
+    type Err = PyForgeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let operators = [
+            ("===", Operator::Arbitrary),
+            ("~=", Operator::Compatible),
+            ("==", Operator::Equal),
+            ("!=", Operator::NotEqual),
+            ("<=", Operator::LessEqual),
+            (">=", Operator::GreaterEqual),
+            ("<", Operator::Less),
+            (">", Operator::Greater),
+        ];
+        let (symbol, operator) = operators
+            .iter()
+            .find(|(symbol, _)| s.starts_with(symbol))
+            .ok_or_else(|| invalid_specifier(s))?;
+
+        let raw = s[symbol.len()..].trim();
+        if raw.is_empty() {
+            return Err(invalid_specifier(s));
+        }
+        if *operator == Operator::Arbitrary {
+            return Ok(Specifier {
+                operator: *operator,
+                raw: raw.to_string(),
+                version: None,
+                wildcard: false,
+            });
+        }
+
+        let (raw, wildcard) = match raw.strip_suffix(".*") {
+            Some(prefix) if matches!(operator, Operator::Equal | Operator::NotEqual) => (prefix, true),
+            Some(_) => return Err(invalid_specifier(s)),
+            None => (raw, false),
+        };
+        let version = raw.parse::<Version>().map_err(|_| invalid_specifier(s))?;
+        if *operator == Operator::Compatible && version.release.len() < 2 {
+            return Err(invalid_specifier(s));
+        }
+
+        Ok(Specifier {
+            operator: *operator,
+            raw: raw.to_string(),
+            version: Some(version),
+            wildcard,
+        })
+    }
+}
+
+impl fmt::Display for Specifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.operator.as_str(), self.raw)?;
+        if self.wildcard {
+            write!(f, ".*")?;
+        }
+        Ok(())
+    }
+}
+
+fn invalid_specifier(s: &str) -> PyForgeError {
+    PyForgeError::ParseError {
+        file_type: "version specifier".to_string(),
+        message: format!("'{}' is not a valid PEP 440 specifier", s),
+    }
+}
+
+/// A comma-separated set of specifiers, all of which must match
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SpecifierSet(pub Vec<Specifier>);
+
+impl SpecifierSet {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Whether `version` satisfies every clause.
+    ///
+    /// Pre-releases only match when `allow_prereleases` is set or a clause names one.
+    pub fn contains(&self, version: &Version, allow_prereleases: bool) -> bool {
+        if version.is_prerelease() && !allow_prereleases && !self.0.iter().any(Specifier::mentions_prerelease) {
+            return false;
+        }
+        self.0.iter().all(|spec| spec.contains(version))
+    }
+}
+
+impl FromStr for SpecifierSet {
+    type Err = PyForgeError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<_>>>()
+            .map(SpecifierSet)
+    }
+}
+
+impl fmt::Display for SpecifierSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self.0.iter().map(Specifier::to_string).collect();
+        write!(f, "{}", parts.join(","))
+    }
+}
@@ -0,0 +1,134 @@
+//! PEP 508 dependency specifiers and PEP 503 name normalization.
+
+use crate::core::error::{PyForgeError, Result};
+use crate::core::pep440::SpecifierSet;
+use regex::Regex;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+static NAME_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Za-z0-9](?:[A-Za-z0-9._-]*[A-Za-z0-9])?").expect("valid name regex"));
+
+static SEPARATOR_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[-_.]+").expect("valid separator regex"));
+
+/// Normalize a distribution name per PEP 503 (`Foo.Bar_baz` → `foo-bar-baz`)
+pub fn normalize_name(name: &str) -> String {
+    SEPARATOR_RE.replace_all(name, "-").to_lowercase()
+}
+
+/// A dependency such as `requests[socks]>=2.31; python_version < "3.12"`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Requirement {
+    /// Name as written
+    pub name: String,
+    pub extras: Vec<String>,
+    pub specifier: SpecifierSet,
+    /// Direct reference (`name @ https://...`)
+    pub url: Option<String>,
+    /// Environment marker expression, unevaluated
+    pub marker: Option<String>,
+}
+
+impl Requirement {
+    pub fn parse(s: &str) -> Result<Self> {
+        s.parse()
+    }
+
+    /// PEP 503 normalized name
+    pub fn normalized_name(&self) -> String {
+        normalize_name(&self.name)
+    }
+}
+
+impl FromStr for Requirement {
+    type Err = PyForgeError;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let s = input.trim();
+        let name = NAME_RE.find(s).ok_or_else(|| invalid_requirement(input, "missing package name"))?;
+        let mut rest = s[name.end()..].trim_start();
+
+        let mut extras = Vec::new();
+        if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(|| invalid_requirement(input, "unclosed '['"))?;
+            extras = after[..end]
+                .split(',')
+                .map(str::trim)
+                .filter(|extra| !extra.is_empty())
+                .map(str::to_string)
+                .collect();
+            rest = after[end + 1..].trim_start();
+        }
+
+        let mut url = None;
+        let mut specifier = SpecifierSet::default();
+        let marker_part;
+
+        if let Some(after) = rest.strip_prefix('@') {
+            // Markers after a URL must be separated by whitespace, since URLs may contain ';'
+            let after = after.trim_start();
+            let (url_part, marker) = match after.find(" ;").or_else(|| after.find("\t;")) {
+                Some(index) => (&after[..index], Some(&after[index..])),
+                None => (after, None),
+            };
+            if url_part.trim().is_empty() {
+                return Err(invalid_requirement(input, "missing URL after '@'"));
+            }
+            url = Some(url_part.trim().to_string());
+            marker_part = marker;
+        } else {
+            let (spec_part, marker) = match rest.find(';') {
+                Some(index) => (&rest[..index], Some(&rest[index..])),
+                None => (rest, None),
+            };
+            let spec_part = spec_part.trim();
+            let spec_part = spec_part
+                .strip_prefix('(')
+                .and_then(|inner| inner.strip_suffix(')'))
+                .unwrap_or(spec_part);
+            specifier = spec_part
+                .parse()
+                .map_err(|_| invalid_requirement(input, &format!("invalid version specifier '{}'", spec_part)))?;
+            marker_part = marker;
+        }
+
+        let marker = marker_part
+            .map(|m| m.trim_start().trim_start_matches(';').trim().to_string())
+            .filter(|m| !m.is_empty());
+
+        Ok(Requirement {
+            name: name.as_str().to_string(),
+            extras,
+            specifier,
+            url,
+            marker,
+        })
+    }
+}
+
+impl fmt::Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.extras.is_empty() {
+            write!(f, "[{}]", self.extras.join(","))?;
+        }
+        match &self.url {
+            Some(url) => write!(f, " @ {}", url)?,
+            None => write!(f, "{}", self.specifier)?,
+        }
+        if let Some(marker) = &self.marker {
+            // A URL needs whitespace before the marker separator
+            let separator = if self.url.is_some() { " ; " } else { "; " };
+            write!(f, "{}{}", separator, marker)?;
+        }
+        Ok(())
+    }
+}
+
+fn invalid_requirement(input: &str, reason: &str) -> PyForgeError {
+    PyForgeError::ParseError {
+        file_type: "requirement".to_string(),
+        message: format!("'{}': {}", input.trim(), reason),
+    }
+}
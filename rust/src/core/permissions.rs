@@ -0,0 +1,142 @@
+//! File modes for what pyforge writes: scripts that must stay executable, and
+//! `chmod` rules from templates and `pyforge init --chmod`.
+//!
+//! Files are created with the process umask applied. Making a file executable
+//! adds an execute bit only where the file is readable, like `chmod +x`, so a
+//! umask of 077 gives 0700 rather than 0755. Octal modes are set as written.
+//! Platforms without Unix permissions ignore all of this.
+
+use crate::core::error::{PyForgeError, Result};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// What a rule does to a file's mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// `+x`: executable wherever it is readable
+    Executable,
+    /// `-x`: no execute bits
+    NotExecutable,
+    /// An exact mode such as `0755`
+    Octal(u32),
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(text: &str) -> std::result::Result<Self, Self::Err> {
+        match text {
+            "+x" => Ok(Mode::Executable),
+            "-x" => Ok(Mode::NotExecutable),
+            octal if (3..=4).contains(&octal.len()) => u32::from_str_radix(octal, 8)
+                .ok()
+                .filter(|mode| *mode <= 0o7777)
+                .map(Mode::Octal)
+                .ok_or_else(|| format!("'{}' is not an octal mode", octal)),
+            other => Err(format!("'{}' is not a mode; use +x, -x or an octal mode like 755", other)),
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mode::Executable => write!(f, "+x"),
+            Mode::NotExecutable => write!(f, "-x"),
+            Mode::Octal(mode) => write!(f, "{:03o}", mode),
+        }
+    }
+}
+
+/// `PATTERN=MODE`: a glob over paths relative to the project, and the mode to set
+#[derive(Debug, Clone)]
+pub struct ChmodRule {
+    pub pattern: glob::Pattern,
+    pub mode: Mode,
+}
+
+impl ChmodRule {
+    pub fn parse(rule: &str) -> Result<Self> {
+        let (pattern, mode) = rule.rsplit_once('=').ok_or_else(|| PyForgeError::Usage {
+            message: format!("Invalid chmod rule '{}'; expected PATTERN=MODE, e.g. 'bin/*=+x'", rule),
+        })?;
+        Self::new(pattern.trim(), mode.trim())
+    }
+
+    pub fn new(pattern: &str, mode: &str) -> Result<Self> {
+        let usage = |message: String| PyForgeError::Usage {
+            message: format!("Invalid chmod rule '{}={}': {}", pattern, mode, message),
+        };
+        Ok(Self {
+            pattern: glob::Pattern::new(pattern).map_err(|e| usage(e.to_string()))?,
+            mode: mode.parse().map_err(usage)?,
+        })
+    }
+
+    /// The mode the last matching rule gives `relative`
+    pub fn mode_for(rules: &[ChmodRule], relative: &Path) -> Option<Mode> {
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+        rules
+            .iter()
+            .rev()
+            .find(|rule| rule.pattern.matches_path_with(relative, options))
+            .map(|rule| rule.mode)
+    }
+}
+
+impl fmt::Display for ChmodRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.pattern, self.mode)
+    }
+}
+
+/// Content starting with `#!`, which only runs directly when executable
+pub fn is_script(content: &[u8]) -> bool {
+    content.starts_with(b"#!")
+}
+
+/// Whether any execute bit is set on `path`
+#[cfg(unix)]
+pub fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+pub fn is_executable(_path: &Path) -> bool {
+    false
+}
+
+/// Change the mode of an existing file
+#[cfg(unix)]
+pub fn apply(path: &Path, mode: Mode) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let set = |path: &Path| -> std::io::Result<()> {
+        let current = fs::metadata(path)?.permissions().mode() & 0o7777;
+        let updated = match mode {
+            Mode::Executable => current | (current & 0o444) >> 2,
+            Mode::NotExecutable => current & !0o111,
+            Mode::Octal(mode) => mode,
+        };
+        if updated != current {
+            fs::set_permissions(path, fs::Permissions::from_mode(updated))?;
+        }
+        Ok(())
+    };
+    set(path).map_err(|e| PyForgeError::file_error(format!("Could not set the mode of {}", path.display()), e))
+}
+
+#[cfg(not(unix))]
+pub fn apply(_path: &Path, _mode: Mode) -> Result<()> {
+    Ok(())
+}
+
+/// Mode for a file added to an archive: 0755 for executables, else 0644
+pub fn archive_mode(path: &Path) -> u32 {
+    if is_executable(path) { 0o755 } else { 0o644 }
+}
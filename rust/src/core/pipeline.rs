@@ -0,0 +1,139 @@
+//! Build steps (`[[tool.pyforge.build.steps]]`) run in the project before the
+//! PEP 517 backend: code generation, asset compilation and the like.
+//!
+//! A step with `inputs` is skipped when its command, its environment
+//! (`[tool.pyforge.build.env]`), its input files and its output files are the
+//! same as after its last successful run.
+
+use crate::core::buildenv;
+use crate::core::buildinfo;
+use crate::core::capture;
+use crate::core::config::{BuildStep, Config};
+use crate::core::dirs::cache_dir;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::process;
+use crate::core::project::Project;
+use crate::core::venv::Venv;
+use crate::core::timings;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Inputs and outputs of a step as they are now
+pub struct StepState {
+    record: PathBuf,
+    /// `[tool.pyforge.build.env]` for the release profile
+    env: BTreeMap<String, String>,
+    inputs: String,
+    outputs: String,
+}
+
+impl StepState {
+    pub fn current(project: &Project, config: &Config, step: &BuildStep) -> Result<Self> {
+        let key = Sha256::digest(format!("{}\0{}", project.root.display(), step.name).as_bytes());
+        let env = buildenv::variables(project, config, buildinfo::RELEASE)?;
+        let mut inputs = Sha256::new();
+        inputs.update(step.command.as_bytes());
+        inputs.update([0]);
+        for (name, value) in &env {
+            inputs.update(format!("{}={}", name, value).as_bytes());
+            inputs.update([0]);
+        }
+        inputs.update(hash_files(&project.root, &step.inputs)?);
+        Ok(Self {
+            record: cache_dir().join("build-steps").join(format!("{:x}", key)),
+            env,
+            inputs: format!("{:x}", inputs.finalize()),
+            outputs: hash_files(&project.root, &step.outputs)?,
+        })
+    }
+
+    /// Whether the last successful run saw the same inputs and left the same outputs
+    pub fn is_fresh(&self, project: &Project, step: &BuildStep) -> bool {
+        if step.inputs.is_empty() || !step.outputs.iter().all(|pattern| !matches(&project.root, pattern).is_empty()) {
+            return false;
+        }
+        fs::read_to_string(&self.record).is_ok_and(|record| record == format!("{}\n{}\n", self.inputs, self.outputs))
+    }
+}
+
+/// Run `step` from the project root, inside the environment when there is one,
+/// then check and record its outputs
+pub fn run(project: &Project, config: &Config, step: &BuildStep, state: &StepState) -> Result<()> {
+    let _span = timings::span("build step");
+    let mut command = process::shell_command(&step.command);
+    command.current_dir(&project.root).envs(&state.env);
+    if let Some(venv) = Venv::open(project, config) {
+        venv.activate(&mut command)?;
+    }
+    let capture = capture::open(project, config, "build", &format!("step '{}' ({})", step.name, step.command))?;
+    let _subprocess = timings::subprocess(&command);
+    let status = capture::status(capture, &mut command).map_err(|_| PyForgeError::CommandNotFound {
+        command: step.command.clone(),
+    })?;
+    if !status.success() {
+        return Err(PyForgeError::command_failed(
+            format!("build step '{}' ({})", step.name, step.command),
+            status.code().unwrap_or(1),
+        ));
+    }
+
+    for pattern in &step.outputs {
+        if matches(&project.root, pattern).is_empty() {
+            return Err(PyForgeError::Usage {
+                message: format!(
+                    "Build step '{}' succeeded but produced nothing matching its output '{}'",
+                    step.name, pattern
+                ),
+            });
+        }
+    }
+
+    let outputs = hash_files(&project.root, &step.outputs)?;
+    if let Some(dir) = state.record.parent() {
+        fs::create_dir_all(dir).map_err(|e| PyForgeError::file_error(format!("Could not create {}", dir.display()), e))?;
+    }
+    fs::write(&state.record, format!("{}\n{}\n", state.inputs, outputs))
+        .map_err(|e| PyForgeError::file_error(format!("Could not write {}", state.record.display()), e))
+}
+
+/// Files matching a glob pattern relative to `root`
+fn matches(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let full = root.join(pattern);
+    let Ok(paths) = glob::glob(&full.to_string_lossy()) else {
+        return Vec::new();
+    };
+    let mut files = Vec::new();
+    for path in paths.flatten() {
+        if path.is_dir() {
+            files.extend(
+                walkdir::WalkDir::new(&path)
+                    .into_iter()
+                    .flatten()
+                    .filter(|entry| entry.file_type().is_file())
+                    .map(|entry| entry.into_path()),
+            );
+        } else if path.is_file() {
+            files.push(path);
+        }
+    }
+    files
+}
+
+/// Hash of the names and contents of every file matching `patterns`
+fn hash_files(root: &Path, patterns: &[String]) -> Result<String> {
+    let mut files: Vec<PathBuf> = patterns.iter().flat_map(|pattern| matches(root, pattern)).collect();
+    files.sort();
+    files.dedup();
+
+    let mut hasher = Sha256::new();
+    for file in files {
+        let content = fs::read(&file).map_err(|e| PyForgeError::file_error(format!("Could not read {}", file.display()), e))?;
+        hasher.update(file.strip_prefix(root).unwrap_or(&file).to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(&content);
+        hasher.update([0]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
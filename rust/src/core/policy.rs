@@ -0,0 +1,239 @@
+use crate::core::config::{Config, global_config_path, read_toml};
+use crate::core::dirs::cache_dir;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::lockfile::Lockfile;
+use crate::core::pep440::{SpecifierSet, Version};
+use crate::core::pep508::{Requirement, normalize_name};
+use crate::core::project::Project;
+use crate::core::remote;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// Machine-wide policy location, for rollouts managed by configuration tools
+pub const SYSTEM_POLICY_PATH: &str = "/etc/pyforge/policy.toml";
+
+/// Python versions checked when comparing `requires-python` ranges
+const PYTHON_MINORS: std::ops::RangeInclusive<u64> = 0..=20;
+
+/// Rules an organization enforces on every project (`policy.toml`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Policy {
+    pub name: String,
+
+    /// Index URL prefixes projects may use
+    pub allowed_indexes: Vec<String>,
+
+    /// Requirements that must not be used, e.g. `pycrypto` or `urllib3<1.26`
+    pub banned_packages: Vec<String>,
+
+    /// Python versions projects may declare support for
+    pub requires_python: Option<String>,
+
+    /// SPDX license identifiers allowed for the project and its dependencies
+    pub allowed_licenses: Vec<String>,
+
+    /// Where the policy was loaded from
+    #[serde(skip)]
+    pub source: String,
+}
+
+/// The `[policy]` table of the global config
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct PolicySource {
+    /// Path or http(s) URL of the policy file
+    source: String,
+
+    /// Expected SHA-256 of the policy file; tampered or unexpected files are rejected
+    sha256: Option<String>,
+}
+
+/// A single broken rule
+#[derive(Debug, Clone)]
+pub struct Violation {
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl Policy {
+    /// Load the active policy, if any.
+    ///
+    /// Only the global config (`[policy]`) or the system-wide policy file can
+    /// activate a policy, so a cloned project cannot opt itself out.
+    pub fn load() -> Result<Option<Self>> {
+        let global = read_toml(&global_config_path())?;
+        let source = match global.as_ref().and_then(|g| g.get("policy")) {
+            Some(table) => Some(table.clone().try_into::<PolicySource>().map_err(|e| PyForgeError::InvalidConfig {
+                file: global_config_path().display().to_string(),
+                source: Box::new(e),
+            })?),
+            None if std::path::Path::new(SYSTEM_POLICY_PATH).is_file() => Some(PolicySource {
+                source: SYSTEM_POLICY_PATH.to_string(),
+                sha256: None,
+            }),
+            None => None,
+        };
+
+        let Some(source) = source else {
+            return Ok(None);
+        };
+
+        let content = remote::fetch(&source.source, "policy", &cache_path())?;
+        remote::check_digest(&source.source, "policy", &content, source.sha256.as_deref())?;
+
+        let mut policy: Policy = toml::from_str(&content).map_err(|e| PyForgeError::InvalidToml {
+            file: source.source.clone(),
+            message: e.to_string(),
+        })?;
+        if policy.name.is_empty() {
+            policy.name = "organization policy".to_string();
+        }
+        policy.source = source.source;
+        Ok(Some(policy))
+    }
+
+    /// Whether an index URL is allowed
+    pub fn check_index(&self, url: &str) -> Option<Violation> {
+        let url = url.trim_end_matches('/');
+        let allowed = self.allowed_indexes.is_empty()
+            || self
+                .allowed_indexes
+                .iter()
+                .any(|prefix| url.starts_with(prefix.trim_end_matches('/')));
+
+        (!allowed).then(|| Violation {
+            rule: "allowed-indexes",
+            message: format!("index '{}' is not in the allowed list", url),
+        })
+    }
+
+    /// Whether a package (optionally at a specific version) is banned
+    pub fn check_package(&self, name: &str, version: Option<&Version>) -> Option<Violation> {
+        let name = normalize_name(name);
+        self.banned_packages.iter().find_map(|banned| {
+            let banned = Requirement::parse(banned).ok()?;
+            if banned.normalized_name() != name {
+                return None;
+            }
+            let matches = banned.specifier.is_empty()
+                || version.is_some_and(|v| banned.specifier.contains(v, true));
+            matches.then(|| Violation {
+                rule: "banned-packages",
+                message: match version {
+                    Some(version) => format!("{} {} is banned ({})", name, version, banned),
+                    None => format!("{} is banned ({})", name, banned),
+                },
+            })
+        })
+    }
+
+    /// Whether a project's `requires-python` stays within the allowed range
+    pub fn check_requires_python(&self, requires_python: Option<&str>) -> Option<Violation> {
+        let allowed: SpecifierSet = self.requires_python.as_ref()?.parse().ok()?;
+        let Some(declared) = requires_python else {
+            return Some(Violation {
+                rule: "requires-python",
+                message: format!("project must declare requires-python (allowed: {})", allowed),
+            });
+        };
+        let declared: SpecifierSet = declared.parse().ok()?;
+
+        let outside: Vec<String> = PYTHON_MINORS
+            .map(|minor| Version::parse(&format!("3.{}", minor)).expect("valid version"))
+            .filter(|v| declared.contains(v, true) && !allowed.contains(v, true))
+            .map(|v| v.to_string())
+            .collect();
+
+        (!outside.is_empty()).then(|| Violation {
+            rule: "requires-python",
+            message: format!(
+                "requires-python '{}' allows Python {} (allowed: {})",
+                declared,
+                outside.join(", "),
+                allowed
+            ),
+        })
+    }
+
+    /// Whether a license expression satisfies the allowlist
+    pub fn check_license(&self, package: &str, license: Option<&str>) -> Option<Violation> {
+        if self.allowed_licenses.is_empty() {
+            return None;
+        }
+        let allowed = license.is_some_and(|expression| {
+            // Any OR alternative may be chosen; every AND term must be allowed
+            expression.split(" OR ").any(|alternative| {
+                alternative.split(" AND ").all(|term| {
+                    let term = term.trim().trim_matches(['(', ')']).trim();
+                    self.allowed_licenses.iter().any(|l| l.eq_ignore_ascii_case(term))
+                })
+            })
+        });
+
+        (!allowed).then(|| Violation {
+            rule: "allowed-licenses",
+            message: format!("{} has license '{}', which is not allowed", package, license.unwrap_or("unknown")),
+        })
+    }
+
+    /// Check a project's configuration, declared dependencies and metadata
+    pub fn check_project(&self, project: &Project, config: &Config) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        for index in config.indexes() {
+            violations.extend(index.urls().filter_map(|url| self.check_index(url)));
+        }
+
+        let dependencies = project
+            .dependencies()
+            .into_iter()
+            .chain(project.optional_dependencies().into_iter().flat_map(|(_, deps)| deps));
+        for dependency in dependencies {
+            if let Ok(requirement) = Requirement::parse(&dependency) {
+                violations.extend(self.check_package(&requirement.name, None));
+            }
+        }
+
+        violations.extend(self.check_requires_python(project.requires_python()));
+        let name = project.name().unwrap_or("project");
+        violations.extend(self.check_license(name, project.license().as_deref()));
+        violations
+    }
+
+    /// Check the packages pinned in a lockfile
+    pub fn check_lock(&self, lockfile: &Lockfile) -> Vec<Violation> {
+        let indexes: std::collections::BTreeSet<&str> = lockfile
+            .packages
+            .iter()
+            .filter(|p| !p.index.is_empty())
+            .map(|p| p.index.as_str())
+            .collect();
+        let mut violations: Vec<Violation> = indexes.into_iter().filter_map(|index| self.check_index(index)).collect();
+        for package in &lockfile.packages {
+            let version = Version::parse(&package.version).ok();
+            violations.extend(self.check_package(&package.name, version.as_ref()));
+            violations.extend(self.check_license(&package.name, package.license.as_deref()));
+        }
+        violations
+    }
+
+    /// Turn violations into a policy error
+    pub fn enforce(&self, violations: Vec<Violation>) -> Result<()> {
+        if violations.is_empty() {
+            return Ok(());
+        }
+        Err(PyForgeError::PolicyViolation {
+            policy: self.name.clone(),
+            violations: violations
+                .into_iter()
+                .map(|v| format!("[{}] {}", v.rule, v.message))
+                .collect(),
+        })
+    }
+}
+
+/// Last successfully fetched remote policy, used when the source is unreachable
+fn cache_path() -> PathBuf {
+    cache_dir().join("policy-cache.toml")
+}
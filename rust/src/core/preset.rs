@@ -0,0 +1,246 @@
+use crate::core::dirs::config_dir;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::pep508::Requirement;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{Array, DocumentMut, Item, Table};
+
+/// Presets shipped with PyForge
+const BUILTIN_PRESETS: &[(&str, &str)] = &[
+    ("minimal", include_str!("../presets/minimal.toml")),
+    ("strict", include_str!("../presets/strict.toml")),
+    ("data", include_str!("../presets/data.toml")),
+];
+
+/// A declarative tooling bundle applied at init time.
+///
+/// Presets are TOML files: `[dependency-groups]` become optional dependency
+/// groups, `[tool.*]` tables are merged into `pyproject.toml`, and
+/// `[[pre-commit]]` entries produce `.pre-commit-config.yaml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Preset {
+    #[serde(skip)]
+    pub name: String,
+
+    #[serde(default)]
+    pub description: String,
+
+    #[serde(default)]
+    pub dependency_groups: BTreeMap<String, Vec<String>>,
+
+    #[serde(default)]
+    pub tool: toml::Table,
+
+    #[serde(default)]
+    pub pre_commit: Vec<PreCommitRepo>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PreCommitRepo {
+    pub repo: String,
+    pub rev: String,
+    pub hooks: Vec<String>,
+}
+
+/// Directory searched for user and organization presets
+pub fn user_preset_dir() -> PathBuf {
+    config_dir().join("presets")
+}
+
+impl Preset {
+    /// Resolve a preset by path, then user preset directory, then built-ins
+    pub fn load(name: &str) -> Result<Self> {
+        let path = Path::new(name);
+        if path.extension().is_some_and(|ext| ext == "toml") && path.is_file() {
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            return Self::from_file(&stem, path);
+        }
+
+        let user = user_preset_dir().join(format!("{}.toml", name));
+        if user.is_file() {
+            return Self::from_file(name, &user);
+        }
+
+        BUILTIN_PRESETS
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|(_, content)| Self::parse(name, content, "built-in preset"))
+            .unwrap_or_else(|| Err(PyForgeError::PresetNotFound { preset: name.to_string() }))
+    }
+
+    /// All presets available by name: user presets shadow built-ins
+    pub fn available() -> Vec<String> {
+        let mut names: Vec<String> = BUILTIN_PRESETS.iter().map(|(name, _)| name.to_string()).collect();
+        if let Ok(entries) = fs::read_dir(user_preset_dir()) {
+            for path in entries.flatten().map(|e| e.path()) {
+                if path.extension().is_some_and(|ext| ext == "toml")
+                    && let Some(stem) = path.file_stem()
+                {
+                    names.push(stem.to_string_lossy().to_string());
+                }
+            }
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    fn from_file(name: &str, path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| PyForgeError::file_error(format!("Could not read preset {}", path.display()), e))?;
+        Self::parse(name, &content, &path.display().to_string())
+    }
+
+    fn parse(name: &str, content: &str, origin: &str) -> Result<Self> {
+        let mut preset: Preset = toml::from_str(content).map_err(|e| PyForgeError::InvalidToml {
+            file: origin.to_string(),
+            message: e.to_string(),
+        })?;
+        preset.name = name.to_string();
+        Ok(preset)
+    }
+
+    /// Apply the preset to the project at `root`, returning the files written
+    pub fn apply(&self, root: &Path) -> Result<Vec<PathBuf>> {
+        let mut written = Vec::new();
+
+        let pyproject = root.join("pyproject.toml");
+        let content = fs::read_to_string(&pyproject)
+            .map_err(|e| PyForgeError::file_error(format!("Could not read {}", pyproject.display()), e))?;
+        let mut document = content.parse::<DocumentMut>().map_err(|e| PyForgeError::InvalidToml {
+            file: pyproject.display().to_string(),
+            message: e.to_string(),
+        })?;
+
+        self.merge_into(&mut document)?;
+        normalize_layout(&mut document);
+        fs::write(&pyproject, document.to_string())?;
+        written.push(pyproject);
+
+        if !self.pre_commit.is_empty() {
+            let path = root.join(".pre-commit-config.yaml");
+            fs::write(&path, self.pre_commit_config()?)?;
+            written.push(path);
+        }
+
+        Ok(written)
+    }
+
+    fn merge_into(&self, document: &mut DocumentMut) -> Result<()> {
+        add_dependency_groups(document, &self.dependency_groups);
+
+        if !self.tool.is_empty() {
+            let overlay = toml::Table::from_iter([("tool".to_string(), toml::Value::Table(self.tool.clone()))]);
+            merge_tables(document.as_table_mut(), &to_edit_table(&overlay)?);
+        }
+        Ok(())
+    }
+
+    fn pre_commit_config(&self) -> Result<String> {
+        #[derive(Serialize)]
+        struct Hook<'a> {
+            id: &'a str,
+        }
+        #[derive(Serialize)]
+        struct Repo<'a> {
+            repo: &'a str,
+            rev: &'a str,
+            hooks: Vec<Hook<'a>>,
+        }
+        #[derive(Serialize)]
+        struct PreCommitConfig<'a> {
+            repos: Vec<Repo<'a>>,
+        }
+
+        let config = PreCommitConfig {
+            repos: self
+                .pre_commit
+                .iter()
+                .map(|r| Repo {
+                    repo: &r.repo,
+                    rev: &r.rev,
+                    hooks: r.hooks.iter().map(|id| Hook { id }).collect(),
+                })
+                .collect(),
+        };
+        serde_yaml::to_string(&config).map_err(|e| PyForgeError::internal(format!("Could not render pre-commit config: {}", e)))
+    }
+}
+
+/// Add the requirements missing from each optional dependency group, keeping
+/// the ones already there
+pub fn add_dependency_groups(document: &mut DocumentMut, dependency_groups: &BTreeMap<String, Vec<String>>) {
+    for (group, requirements) in dependency_groups {
+        let project = document
+            .as_table_mut()
+            .entry("project")
+            .or_insert_with(toml_edit::table)
+            .as_table_like_mut();
+        let Some(groups) = project.map(|p| p.entry("optional-dependencies").or_insert(toml_edit::table())) else {
+            continue;
+        };
+        let Some(array) = groups
+            .as_table_like_mut()
+            .and_then(|g| g.entry(group).or_insert(Item::Value(Array::new().into())).as_array_mut())
+        else {
+            continue;
+        };
+        let present: Vec<String> = array
+            .iter()
+            .filter_map(|value| value.as_str())
+            .filter_map(|requirement| Requirement::parse(requirement).ok())
+            .map(|requirement| requirement.normalized_name())
+            .collect();
+        for requirement in requirements {
+            let name = Requirement::parse(requirement).map(|r| r.normalized_name()).unwrap_or_default();
+            if !present.contains(&name) {
+                array.push(requirement.as_str());
+            }
+        }
+    }
+}
+
+pub fn to_edit_table(table: &toml::Table) -> Result<Table> {
+    let rendered = toml::to_string(table).map_err(|e| PyForgeError::internal(e.to_string()))?;
+    let document = rendered
+        .parse::<DocumentMut>()
+        .map_err(|e| PyForgeError::internal(e.to_string()))?;
+    Ok(document.as_table().clone())
+}
+
+/// Merge `source` into `target`, keeping existing keys and formatting where possible
+pub fn merge_tables(target: &mut Table, source: &Table) {
+    for (key, item) in source.iter() {
+        match (target.get_mut(key), item) {
+            (Some(Item::Table(existing)), Item::Table(incoming)) => merge_tables(existing, incoming),
+            _ => {
+                target.insert(key, item.clone());
+            }
+        }
+    }
+}
+
+/// Renumber table positions depth-first so merged tables land next to their
+/// parents, separating each table header with a blank line
+pub fn normalize_layout(document: &mut DocumentMut) {
+    fn renumber(table: &mut Table, next: &mut usize) {
+        for (_, item) in table.iter_mut() {
+            if let Item::Table(child) = item {
+                if !child.is_implicit() {
+                    if *next > 0 && child.decor().prefix().is_none_or(|p| p.as_str() == Some("")) {
+                        child.decor_mut().set_prefix("\n");
+                    }
+                    child.set_position(*next);
+                    *next += 1;
+                }
+                renumber(child, next);
+            }
+        }
+    }
+
+    let mut next = 0;
+    renumber(document.as_table_mut(), &mut next);
+}
@@ -0,0 +1,154 @@
+use crate::core::error::{PyForgeError, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the project manifest
+pub const PYPROJECT_FILE: &str = "pyproject.toml";
+
+/// A Python project rooted at the directory containing `pyproject.toml`
+#[derive(Debug, Clone)]
+pub struct Project {
+    pub root: PathBuf,
+    pub pyproject: toml::Value,
+}
+
+impl Project {
+    /// Find the project containing the current directory, walking up to the filesystem root
+    pub fn discover() -> Result<Option<Self>> {
+        let cwd = std::env::current_dir()?;
+        Self::discover_from(&cwd)
+    }
+
+    /// Find the project containing `start`, walking up to the filesystem root
+    pub fn discover_from(start: &Path) -> Result<Option<Self>> {
+        for dir in start.ancestors() {
+            if dir.join(PYPROJECT_FILE).is_file() {
+                return Self::load(dir).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Like `discover`, but fails when no project is found
+    pub fn require() -> Result<Self> {
+        Self::discover()?.ok_or(PyForgeError::NotAPythonProject)
+    }
+
+    /// Load the project rooted at `root`
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(PYPROJECT_FILE);
+        let content = fs::read_to_string(&path)
+            .map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+        let pyproject = content.parse::<toml::Value>().map_err(|e| PyForgeError::InvalidToml {
+            file: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            pyproject,
+        })
+    }
+
+    /// Path to the project's `pyproject.toml`
+    pub fn pyproject_path(&self) -> PathBuf {
+        self.root.join(PYPROJECT_FILE)
+    }
+
+    /// Project name from `[project].name`
+    pub fn name(&self) -> Option<&str> {
+        self.pyproject.get("project")?.get("name")?.as_str()
+    }
+
+    /// Project version from `[project].version`
+    pub fn version(&self) -> Option<&str> {
+        self.pyproject.get("project")?.get("version")?.as_str()
+    }
+
+    /// `[project].requires-python`
+    pub fn requires_python(&self) -> Option<&str> {
+        self.pyproject.get("project")?.get("requires-python")?.as_str()
+    }
+
+    /// License expression from `[project].license`, either a string or `{ text = ... }`
+    pub fn license(&self) -> Option<String> {
+        let license = self.pyproject.get("project")?.get("license")?;
+        license
+            .as_str()
+            .or_else(|| license.get("text")?.as_str())
+            .map(str::to_string)
+    }
+
+    /// Build requirements from `[build-system].requires`
+    pub fn build_requires(&self) -> Vec<String> {
+        string_array(self.pyproject.get("build-system").and_then(|b| b.get("requires")))
+    }
+
+    /// PEP 517 backend from `[build-system].build-backend`
+    pub fn build_backend(&self) -> Option<&str> {
+        self.pyproject.get("build-system")?.get("build-backend")?.as_str()
+    }
+
+    /// In-tree backend directories from `[build-system].backend-path`
+    pub fn backend_path(&self) -> Vec<String> {
+        string_array(self.pyproject.get("build-system").and_then(|b| b.get("backend-path")))
+    }
+
+    /// Dependency specifiers from `[project.dependencies]`
+    pub fn dependencies(&self) -> Vec<String> {
+        string_array(self.pyproject.get("project").and_then(|p| p.get("dependencies")))
+    }
+
+    /// Dependency groups from `[project.optional-dependencies]`, keyed by group name
+    pub fn optional_dependencies(&self) -> Vec<(String, Vec<String>)> {
+        self.pyproject
+            .get("project")
+            .and_then(|p| p.get("optional-dependencies"))
+            .and_then(toml::Value::as_table)
+            .map(|groups| {
+                groups
+                    .iter()
+                    .map(|(name, deps)| (name.clone(), string_array(Some(deps))))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Edit `pyproject.toml` in place, preserving formatting and comments
+    pub fn update_pyproject(&mut self, edit: impl FnOnce(&mut toml_edit::DocumentMut)) -> Result<()> {
+        self.try_update_pyproject(|document| {
+            edit(document);
+            Ok(())
+        })
+    }
+
+    /// Like `update_pyproject`, leaving the file untouched when `edit` fails
+    pub fn try_update_pyproject(&mut self, edit: impl FnOnce(&mut toml_edit::DocumentMut) -> Result<()>) -> Result<()> {
+        let path = self.pyproject_path();
+        let content = fs::read_to_string(&path)
+            .map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+        let mut document = content.parse::<toml_edit::DocumentMut>().map_err(|e| PyForgeError::InvalidToml {
+            file: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+
+        edit(&mut document)?;
+        let updated = document.to_string();
+        fs::write(&path, &updated)
+            .map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))?;
+        self.pyproject = toml::from_str(&updated)?;
+        Ok(())
+    }
+
+    /// The `[tool.pyforge]` table, if present
+    pub fn tool_config(&self) -> Option<&toml::Value> {
+        self.pyproject.get("tool")?.get("pyforge")
+    }
+}
+
+fn string_array(value: Option<&toml::Value>) -> Vec<String> {
+    value
+        .and_then(toml::Value::as_array)
+        .map(|items| items.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,148 @@
+//! The standard `pylock.toml` lockfile format (PEP 751), for exchanging
+//! locks with other tools.
+//!
+//! A pyforge lock is resolved for one environment, so an exported
+//! `pylock.toml` lists that environment under `environments`, and installers
+//! refuse it elsewhere. Importing goes through [`crate::core::lockimport`].
+
+use crate::core::error::{PyForgeError, Result};
+use crate::core::lockfile::{LockedFile, LockedPackage, Lockfile};
+use crate::core::markers::Environment;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Version of PEP 751 written
+pub const LOCK_VERSION: &str = "1.0";
+
+/// Default file name; PEP 751 also allows `pylock.<name>.toml`
+pub const PYLOCK: &str = "pylock.toml";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Pylock {
+    pub lock_version: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub environments: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requires_python: Option<String>,
+    #[serde(default)]
+    pub created_by: String,
+    #[serde(default)]
+    pub packages: Vec<Package>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Package {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub marker: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<Dependency>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directory: Option<Directory>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vcs: Option<toml::Table>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive: Option<toml::Table>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sdist: Option<File>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wheels: Vec<File>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Dependency {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Directory {
+    pub path: String,
+    #[serde(default)]
+    pub editable: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct File {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub hashes: BTreeMap<String, String>,
+}
+
+impl Pylock {
+    /// The lock in PEP 751 form
+    pub fn from_lockfile(lockfile: &Lockfile) -> Self {
+        Self {
+            lock_version: LOCK_VERSION.to_string(),
+            environments: vec![environment_marker(&lockfile.environment)],
+            requires_python: lockfile.requires_python.clone(),
+            created_by: "pyforge".to_string(),
+            packages: lockfile.packages.iter().map(Package::from_locked).collect(),
+        }
+    }
+
+    pub fn parse(content: &str, file: &str) -> Result<Self> {
+        let pylock: Pylock = toml::from_str(content).map_err(|e| PyForgeError::InvalidToml {
+            file: file.to_string(),
+            message: e.to_string(),
+        })?;
+        if !pylock.lock_version.starts_with("1.") {
+            return Err(PyForgeError::ParseError {
+                file_type: PYLOCK.to_string(),
+                message: format!("{} has lock-version {}; only 1.x is supported", file, pylock.lock_version),
+            });
+        }
+        Ok(pylock)
+    }
+
+    pub fn render(&self) -> Result<String> {
+        let body = toml::to_string(self).map_err(|e| PyForgeError::internal(format!("Could not render {}: {}", PYLOCK, e)))?;
+        Ok(format!("# Exported by `pyforge lock export` from pyforge.lock.\n\n{}", body))
+    }
+}
+
+impl Package {
+    fn from_locked(package: &LockedPackage) -> Self {
+        let file = |locked: &LockedFile| File {
+            name: Some(locked.name.clone()),
+            url: Some(locked.url.clone()),
+            path: None,
+            hashes: locked.hashes.clone(),
+        };
+        let (wheels, sdists): (Vec<&LockedFile>, Vec<&LockedFile>) =
+            package.files.iter().partition(|locked| locked.name.ends_with(".whl"));
+        Self {
+            name: package.name.clone(),
+            version: Some(package.version.clone()),
+            marker: None,
+            index: (!package.index.is_empty()).then(|| package.index.clone()),
+            dependencies: package.dependencies.iter().map(|name| Dependency { name: name.clone() }).collect(),
+            directory: package.path.as_ref().map(|path| Directory {
+                path: path.clone(),
+                editable: false,
+            }),
+            vcs: None,
+            archive: None,
+            sdist: sdists.first().map(|locked| file(locked)),
+            wheels: wheels.into_iter().map(file).collect(),
+        }
+    }
+}
+
+/// A marker that only `environment` satisfies, as far as wheels can tell apart
+fn environment_marker(environment: &Environment) -> String {
+    format!(
+        "implementation_name == '{}' and python_version == '{}' and sys_platform == '{}' and platform_machine == '{}'",
+        environment.implementation_name, environment.python_version, environment.sys_platform, environment.platform_machine
+    )
+}
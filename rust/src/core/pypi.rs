@@ -0,0 +1,225 @@
+//! Uploading distributions to PyPI (or a compatible repository) through the
+//! legacy upload API that twine uses, authenticated with an API token
+//! (`pyforge auth login pypi`), and reading a project's collaborators through
+//! the XML-RPC API, the only part of the index API that exposes them.
+
+use crate::core::auth;
+use crate::core::config::{Config, NetworkConfig};
+use crate::core::dist::{DistFilename, DistKind};
+use crate::core::error::{PyForgeError, Result};
+use crate::core::index;
+use crate::core::pep508::normalize_name;
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// PyPI's upload endpoint
+pub const DEFAULT_REPOSITORY_URL: &str = "https://upload.pypi.org/legacy/";
+
+/// A repository accepting uploads, with the token to upload with
+pub struct Repository {
+    pub url: String,
+    token: String,
+    network: NetworkConfig,
+}
+
+impl Repository {
+    pub fn new(config: &Config) -> Result<Self> {
+        let url = Self::url(config);
+        let token = auth::require_token(&Self::service(config)?, &format!("upload to {}", url))?.secret;
+        Ok(Self {
+            url,
+            token,
+            network: NetworkConfig {
+                timeout: config.network.timeout.max(120),
+                ..config.network.clone()
+            },
+        })
+    }
+
+    fn url(config: &Config) -> String {
+        config.publish.repository_url.clone().unwrap_or_else(|| DEFAULT_REPOSITORY_URL.to_string())
+    }
+
+    /// The stored token uploads to the configured repository use, see `auth::upload_service`
+    fn service(config: &Config) -> Result<String> {
+        let url = Self::url(config);
+        auth::upload_service(&url).ok_or_else(|| PyForgeError::Usage {
+            message: format!("publish.repository-url must be an https URL, not '{}'", url),
+        })
+    }
+
+    /// Upload one sdist or wheel of `name` `version`
+    pub fn upload(&self, path: &Path, name: &str, version: &str) -> Result<()> {
+        let filename = path.file_name().and_then(|f| f.to_str()).unwrap_or_default().to_string();
+        let content = fs::read(path).map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+        let (filetype, pyversion) = match DistFilename::parse(&filename, name).map(|dist| dist.kind) {
+            Some(DistKind::Wheel { python, .. }) => ("bdist_wheel", python.join(".")),
+            _ => ("sdist", "source".to_string()),
+        };
+
+        let fields = [
+            (":action", "file_upload".to_string()),
+            ("protocol_version", "1".to_string()),
+            ("metadata_version", "2.1".to_string()),
+            ("name", name.to_string()),
+            ("version", version.to_string()),
+            ("filetype", filetype.to_string()),
+            ("pyversion", pyversion),
+            ("sha256_digest", format!("{:x}", Sha256::digest(&content))),
+        ];
+        let boundary = format!(
+            "pyforge-{:x}",
+            Sha256::digest(format!("{:?}{}", SystemTime::now(), filename).as_bytes())
+        );
+        let mut body = Vec::new();
+        for (field, value) in &fields {
+            body.extend(format!("--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n", boundary, field, value).bytes());
+        }
+        body.extend(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"content\"; filename=\"{}\"\r\n\
+                 Content-Type: application/octet-stream\r\n\r\n",
+                boundary, filename
+            )
+            .bytes(),
+        );
+        body.extend(&content);
+        body.extend(format!("\r\n--{}--\r\n", boundary).bytes());
+
+        let client = index::client_builder(&self.network)?
+            .build()
+            .map_err(|e| PyForgeError::network_error("Could not create HTTP client", Some(e)))?;
+        let response = client
+            .post(&self.url)
+            .basic_auth("__token__", Some(&self.token))
+            .header("Content-Type", format!("multipart/form-data; boundary={}", boundary))
+            .body(body)
+            .send()
+            .map_err(|e| PyForgeError::network_error(format!("Could not upload {}", filename), Some(e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            // Warehouse puts the reason in the status line and an HTML body
+            let reason = status.canonical_reason().unwrap_or_default();
+            let text = response.text().unwrap_or_default();
+            let detail = text.lines().find(|line| !line.trim().is_empty() && !line.contains('<')).unwrap_or(reason);
+            return Err(PyForgeError::network_error(
+                format!("Could not upload {}: HTTP {} {}", filename, status.as_u16(), detail.trim()),
+                None,
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A collaborator on a project, e.g. `Owner` `alice`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Role {
+    pub role: String,
+    pub user: String,
+}
+
+/// The index's website: the upload host without its `upload.` prefix
+/// (`https://upload.pypi.org/legacy/` → `https://pypi.org`)
+pub fn web_url(config: &Config) -> String {
+    let repository = config.publish.repository_url.as_deref().unwrap_or(DEFAULT_REPOSITORY_URL);
+    match reqwest::Url::parse(repository) {
+        Ok(url) => {
+            let host = url.host_str().unwrap_or_default();
+            let host = host.strip_prefix("upload.").unwrap_or(host);
+            match url.port() {
+                Some(port) => format!("{}://{}:{}", url.scheme(), host, port),
+                None => format!("{}://{}", url.scheme(), host),
+            }
+        }
+        Err(_) => repository.trim_end_matches('/').to_string(),
+    }
+}
+
+/// Page where a release's owner can yank it
+pub fn manage_release_url(config: &Config, name: &str, version: &str) -> String {
+    format!("{}/manage/project/{}/release/{}/", web_url(config), normalize_name(name), version)
+}
+
+/// Page where owners invite and remove collaborators
+pub fn manage_collaboration_url(config: &Config, name: &str) -> String {
+    format!("{}/manage/project/{}/collaboration/", web_url(config), normalize_name(name))
+}
+
+/// Collaborators of `name`, via the XML-RPC `package_roles` call; an unknown
+/// project has none
+pub fn package_roles(config: &Config, name: &str) -> Result<Vec<Role>> {
+    let endpoint = format!("{}/pypi", web_url(config));
+    let request = format!(
+        "<?xml version=\"1.0\"?>\n<methodCall><methodName>package_roles</methodName>\
+         <params><param><value><string>{}</string></value></param></params></methodCall>\n",
+        escape_xml(name)
+    );
+    let client = index::client_builder(&config.network)?
+        .build()
+        .map_err(|e| PyForgeError::network_error("Could not create HTTP client", Some(e)))?;
+    let response = client
+        .post(&endpoint)
+        .header("Content-Type", "text/xml")
+        .body(request)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| PyForgeError::network_error(format!("Could not query {}", endpoint), Some(e)))?;
+    let body = response
+        .text()
+        .map_err(|e| PyForgeError::network_error(format!("Could not read the response of {}", endpoint), Some(e)))?;
+    if body.contains("<fault>") {
+        return Err(PyForgeError::network_error(
+            format!("{} rejected package_roles: {}", endpoint, xml_values(&body).join(" ")),
+            None,
+        ));
+    }
+
+    // The result is an array of [role, user] pairs
+    let values = xml_values(&body);
+    Ok(values
+        .chunks_exact(2)
+        .map(|pair| Role {
+            role: pair[0].clone(),
+            user: pair[1].clone(),
+        })
+        .collect())
+}
+
+/// Scalar string values of an XML-RPC response, in document order
+fn xml_values(body: &str) -> Vec<String> {
+    let pattern = Regex::new(r"<value>(?:<string>)?([^<]*)(?:</string>)?</value>").expect("valid regex");
+    pattern
+        .captures_iter(body)
+        .map(|captures| {
+            captures[1]
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&amp;", "&")
+        })
+        .collect()
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pypi_token_is_only_for_pypi() {
+        assert_eq!(Repository::service(&Config::default()).unwrap(), "pypi");
+
+        let project: Config = toml::from_str("[publish]\nrepository-url = \"https://uploads.attacker.example/legacy/\"").unwrap();
+        assert_eq!(Repository::service(&project).unwrap(), "pypi:uploads.attacker.example");
+
+        let plain: Config = toml::from_str("[publish]\nrepository-url = \"http://upload.pypi.org/legacy/\"").unwrap();
+        assert!(Repository::service(&plain).is_err());
+    }
+}
@@ -0,0 +1,246 @@
+//! Python interpreter discovery and per-project pins.
+//!
+//! Interpreters are found on PATH, in pyenv and uv installs, and in the
+//! usual install locations of each platform. A project pins one with
+//! `[tool.pyforge] python = "3.12"` or a `.python-version` file; the global
+//! `python` setting applies to projects without a pin.
+
+use crate::core::error::{PyForgeError, Result};
+use crate::core::pep440::Version;
+use crate::core::project::Project;
+use crate::core::timings;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use std::thread;
+
+/// Pin file shared with pyenv, uv and other tools
+pub const VERSION_FILE: &str = ".python-version";
+
+/// An interpreter found on this machine
+#[derive(Debug, Clone)]
+pub struct Interpreter {
+    pub path: PathBuf,
+    pub version: Version,
+}
+
+/// What a pin asks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    /// A version or version prefix, e.g. `3.12` or `3.12.4`
+    Version(String),
+    /// A command on PATH or a path, e.g. `python3.12` or `/opt/py/bin/python`
+    Command(String),
+}
+
+impl Request {
+    pub fn parse(value: &str) -> Self {
+        let value = value.trim();
+        let is_version = !value.is_empty() && value.split('.').all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()));
+        if is_version {
+            Request::Version(value.to_string())
+        } else {
+            Request::Command(value.to_string())
+        }
+    }
+}
+
+impl std::fmt::Display for Request {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Request::Version(value) | Request::Command(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Where a pin comes from, for messages
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PinSource {
+    Pyproject,
+    VersionFile(PathBuf),
+    GlobalConfig,
+}
+
+impl std::fmt::Display for PinSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PinSource::Pyproject => write!(f, "[tool.pyforge] python"),
+            PinSource::VersionFile(path) => write!(f, "{}", path.display()),
+            PinSource::GlobalConfig => write!(f, "python in the global config"),
+        }
+    }
+}
+
+/// The interpreter `project` is pinned to: `[tool.pyforge] python`, else its
+/// `.python-version`, else the global `python` setting
+pub fn pin(project: Option<&Project>, global: Option<&str>) -> Option<(Request, PinSource)> {
+    if let Some(project) = project {
+        let configured = project
+            .pyproject
+            .get("tool")
+            .and_then(|tool| tool.get("pyforge"))
+            .and_then(|pyforge| pyforge.get("python"))
+            .and_then(|python| python.as_str());
+        if let Some(python) = configured {
+            return Some((Request::parse(python), PinSource::Pyproject));
+        }
+        let file = project.root.join(VERSION_FILE);
+        if let Some(version) = read_version_file(&file) {
+            return Some((Request::parse(&version), PinSource::VersionFile(file)));
+        }
+    }
+    global.map(|python| (Request::parse(python), PinSource::GlobalConfig))
+}
+
+/// The first entry of a `.python-version` file; `system` means no pin
+fn read_version_file(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter(|line| *line != "system")
+        .map(str::to_string)
+}
+
+/// Write `version` as the project's `.python-version`
+pub fn write_pin(root: &Path, version: &str) -> Result<PathBuf> {
+    let path = root.join(VERSION_FILE);
+    fs::write(&path, format!("{}\n", version)).map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))?;
+    Ok(path)
+}
+
+/// The interpreter for `request`: the newest discovered one of a version,
+/// or a command or path as given. Without a request, `python3` or `python` on PATH.
+pub fn find(request: Option<&Request>) -> Result<PathBuf> {
+    match request {
+        Some(Request::Version(version)) => matching(version)
+            .map(|interpreter| interpreter.path)
+            .ok_or_else(|| PyForgeError::UnsupportedPythonVersion { version: version.clone() }),
+        Some(Request::Command(command)) => {
+            let path = which::which(command).unwrap_or_else(|_| PathBuf::from(command));
+            if path.is_file() {
+                Ok(path)
+            } else {
+                Err(PyForgeError::CommandNotFound { command: command.clone() })
+            }
+        }
+        None => which::which("python3")
+            .or_else(|_| which::which("python"))
+            .map_err(|_| PyForgeError::CommandNotFound { command: "python3".to_string() }),
+    }
+}
+
+/// The newest installed interpreter of `version`, e.g. `3.12`
+pub fn installed(version: &str) -> Result<Interpreter> {
+    matches!(Request::parse(version), Request::Version(_))
+        .then(|| matching(version))
+        .flatten()
+        .ok_or_else(|| PyForgeError::UnsupportedPythonVersion { version: version.to_string() })
+}
+
+/// The newest discovered interpreter whose version starts with `version`
+pub fn matching(version: &str) -> Option<Interpreter> {
+    // Newest first, so the first match is the one to use
+    discover().iter().find(|interpreter| version_matches(&interpreter.version, version)).cloned()
+}
+
+/// Whether `version` starts with the release components of `prefix`: `3.12.4` matches `3.12`
+pub fn version_matches(version: &Version, prefix: &str) -> bool {
+    prefix
+        .split('.')
+        .enumerate()
+        .all(|(i, part)| part.parse::<u64>().is_ok_and(|part| version.release_at(i) == part))
+}
+
+/// Every interpreter found on this machine, newest first; found once per run
+pub fn discover() -> &'static [Interpreter] {
+    static FOUND: OnceLock<Vec<Interpreter>> = OnceLock::new();
+    FOUND.get_or_init(|| {
+        let _span = timings::span("find python");
+        let mut seen = HashSet::new();
+        // The same interpreter is often reachable by several paths; keep the first
+        let candidates: Vec<PathBuf> = candidates()
+            .into_iter()
+            .filter(|path| path.is_file())
+            .filter(|path| seen.insert(fs::canonicalize(path).unwrap_or_else(|_| path.clone())))
+            .collect();
+        let versions: Vec<Option<Version>> = thread::scope(|scope| {
+            let probes: Vec<_> = candidates.iter().map(|path| scope.spawn(move || version_of(path))).collect();
+            probes.into_iter().map(|probe| probe.join().ok().flatten()).collect()
+        });
+        let mut found: Vec<Interpreter> = candidates
+            .into_iter()
+            .zip(versions)
+            .filter_map(|(path, version)| Some(Interpreter { path, version: version? }))
+            .collect();
+        // Stable, so PATH order decides between equal versions
+        found.sort_by(|a, b| b.version.cmp(&a.version));
+        found
+    })
+}
+
+/// The version `python` reports, if it runs
+pub fn version_of(python: &Path) -> Option<Version> {
+    let mut command = Command::new(python);
+    command.args(["-c", "import platform; print(platform.python_version())"]);
+    let _subprocess = timings::subprocess(&command);
+    let output = command.output().ok().filter(|output| output.status.success())?;
+    Version::parse(String::from_utf8_lossy(&output.stdout).trim()).ok()
+}
+
+/// Paths that may be interpreters: PATH first, then pyenv, uv and install locations
+fn candidates() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    for dir in std::env::split_paths(&std::env::var_os("PATH").unwrap_or_default()) {
+        // pyenv shims run whichever version is active, not a fixed one
+        if dir.ends_with("shims") {
+            continue;
+        }
+        if let Ok(entries) = fs::read_dir(&dir) {
+            let mut names: Vec<PathBuf> =
+                entries.flatten().map(|entry| entry.path()).filter(|path| is_interpreter_name(path)).collect();
+            names.sort();
+            candidates.extend(names);
+        }
+    }
+
+    let home = home::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let pyenv = std::env::var_os("PYENV_ROOT").map(PathBuf::from).unwrap_or_else(|| home.join(".pyenv"));
+    let mut patterns = vec![
+        pyenv.join("versions/*/bin/python3"),
+        pyenv.join("versions/*/python.exe"),
+        home.join(".local/share/uv/python/*/bin/python3"),
+    ];
+    if cfg!(windows) {
+        if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+            patterns.push(PathBuf::from(local).join("Programs/Python/Python3*/python.exe"));
+        }
+        patterns.push(PathBuf::from("C:/Python3*/python.exe"));
+    } else {
+        for dir in ["/usr/bin", "/usr/local/bin", "/opt/homebrew/bin"] {
+            patterns.push(Path::new(dir).join("python3*"));
+        }
+        patterns.push(PathBuf::from("/Library/Frameworks/Python.framework/Versions/*/bin/python3"));
+    }
+    for pattern in patterns {
+        if let Ok(paths) = glob::glob(&pattern.to_string_lossy()) {
+            candidates.extend(paths.flatten().filter(|path| is_interpreter_name(path)));
+        }
+    }
+    candidates
+}
+
+/// `python`, `python3` or `python3.12`, not `python3-config` or `python3.12m`
+fn is_interpreter_name(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let name = name.strip_suffix(".exe").unwrap_or(name);
+    let Some(rest) = name.strip_prefix("python") else {
+        return false;
+    };
+    rest.is_empty() || rest == "3" || rest.strip_prefix("3.").is_some_and(|minor| !minor.is_empty() && minor.bytes().all(|b| b.is_ascii_digit()))
+}
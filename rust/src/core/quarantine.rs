@@ -0,0 +1,162 @@
+//! Packages that run code of their own when installed, held back during a
+//! sync until someone has looked at them.
+//!
+//! Like the vetting heuristics this is a safety net, not a scanner: it only
+//! notices the usual ways a distribution gets code run outside an import —
+//! `.pth` files executed at interpreter start, scripts copied into the
+//! environment, commands shadowing common tools, and sdists whose build runs
+//! code from the archive itself.
+
+use crate::core::error::{PyForgeError, Result};
+use crate::core::pep508::normalize_name;
+use flate2::read::GzDecoder;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// Commands a package has no business replacing
+const SHADOWED_COMMANDS: &[&str] = &[
+    "python", "python3", "pip", "pip3", "sh", "bash", "zsh", "env", "sudo", "su", "ssh", "scp", "git", "curl", "wget",
+    "ls", "cp", "mv", "rm", "cat", "which", "make", "node", "npm", "docker", "kubectl",
+];
+
+/// Something a distribution does when installed or at interpreter start
+#[derive(Debug, Clone)]
+pub struct Hook {
+    pub kind: &'static str,
+    pub message: String,
+}
+
+/// A package held back until its hooks are approved
+#[derive(Debug, Clone)]
+pub struct Held {
+    pub package: String,
+    pub version: String,
+    pub hooks: Vec<Hook>,
+}
+
+/// The hooks of the wheel or sdist at `path`, for package `name`
+pub fn inspect(path: &Path, name: &str) -> Result<Vec<Hook>> {
+    let filename = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let file = fs::File::open(path).map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+    if filename.ends_with(".whl") {
+        inspect_wheel(file, name)
+    } else if filename.ends_with(".tar.gz") {
+        inspect_sdist(file)
+    } else {
+        Ok(vec![Hook {
+            kind: "source-build",
+            message: format!("{} is built from source when installed", filename),
+        }])
+    }
+}
+
+fn inspect_wheel(file: fs::File, name: &str) -> Result<Vec<Hook>> {
+    let mut archive = zip::ZipArchive::new(file)?;
+    let mut hooks = Vec::new();
+    for index in 0..archive.len() {
+        let Ok(mut entry) = archive.by_index(index) else {
+            continue;
+        };
+        if entry.is_dir() {
+            continue;
+        }
+        let path = entry.name().to_string();
+        // `.pth` files are read from site-packages itself, which `*.data/purelib` and `platlib` install into
+        let installed = match path.split_once('/') {
+            Some((top, rest)) if top.ends_with(".data") => {
+                rest.strip_prefix("purelib/").or_else(|| rest.strip_prefix("platlib/")).unwrap_or(rest).to_string()
+            }
+            _ => path.clone(),
+        };
+
+        if let Some(script) = path
+            .split_once('/')
+            .filter(|(top, _)| top.ends_with(".data"))
+            .and_then(|(_, rest)| rest.strip_prefix("scripts/"))
+        {
+            hooks.push(Hook {
+                kind: "script",
+                message: format!("installs the script {} into the environment", script),
+            });
+        } else if installed.ends_with(".pth") && !installed.contains('/') {
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap_or_default();
+            if content.lines().any(|line| line.starts_with("import ") || line.starts_with("import\t")) {
+                hooks.push(Hook {
+                    kind: "startup-code",
+                    message: format!("adds {}, which runs code whenever Python starts", installed),
+                });
+            }
+        } else if path.ends_with(".dist-info/entry_points.txt") && path.matches('/').count() == 1 {
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap_or_default();
+            for command in shadowed_commands(&content, name) {
+                hooks.push(Hook {
+                    kind: "shadowed-command",
+                    message: format!("installs a '{}' command, which shadows the usual one", command),
+                });
+            }
+        }
+    }
+    Ok(hooks)
+}
+
+/// Console and GUI scripts named like common tools, apart from the package's own (`pip` installing `pip3`)
+fn shadowed_commands(entry_points: &str, name: &str) -> Vec<String> {
+    let name = normalize_name(name);
+    let mut section = "";
+    let mut commands = Vec::new();
+    for line in entry_points.lines().map(str::trim) {
+        if let Some(header) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            section = header.trim();
+            continue;
+        }
+        if !matches!(section, "console_scripts" | "gui_scripts") {
+            continue;
+        }
+        let Some((command, _)) = line.split_once('=') else {
+            continue;
+        };
+        let command = command.trim();
+        if SHADOWED_COMMANDS.contains(&command) && !normalize_name(command).starts_with(&name) {
+            commands.push(command.to_string());
+        }
+    }
+    commands
+}
+
+fn inspect_sdist(file: fs::File) -> Result<Vec<Hook>> {
+    let mut hooks = Vec::new();
+    for entry in tar::Archive::new(GzDecoder::new(file)).entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().to_string();
+        // Only the `app-1.0/` directory's own files decide how it builds
+        let Some((_, relative)) = name.split_once('/') else {
+            continue;
+        };
+        match relative {
+            "setup.py" => hooks.push(Hook {
+                kind: "source-build",
+                message: "is built from source by running its setup.py".to_string(),
+            }),
+            "pyproject.toml" => {
+                let mut content = String::new();
+                entry.read_to_string(&mut content).unwrap_or_default();
+                let in_tree = content
+                    .parse::<toml::Table>()
+                    .ok()
+                    .and_then(|table| table.get("build-system")?.get("backend-path").cloned())
+                    .is_some();
+                if in_tree {
+                    hooks.push(Hook {
+                        kind: "source-build",
+                        message: "is built from source with a build backend shipped inside the sdist".to_string(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(hooks)
+}
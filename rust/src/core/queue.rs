@@ -0,0 +1,299 @@
+//! The project's command queue (`pyforge q`).
+//!
+//! Each job is a `<id>.toml` file in `.pyforge/queue`, with its output in
+//! `<id>.log` next to it. `pyforge q <command>` writes a job and starts a
+//! worker in the background; the worker runs queued jobs oldest first, at
+//! most `[tool.pyforge.queue] jobs` at a time, and exits when none are left.
+//! A lock on `worker.lock` keeps it to one worker per project.
+
+use crate::core::error::{PyForgeError, Result};
+use crate::core::project::Project;
+use crate::core::utils::timestamp;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const WORKER_LOCK: &str = "worker.lock";
+
+/// How often the worker checks on running jobs and new ones
+const POLL: Duration = Duration::from_millis(200);
+
+/// A queued pyforge command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Job {
+    #[serde(skip)]
+    pub id: u64,
+
+    /// Arguments to pyforge, e.g. `["test", "-k", "slow"]`
+    pub args: Vec<String>,
+
+    /// Directory the job was queued from, and runs in
+    pub dir: PathBuf,
+
+    pub queued: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished: Option<String>,
+
+    /// Exit code; missing for a finished job that was interrupted or killed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit: Option<i32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seconds: Option<f64>,
+}
+
+/// Where a job is in the queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Queued,
+    Running,
+    /// Finished with this exit code
+    Done(i32),
+    /// The worker or the job was killed before it finished
+    Interrupted,
+}
+
+impl Job {
+    pub fn state(&self) -> State {
+        match (&self.started, &self.finished, self.exit) {
+            (None, _, _) => State::Queued,
+            (Some(_), None, _) => State::Running,
+            (Some(_), Some(_), Some(code)) => State::Done(code),
+            (Some(_), Some(_), None) => State::Interrupted,
+        }
+    }
+
+    /// `pyforge test -k slow`
+    pub fn command(&self) -> String {
+        std::iter::once("pyforge").chain(self.args.iter().map(String::as_str)).collect::<Vec<_>>().join(" ")
+    }
+
+    fn save(&self, dir: &Path) -> Result<()> {
+        let body = toml::to_string(self).map_err(|e| PyForgeError::internal(format!("Could not render job {}: {}", self.id, e)))?;
+        // Written aside and renamed, so a reader never sees half a job
+        let path = job_path(dir, self.id);
+        let partial = path.with_extension("toml.tmp");
+        fs::write(&partial, body).map_err(|e| PyForgeError::file_error(format!("Could not write {}", partial.display()), e))?;
+        fs::rename(&partial, &path).map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))
+    }
+
+    fn finish(&mut self, dir: &Path, exit: Option<i32>, seconds: f64) -> Result<()> {
+        self.finished = Some(timestamp());
+        self.exit = exit;
+        self.seconds = Some(seconds);
+        self.save(dir)
+    }
+}
+
+/// Directory holding the project's jobs and their output
+pub fn queue_dir(project: &Project) -> PathBuf {
+    project.root.join(".pyforge").join("queue")
+}
+
+fn job_path(dir: &Path, id: u64) -> PathBuf {
+    dir.join(format!("{}.toml", id))
+}
+
+/// File the output of job `id` goes to
+pub fn log_path(project: &Project, id: u64) -> PathBuf {
+    queue_dir(project).join(format!("{}.log", id))
+}
+
+/// The project's jobs, oldest first
+pub fn jobs(project: &Project) -> Result<Vec<Job>> {
+    read_jobs(&queue_dir(project))
+}
+
+fn read_jobs(dir: &Path) -> Result<Vec<Job>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut jobs = Vec::new();
+    for entry in fs::read_dir(dir).map_err(|e| PyForgeError::file_error(format!("Could not read {}", dir.display()), e))? {
+        let path = entry?.path();
+        let Some(id) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".toml"))
+            .and_then(|id| id.parse().ok())
+        else {
+            continue;
+        };
+        // A job being created may still be empty; the next read sees it
+        let Some(mut job) = fs::read_to_string(&path).ok().and_then(|content| toml::from_str::<Job>(&content).ok()) else {
+            continue;
+        };
+        job.id = id;
+        jobs.push(job);
+    }
+    jobs.sort_by_key(|job| job.id);
+    Ok(jobs)
+}
+
+/// Queue `args` to run from `dir`, and start a worker unless one is running
+pub fn enqueue(project: &Project, args: &[String], dir: &Path) -> Result<Job> {
+    let queue = queue_dir(project);
+    fs::create_dir_all(&queue).map_err(|e| PyForgeError::file_error(format!("Could not create {}", queue.display()), e))?;
+    let ignore = project.root.join(".pyforge").join(".gitignore");
+    if !ignore.exists() {
+        fs::write(&ignore, "*\n").map_err(|e| PyForgeError::file_error(format!("Could not write {}", ignore.display()), e))?;
+    }
+
+    let mut job = Job {
+        id: read_jobs(&queue)?.last().map_or(1, |job| job.id + 1),
+        args: args.to_vec(),
+        dir: dir.to_path_buf(),
+        queued: timestamp(),
+        started: None,
+        finished: None,
+        exit: None,
+        seconds: None,
+    };
+    // Claim the id, taking the next one when another `pyforge q` got there first
+    loop {
+        match OpenOptions::new().write(true).create_new(true).open(job_path(&queue, job.id)) {
+            Ok(_) => break,
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => job.id += 1,
+            Err(e) => return Err(PyForgeError::file_error(format!("Could not create a job in {}", queue.display()), e)),
+        }
+    }
+    job.save(&queue)?;
+    start_worker(project)?;
+    Ok(job)
+}
+
+/// Start `pyforge q work` detached from this terminal; it exits at once when
+/// another worker holds the lock
+fn start_worker(project: &Project) -> Result<()> {
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .args(["q", "work"])
+        .current_dir(&project.root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Its own process group, so Ctrl-C in this terminal doesn't stop the queue
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    command.spawn().map_err(|e| PyForgeError::file_error("Could not start the queue worker", e))?;
+    Ok(())
+}
+
+fn open_lock(project: &Project) -> Result<File> {
+    let path = queue_dir(project).join(WORKER_LOCK);
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .map_err(|e| PyForgeError::file_error(format!("Could not open {}", path.display()), e))
+}
+
+/// Whether a worker is running jobs of the project
+pub fn worker_running(project: &Project) -> Result<bool> {
+    if !queue_dir(project).is_dir() {
+        return Ok(false);
+    }
+    // Locking succeeds only when no worker holds it; dropping the file releases it
+    Ok(open_lock(project)?.try_lock().is_err())
+}
+
+/// Run queued jobs until there are none left, `jobs` at a time
+pub fn work(project: &Project, jobs: usize) -> Result<()> {
+    let queue = queue_dir(project);
+    let lock = open_lock(project)?;
+    loop {
+        if lock.try_lock().is_err() {
+            return Ok(());
+        }
+        // Jobs a killed worker left behind won't finish
+        for mut job in read_jobs(&queue)?.into_iter().filter(|job| job.state() == State::Running) {
+            job.finish(&queue, None, 0.0)?;
+        }
+        run_queued(project, &queue, jobs.max(1))?;
+        lock.unlock()?;
+        // A job queued while the lock was held, whose worker gave up on it
+        if !read_jobs(&queue)?.iter().any(|job| job.state() == State::Queued) {
+            return Ok(());
+        }
+    }
+}
+
+fn run_queued(project: &Project, queue: &Path, limit: usize) -> Result<()> {
+    let exe = std::env::current_exe()?;
+    let mut running: Vec<(Job, Child, Instant)> = Vec::new();
+    loop {
+        let mut still_running = Vec::new();
+        for (mut job, mut child, started) in running {
+            match child.try_wait()? {
+                Some(status) => job.finish(queue, status.code(), started.elapsed().as_secs_f64())?,
+                None => still_running.push((job, child, started)),
+            }
+        }
+        running = still_running;
+
+        let mut queued = read_jobs(queue)?.into_iter().filter(|job| job.state() == State::Queued);
+        while running.len() < limit {
+            let Some(mut job) = queued.next() else {
+                break;
+            };
+            job.started = Some(timestamp());
+            job.save(queue)?;
+            match start(project, &exe, &job) {
+                Ok(child) => running.push((job, child, Instant::now())),
+                Err(error) => {
+                    if let Ok(mut log) = OpenOptions::new().append(true).create(true).open(log_path(project, job.id)) {
+                        let _ = writeln!(log, "Could not start {}: {}", job.command(), error);
+                    }
+                    job.finish(queue, Some(-1), 0.0)?;
+                }
+            }
+        }
+
+        if running.is_empty() {
+            return Ok(());
+        }
+        thread::sleep(POLL);
+    }
+}
+
+fn start(project: &Project, exe: &Path, job: &Job) -> Result<Child> {
+    let path = log_path(project, job.id);
+    let log = File::create(&path).map_err(|e| PyForgeError::file_error(format!("Could not create {}", path.display()), e))?;
+    let child = Command::new(exe)
+        .args(&job.args)
+        .current_dir(&job.dir)
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log)
+        .spawn()?;
+    Ok(child)
+}
+
+/// Remove finished jobs and their output, returning how many
+pub fn clear(project: &Project) -> Result<usize> {
+    let queue = queue_dir(project);
+    let mut removed = 0;
+    for job in read_jobs(&queue)? {
+        if matches!(job.state(), State::Queued | State::Running) {
+            continue;
+        }
+        let path = job_path(&queue, job.id);
+        fs::remove_file(&path).map_err(|e| PyForgeError::file_error(format!("Could not remove {}", path.display()), e))?;
+        let _ = fs::remove_file(log_path(project, job.id));
+        removed += 1;
+    }
+    Ok(removed)
+}
@@ -0,0 +1,227 @@
+//! What an upgrade touches (`pyforge bump`): for each changed package, the
+//! direct dependencies that pull it in and the project modules that import
+//! it or one of those, with a rough blast radius.
+
+use crate::core::dist::Metadata;
+use crate::core::inline;
+use crate::core::lockfile::{Lockfile, VersionChange};
+use crate::core::pep440::Version;
+use crate::core::pep508::normalize_name;
+use crate::core::project::Project;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Directories never searched for project modules
+const SKIPPED_DIRS: &[&str] = &[".git", ".venv", "venv", "node_modules", "build", "dist", "__pycache__", ".tox", ".nox"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Radius {
+    Low,
+    Medium,
+    High,
+}
+
+impl fmt::Display for Radius {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Radius::Low => "low",
+            Radius::Medium => "medium",
+            Radius::High => "high",
+        })
+    }
+}
+
+/// How one version change reaches the project
+#[derive(Debug, Clone)]
+pub struct Reach {
+    pub package: String,
+    /// Whether the project depends on the package itself
+    pub direct: bool,
+    /// Direct dependencies that require the package, directly or further down
+    pub via: Vec<String>,
+    /// Project modules importing the package or one of `via`, e.g. `app.api`
+    pub modules: Vec<String>,
+    /// The major version changes (or the minor one, before 1.0)
+    pub breaking: bool,
+    pub radius: Radius,
+}
+
+/// How far an upgrade reaches
+#[derive(Debug, Clone)]
+pub struct Analysis {
+    pub reaches: Vec<Reach>,
+    /// How many modules the project has, for the share a change reaches
+    pub modules: usize,
+}
+
+impl Analysis {
+    pub fn get(&self, package: &str) -> Option<&Reach> {
+        self.reaches.iter().find(|reach| reach.package == package)
+    }
+
+    /// The widest radius of any change
+    pub fn radius(&self) -> Radius {
+        self.reaches.iter().map(|reach| reach.radius).max().unwrap_or(Radius::Low)
+    }
+}
+
+/// The reach of each of `changes`, from the `previous` lockfile's graph and
+/// the project's imports; installed packages in `site_packages` tell which
+/// modules each distribution provides
+pub fn analyze(
+    project: &Project,
+    previous: &Lockfile,
+    changes: &[VersionChange],
+    site_packages: Option<&Path>,
+) -> Analysis {
+    let direct: BTreeSet<String> = crate::core::lockfile::requirements(project)
+        .unwrap_or_default()
+        .iter()
+        .map(|requirement| requirement.normalized_name())
+        .collect();
+    let provided = site_packages.map(provided_modules).unwrap_or_default();
+    let modules_of = |package: &str| -> BTreeSet<String> {
+        provided
+            .get(package)
+            .cloned()
+            .unwrap_or_else(|| BTreeSet::from([package.replace(['-', '.'], "_")]))
+    };
+    let imports = project_imports(project);
+    let total = imports.len().max(1);
+
+    let mut reaches = Vec::new();
+    for change in changes {
+        let package = &change.name;
+        let via: Vec<String> = direct
+            .iter()
+            .filter(|root| *root != package && requires(previous, root, package))
+            .cloned()
+            .collect();
+        let is_direct = direct.contains(package);
+
+        let mut reached: BTreeSet<String> = via.iter().flat_map(|name| modules_of(name)).collect();
+        reached.extend(modules_of(package));
+        let modules: Vec<String> = imports
+            .iter()
+            .filter(|(_, imported)| !imported.is_disjoint(&reached))
+            .map(|(module, _)| module.clone())
+            .collect();
+
+        let breaking = match (change.old.as_deref(), change.new.as_deref()) {
+            (Some(old), Some(new)) => breaking(old, new),
+            _ => true,
+        };
+        let share = modules.len() as f64 / total as f64;
+        let radius = if (breaking && !modules.is_empty()) || share >= 0.3 {
+            Radius::High
+        } else if breaking || share >= 0.1 || is_direct {
+            Radius::Medium
+        } else {
+            Radius::Low
+        };
+        reaches.push(Reach {
+            package: package.clone(),
+            direct: is_direct,
+            via,
+            modules,
+            breaking,
+            radius,
+        });
+    }
+    Analysis {
+        reaches,
+        modules: imports.len(),
+    }
+}
+
+/// Whether `root` requires `package`, directly or further down
+fn requires(lockfile: &Lockfile, root: &str, package: &str) -> bool {
+    let mut seen = BTreeSet::new();
+    let mut queue = vec![root.to_string()];
+    while let Some(name) = queue.pop() {
+        if !seen.insert(name.clone()) {
+            continue;
+        }
+        let Some(locked) = lockfile.package(&name) else {
+            continue;
+        };
+        for dependency in &locked.dependencies {
+            if dependency == package {
+                return true;
+            }
+            queue.push(dependency.clone());
+        }
+    }
+    false
+}
+
+/// `1.x → 2.x`, or `0.3 → 0.4` before 1.0
+fn breaking(old: &str, new: &str) -> bool {
+    let (Ok(old), Ok(new)) = (Version::parse(old), Version::parse(new)) else {
+        return true;
+    };
+    old.epoch != new.epoch
+        || old.release_at(0) != new.release_at(0)
+        || (old.release_at(0) == 0 && old.release_at(1) != new.release_at(1))
+}
+
+/// Top-level modules of each installed distribution, from `top_level.txt` or RECORD
+fn provided_modules(site_packages: &Path) -> BTreeMap<String, BTreeSet<String>> {
+    let mut provided = BTreeMap::new();
+    let Ok(entries) = fs::read_dir(site_packages) else {
+        return provided;
+    };
+    for dist_info in entries.flatten().map(|entry| entry.path()) {
+        if dist_info.extension().is_none_or(|extension| extension != "dist-info") {
+            continue;
+        }
+        let Some(metadata) = fs::read_to_string(dist_info.join("METADATA"))
+            .ok()
+            .and_then(|content| Metadata::parse(&content).ok())
+        else {
+            continue;
+        };
+        let modules: BTreeSet<String> = match fs::read_to_string(dist_info.join("top_level.txt")) {
+            Ok(top_level) => top_level.lines().map(str::trim).filter(|line| !line.is_empty()).map(str::to_string).collect(),
+            Err(_) => fs::read_to_string(dist_info.join("RECORD"))
+                .unwrap_or_default()
+                .lines()
+                .filter_map(|line| line.split(',').next()?.split('/').next())
+                .filter(|top| !top.contains(".dist-info") && !top.contains(".data") && !top.starts_with(['.', '_']))
+                .filter(|top| !top.ends_with(".pth") && top != &"..")
+                .map(|top| top.strip_suffix(".py").unwrap_or(top).to_string())
+                .collect(),
+        };
+        provided.insert(normalize_name(&metadata.name), modules);
+    }
+    provided
+}
+
+/// Every project module with the top-level modules it imports
+fn project_imports(project: &Project) -> BTreeMap<String, BTreeSet<String>> {
+    let src = project.root.join("src");
+    let root = if src.is_dir() { src } else { project.root.clone() };
+    let mut imports = BTreeMap::new();
+    let walker = walkdir::WalkDir::new(&root).into_iter().filter_entry(|entry| {
+        let name = entry.file_name().to_string_lossy();
+        entry.depth() == 0 || !(entry.file_type().is_dir() && (name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref())))
+    });
+    for entry in walker.flatten() {
+        let path = entry.path();
+        if path.extension().is_none_or(|extension| extension != "py") {
+            continue;
+        }
+        let Ok(source) = fs::read_to_string(path) else {
+            continue;
+        };
+        let relative = path.strip_prefix(&root).unwrap_or(path).with_extension("");
+        let mut parts: Vec<String> = relative.components().map(|part| part.as_os_str().to_string_lossy().to_string()).collect();
+        if parts.len() > 1 && parts.last().is_some_and(|last| last == "__init__") {
+            parts.pop();
+        }
+        imports.insert(parts.join("."), inline::imported_modules(&source));
+    }
+    imports
+}
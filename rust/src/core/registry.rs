@@ -0,0 +1,158 @@
+//! Projects pyforge knows about, for `pyforge workon`.
+//!
+//! `pyforge init` and `pyforge sync` remember the project they work on, and
+//! `pyforge workon --add` registers existing ones. The list lives in the state
+//! directory; projects whose directory is gone are skipped, not removed, so a
+//! disconnected drive doesn't forget them.
+
+use crate::core::dirs::state_dir;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::fuzzy;
+use crate::core::pep508::normalize_name;
+use crate::core::project::Project;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Known projects, in the state directory
+const REGISTRY_FILE: &str = "projects.toml";
+
+/// A registered project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Registry {
+    project: Vec<Entry>,
+}
+
+pub fn registry_path() -> PathBuf {
+    state_dir().join(REGISTRY_FILE)
+}
+
+/// Every registered project, by name
+pub fn entries() -> Vec<Entry> {
+    let mut entries = load().project;
+    entries.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.path.cmp(&b.path)));
+    entries
+}
+
+/// Register `project`, or update its name; returns whether it is new
+pub fn remember(project: &Project) -> Result<bool> {
+    let path = project.root.canonicalize().unwrap_or_else(|_| project.root.clone());
+    let name = project
+        .name()
+        .map(str::to_string)
+        .unwrap_or_else(|| path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default());
+    let mut registry = load();
+    match registry.project.iter_mut().find(|entry| entry.path == path) {
+        Some(entry) if entry.name == name => return Ok(false),
+        Some(entry) => entry.name = name,
+        None => registry.project.push(Entry { name, path }),
+    }
+    save(&registry)?;
+    Ok(true)
+}
+
+/// Register the project in `dir`, or else every project one level below it;
+/// returns the projects registered
+pub fn add(dir: &Path) -> Result<Vec<Entry>> {
+    let mut projects = Vec::new();
+    if let Some(project) = Project::discover_from(dir)?.filter(|project| project.root == dir) {
+        projects.push(project);
+    } else {
+        let entries = fs::read_dir(dir).map_err(|e| PyForgeError::file_error(format!("Could not read {}", dir.display()), e))?;
+        let mut children: Vec<PathBuf> =
+            entries.flatten().map(|entry| entry.path()).filter(|path| path.join("pyproject.toml").is_file()).collect();
+        children.sort();
+        for child in children {
+            projects.push(Project::load(&child)?);
+        }
+    }
+    for project in &projects {
+        remember(project)?;
+    }
+    let registered = entries();
+    Ok(projects
+        .iter()
+        .filter_map(|project| {
+            let path = project.root.canonicalize().unwrap_or_else(|_| project.root.clone());
+            registered.iter().find(|entry| entry.path == path).cloned()
+        })
+        .collect())
+}
+
+/// Forget the projects named `name`; returns those removed
+pub fn forget(name: &str) -> Result<Vec<Entry>> {
+    let mut registry = load();
+    let wanted = normalize_name(name);
+    let (removed, kept): (Vec<Entry>, Vec<Entry>) =
+        registry.project.into_iter().partition(|entry| normalize_name(&entry.name) == wanted);
+    registry.project = kept;
+    if !removed.is_empty() {
+        save(&registry)?;
+    }
+    Ok(removed)
+}
+
+/// The registered project `query` means: one named exactly so, else the best
+/// fuzzy match on the name or directory. Fails when several match equally well.
+pub fn find(query: &str) -> Result<Entry> {
+    let present: Vec<Entry> = entries().into_iter().filter(|entry| entry.path.is_dir()).collect();
+    let wanted = normalize_name(query);
+    let exact: Vec<&Entry> = present.iter().filter(|entry| normalize_name(&entry.name) == wanted).collect();
+    if let [entry] = exact.as_slice() {
+        return Ok((*entry).clone());
+    }
+
+    let candidates: Vec<&Entry> = if exact.is_empty() { present.iter().collect() } else { exact };
+    let mut scored: Vec<(i64, &Entry)> = candidates
+        .into_iter()
+        .filter_map(|entry| {
+            let by_name = fuzzy::score(query, &entry.name);
+            let by_path = fuzzy::score(query, &entry.path.display().to_string()).map(|score| score / 2);
+            by_name.max(by_path).map(|score| (score, entry))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    match scored.as_slice() {
+        [] => Err(PyForgeError::Usage {
+            message: format!("No registered project matches '{}'; register one with pyforge workon --add <dir>", query),
+        }),
+        [(_, entry)] => Ok((*entry).clone()),
+        [(best, entry), (next, _), ..] if best > next => Ok((*entry).clone()),
+        [(best, _), ..] => Err(PyForgeError::Usage {
+            message: format!(
+                "'{}' matches several projects: {}",
+                query,
+                scored
+                    .iter()
+                    .take_while(|(score, _)| score == best)
+                    .map(|(_, entry)| format!("{} ({})", entry.name, entry.path.display()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }),
+    }
+}
+
+fn load() -> Registry {
+    fs::read_to_string(registry_path())
+        .ok()
+        .and_then(|content| toml::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save(registry: &Registry) -> Result<()> {
+    let path = registry_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| PyForgeError::file_error(format!("Could not create {}", parent.display()), e))?;
+    }
+    let body = toml::to_string(registry).map_err(|e| PyForgeError::internal(format!("Could not render the project registry: {}", e)))?;
+    fs::write(&path, body).map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))
+}
@@ -0,0 +1,58 @@
+//! Organization-managed files (policy, init defaults) loaded from a path or an
+//! http(s) URL, with a cached copy for when the network is down.
+
+use crate::core::error::{PyForgeError, Result};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// Read `source`; URLs are cached at `cache`, which is used when fetching fails
+pub fn fetch(source: &str, what: &str, cache: &Path) -> Result<String> {
+    if !(source.starts_with("http://") || source.starts_with("https://")) {
+        return fs::read_to_string(source)
+            .map_err(|e| PyForgeError::file_error(format!("Could not read {} {}", what, source), e));
+    }
+
+    let fetched = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .and_then(|client| client.get(source).send())
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.text());
+
+    match fetched {
+        Ok(content) => {
+            if let Some(dir) = cache.parent() {
+                let _ = fs::create_dir_all(dir);
+            }
+            let _ = fs::write(cache, &content);
+            Ok(content)
+        }
+        Err(e) => match fs::read_to_string(cache) {
+            Ok(cached) => {
+                log::warn!("could not fetch {} from {} ({}), using cached copy", what, source, e);
+                Ok(cached)
+            }
+            Err(_) => Err(PyForgeError::network_error(format!("Could not fetch {} from {}", what, source), Some(e))),
+        },
+    }
+}
+
+/// Reject `content` unless its SHA-256 matches `expected` (when one is configured)
+pub fn check_digest(source: &str, what: &str, content: &str, expected: Option<&str>) -> Result<()> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+    let actual = format!("{:x}", Sha256::digest(content.as_bytes()));
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        return Ok(());
+    }
+    Err(PyForgeError::InvalidConfig {
+        file: source.to_string(),
+        source: Box::new(std::io::Error::other(format!(
+            "{} digest mismatch: expected {}, got {}",
+            what, expected, actual
+        ))),
+    })
+}
@@ -0,0 +1,550 @@
+//! Dependency resolution against the configured indexes and `find-links`
+//! directories.
+//!
+//! The resolver works for a single marker environment: it picks the newest
+//! version allowed by every constraint seen so far (or a preferred version,
+//! when one is set and allowed, or the oldest with the `lowest` strategy),
+//! and re-picks a package when a later constraint excludes its pin.
+//! `[tool.pyforge.resolver]` bounds the number of re-picks and the time spent,
+//! and `exclude-newer` resolves as if the indexes were frozen at a date.
+
+use crate::core::config::{Config, IndexConfig, ResolutionStrategy, ResolverConfig};
+use crate::core::dist::{DistFilename, Metadata};
+use crate::core::error::{PyForgeError, Result};
+use crate::core::index::{self, IndexClient, IndexFile};
+use crate::core::lockfile::{LockedFile, LockedPackage};
+use crate::core::markers::Environment;
+use crate::core::pep440::{SpecifierSet, Version};
+use crate::core::pep508::{Requirement, normalize_name};
+use crate::core::workspace::Member;
+use crate::core::timings;
+use reqwest::Url;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use time::format_description::well_known::Rfc3339;
+use time::{Date, OffsetDateTime};
+
+/// Origin of the project's own requirements
+const ROOT: &str = "<project>";
+
+/// Every version of a package on the first index that has it
+struct Candidates {
+    index: String,
+    /// Newest first, each with its compatible files (best match first)
+    versions: Vec<(Version, Vec<IndexFile>)>,
+}
+
+struct Pin {
+    version: Version,
+    files: Vec<IndexFile>,
+    metadata: Metadata,
+    /// Extras whose dependencies have been added
+    extras: BTreeSet<String>,
+}
+
+/// Progress callback, called with each package name as it is resolved
+pub type Progress<'a> = &'a dyn Fn(&str);
+
+pub struct Resolver<'a> {
+    client: IndexClient,
+    indexes: Vec<IndexConfig>,
+    /// Local directories of wheels and sdists, searched before the indexes
+    find_links: Vec<PathBuf>,
+    settings: ResolverConfig,
+    /// Files uploaded at or after this are ignored
+    exclude_newer: Option<OffsetDateTime>,
+    environment: &'a Environment,
+    candidates: HashMap<String, Candidates>,
+    metadata: HashMap<(String, String), Metadata>,
+    /// Versions to keep when the constraints allow them, e.g. from an existing lock
+    preferences: HashMap<String, Version>,
+    /// Packages resolved from a local project directory instead of an index
+    members: BTreeMap<String, Member>,
+    /// Package whose decisions are recorded in `trace`
+    explain: Option<String>,
+    trace: Vec<String>,
+}
+
+impl<'a> Resolver<'a> {
+    pub fn new(config: &Config, environment: &'a Environment) -> Result<Self> {
+        Ok(Self {
+            client: IndexClient::new(config)?,
+            indexes: if config.install.no_index { Vec::new() } else { config.indexes() },
+            find_links: Vec::new(),
+            settings: config.resolver.clone(),
+            exclude_newer: config.resolver.exclude_newer.as_deref().map(parse_cutoff).transpose()?,
+            environment,
+            candidates: HashMap::new(),
+            metadata: HashMap::new(),
+            preferences: HashMap::new(),
+            members: BTreeMap::new(),
+            explain: None,
+            trace: Vec::new(),
+        })
+    }
+
+    /// Record every decision about `name`: constraints, candidates and rejected versions
+    pub fn explain(&mut self, name: &str) {
+        self.explain = Some(normalize_name(name));
+    }
+
+    /// Decisions recorded for the package passed to [`Resolver::explain`], in order
+    pub fn trace(&self) -> &[String] {
+        &self.trace
+    }
+
+    fn note(&mut self, name: &str, message: impl FnOnce() -> String) {
+        if self.explain.as_deref() == Some(name) {
+            self.trace.push(message());
+        }
+    }
+
+    /// Resolve these packages from their project directories (see `workspace::members`)
+    pub fn set_members(&mut self, members: BTreeMap<String, Member>) {
+        self.members = members;
+    }
+
+    /// Also resolve from the wheels and sdists in these directories
+    /// (`[tool.pyforge.install] find-links`, relative to the project root)
+    pub fn set_find_links(&mut self, dirs: Vec<PathBuf>) {
+        self.find_links = dirs;
+    }
+
+    /// Pick `version` of `name` whenever it satisfies the constraints, instead of the newest
+    pub fn prefer(&mut self, name: &str, version: Version) {
+        self.preferences.insert(normalize_name(name), version);
+    }
+
+    /// Resolve `requirements` and everything they depend on
+    pub fn resolve(&mut self, requirements: &[Requirement], progress: Progress) -> Result<Vec<LockedPackage>> {
+        let _span = timings::span("resolve");
+        let no_extras = BTreeSet::new();
+        let mut constraints: BTreeMap<String, Vec<(String, Requirement)>> = BTreeMap::new();
+        let mut requested_extras: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut pins: BTreeMap<String, Pin> = BTreeMap::new();
+        let mut queue = VecDeque::new();
+
+        for requirement in requirements {
+            if self.applies(ROOT, requirement, &no_extras)? {
+                queue.push_back(self.add_constraint(&mut constraints, &mut requested_extras, ROOT, requirement)?);
+            }
+        }
+
+        let started = Instant::now();
+        let budget = self.settings.timeout.map(Duration::from_secs);
+        let mut steps = 0;
+        while let Some(name) = queue.pop_front() {
+            steps += 1;
+            let limit = if steps > self.settings.max_steps {
+                Some(format!("constraints did not converge within {} steps", self.settings.max_steps))
+            } else {
+                budget
+                    .filter(|budget| started.elapsed() > *budget)
+                    .map(|budget| format!("time budget of {}s used up", budget.as_secs()))
+            };
+            if let Some(limit) = limit {
+                let unsettled: BTreeSet<String> = std::iter::once(name).chain(queue).collect();
+                return Err(PyForgeError::ResolutionLimit {
+                    limit,
+                    pinned: pins
+                        .iter()
+                        .filter(|(name, _)| !unsettled.contains(*name))
+                        .map(|(name, pin)| format!("{}=={}", name, pin.version))
+                        .collect(),
+                    unsettled: unsettled.into_iter().collect(),
+                });
+            }
+
+            let Some(requirements) = constraints.get(&name).filter(|c| !c.is_empty()) else {
+                // Nothing requires the package anymore: drop it and what it required
+                if let Some(pin) = pins.remove(&name) {
+                    self.note(&name, || format!("dropped {}: nothing requires it anymore", pin.version));
+                    queue.extend(self.withdraw(&mut constraints, &name));
+                }
+                continue;
+            };
+            let specifier = SpecifierSet(requirements.iter().flat_map(|(_, r)| r.specifier.0.clone()).collect());
+            let extras = requested_extras.get(&name).cloned().unwrap_or_default();
+
+            if let Some(pin) = pins.get(&name)
+                && specifier.contains(&pin.version, true)
+                && pin.extras == extras
+            {
+                let version = pin.version.clone();
+                self.note(&name, || format!("kept {}: still allowed by '{}'", version, specifier));
+                continue;
+            }
+
+            let version_changed = pins.get(&name).is_none_or(|pin| !specifier.contains(&pin.version, true));
+            let pin = if version_changed {
+                progress(&name);
+                let (version, files) = self.select(&name, &specifier, requirements)?;
+                let metadata = self.metadata(&name, &version, &files)?;
+                Pin {
+                    version,
+                    files,
+                    metadata,
+                    extras: extras.clone(),
+                }
+            } else {
+                let mut pin = pins.remove(&name).expect("pinned");
+                pin.extras = extras.clone();
+                pin
+            };
+
+            // Replace the constraints this package contributed with its new ones
+            queue.extend(self.withdraw(&mut constraints, &name));
+            for requirement in &pin.metadata.requires_dist {
+                if self.applies(&name, requirement, &pin.extras)? {
+                    queue.push_back(self.add_constraint(&mut constraints, &mut requested_extras, &name, requirement)?);
+                }
+            }
+            pins.insert(name, pin);
+        }
+
+        let reachable = reachable(&constraints);
+        let packages = pins
+            .into_iter()
+            .filter(|(name, _)| reachable.contains(name))
+            .map(|(name, pin)| {
+                let dependencies = constraints
+                    .iter()
+                    .filter(|(dependency, from)| reachable.contains(*dependency) && from.iter().any(|(origin, _)| *origin == name))
+                    .map(|(dependency, _)| dependency.clone())
+                    .collect();
+                let index = self.candidates.get(&name).map(|c| c.index.clone()).unwrap_or_default();
+                let path = self.members.get(&name).map(|member| member.path.clone());
+                LockedPackage {
+                    name,
+                    version: pin.version.to_string(),
+                    index,
+                    path,
+                    dependencies,
+                    license: pin.metadata.license,
+                    files: pin
+                        .files
+                        .into_iter()
+                        .map(|file| LockedFile {
+                            name: file.filename,
+                            url: file.url,
+                            hashes: file.sha256.map(|sha256| BTreeMap::from([("sha256".to_string(), sha256)])).unwrap_or_default(),
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+        Ok(packages)
+    }
+
+    /// Whether `origin`'s `requirement` applies to the environment
+    fn applies(&mut self, origin: &str, requirement: &Requirement, extras: &BTreeSet<String>) -> Result<bool> {
+        let applies = match &requirement.marker {
+            Some(marker) => self.environment.evaluate(marker, extras)?,
+            None => true,
+        };
+        if !applies {
+            self.note(&requirement.normalized_name(), || {
+                format!("ignored '{}' from {}: its marker doesn't match this environment", requirement, origin)
+            });
+        }
+        Ok(applies)
+    }
+
+    /// Remove the constraints contributed by `origin`, returning the packages affected
+    fn withdraw(&mut self, constraints: &mut BTreeMap<String, Vec<(String, Requirement)>>, origin: &str) -> Vec<String> {
+        let affected = withdraw(constraints, origin);
+        for name in &affected {
+            self.note(name, || format!("withdrew the requirements of {}, which is being re-pinned", origin));
+        }
+        affected
+    }
+
+    fn add_constraint(
+        &mut self,
+        constraints: &mut BTreeMap<String, Vec<(String, Requirement)>>,
+        requested_extras: &mut BTreeMap<String, BTreeSet<String>>,
+        origin: &str,
+        requirement: &Requirement,
+    ) -> Result<String> {
+        if let Some(url) = &requirement.url {
+            return Err(PyForgeError::ResolutionFailed {
+                package: requirement.name.clone(),
+                reason: format!("direct URL dependencies are not supported ({})", url),
+            });
+        }
+
+        let name = requirement.normalized_name();
+        self.note(&name, || format!("{} requires '{}'", origin, requirement));
+        constraints
+            .entry(name.clone())
+            .or_default()
+            .push((origin.to_string(), requirement.clone()));
+        requested_extras
+            .entry(name.clone())
+            .or_default()
+            .extend(requirement.extras.iter().map(|extra| normalize_name(extra)));
+        Ok(name)
+    }
+
+    /// Newest version satisfying `specifier` that has a file usable in the environment
+    fn select(
+        &mut self,
+        name: &str,
+        specifier: &SpecifierSet,
+        requirements: &[(String, Requirement)],
+    ) -> Result<(Version, Vec<IndexFile>)> {
+        let preferred = self.preferences.get(name).cloned();
+        let explaining = self.explain.as_deref() == Some(name);
+        let lowest = self.settings.strategy == ResolutionStrategy::Lowest;
+        let candidates = self.candidates(name)?;
+        let mut notes = Vec::new();
+        if explaining {
+            let from: Vec<String> = requirements.iter().map(|(origin, _)| origin.clone()).collect();
+            notes.push(format!(
+                "choosing among {} version(s) for '{}' (from {})",
+                candidates.versions.len(),
+                specifier,
+                from.join(", ")
+            ));
+        }
+        let found = match preferred {
+            Some(preferred) => {
+                let found = candidates
+                    .versions
+                    .iter()
+                    .find(|(version, _)| *version == preferred && specifier.contains(version, true));
+                if explaining && found.is_none() {
+                    notes.push(format!("previously locked {} is no longer allowed", preferred));
+                }
+                found.cloned()
+            }
+            None => None,
+        };
+        // Newest first, or oldest first for the `lowest` strategy
+        let order = || -> Box<dyn Iterator<Item = &(Version, Vec<IndexFile>)>> {
+            if lowest {
+                Box::new(candidates.versions.iter().rev())
+            } else {
+                Box::new(candidates.versions.iter())
+            }
+        };
+        let allowed = |allow_prereleases: bool| order().find(|(version, _)| specifier.contains(version, allow_prereleases)).cloned();
+
+        // Pre-releases are only picked when nothing else satisfies the constraints
+        let picked = if let Some(found) = found {
+            notes.push(format!("picked {}: kept from the previous lock", found.0));
+            Some(found)
+        } else {
+            let picked = allowed(false).or_else(|| allowed(true));
+            if explaining {
+                for (version, _) in order() {
+                    if picked.as_ref().is_some_and(|(picked, _)| picked == version) {
+                        notes.push(format!("picked {}: the {} allowed", version, if lowest { "oldest" } else { "newest" }));
+                        break;
+                    }
+                    notes.push(if specifier.contains(version, true) {
+                        format!("rejected {}: pre-releases are only used when nothing else fits", version)
+                    } else {
+                        format!("rejected {}: excluded by '{}'", version, specifier)
+                    });
+                }
+            }
+            picked
+        };
+        if explaining {
+            self.trace.extend(notes);
+        }
+        picked.ok_or_else(|| {
+            let wanted: Vec<String> = requirements
+                .iter()
+                .map(|(origin, requirement)| format!("{} (from {})", requirement, origin))
+                .collect();
+            PyForgeError::ResolutionFailed {
+                package: name.to_string(),
+                reason: format!("no compatible version satisfies {}", wanted.join(", ")),
+            }
+        })
+    }
+
+    fn candidates(&mut self, name: &str) -> Result<&Candidates> {
+        if !self.candidates.contains_key(name)
+            && let Some(member) = self.members.get(name)
+        {
+            let version = Version::parse(&member.version).map_err(|_| PyForgeError::ResolutionFailed {
+                package: name.to_string(),
+                reason: format!("invalid version '{}' in {}", member.version, member.path),
+            })?;
+            self.metadata.insert((name.to_string(), version.to_string()), member.metadata()?);
+            self.candidates.insert(
+                name.to_string(),
+                Candidates {
+                    index: String::new(),
+                    versions: vec![(version, Vec::new())],
+                },
+            );
+        }
+
+        if !self.candidates.contains_key(name) {
+            // Local files come first, so a locally built wheel wins over the index's copy of the same file
+            let mut found = None;
+            let mut files = Vec::new();
+            for dir in &self.find_links {
+                let local = index::directory_files(dir, name)?;
+                if !local.is_empty() && found.is_none() {
+                    found = Some(Url::from_directory_path(dir.canonicalize()?).map(String::from).unwrap_or_default());
+                }
+                files.extend(local);
+            }
+            for index in &self.indexes {
+                if let Some(listed) = self.client.project_files(index, name)? {
+                    found = Some(index.url.clone());
+                    let local: BTreeSet<String> = files.iter().map(|file| file.filename.clone()).collect();
+                    files.extend(listed.into_iter().filter(|file| !local.contains(&file.filename)));
+                    break;
+                }
+            }
+            let index = found.ok_or_else(|| PyForgeError::ResolutionFailed {
+                package: name.to_string(),
+                reason: if self.find_links.is_empty() {
+                    "not found on any configured index".to_string()
+                } else {
+                    "not found on any configured index or find-links directory".to_string()
+                },
+            })?;
+
+            let python = Version::parse(&self.environment.python_full_version).ok();
+            let mut by_version: BTreeMap<Version, Vec<(u32, IndexFile)>> = BTreeMap::new();
+            // Why each file of a version was unusable, for `explain`
+            let mut unusable: BTreeMap<Version, BTreeSet<String>> = BTreeMap::new();
+            for file in files {
+                let Some(parsed) = DistFilename::parse(&file.filename, name) else {
+                    continue;
+                };
+                if file.yanked {
+                    unusable.entry(parsed.version).or_default().insert("yanked".to_string());
+                    continue;
+                }
+                if let Some(cutoff) = self.exclude_newer {
+                    let reason = match file.upload_time {
+                        Some(uploaded) if uploaded < cutoff => None,
+                        Some(uploaded) => Some(format!("uploaded {}, after the exclude-newer cutoff", uploaded.date())),
+                        None => Some("the index doesn't report when it was uploaded".to_string()),
+                    };
+                    if let Some(reason) = reason {
+                        unusable.entry(parsed.version).or_default().insert(reason);
+                        continue;
+                    }
+                }
+                let python_ok = match (&file.requires_python, &python) {
+                    (Some(requires), Some(python)) => requires
+                        .parse::<SpecifierSet>()
+                        .map(|spec| spec.contains(python, true))
+                        .unwrap_or(true),
+                    _ => true,
+                };
+                if !python_ok {
+                    let reason = format!("requires Python {}", file.requires_python.as_deref().unwrap_or_default());
+                    unusable.entry(parsed.version).or_default().insert(reason);
+                } else if let Some(score) = parsed.compatibility(self.environment) {
+                    by_version.entry(parsed.version).or_default().push((score, file));
+                } else {
+                    let reason = format!("no file for {} on {}", self.environment.python_version, self.environment.sys_platform);
+                    unusable.entry(parsed.version).or_default().insert(reason);
+                }
+            }
+            self.note(name, || format!("found {} usable version(s) on {}", by_version.len(), index));
+            for (version, reasons) in unusable.iter().rev().filter(|(version, _)| !by_version.contains_key(*version)) {
+                let reasons: Vec<&str> = reasons.iter().map(String::as_str).collect();
+                self.note(name, || format!("skipped {}: {}", version, reasons.join(", ")));
+            }
+
+            let versions = by_version
+                .into_iter()
+                .rev()
+                .map(|(version, mut files)| {
+                    files.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+                    (version, files.into_iter().map(|(_, file)| file).collect())
+                })
+                .collect();
+            self.candidates.insert(name.to_string(), Candidates { index, versions });
+        }
+        Ok(&self.candidates[name])
+    }
+
+    /// Core metadata of a version, from PEP 658 metadata files or the wheel itself
+    fn metadata(&mut self, name: &str, version: &Version, files: &[IndexFile]) -> Result<Metadata> {
+        let key = (name.to_string(), version.to_string());
+        if let Some(metadata) = self.metadata.get(&key) {
+            return Ok(metadata.clone());
+        }
+
+        let wheel = files.iter().find(|file| file.filename.ends_with(".whl"));
+        let metadata = match wheel {
+            Some(file) if file.has_metadata => {
+                let bytes = self.client.download(&format!("{}.metadata", file.url))?;
+                Metadata::parse(&String::from_utf8_lossy(&bytes))?
+            }
+            Some(file) => Metadata::from_wheel(self.client.download_temp(&file.url, file.sha256.as_deref())?)?,
+            None => {
+                // Without building the sdist its dependencies are unknown
+                log::warn!("{} {} has no wheel; its dependencies were not locked", name, version);
+                Metadata::default()
+            }
+        };
+
+        self.metadata.insert(key, metadata.clone());
+        Ok(metadata)
+    }
+}
+
+/// Remove the constraints contributed by `origin`, returning the packages affected
+fn withdraw(constraints: &mut BTreeMap<String, Vec<(String, Requirement)>>, origin: &str) -> Vec<String> {
+    let mut affected = Vec::new();
+    for (dependency, from) in constraints.iter_mut() {
+        let before = from.len();
+        from.retain(|(o, _)| o != origin);
+        if from.len() != before {
+            affected.push(dependency.clone());
+        }
+    }
+    affected
+}
+
+/// Packages reachable from the project's own requirements
+fn reachable(constraints: &BTreeMap<String, Vec<(String, Requirement)>>) -> BTreeSet<String> {
+    let mut reachable = BTreeSet::new();
+    let mut stack = vec![ROOT.to_string()];
+    while let Some(origin) = stack.pop() {
+        for (name, from) in constraints {
+            if from.iter().any(|(o, _)| *o == origin) && reachable.insert(name.clone()) {
+                stack.push(name.clone());
+            }
+        }
+    }
+    reachable
+}
+
+/// The instant an `exclude-newer` value stands for: a date means its start (UTC)
+pub fn parse_cutoff(value: &str) -> Result<OffsetDateTime> {
+    if let Ok(timestamp) = OffsetDateTime::parse(value, &Rfc3339) {
+        return Ok(timestamp);
+    }
+    let date = value
+        .splitn(3, '-')
+        .map(|part| part.parse::<i32>().ok())
+        .collect::<Option<Vec<_>>>()
+        .and_then(|parts| match parts[..] {
+            [year, month, day] => {
+                let month = time::Month::try_from(u8::try_from(month).ok()?).ok()?;
+                Date::from_calendar_date(year, month, u8::try_from(day).ok()?).ok()
+            }
+            _ => None,
+        });
+    date.map(|date| date.midnight().assume_utc()).ok_or_else(|| PyForgeError::Usage {
+        message: format!(
+            "Invalid exclude-newer '{}': expected a date such as 2024-05-01 or an RFC 3339 timestamp",
+            value
+        ),
+    })
+}
@@ -0,0 +1,145 @@
+//! Restricted execution for untrusted build code (PEP 517 backends and their hooks).
+//!
+//! Where available, processes run without network access and can only write
+//! to the given directories:
+//!
+//! - Linux: `bwrap` (read-only filesystem, private /tmp, no network), or
+//!   `unshare` (no network only)
+//! - macOS: `sandbox-exec` with a generated profile
+//!
+//! Elsewhere commands run unrestricted, with a warning.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    /// `false` runs commands unrestricted (`[tool.pyforge.build] sandbox = false`)
+    pub enabled: bool,
+    pub allow_network: bool,
+    /// Directories the sandboxed process may write to
+    pub writable: Vec<PathBuf>,
+}
+
+impl Sandbox {
+    pub fn new(writable: Vec<PathBuf>, allow_network: bool) -> Self {
+        Self {
+            enabled: true,
+            allow_network,
+            writable,
+        }
+    }
+
+    /// A sandbox that restricts nothing
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            allow_network: true,
+            writable: Vec::new(),
+        }
+    }
+
+    /// What the sandbox restricts on this machine, for display
+    pub fn describe(&self) -> &'static str {
+        if !self.enabled {
+            return "sandbox disabled in configuration";
+        }
+        let (confined, offline) = match tool() {
+            Some(Tool::Bwrap | Tool::SandboxExec) => (true, !self.allow_network),
+            Some(Tool::Unshare) => (false, !self.allow_network),
+            None => (false, false),
+        };
+        match (confined, offline) {
+            (true, true) => "network disabled, writes confined to the build directory",
+            (true, false) => "writes confined to the build directory, network allowed",
+            (false, true) => "network disabled (install bubblewrap to also confine writes)",
+            (false, false) => "not sandboxed",
+        }
+    }
+
+    /// A command running `program` inside the sandbox
+    pub fn command(&self, program: &Path) -> Command {
+        if !self.enabled {
+            return Command::new(program);
+        }
+        match tool() {
+            Some(Tool::Bwrap) => {
+                let mut command = Command::new("bwrap");
+                command.args(["--ro-bind", "/", "/", "--dev", "/dev", "--proc", "/proc", "--tmpfs", "/tmp"]);
+                for dir in &self.writable {
+                    command.arg("--bind").arg(dir).arg(dir);
+                }
+                if !self.allow_network {
+                    command.arg("--unshare-net");
+                }
+                command.args(["--unshare-pid", "--die-with-parent", "--"]).arg(program);
+                command
+            }
+            Some(Tool::SandboxExec) => {
+                let mut command = Command::new("sandbox-exec");
+                command.arg("-p").arg(self.macos_profile()).arg(program);
+                command
+            }
+            Some(Tool::Unshare) if !self.allow_network => {
+                let mut command = Command::new("unshare");
+                command.args(["--map-root-user", "--net", "--"]).arg(program);
+                command
+            }
+            _ => Command::new(program),
+        }
+    }
+
+    fn macos_profile(&self) -> String {
+        let mut profile = String::from("(version 1)\n(allow default)\n(deny file-write*)\n");
+        profile.push_str("(allow file-write* (literal \"/dev/null\") (subpath \"/dev/fd\")");
+        for dir in &self.writable {
+            let dir = dir.canonicalize().unwrap_or_else(|_| dir.clone());
+            profile.push_str(&format!(" (subpath \"{}\")", dir.display().to_string().replace('"', "\\\"")));
+        }
+        profile.push_str(")\n");
+        if !self.allow_network {
+            profile.push_str("(deny network*)\n(allow network* (local unix))\n");
+        }
+        profile
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Tool {
+    Bwrap,
+    SandboxExec,
+    Unshare,
+}
+
+/// The best sandbox tool that works here, detected once per process
+fn tool() -> Option<Tool> {
+    static TOOL: OnceLock<Option<Tool>> = OnceLock::new();
+    *TOOL.get_or_init(detect)
+}
+
+fn detect() -> Option<Tool> {
+    if cfg!(target_os = "macos") {
+        return which::which("sandbox-exec").ok().map(|_| Tool::SandboxExec);
+    }
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    // Unprivileged user namespaces may be disabled, so check the tools actually work
+    let works = |program: &str, args: &[&str]| {
+        which::which(program).is_ok()
+            && Command::new(program)
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success())
+    };
+    if works("bwrap", &["--ro-bind", "/", "/", "--unshare-net", "--", "true"]) {
+        Some(Tool::Bwrap)
+    } else if works("unshare", &["--map-root-user", "--net", "--", "true"]) {
+        Some(Tool::Unshare)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,122 @@
+use crate::core::error::{PyForgeError, Result};
+use crate::core::feature::FeatureFile;
+use crate::core::pep508::normalize_name;
+use crate::core::template::{context, substitute};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Project layouts shipped with PyForge
+const BUILTIN_LAYOUTS: &[(&str, &str)] = &[
+    ("cli", include_str!("../templates/cli.toml")),
+    ("fastapi", include_str!("../templates/fastapi.toml")),
+    ("lib", include_str!("../templates/lib.toml")),
+    ("minimal", include_str!("../templates/minimal.toml")),
+];
+
+/// Layout `pyforge init` uses without `--template`
+pub const DEFAULT_LAYOUT: &str = "lib";
+
+/// A built-in starting layout for `pyforge init --template <name>`.
+///
+/// Layouts are TOML files of `[[file]]` entries like features; paths and
+/// contents are rendered with `{{ project_name }}`, `{{ module_name }}`,
+/// `{{ requires_python }}`, `{{ python_version }}` and the [`context`] variables.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Layout {
+    #[serde(skip)]
+    pub name: String,
+
+    #[serde(default)]
+    pub description: String,
+
+    #[serde(default, rename = "file")]
+    pub files: Vec<FeatureFile>,
+}
+
+impl Layout {
+    /// The built-in layout `name`, unless a template of the same name in
+    /// `user_templates` shadows it
+    pub fn builtin(name: &str, user_templates: &Path) -> Result<Option<Self>> {
+        if user_templates.join(name).is_dir() {
+            return Ok(None);
+        }
+        Self::named(name)
+    }
+
+    fn named(name: &str) -> Result<Option<Self>> {
+        BUILTIN_LAYOUTS
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|(name, content)| {
+                let mut layout: Layout = toml::from_str(content).map_err(|e| PyForgeError::InvalidToml {
+                    file: format!("built-in layout '{}'", name),
+                    message: e.to_string(),
+                })?;
+                layout.name = name.to_string();
+                Ok(layout)
+            })
+            .transpose()
+    }
+
+    /// Names of the built-in layouts
+    pub fn available() -> Vec<String> {
+        BUILTIN_LAYOUTS.iter().map(|(name, _)| name.to_string()).collect()
+    }
+
+    /// The minimal layout's `pyproject.toml`, for templates that don't bring one
+    pub fn pyproject(variables: &BTreeMap<String, String>) -> Result<String> {
+        let minimal = Self::named("minimal")?.unwrap_or_else(|| unreachable!("minimal is a built-in layout"));
+        let file = minimal.files.iter().find(|file| file.path == "pyproject.toml");
+        Ok(file.map(|file| substitute(&file.content, variables)).unwrap_or_default())
+    }
+
+    /// Variables for a new project `project_name` requiring `requires_python`
+    pub fn variables(project_name: &str, requires_python: &str) -> BTreeMap<String, String> {
+        let mut variables = context();
+        variables.insert("project_name".to_string(), project_name.to_string());
+        variables.insert("module_name".to_string(), normalize_name(project_name).replace('-', "_"));
+        variables.insert("requires_python".to_string(), requires_python.to_string());
+        variables.insert("python_version".to_string(), minimum_python(requires_python));
+        variables
+    }
+
+    /// Write the layout's files under `dest`, keeping files that exist already;
+    /// returns the relative paths written
+    pub fn render(&self, dest: &Path, variables: &BTreeMap<String, String>) -> Result<Vec<PathBuf>> {
+        let mut written = Vec::new();
+        for file in &self.files {
+            let relative = PathBuf::from(substitute(&file.path, variables));
+            let path = dest.join(&relative);
+            if path.exists() {
+                continue;
+            }
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| PyForgeError::file_error(format!("Could not create {}", parent.display()), e))?;
+            }
+            fs::write(&path, substitute(&file.content, variables))
+                .map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))?;
+            written.push(relative);
+        }
+        Ok(written)
+    }
+}
+
+/// The lowest Python version a `requires-python` specifier allows, e.g. `3.10`
+/// for `>=3.10,<4`; `3` when it has no lower bound
+fn minimum_python(requires_python: &str) -> String {
+    requires_python
+        .split(',')
+        .filter_map(|clause| {
+            let clause = clause.trim();
+            clause
+                .strip_prefix(">=")
+                .or_else(|| clause.strip_prefix("~="))
+                .or_else(|| clause.strip_prefix("=="))
+        })
+        .map(|version| version.trim().trim_end_matches(".*").to_string())
+        .next()
+        .unwrap_or_else(|| "3".to_string())
+}
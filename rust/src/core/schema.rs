@@ -0,0 +1,659 @@
+//! The keys pyforge reads from `[tool.pyforge]` and the global config.
+//!
+//! Serde skips keys it doesn't know, so a typo such as `[tool.pyforge.tset]`
+//! would quietly do nothing. Both files are checked against the schema below
+//! when the configuration is loaded: unknown and deprecated keys get a warning
+//! with their line, and a value of the wrong type fails with its position
+//! rather than serde's bare message. Keep the schema in step with the structs
+//! in `config.rs`.
+
+use crate::core::error::{PyForgeError, Result};
+use crate::core::vetting::edit_distance;
+use colored::*;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use toml_edit::{DocumentMut, ImDocument, Item, Table, TableLike, Value};
+
+/// What a value must look like
+#[derive(Debug, Clone, Copy)]
+pub enum Shape {
+    Bool,
+    Integer,
+    String,
+    /// One of the listed strings
+    Choice(&'static [&'static str]),
+    /// A string or an array of strings
+    StringOrList,
+    List(&'static Shape),
+    Table(&'static [(&'static str, Shape)]),
+    /// A table whose keys are names the user picks, e.g. `[tool.pyforge.scripts]`
+    Map(&'static Shape),
+}
+
+const STRINGS: Shape = Shape::List(&Shape::String);
+
+const TLS: Shape = Shape::Table(&[
+    ("ca-certs", STRINGS),
+    ("pin", Shape::Bool),
+    ("client-cert", Shape::String),
+    ("client-key", Shape::String),
+]);
+
+const INDEX: Shape = Shape::Table(&[
+    ("name", Shape::String),
+    ("url", Shape::String),
+    ("mirrors", STRINGS),
+    ("tls", TLS),
+]);
+
+const NETWORK: Shape = Shape::Table(&[
+    ("timeout", Shape::Integer),
+    ("connect-timeout", Shape::Integer),
+    ("ip-version", Shape::Choice(&["auto", "ipv4", "ipv6"])),
+    ("hosts", Shape::Map(&Shape::StringOrList)),
+    ("retries", Shape::Integer),
+    ("max-download-rate", Shape::String),
+    ("metered", Shape::Bool),
+]);
+
+const WATCHER: Shape = Shape::Table(&[
+    ("command", Shape::String),
+    ("watch", STRINGS),
+    ("error-pattern", Shape::String),
+]);
+
+const BUILD_STEP: Shape = Shape::Table(&[
+    ("name", Shape::String),
+    ("command", Shape::String),
+    ("inputs", STRINGS),
+    ("outputs", STRINGS),
+]);
+
+const BUILD: Shape = Shape::Table(&[
+    ("sandbox", Shape::Bool),
+    ("allow-network", Shape::Bool),
+    ("steps", Shape::List(&BUILD_STEP)),
+    ("include", Shape::Map(&Shape::String)),
+    ("info", Shape::Table(&[("path", Shape::String), ("constants", Shape::Map(&Shape::String))])),
+    (
+        "checks",
+        Shape::Table(&[
+            ("max-size", Shape::String),
+            ("forbid", STRINGS),
+            ("require", STRINGS),
+            ("strict", Shape::Bool),
+        ]),
+    ),
+    ("env", Shape::Map(&Shape::String)),
+    ("profiles", Shape::Map(&Shape::Table(&[("env", Shape::Map(&Shape::String))]))),
+]);
+
+const INSTALL: Shape = Shape::Table(&[
+    ("no-index", Shape::Bool),
+    ("find-links", STRINGS),
+    ("jobs", Shape::Integer),
+    ("hooks", Shape::Table(&[("allow", STRINGS), ("strict", Shape::Bool)])),
+]);
+
+const VENV: Shape = Shape::Table(&[
+    ("interpreter", Shape::Choice(&["symlink", "copy"])),
+    ("system-site-packages", Shape::Bool),
+    ("prompt", Shape::String),
+    ("upgrade-deps", Shape::Bool),
+    ("seed", STRINGS),
+]);
+
+const INIT: Shape = Shape::Table(&[
+    ("org-defaults", Shape::String),
+    ("org-defaults-sha256", Shape::String),
+    ("author", Shape::Table(&[("name", Shape::String), ("email", Shape::String)])),
+]);
+
+const TEST: Shape = Shape::Table(&[
+    ("command", Shape::String),
+    ("image", Shape::String),
+    ("profiles", Shape::Map(&Shape::Table(&[("image", Shape::String)]))),
+]);
+
+const RESOLVER: Shape = Shape::Table(&[
+    ("max-steps", Shape::Integer),
+    ("timeout", Shape::Integer),
+    ("strategy", Shape::Choice(&["newest", "locked", "lowest"])),
+    ("exclude-newer", Shape::String),
+]);
+
+const NOTIFY: Shape = Shape::Table(&[
+    ("after", Shape::Integer),
+    ("desktop", Shape::Bool),
+    ("webhook", Shape::String),
+    ("slack-webhook", Shape::String),
+    ("ignore", STRINGS),
+]);
+
+/// Everything `Config` reads, from either file
+const CONFIG: &[(&str, Shape)] = &[
+    ("index", Shape::List(&INDEX)),
+    ("network", NETWORK),
+    ("alias", Shape::Map(&Shape::StringOrList)),
+    ("scripts", Shape::Map(&Shape::String)),
+    ("dev", Shape::Table(&[("watchers", Shape::Map(&WATCHER))])),
+    ("install", INSTALL),
+    ("frozen-toolchain", Shape::Bool),
+    ("build", BUILD),
+    ("venv-strategy", Shape::Choice(&["per-worktree", "shared", "per-branch"])),
+    ("venv-dir", Shape::String),
+    ("venv", VENV),
+    ("init", INIT),
+    ("test", TEST),
+    ("remote", Shape::Table(&[("dir", Shape::String), ("pyforge", Shape::String), ("exclude", STRINGS)])),
+    ("version", Shape::Table(&[("source", Shape::Choice(&["git"])), ("tag-prefix", Shape::String)])),
+    ("publish", Shape::Table(&[("repository-url", Shape::String)])),
+    ("python", Shape::String),
+    ("color", Shape::Choice(&["auto", "always", "never"])),
+    ("resolver", RESOLVER),
+    ("show", Shape::Table(&[("download-stats", Shape::Bool), ("stats-url", Shape::String)])),
+    ("report", Shape::Table(&[("vulnerability-url", Shape::String)])),
+    ("isolation", Shape::Table(&[("allow-env", STRINGS)])),
+    ("logs", Shape::Table(&[("capture", Shape::Bool), ("keep", Shape::Integer)])),
+    ("queue", Shape::Table(&[("jobs", Shape::Integer)])),
+];
+
+const CHANNEL: (&str, Shape) = ("channel", Shape::Choice(&["stable", "nightly"]));
+const FORGE_KIND: (&str, Shape) = ("kind", Shape::Choice(&["github", "gitlab"]));
+
+/// Settings a cloned project mustn't make for the user: the policy, where
+/// the self-updater downloads from, where forge tokens are sent, and where
+/// every command's arguments are reported. A project's values are ignored.
+const GLOBAL_ONLY: &[(&str, Shape)] = &[
+    ("notify", NOTIFY),
+    ("policy", Shape::Table(&[("source", Shape::String), ("sha256", Shape::String)])),
+    ("self-update", Shape::Table(&[CHANNEL, ("releases-url", Shape::String)])),
+    ("forge", Shape::Table(&[FORGE_KIND, ("api-url", Shape::String)])),
+];
+
+/// Path dependencies of workspace members, and the parts of global-only
+/// tables a project may set
+const PROJECT_ONLY: &[(&str, Shape)] = &[
+    ("sources", Shape::Map(&Shape::Table(&[("path", Shape::String), ("editable", Shape::Bool)]))),
+    ("self-update", Shape::Table(&[CHANNEL])),
+    ("forge", Shape::Table(&[FORGE_KIND])),
+];
+
+/// A renamed key, written as a dotted path below the config root
+#[derive(Debug, Clone, Copy)]
+pub struct DeprecatedKey {
+    pub old: &'static str,
+    pub new: &'static str,
+    /// First release warning about `old`
+    pub since: &'static str,
+}
+
+/// Every renamed key, oldest first. The old spelling keeps working: it is
+/// moved to the new one before the configuration is deserialized.
+pub const DEPRECATED_KEYS: &[DeprecatedKey] = &[];
+
+/// Which file is checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// `config.toml` in the config directory
+    Global,
+    /// A pyproject.toml, whose settings sit in `[tool.pyforge]`
+    Project,
+}
+
+impl Scope {
+    fn fields(self) -> [&'static [(&'static str, Shape)]; 2] {
+        match self {
+            Scope::Global => [CONFIG, GLOBAL_ONLY],
+            Scope::Project => [CONFIG, PROJECT_ONLY],
+        }
+    }
+
+    fn prefix(self) -> &'static str {
+        match self {
+            Scope::Global => "",
+            Scope::Project => "tool.pyforge",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Problem {
+    Unknown { suggestion: Option<String> },
+    Deprecated { new: String, since: &'static str },
+    Mismatch { expected: String, found: String },
+    /// Set in a project, but only read from the global config
+    GlobalOnly,
+}
+
+/// One problem with a key, at its line and column
+#[derive(Debug, Clone)]
+pub struct Issue {
+    pub file: PathBuf,
+    pub position: Option<(usize, usize)>,
+    /// Dotted path of the key, as written in the file, e.g. `tool.pyforge.test.command`
+    pub key: String,
+    pub problem: Problem,
+}
+
+impl Issue {
+    /// Whether the configuration can't be used as written
+    pub fn is_error(&self) -> bool {
+        matches!(self.problem, Problem::Mismatch { .. })
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.position {
+            Some((line, column)) => write!(f, "{}:{}:{}: ", self.file.display(), line, column)?,
+            None => write!(f, "{}: ", self.file.display())?,
+        }
+        match &self.problem {
+            Problem::Unknown { suggestion: Some(suggestion) } => {
+                write!(f, "unknown key `{}`; did you mean `{}`?", self.key, suggestion)
+            }
+            Problem::Unknown { suggestion: None } => write!(f, "unknown key `{}`", self.key),
+            Problem::Deprecated { new, since } => {
+                write!(f, "`{}` is deprecated since {}; use `{}`", self.key, since, new)
+            }
+            Problem::Mismatch { expected, found } => write!(f, "`{}` should be {}, found {}", self.key, expected, found),
+            Problem::GlobalOnly => write!(f, "`{}` is only read from the global config; ignoring it", self.key),
+        }
+    }
+}
+
+impl std::error::Error for Issue {}
+
+/// Files already warned about in this process, as configuration is loaded
+/// more than once per command
+static WARNED: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+/// Check `file` the way `Config::load` reads it: warn once about unknown and
+/// deprecated keys, and fail on the first value of the wrong type. Missing or
+/// unparsable files are left to `read_toml`.
+pub fn validate(file: &Path, scope: Scope) -> Result<()> {
+    let Ok(source) = fs::read_to_string(file) else {
+        return Ok(());
+    };
+    let issues = check(file, &source, scope);
+    if let Some(mismatch) = issues.iter().find(|issue| issue.is_error()) {
+        return Err(PyForgeError::InvalidConfig {
+            file: file.display().to_string(),
+            source: Box::new(mismatch.clone()),
+        });
+    }
+    if issues.is_empty() || !WARNED.lock().map(|mut warned| warned.insert(file.to_path_buf())).unwrap_or(false) {
+        return Ok(());
+    }
+    for issue in &issues {
+        eprintln!("{} {}", "⚠️".yellow(), issue);
+    }
+    if issues.iter().any(|issue| matches!(issue.problem, Problem::Deprecated { .. })) {
+        eprintln!("   Run {} to rewrite renamed keys", "pyforge config doctor".cyan());
+    }
+    Ok(())
+}
+
+/// Don't warn about `file` again in this process, as its problems were shown already
+pub fn mark_reported(file: &Path) {
+    if let Ok(mut warned) = WARNED.lock() {
+        warned.insert(file.to_path_buf());
+    }
+}
+
+/// Every problem with the pyforge settings in `source`, the content of `file`
+pub fn check(file: &Path, source: &str, scope: Scope) -> Vec<Issue> {
+    let Ok(document) = ImDocument::parse(source) else {
+        return Vec::new();
+    };
+    let mut root: &Item = document.as_item();
+    for segment in scope.prefix().split('.').filter(|segment| !segment.is_empty()) {
+        match root.get(segment) {
+            Some(item) => root = item,
+            None => return Vec::new(),
+        }
+    }
+    let mut walker = Walker {
+        file,
+        source,
+        scope,
+        issues: Vec::new(),
+    };
+    match root.as_table_like() {
+        Some(table) => walker.table(table, "", &scope.fields()),
+        None => walker.mismatch(scope.prefix(), "a table".to_string(), root, None),
+    }
+    walker.issues
+}
+
+/// Remove settings only the global config may make from a project's
+/// `[tool.pyforge]`, before it is merged over the global config
+pub fn strip_global_only(tool: &mut toml::Value) {
+    strip(tool, GLOBAL_ONLY, &[CONFIG, PROJECT_ONLY]);
+}
+
+fn strip(value: &mut toml::Value, global: &[(&str, Shape)], allowed: &[&[(&str, Shape)]]) {
+    let Some(table) = value.as_table_mut() else {
+        return;
+    };
+    for (key, shape) in global {
+        match (allowed.iter().flat_map(|fields| fields.iter()).find(|(name, _)| name == key), shape) {
+            (None, _) => {
+                table.remove(*key);
+            }
+            (Some((_, Shape::Table(fields))), Shape::Table(global)) => {
+                if let Some(child) = table.get_mut(*key) {
+                    strip(child, global, &[fields]);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether dotted `path` is a setting of `GLOBAL_ONLY`
+fn is_global_only(path: &str) -> bool {
+    let mut fields = GLOBAL_ONLY;
+    for segment in path.split('.') {
+        match fields.iter().find(|(name, _)| *name == segment) {
+            Some((_, Shape::Table(inner))) => fields = inner,
+            Some(_) => return true,
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Move deprecated keys in the merged configuration to their new names,
+/// unless the new name is set too
+pub fn migrate(config: &mut toml::Value) {
+    'keys: for deprecation in DEPRECATED_KEYS {
+        let Some(value) = take(&mut *config, deprecation.old) else {
+            continue;
+        };
+        let (parents, last) = deprecation.new.rsplit_once('.').unwrap_or(("", deprecation.new));
+        let mut table = &mut *config;
+        for segment in parents.split('.').filter(|segment| !segment.is_empty()) {
+            let Some(map) = table.as_table_mut() else {
+                continue 'keys;
+            };
+            table = map.entry(segment).or_insert_with(|| toml::Value::Table(Default::default()));
+        }
+        if let Some(map) = table.as_table_mut() {
+            map.entry(last).or_insert(value);
+        }
+    }
+}
+
+/// A deprecated key moved by `rename`
+#[derive(Debug, Clone)]
+pub struct Renamed {
+    pub old: String,
+    pub new: String,
+    /// The new key was set as well, so the old one was only removed
+    pub dropped: bool,
+}
+
+/// Move deprecated keys in `document` to their new names, keeping comments
+/// and formatting; what `pyforge config doctor` writes back
+pub fn rename(document: &mut DocumentMut, scope: Scope) -> Vec<Renamed> {
+    let mut root = document.as_item_mut();
+    for segment in scope.prefix().split('.').filter(|segment| !segment.is_empty()) {
+        match root.get_mut(segment) {
+            Some(item) => root = item,
+            None => return Vec::new(),
+        }
+    }
+    let Some(root) = root.as_table_like_mut() else {
+        return Vec::new();
+    };
+    let mut renamed = Vec::new();
+    for deprecation in DEPRECATED_KEYS {
+        let Some(item) = remove_item(root, deprecation.old) else {
+            continue;
+        };
+        renamed.push(Renamed {
+            old: join(scope.prefix(), deprecation.old),
+            new: join(scope.prefix(), deprecation.new),
+            dropped: !insert_item(root, deprecation.new, item),
+        });
+    }
+    renamed
+}
+
+fn remove_item(table: &mut dyn TableLike, path: &str) -> Option<Item> {
+    match path.split_once('.') {
+        Some((first, rest)) => remove_item(table.get_mut(first)?.as_table_like_mut()?, rest),
+        None => table.remove(path),
+    }
+}
+
+/// Insert `item` at dotted `path` unless something is there already
+fn insert_item(table: &mut dyn TableLike, path: &str, item: Item) -> bool {
+    let Some((first, rest)) = path.split_once('.') else {
+        if table.contains_key(path) {
+            return false;
+        }
+        table.insert(path, item);
+        return true;
+    };
+    if !table.contains_key(first) {
+        let mut child = Table::new();
+        child.set_implicit(true);
+        table.insert(first, Item::Table(child));
+    }
+    match table.get_mut(first).and_then(Item::as_table_like_mut) {
+        Some(child) => insert_item(child, rest, item),
+        None => false,
+    }
+}
+
+/// Remove the value at dotted `path`
+fn take(config: &mut toml::Value, path: &str) -> Option<toml::Value> {
+    let (parents, last) = path.rsplit_once('.').unwrap_or(("", path));
+    let mut table = config;
+    for segment in parents.split('.').filter(|segment| !segment.is_empty()) {
+        table = table.get_mut(segment)?;
+    }
+    table.as_table_mut()?.remove(last)
+}
+
+struct Walker<'a> {
+    file: &'a Path,
+    source: &'a str,
+    scope: Scope,
+    issues: Vec<Issue>,
+}
+
+impl Walker<'_> {
+    /// Check the keys of `table` at `path` (relative to the config root)
+    fn table(&mut self, table: &dyn TableLike, path: &str, fields: &[&'static [(&'static str, Shape)]]) {
+        for (key, item) in table.iter() {
+            let dotted = join(path, key);
+            let key_span = table.get_key_value(key).and_then(|(key, _)| key.span());
+            let known = fields.iter().flat_map(|fields| fields.iter()).find(|(name, _)| *name == key);
+            if let Some((_, shape)) = known {
+                self.item(item, &dotted, *shape, key_span);
+                continue;
+            }
+            let problem = match DEPRECATED_KEYS.iter().find(|deprecation| deprecation.old == dotted) {
+                Some(deprecation) => Problem::Deprecated {
+                    new: self.display(deprecation.new),
+                    since: deprecation.since,
+                },
+                None if self.scope == Scope::Project && is_global_only(&dotted) => Problem::GlobalOnly,
+                None => Problem::Unknown {
+                    suggestion: suggest(key, fields).map(|name| self.display(&join(path, name))),
+                },
+            };
+            self.issues.push(Issue {
+                file: self.file.to_path_buf(),
+                position: key_span.map(|span| self.position(span.start)),
+                key: self.display(&dotted),
+                problem,
+            });
+        }
+    }
+
+    /// Check a table against a `Table` or `Map` shape
+    fn entries(&mut self, table: &dyn TableLike, path: &str, shape: Shape) {
+        match shape {
+            Shape::Table(fields) => self.table(table, path, &[fields]),
+            Shape::Map(inner) => {
+                for (key, item) in table.iter() {
+                    let span = table.get_key_value(key).and_then(|(key, _)| key.span());
+                    self.item(item, &join(path, key), *inner, span);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn item(&mut self, item: &Item, path: &str, shape: Shape, key_span: Option<Range<usize>>) {
+        match (shape, item) {
+            (Shape::Table(_) | Shape::Map(_), _) if let Some(table) = item.as_table_like() => {
+                self.entries(table, path, shape);
+            }
+            (Shape::List(inner), Item::ArrayOfTables(tables)) => {
+                for (index, table) in tables.iter().enumerate() {
+                    let entry = format!("{}[{}]", path, index);
+                    match inner {
+                        Shape::Table(fields) => self.table(table, &entry, &[fields]),
+                        _ => self.mismatch(&entry, expected(*inner), item, key_span.clone()),
+                    }
+                }
+            }
+            (_, Item::Value(value)) => self.value(value, path, shape, key_span),
+            _ => self.mismatch(path, expected(shape), item, key_span),
+        }
+    }
+
+    fn value(&mut self, value: &Value, path: &str, shape: Shape, key_span: Option<Range<usize>>) {
+        let fits = match (shape, value) {
+            (Shape::Bool, Value::Boolean(_)) | (Shape::Integer, Value::Integer(_)) | (Shape::String, Value::String(_)) => true,
+            (Shape::Choice(choices), Value::String(choice)) => choices.contains(&choice.value().as_str()),
+            (Shape::StringOrList, Value::String(_)) => true,
+            (Shape::StringOrList, Value::Array(array)) => {
+                for (index, item) in array.iter().enumerate() {
+                    self.value(item, &format!("{}[{}]", path, index), Shape::String, key_span.clone());
+                }
+                true
+            }
+            (Shape::List(inner), Value::Array(array)) => {
+                for (index, item) in array.iter().enumerate() {
+                    self.value(item, &format!("{}[{}]", path, index), *inner, key_span.clone());
+                }
+                true
+            }
+            (Shape::Table(_) | Shape::Map(_), Value::InlineTable(table)) => {
+                self.entries(table, path, shape);
+                true
+            }
+            _ => false,
+        };
+        if !fits {
+            let span = value.span().or(key_span);
+            let found = match value {
+                Value::String(string) if matches!(shape, Shape::Choice(_)) => format!("\"{}\"", string.value()),
+                _ => describe(value).to_string(),
+            };
+            self.push_mismatch(path, expected(shape), found, span);
+        }
+    }
+
+    fn mismatch(&mut self, path: &str, expected: String, item: &Item, key_span: Option<Range<usize>>) {
+        let found = match item {
+            Item::Value(value) => describe(value),
+            Item::Table(_) => "a table",
+            Item::ArrayOfTables(_) => "an array of tables",
+            Item::None => "nothing",
+        };
+        self.push_mismatch(path, expected, found.to_string(), item.span().or(key_span));
+    }
+
+    fn push_mismatch(&mut self, path: &str, expected: String, found: String, span: Option<Range<usize>>) {
+        self.issues.push(Issue {
+            file: self.file.to_path_buf(),
+            position: span.map(|span| self.position(span.start)),
+            key: self.display(path),
+            problem: Problem::Mismatch { expected, found },
+        });
+    }
+
+    /// 1-based line and column of byte `offset`
+    fn position(&self, offset: usize) -> (usize, usize) {
+        let before = &self.source[..offset.min(self.source.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().map_or(0, |line| line.chars().count()) + 1;
+        (line, column)
+    }
+
+    /// `path` as written in the file
+    fn display(&self, path: &str) -> String {
+        join(self.scope.prefix(), path)
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    match (path.is_empty(), key.is_empty()) {
+        (true, _) => key.to_string(),
+        (_, true) => path.to_string(),
+        _ => format!("{}.{}", path, key),
+    }
+}
+
+/// The known key `key` is most likely a misspelling of
+fn suggest(key: &str, fields: &[&'static [(&'static str, Shape)]]) -> Option<&'static str> {
+    let max_distance = if key.len() >= 8 { 2 } else { 1 };
+    fields
+        .iter()
+        .flat_map(|fields| fields.iter())
+        .map(|(name, _)| (edit_distance(&key.to_lowercase(), name), *name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
+
+fn expected(shape: Shape) -> String {
+    match shape {
+        Shape::Bool => "a boolean".to_string(),
+        Shape::Integer => "an integer".to_string(),
+        Shape::String => "a string".to_string(),
+        Shape::Choice(choices) => format!(
+            "one of {}",
+            choices.iter().map(|choice| format!("\"{}\"", choice)).collect::<Vec<_>>().join(", ")
+        ),
+        Shape::StringOrList => "a string or an array of strings".to_string(),
+        Shape::List(inner) => match inner {
+            Shape::Table(_) => "an array of tables".to_string(),
+            _ => format!("an array of {}", plural(*inner)),
+        },
+        Shape::Table(_) | Shape::Map(_) => "a table".to_string(),
+    }
+}
+
+fn plural(shape: Shape) -> &'static str {
+    match shape {
+        Shape::Bool => "booleans",
+        Shape::Integer => "integers",
+        Shape::String | Shape::Choice(_) => "strings",
+        _ => "values",
+    }
+}
+
+fn describe(value: &Value) -> &'static str {
+    match value {
+        Value::String(_) => "a string",
+        Value::Integer(_) => "an integer",
+        Value::Float(_) => "a float",
+        Value::Boolean(_) => "a boolean",
+        Value::Datetime(_) => "a date-time",
+        Value::Array(_) => "an array",
+        Value::InlineTable(_) => "an inline table",
+    }
+}
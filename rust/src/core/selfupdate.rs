@@ -0,0 +1,223 @@
+//! Replacing the running pyforge binary with another release
+//! (`pyforge upgrade-self`).
+//!
+//! Releases come from the GitHub releases of the pyforge repository, or a
+//! mirror serving the same JSON (`[self-update] releases-url`). Stable
+//! releases are tagged `vX.Y.Z`; nightlies are prereleases tagged
+//! `nightly-...`. Each release carries one binary per platform,
+//! `pyforge-<arch>-<os>[.exe]`, and its `.sha256`. The checksum always comes
+//! from the upstream release of the same tag: a mirror serves the binary, but
+//! can't vouch for it.
+//!
+//! Which release is installed is recorded in the state directory, since a
+//! nightly binary reports the version of the stable release it follows.
+
+use crate::core::config::Config;
+use crate::core::dirs::state_dir;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::index::IndexClient;
+use crate::core::pep440::Version;
+use crate::core::permissions::{self, Mode};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Release feed of the upstream repository
+pub const DEFAULT_RELEASES_URL: &str = "https://api.github.com/repos/tuusuario/pyforge/releases";
+
+const INSTALLED_FILE: &str = "self-update.toml";
+
+/// A stream of releases to follow
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Channel {
+    #[default]
+    Stable,
+    Nightly,
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Channel::Stable => "stable",
+            Channel::Nightly => "nightly",
+        })
+    }
+}
+
+/// The release this binary came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Installed {
+    pub channel: Channel,
+    pub tag: String,
+}
+
+impl Installed {
+    /// The recorded release, else the stable release matching this binary's version
+    pub fn current() -> Self {
+        let recorded = fs::read_to_string(state_dir().join(INSTALLED_FILE))
+            .ok()
+            .and_then(|content| toml::from_str::<Installed>(&content).ok());
+        let built = Self {
+            channel: Channel::Stable,
+            tag: format!("v{}", env!("CARGO_PKG_VERSION")),
+        };
+        // A binary installed some other way since then wins over the record
+        match recorded {
+            Some(recorded) if recorded.channel == Channel::Nightly || recorded.tag == built.tag => recorded,
+            _ => built,
+        }
+    }
+
+    pub fn record(&self) -> Result<()> {
+        let dir = state_dir();
+        fs::create_dir_all(&dir).map_err(|e| PyForgeError::file_error(format!("Could not create {}", dir.display()), e))?;
+        let body = toml::to_string(self).map_err(|e| PyForgeError::internal(format!("Could not render {}: {}", INSTALLED_FILE, e)))?;
+        fs::write(dir.join(INSTALLED_FILE), body)?;
+        Ok(())
+    }
+
+    /// Version of a stable release, for comparisons
+    pub fn version(&self) -> Option<Version> {
+        release_version(&self.tag)
+    }
+}
+
+/// A release in the feed with a binary for this platform
+#[derive(Debug, Clone)]
+pub struct Release {
+    pub tag: String,
+    pub channel: Channel,
+    pub binary_url: String,
+}
+
+impl Release {
+    pub fn version(&self) -> Option<Version> {
+        release_version(&self.tag)
+    }
+}
+
+#[derive(Deserialize)]
+struct FeedRelease {
+    tag_name: String,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    assets: Vec<FeedAsset>,
+}
+
+#[derive(Deserialize)]
+struct FeedAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+impl FeedRelease {
+    fn asset(&self, name: &str) -> Option<String> {
+        self.assets.iter().find(|asset| asset.name == name).map(|asset| asset.browser_download_url.clone())
+    }
+}
+
+/// Releases in the feed that ship a binary for this platform, newest first
+pub fn releases(config: &Config) -> Result<Vec<Release>> {
+    let url = config.self_update.releases_url.as_deref().unwrap_or(DEFAULT_RELEASES_URL);
+    let body = IndexClient::new(config)?.download(url)?;
+    let feed: Vec<FeedRelease> = serde_json::from_slice(&body).map_err(|e| PyForgeError::ParseError {
+        file_type: "release feed".to_string(),
+        message: format!("{}: {}", url, e),
+    })?;
+
+    let binary = binary_name();
+    let mut releases: Vec<Release> = feed
+        .into_iter()
+        .filter(|release| !release.draft)
+        .filter_map(|release| {
+            let channel = match (release.prerelease, release.tag_name.starts_with("nightly")) {
+                (false, false) => Channel::Stable,
+                (true, true) => Channel::Nightly,
+                // Release candidates and the like belong to neither channel
+                _ => return None,
+            };
+            Some(Release {
+                channel,
+                binary_url: release.asset(&binary)?,
+                tag: release.tag_name,
+            })
+        })
+        .collect();
+    // The feed lists nightlies by date; order stable releases by version
+    releases.sort_by(|a, b| match (a.version(), b.version()) {
+        (Some(a), Some(b)) => b.cmp(&a),
+        _ => std::cmp::Ordering::Equal,
+    });
+    Ok(releases)
+}
+
+/// Download `release` and put it in place of the running binary
+pub fn install(config: &Config, release: &Release) -> Result<PathBuf> {
+    let current = std::env::current_exe()
+        .and_then(|path| path.canonicalize())
+        .map_err(|e| PyForgeError::file_error("Could not locate the running pyforge", e))?;
+    let client = IndexClient::new(config)?;
+    let checksum_url = upstream_checksum_url(&client, release)?;
+    let checksum = String::from_utf8_lossy(&client.download(&checksum_url)?).to_string();
+    let sha256 = checksum.split_whitespace().next().unwrap_or_default().to_lowercase();
+    if sha256.len() != 64 || !sha256.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(PyForgeError::ParseError {
+            file_type: "checksum".to_string(),
+            message: format!("{} does not start with a SHA-256", checksum_url),
+        });
+    }
+
+    let staged = current.with_extension("new");
+    client.download_to(&release.binary_url, &staged, Some(&sha256))?;
+    permissions::apply(&staged, Mode::Octal(0o755))?;
+    replace(&current, &staged)?;
+    Installed {
+        channel: release.channel,
+        tag: release.tag.clone(),
+    }
+    .record()?;
+    Ok(current)
+}
+
+/// The `.sha256` of this platform's binary in the upstream release tagged
+/// like `release`, whichever feed listed it
+fn upstream_checksum_url(client: &IndexClient, release: &Release) -> Result<String> {
+    let url = format!("{}/tags/{}", DEFAULT_RELEASES_URL, release.tag);
+    let upstream: FeedRelease = serde_json::from_slice(&client.download(&url)?).map_err(|e| PyForgeError::ParseError {
+        file_type: "release feed".to_string(),
+        message: format!("{}: {}", url, e),
+    })?;
+    upstream.asset(&format!("{}.sha256", binary_name())).ok_or_else(|| PyForgeError::Usage {
+        message: format!("The upstream release {} publishes no checksum for this platform", release.tag),
+    })
+}
+
+/// `pyforge-<arch>-<os>`, with `.exe` on Windows
+fn binary_name() -> String {
+    format!(
+        "pyforge-{}-{}{}",
+        std::env::consts::ARCH,
+        std::env::consts::OS,
+        std::env::consts::EXE_SUFFIX
+    )
+}
+
+fn release_version(tag: &str) -> Option<Version> {
+    Version::parse(tag.strip_prefix('v').unwrap_or(tag)).ok()
+}
+
+/// Windows can't overwrite a running executable, but it can rename one
+fn replace(current: &Path, staged: &Path) -> Result<()> {
+    let error = |e| PyForgeError::file_error(format!("Could not replace {}", current.display()), e);
+    if cfg!(windows) {
+        let old = current.with_extension("old.exe");
+        let _ = fs::remove_file(&old);
+        fs::rename(current, &old).map_err(error)?;
+    }
+    fs::rename(staged, current).map_err(error)
+}
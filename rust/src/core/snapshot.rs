@@ -0,0 +1,343 @@
+//! Portable snapshots of a synced environment (`pyforge env export/import`).
+//!
+//! A snapshot is a gzipped tarball holding a manifest and the environment's
+//! files. Virtual environments hard-code their own location and their base
+//! interpreter's, so importing rewrites those paths: script shebangs and
+//! activate scripts, `pyvenv.cfg`, `.pth` files and `direct_url.json` records.
+//! Compiled files are taken as they are, which is why a snapshot only imports
+//! on the platform and Python version it was exported from.
+
+use crate::core::error::{PyForgeError, Result};
+use crate::core::lockfile::Lockfile;
+use crate::core::markers::Environment;
+use crate::core::project::Project;
+use crate::core::toolchain;
+use crate::core::venv::{SyncState, Venv};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+/// First entry of every snapshot
+const MANIFEST: &str = "pyforge-snapshot.toml";
+
+/// Directory of the environment's files inside a snapshot
+const ENV_PREFIX: &str = "venv";
+
+/// Where and for what a snapshot was exported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Manifest {
+    /// Environment location on the exporting machine
+    pub venv: PathBuf,
+    /// Project root on the exporting machine
+    pub project: PathBuf,
+    pub implementation: String,
+    pub python: String,
+    pub platform: String,
+    /// `input-hash` of the lockfile the environment was synced from
+    pub input_hash: String,
+}
+
+impl Manifest {
+    fn describe(environment: &Environment) -> (String, String) {
+        (
+            format!("{} {}", environment.implementation_name, environment.python_full_version),
+            format!("{}-{}", environment.sys_platform, environment.platform_machine),
+        )
+    }
+
+    /// Default archive name: `<project>-env-<python>-<platform>.tar.gz`
+    pub fn file_name(&self, project: &Project) -> String {
+        let name = project.name().unwrap_or("project");
+        let python = self.python.rsplit(' ').next().unwrap_or(&self.python);
+        format!("{}-env-py{}-{}.tar.gz", name, python, self.platform)
+    }
+}
+
+/// The manifest `export` would write, checking the environment is in sync with the lockfile
+pub fn manifest(project: &Project, venv: &Venv, lockfile: &Lockfile) -> Result<Manifest> {
+    let synced = venv.sync_state().is_some_and(|state| state.input_hash == lockfile.input_hash);
+    if !synced {
+        return Err(PyForgeError::Usage {
+            message: "The environment is not in sync with pyforge.lock; run 'pyforge sync' before exporting it".to_string(),
+        });
+    }
+    let environment = Environment::from_interpreter(&venv.python().to_string_lossy())?;
+    let (python, platform) = Manifest::describe(&environment);
+    Ok(Manifest {
+        venv: venv.root.clone(),
+        project: project.root.clone(),
+        implementation: environment.implementation_name,
+        python,
+        platform,
+        input_hash: lockfile.input_hash.clone(),
+    })
+}
+
+/// Write `venv` and `manifest` as a snapshot at `output`
+pub fn export(venv: &Venv, manifest: &Manifest, output: &Path) -> Result<()> {
+    let file = File::create(output).map_err(|e| PyForgeError::file_error(format!("Could not create {}", output.display()), e))?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    // Interpreter links stay links; they are re-pointed on import
+    archive.follow_symlinks(false);
+
+    let body = toml::to_string(manifest).map_err(|e| PyForgeError::internal(format!("Could not render {}: {}", MANIFEST, e)))?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(body.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    archive.append_data(&mut header, MANIFEST, body.as_bytes())?;
+    archive.append_dir_all(ENV_PREFIX, &venv.root)?;
+    archive.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// Read only the manifest of the snapshot at `path`
+pub fn read_manifest(path: &Path) -> Result<Manifest> {
+    let mut archive = open(path)?;
+    let mut content = String::new();
+    let read = archive.entries().ok().and_then(|mut entries| entries.next()?.ok()).and_then(|mut entry| {
+        let is_manifest = entry.path().is_ok_and(|name| name.as_ref() == Path::new(MANIFEST));
+        (is_manifest && entry.read_to_string(&mut content).is_ok()).then_some(())
+    });
+    if read.is_none() {
+        return Err(not_a_snapshot(path));
+    }
+    toml::from_str(&content).map_err(|e| PyForgeError::InvalidToml {
+        file: format!("{} in {}", MANIFEST, path.display()),
+        message: e.to_string(),
+    })
+}
+
+/// Refuse a snapshot from another platform or Python than the configured interpreter's
+pub fn check_compatible(manifest: &Manifest) -> Result<()> {
+    let (python, platform) = Manifest::describe(&Environment::current()?);
+    if manifest.platform != platform || manifest.python != python {
+        return Err(PyForgeError::Usage {
+            message: format!(
+                "The snapshot was exported for {} on {}, but the configured interpreter is {} on {}; \
+                 run 'pyforge sync' to install the environment instead",
+                manifest.python, manifest.platform, python, platform
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Extract the snapshot at `path` to `destination`, which must not exist,
+/// and relocate it there
+pub fn import(path: &Path, manifest: &Manifest, project: &Project, destination: &Path) -> Result<Venv> {
+    let parent = destination.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(parent)?;
+    let name = destination.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let staging = parent.join(format!(".{}.importing", name));
+    let _ = fs::remove_dir_all(&staging);
+    fs::create_dir_all(&staging)?;
+
+    // Entries unpack below `staging` as they are named, `venv/...`
+    let unpacked = staging.join(ENV_PREFIX);
+    let result = extract(path, &staging).and_then(|_| {
+        relocate(&unpacked, destination, manifest, project)?;
+        fs::rename(&unpacked, destination)
+            .map_err(|e| PyForgeError::file_error(format!("Could not move the environment to {}", destination.display()), e))
+    });
+    let _ = fs::remove_dir_all(&staging);
+    result?;
+    Ok(Venv {
+        root: destination.to_path_buf(),
+    })
+}
+
+/// Record the import as a sync when `lockfile` is the one the snapshot was
+/// synced from; otherwise the next `pyforge sync` brings it up to date
+pub fn record_sync(venv: &Venv, manifest: &Manifest, project: &Project, lockfile: Option<&Lockfile>) -> Result<bool> {
+    match lockfile.filter(|lockfile| lockfile.input_hash == manifest.input_hash) {
+        Some(lockfile) => {
+            venv.record_sync(&SyncState::current(project, lockfile))?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+fn open(path: &Path) -> Result<tar::Archive<GzDecoder<File>>> {
+    let file = File::open(path).map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+    Ok(tar::Archive::new(GzDecoder::new(file)))
+}
+
+fn not_a_snapshot(path: &Path) -> PyForgeError {
+    PyForgeError::ParseError {
+        file_type: "environment snapshot".to_string(),
+        message: format!("{} does not start with {}; it was not written by `pyforge env export`", path.display(), MANIFEST),
+    }
+}
+
+fn extract(path: &Path, staging: &Path) -> Result<()> {
+    let mut archive = open(path)?;
+    let outside = |name: &Path, what: &str| PyForgeError::ParseError {
+        file_type: "environment snapshot".to_string(),
+        message: format!("{} has {} outside the environment: {}", path.display(), what, name.display()),
+    };
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.into_owned();
+        let Ok(relative) = name.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        if !is_inside(relative) {
+            return Err(outside(&name, "an entry"));
+        }
+        if relative.as_os_str().is_empty() {
+            continue;
+        }
+        let kind = entry.header().entry_type();
+        if kind.is_symlink() || kind.is_hard_link() {
+            let target = entry.link_name()?.map(|target| target.into_owned()).unwrap_or_default();
+            let allowed = if kind.is_hard_link() {
+                // Hard links name another entry of the archive
+                target.strip_prefix(ENV_PREFIX).is_ok_and(is_inside)
+            } else if target.is_absolute() {
+                // Only an interpreter link to the base Python, which relink_interpreters replaces
+                relative.parent().is_some_and(|parent| parent == Path::new("bin") || parent == Path::new("Scripts"))
+                    && relative.file_name().is_some_and(|file| file.to_string_lossy().starts_with("python"))
+            } else {
+                is_inside(&relative.parent().unwrap_or(Path::new("")).join(&target))
+            };
+            if !allowed {
+                return Err(outside(&name, "a link"));
+            }
+        }
+        // unpack_in refuses to write through links unpacked earlier; without
+        // preserved permissions, setuid and setgid bits are dropped
+        let unpacked = entry
+            .unpack_in(staging)
+            .map_err(|e| PyForgeError::file_error(format!("Could not unpack {} from {}", name.display(), path.display()), e))?;
+        if !unpacked {
+            return Err(outside(&name, "an entry"));
+        }
+    }
+    Ok(())
+}
+
+/// Whether `path`, relative to the environment, stays inside it once `..` is applied
+fn is_inside(path: &Path) -> bool {
+    let mut depth = 0usize;
+    for component in path.components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir if depth > 0 => depth -= 1,
+            _ => return false,
+        }
+    }
+    true
+}
+
+/// Rewrite the exporting machine's paths in the environment at `staging`,
+/// which is about to be moved to `destination`
+fn relocate(staging: &Path, destination: &Path, manifest: &Manifest, project: &Project) -> Result<()> {
+    let venv = Venv {
+        root: staging.to_path_buf(),
+    };
+    let base = toolchain::python()?;
+    let replacements = [
+        (manifest.venv.to_string_lossy().to_string(), destination.to_string_lossy().to_string()),
+        (manifest.project.to_string_lossy().to_string(), project.root.to_string_lossy().to_string()),
+    ];
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(venv.bin_dir())?.flatten() {
+        if entry.file_type().is_ok_and(|kind| kind.is_file()) {
+            files.push(entry.path());
+        }
+    }
+    if let Ok(site) = venv.site_packages() {
+        for entry in fs::read_dir(&site)?.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.ends_with(".pth") || name.ends_with(".egg-link") {
+                files.push(path);
+            } else if name.ends_with(".dist-info") {
+                files.push(path.join("direct_url.json"));
+            }
+        }
+    }
+    for file in files {
+        // Binary launchers embed their paths at fixed offsets and are left alone
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        let rewritten = replace_paths(&content, &replacements);
+        if rewritten != content {
+            fs::write(&file, rewritten)?;
+        }
+    }
+
+    rewrite_pyvenv_cfg(staging, &base, &replacements)?;
+    relink_interpreters(&venv, &base)
+}
+
+/// Replace every old path in `content` in a single pass, so a new path that
+/// contains an old one is not rewritten again
+fn replace_paths(content: &str, replacements: &[(String, String)]) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut rest = content;
+    'scan: while let Some(character) = rest.chars().next() {
+        for (old, new) in replacements {
+            if let Some(after) = rest.strip_prefix(old.as_str()) {
+                output.push_str(new);
+                rest = after;
+                continue 'scan;
+            }
+        }
+        output.push(character);
+        rest = &rest[character.len_utf8()..];
+    }
+    output
+}
+
+/// Point `home`, `executable` and `command` at the local base interpreter
+fn rewrite_pyvenv_cfg(staging: &Path, base: &Path, replacements: &[(String, String)]) -> Result<()> {
+    let path = staging.join("pyvenv.cfg");
+    let content = fs::read_to_string(&path).map_err(|e| PyForgeError::file_error(format!("Could not read {}", path.display()), e))?;
+    let home = base.parent().unwrap_or(Path::new("/"));
+    let lines: Vec<String> = content
+        .lines()
+        .map(|line| match line.split_once('=').map(|(key, value)| (key.trim(), value.trim())) {
+            Some(("home", _)) => format!("home = {}", home.display()),
+            Some(("executable", _)) => format!("executable = {}", base.display()),
+            Some(("command", value)) => match value.split_once(" -m venv ") {
+                Some((_, rest)) => format!("command = {} -m venv {}", base.display(), replace_paths(rest, replacements)),
+                None => line.to_string(),
+            },
+            _ => line.to_string(),
+        })
+        .collect();
+    fs::write(&path, lines.join("\n") + "\n")?;
+    Ok(())
+}
+
+/// Re-point interpreter links whose absolute target does not exist here
+#[cfg(unix)]
+fn relink_interpreters(venv: &Venv, base: &Path) -> Result<()> {
+    for entry in fs::read_dir(venv.bin_dir())?.flatten() {
+        let path = entry.path();
+        let Ok(target) = fs::read_link(&path) else {
+            continue;
+        };
+        if target.is_absolute() && !target.exists() {
+            fs::remove_file(&path)?;
+            std::os::unix::fs::symlink(base, &path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn relink_interpreters(_venv: &Venv, _base: &Path) -> Result<()> {
+    Ok(())
+}
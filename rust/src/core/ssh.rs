@@ -0,0 +1,119 @@
+//! Running project commands on other machines over SSH (experimental).
+//!
+//! The project tree is copied to each host with rsync, minus git metadata,
+//! environments and whatever `.gitignore` ignores. Then pyforge on the host
+//! syncs the lockfile and runs the command. Hosts are anything `ssh` accepts,
+//! including `~/.ssh/config` aliases and `user@host`.
+
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::process::shell_quote;
+use crate::core::project::Project;
+use sha2::{Digest, Sha256};
+use std::process::{Command, Stdio};
+
+/// Never prompt: hosts run in parallel without a terminal
+const SSH_OPTIONS: [&str; 2] = ["-o", "BatchMode=yes"];
+
+/// Always left out of the copy
+const EXCLUDED: [&str; 9] = [
+    ".git",
+    ".venv",
+    ".venvs",
+    "__pycache__",
+    ".pytest_cache",
+    ".mypy_cache",
+    "build",
+    "dist",
+    "*.egg-info",
+];
+
+/// Marks a directory on a host as pyforge's copy of a project; the upload
+/// refuses to fill, and `--delete` to empty, a directory without it that
+/// already holds files
+const MARKER: &str = ".pyforge-remote";
+
+/// Check that `ssh` and `rsync` are installed
+pub fn require_tools() -> Result<()> {
+    for tool in ["ssh", "rsync"] {
+        which::which(tool).map_err(|_| PyForgeError::CommandNotFound {
+            command: tool.to_string(),
+        })?;
+    }
+    Ok(())
+}
+
+/// Directory holding the project on the hosts, relative to the remote home
+pub fn remote_dir(project: &Project, config: &Config) -> Result<String> {
+    let Some(dir) = &config.remote.dir else {
+        let hash = format!("{:x}", Sha256::digest(project.root.display().to_string().as_bytes()));
+        return Ok(format!(
+            ".cache/pyforge/remote/{}-{}",
+            project.name().unwrap_or("project"),
+            &hash[..8]
+        ));
+    };
+    let relative = dir.strip_prefix("~/").unwrap_or(dir).trim_end_matches('/');
+    // rsync --delete empties the directory: never the home directory, the
+    // filesystem root or a place outside the home
+    let dedicated = !relative.is_empty()
+        && !relative.starts_with(['/', '~'])
+        && relative.split('/').all(|segment| !matches!(segment, "" | "." | ".."));
+    if !dedicated {
+        return Err(PyForgeError::Usage {
+            message: format!(
+                "remote.dir = \"{}\" must be a subdirectory of the remote home, such as \"~/work/<project>\"",
+                dir
+            ),
+        });
+    }
+    Ok(relative.to_string())
+}
+
+/// rsync invocation mirroring the project tree into `dir` on `host`
+pub fn upload(project: &Project, config: &Config, host: &str, dir: &str) -> Command {
+    let mut command = Command::new("rsync");
+    command
+        .args(["--archive", "--compress", "--delete"])
+        .arg("--rsh")
+        .arg(format!("ssh {}", SSH_OPTIONS.join(" ")))
+        .arg(format!("--rsync-path={} && rsync", claim(dir)))
+        .arg("--filter=:- .gitignore")
+        .arg(format!("--exclude=/{}", MARKER));
+    for pattern in EXCLUDED.iter().copied().chain(config.remote.exclude.iter().map(String::as_str)) {
+        command.arg(format!("--exclude={}", pattern));
+    }
+    command
+        .arg(format!("{}/", project.root.display()))
+        .arg(format!("{}:{}/", host, dir))
+        .stdin(Stdio::null());
+    command
+}
+
+/// Shell commands creating `dir` on a host, or failing unless it is empty or
+/// already pyforge's, then marking it
+fn claim(dir: &str) -> String {
+    let quoted = shell_quote(dir);
+    let marker = shell_quote(&format!("{}/{}", dir, MARKER));
+    format!(
+        "mkdir -p {dir} && {{ [ -e {marker} ] || [ -z \"$(ls -A {dir})\" ] || {{ echo {message} >&2; exit 1; }}; }} && touch {marker}",
+        dir = quoted,
+        marker = marker,
+        message = shell_quote(&format!("~/{} holds files pyforge did not put there; set remote.dir to a new directory", dir)),
+    )
+}
+
+/// ssh invocation syncing the environment in `dir` on `host` and running `line` in it
+pub fn execute(config: &Config, host: &str, dir: &str, line: &str) -> Command {
+    let pyforge = shell_quote(&config.remote.pyforge);
+    let script = format!(
+        "cd {} && {} sync --locked && exec {} env exec -- sh -c {}",
+        shell_quote(dir),
+        pyforge,
+        pyforge,
+        shell_quote(line)
+    );
+    let mut command = Command::new("ssh");
+    command.args(SSH_OPTIONS).arg(host).arg(script).stdin(Stdio::null());
+    command
+}
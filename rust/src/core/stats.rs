@@ -0,0 +1,98 @@
+//! How widely used and how actively released a package is, for
+//! `pyforge show`.
+//!
+//! Release cadence comes from the upload times on the index. Download counts
+//! come from pypistats.org (or `[show] stats-url`), and only for packages found
+//! on PyPI: asking about a package from a private index would publish its name.
+
+use crate::core::config::{Config, IndexConfig};
+use crate::core::dist::DistFilename;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::index::{IndexClient, IndexFile};
+use crate::core::pep440::Version;
+use crate::core::pep508::normalize_name;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use time::{Duration, OffsetDateTime};
+
+/// Download statistics API of pypistats.org
+pub const DEFAULT_STATS_URL: &str = "https://pypistats.org/api";
+
+/// Releases uploaded within this window count towards the cadence
+const CADENCE_WINDOW: Duration = Duration::days(365);
+
+/// Downloads over the last day, week and month
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Downloads {
+    pub last_day: u64,
+    pub last_week: u64,
+    pub last_month: u64,
+}
+
+#[derive(Deserialize)]
+struct RecentResponse {
+    data: Downloads,
+}
+
+/// One version on the index
+#[derive(Debug, Clone)]
+pub struct Release {
+    pub version: Version,
+    /// Earliest upload time of its files, if the index reports them
+    pub uploaded: Option<OffsetDateTime>,
+    /// Every file of the release is yanked
+    pub yanked: bool,
+}
+
+/// Versions of `name` in `files`, oldest first
+pub fn releases(files: &[IndexFile], name: &str) -> Vec<Release> {
+    let mut releases: BTreeMap<Version, Release> = BTreeMap::new();
+    for file in files {
+        let Some(parsed) = DistFilename::parse(&file.filename, name) else {
+            continue;
+        };
+        let release = releases.entry(parsed.version.clone()).or_insert_with(|| Release {
+            version: parsed.version,
+            uploaded: None,
+            yanked: true,
+        });
+        release.uploaded = match (release.uploaded, file.upload_time) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        release.yanked &= file.yanked;
+    }
+    releases.into_values().collect()
+}
+
+/// Releases uploaded in the last year, and the median days between them
+pub fn cadence(releases: &[Release]) -> Option<(usize, Option<i64>)> {
+    let mut uploads: Vec<OffsetDateTime> = releases.iter().filter_map(|release| release.uploaded).collect();
+    if uploads.is_empty() {
+        return None;
+    }
+    uploads.sort();
+    let since = OffsetDateTime::now_utc() - CADENCE_WINDOW;
+    let recent = uploads.iter().filter(|uploaded| **uploaded >= since).count();
+    let mut gaps: Vec<i64> = uploads.windows(2).map(|pair| (pair[1] - pair[0]).whole_days()).collect();
+    gaps.sort();
+    Some((recent, gaps.get(gaps.len() / 2).copied()))
+}
+
+/// Whether download counts may be looked up for a package found on `index`
+pub fn publishes_downloads(config: &Config, index: &IndexConfig) -> bool {
+    config.show.download_stats
+        && reqwest::Url::parse(&index.url).is_ok_and(|url| url.host_str() == Some("pypi.org") || index.name == "pypi")
+}
+
+/// Recent downloads of `name` from the statistics API
+pub fn downloads(config: &Config, name: &str) -> Result<Downloads> {
+    let base = config.show.stats_url.as_deref().unwrap_or(DEFAULT_STATS_URL);
+    let url = format!("{}/packages/{}/recent", base.trim_end_matches('/'), normalize_name(name));
+    let body = IndexClient::new(config)?.download(&url)?;
+    let response: RecentResponse = serde_json::from_slice(&body).map_err(|e| PyForgeError::ParseError {
+        file_type: "download statistics".to_string(),
+        message: format!("{}: {}", url, e),
+    })?;
+    Ok(response.data)
+}
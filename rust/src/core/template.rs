@@ -0,0 +1,857 @@
+//! Project templates: render at init time, and later re-render and merge
+//! template changes into the project (`pyforge template update`).
+//!
+//! A template is a directory, usually a git repository, with an optional
+//! `pyforge-template.toml` and the files to render, under `template/` if that
+//! exists or at the top level otherwise. `{{ name }}` placeholders are
+//! replaced in file contents and paths; besides the template's own variables
+//! they can name built-in context (`git_user_name`, `year`, `os`, ...) and
+//! environment variables (`env.NAME`), with a fallback for unset ones:
+//! `{{ env.COMPANY | default("Example Corp") }}`. Files that are executable in the
+//! template, or start with `#!`, are made executable; `[chmod]` in the
+//! manifest and `pyforge init --chmod` set other modes.
+//!
+//! `extends` in the manifest layers a template over a base template: the
+//! base's files are rendered first and the template's own replace them, and
+//! the manifests merge with the template's settings winning.
+//!
+//! `pyforge template snapshot` renders a template with the canned answers in
+//! `[snapshots]` and compares the result with the copies committed under
+//! `snapshots/`, so template authors notice when a change breaks a render.
+
+use crate::core::config::Config;
+use crate::core::dirs::{cache_dir, data_dir};
+use crate::core::error::{PyForgeError, Result};
+use crate::core::git;
+use crate::core::index;
+use crate::core::paths;
+use crate::core::pep508::normalize_name;
+use crate::core::permissions::{self, ChmodRule, Mode};
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+use time::OffsetDateTime;
+
+/// Template metadata file, at the template root
+pub const MANIFEST_FILE: &str = "pyforge-template.toml";
+
+/// Written into generated projects to record where they came from
+pub const RECORD_FILE: &str = ".pyforge-template.toml";
+
+/// Committed renders of a template, one directory per `[snapshots]` case
+pub const SNAPSHOT_DIR: &str = "snapshots";
+
+/// Fixed stand-ins for the built-in context in snapshots, so renders don't
+/// depend on who makes them, where, or when
+const SNAPSHOT_CONTEXT: [(&str, &str); 5] = [
+    ("year", "2000"),
+    ("date", "2000-01-01"),
+    ("os", "linux"),
+    ("git_user_name", "Jane Doe"),
+    ("git_user_email", "jane@example.com"),
+];
+
+/// Project name snapshots are rendered for
+const SNAPSHOT_PROJECT: &str = "example";
+
+const RECORD_HEADER: &str = "# Written by `pyforge init --template`; used by `pyforge template update`.\n\n";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Manifest {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// Base template whose files and settings this one builds on: a path
+    /// (relative to this template), a user template name, or a git URL
+    pub extends: Option<String>,
+    /// Template variables and their defaults
+    pub variables: BTreeMap<String, String>,
+    /// Modes for rendered files, by glob: `"bin/*" = "+x"`
+    pub chmod: BTreeMap<String, String>,
+    /// Files and directories rendered only when a condition on the variables
+    /// holds, by glob: `"Dockerfile" = "use_docker"`
+    pub when: BTreeMap<String, String>,
+    /// Files copied as they are, placeholders and all, by glob: `["assets/**"]`
+    pub copy: Vec<String>,
+    /// Answers to render snapshots with, by case: `[snapshots.no-docker]`
+    /// `use_docker = "no"`; without any, one `default` case uses the defaults
+    pub snapshots: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+/// Which template (and which revision of it) a project was generated from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Record {
+    pub source: String,
+    /// Git commit the project was last rendered from; unset for plain directories
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+    /// `--chmod` rules given at init, applied again on update
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chmod: Vec<String>,
+    /// Git commits of the base templates, by source
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub bases: BTreeMap<String, String>,
+}
+
+impl Record {
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = project_root.join(RECORD_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)?;
+        toml::from_str(&content).map(Some).map_err(|e| PyForgeError::InvalidToml {
+            file: path.display().to_string(),
+            message: e.to_string(),
+        })
+    }
+
+    pub fn write(&self, project_root: &Path) -> Result<()> {
+        let body = toml::to_string(self).map_err(|e| PyForgeError::internal(format!("Could not render template record: {}", e)))?;
+        fs::write(project_root.join(RECORD_FILE), format!("{}{}", RECORD_HEADER, body))?;
+        Ok(())
+    }
+}
+
+/// Base templates extending each other deeper than this are assumed to loop
+const MAX_BASES: usize = 8;
+
+/// A template checked out at one revision
+pub struct Template {
+    pub source: String,
+    pub commit: Option<String>,
+    /// Its own manifest merged over those of its bases
+    pub manifest: Manifest,
+    root: PathBuf,
+    /// The template named by `extends`, fetched with its own bases
+    base: Option<Box<Template>>,
+    _checkout: Option<TempDir>,
+}
+
+impl Template {
+    /// Fetch `source` (a path, a user template name, or a git URL) at `revision`,
+    /// or at its default branch. A `#ref` suffix on the source also selects a revision.
+    /// Base templates are fetched at the revision their `extends` names.
+    pub fn fetch(source: &str, revision: Option<&str>) -> Result<Self> {
+        Self::fetch_pinned(source, revision, &BTreeMap::new())
+    }
+
+    /// Like [`Template::fetch`], with git base templates at the commits in
+    /// `bases` (by source) instead, to render a project's original revision
+    pub fn fetch_pinned(source: &str, revision: Option<&str>, bases: &BTreeMap<String, String>) -> Result<Self> {
+        Self::fetch_layer(source, revision, bases, Path::new("."), &mut Vec::new(), true)
+    }
+
+    /// The template in the local directory `path` as it is on disk, uncommitted
+    /// changes included, for its author to check; its bases are fetched as usual
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::fetch_layer(&absolute(path).display().to_string(), None, &BTreeMap::new(), Path::new("."), &mut Vec::new(), false)
+    }
+
+    fn fetch_layer(
+        source: &str,
+        revision: Option<&str>,
+        pinned: &BTreeMap<String, String>,
+        relative_to: &Path,
+        chain: &mut Vec<String>,
+        clone: bool,
+    ) -> Result<Self> {
+        let (location, fragment) = match source.rsplit_once('#') {
+            Some((location, fragment)) => (location, Some(fragment)),
+            None => (source, None),
+        };
+        let location = expand_shorthand(location);
+        let location = location.as_str();
+
+        let archive = if is_archive(location) {
+            if fragment.is_some() {
+                return Err(PyForgeError::Usage {
+                    message: format!("'{}' is an archive; #<ref> only selects revisions of git templates", source),
+                });
+            }
+            Some(fetch_archive(location)?)
+        } else {
+            None
+        };
+        let local = archive.clone().or_else(|| {
+            [relative_to.join(location), user_template_dir().join(location)]
+                .into_iter()
+                .find(|path| path.is_dir())
+        });
+        // Record local templates by absolute path, since init runs outside the project
+        let recorded = match &local {
+            Some(path) if archive.is_none() => absolute(path).display().to_string(),
+            _ => location.to_string(),
+        };
+        if chain.contains(&recorded) || chain.len() > MAX_BASES {
+            chain.push(recorded);
+            return Err(PyForgeError::Usage {
+                message: format!("Templates extend each other in a loop: {}", chain.join(" → ")),
+            });
+        }
+        chain.push(recorded.clone());
+        let revision = if chain.len() > 1 { pinned.get(&recorded).map(String::as_str) } else { None }
+            .or(revision)
+            .or(fragment);
+
+        let is_git = match &local {
+            Some(_) if archive.is_some() => false,
+            Some(path) => git(path, &["rev-parse", "--git-dir"]).is_ok(),
+            None => is_remote(location),
+        };
+        if local.is_none() && !is_git {
+            return Err(PyForgeError::TemplateNotFound { template: source.to_string() });
+        }
+
+        let (root, commit, checkout) = if is_git && clone {
+            // Clone even local repositories, so any revision can be rendered
+            let checkout = tempfile::Builder::new().prefix("pyforge-template-").tempdir()?;
+            let url = local.as_ref().map_or_else(|| location.to_string(), |path| absolute(path).display().to_string());
+            let status = Command::new("git")
+                .args(["clone", "--quiet", &url])
+                .arg(checkout.path())
+                .status()
+                .map_err(|_| PyForgeError::CommandNotFound { command: "git".to_string() })?;
+            if !status.success() {
+                return Err(PyForgeError::command_failed(format!("git clone {}", url), status.code().unwrap_or(-1)));
+            }
+            if let Some(revision) = revision {
+                git(checkout.path(), &["checkout", "--quiet", revision])?;
+            }
+            let commit = git(checkout.path(), &["rev-parse", "HEAD"])?.trim().to_string();
+            (checkout.path().to_path_buf(), Some(commit), Some(checkout))
+        } else {
+            (local.clone().expect("checked above"), None, None)
+        };
+
+        let manifest_path = root.join(MANIFEST_FILE);
+        let mut manifest: Manifest = if manifest_path.is_file() {
+            toml::from_str(&fs::read_to_string(&manifest_path)?).map_err(|e| PyForgeError::InvalidToml {
+                file: manifest_path.display().to_string(),
+                message: e.to_string(),
+            })?
+        } else {
+            Manifest::default()
+        };
+
+        // Relative paths in `extends` are relative to where the template lives, not its checkout
+        let base = match &manifest.extends {
+            Some(extends) => {
+                let relative_to = local.clone().unwrap_or_else(|| root.clone());
+                Some(Box::new(Self::fetch_layer(extends, None, pinned, &relative_to, chain, true)?))
+            }
+            None => None,
+        };
+        if let Some(base) = &base {
+            manifest = base.manifest.clone().extended_by(manifest);
+        }
+
+        Ok(Self {
+            source: recorded,
+            commit,
+            manifest,
+            root,
+            base,
+            _checkout: checkout,
+        })
+    }
+
+    /// Git commits of the base templates, by source
+    pub fn bases(&self) -> BTreeMap<String, String> {
+        let mut bases = BTreeMap::new();
+        let mut next = self.base.as_deref();
+        while let Some(base) = next {
+            if let Some(commit) = &base.commit {
+                bases.insert(base.source.clone(), commit.clone());
+            }
+            next = base.base.as_deref();
+        }
+        bases
+    }
+
+    /// Variables for a new project: the template's defaults plus the built-ins
+    pub fn variables(&self, project_name: &str) -> BTreeMap<String, String> {
+        let mut variables = self.defaults();
+        variables.insert("project_name".to_string(), project_name.to_string());
+        variables.insert("module_name".to_string(), normalize_name(project_name).replace('-', "_"));
+        variables
+    }
+
+    /// The built-in context, the environment variables the template refers to,
+    /// and the manifest's defaults, which may refer to both. Everything is
+    /// recorded in the project, so `template update` renders with the same values.
+    pub fn defaults(&self) -> BTreeMap<String, String> {
+        let mut variables = context();
+        let mut referenced: Vec<String> = self.manifest.variables.values().cloned().collect();
+        if let Ok(files) = self.all_files(&BTreeMap::new()) {
+            for (path, source) in files {
+                referenced.push(path.to_string_lossy().to_string());
+                referenced.extend(fs::read_to_string(&source));
+            }
+        }
+        variables.extend(environment(referenced.iter().map(String::as_str)));
+        let defaults: Vec<(String, String)> = self
+            .manifest
+            .variables
+            .iter()
+            .map(|(name, default)| (name.clone(), substitute(default, &variables)))
+            .collect();
+        variables.extend(defaults);
+        variables
+    }
+
+    /// Variables to render each snapshot case with: the case's answers over
+    /// the manifest's defaults, with fixed values for the built-in context
+    /// and no environment variables
+    pub fn snapshot_cases(&self) -> BTreeMap<String, BTreeMap<String, String>> {
+        let mut variables: BTreeMap<String, String> =
+            SNAPSHOT_CONTEXT.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+        let defaults: Vec<(String, String)> = self
+            .manifest
+            .variables
+            .iter()
+            .map(|(name, default)| (name.clone(), substitute(default, &variables)))
+            .collect();
+        variables.extend(defaults);
+        variables.insert("project_name".to_string(), SNAPSHOT_PROJECT.to_string());
+        variables.insert("module_name".to_string(), SNAPSHOT_PROJECT.to_string());
+
+        if self.manifest.snapshots.is_empty() {
+            return BTreeMap::from([("default".to_string(), variables)]);
+        }
+        self.manifest
+            .snapshots
+            .iter()
+            .map(|(case, answers)| {
+                let mut variables = variables.clone();
+                variables.extend(answers.clone());
+                (case.clone(), variables)
+            })
+            .collect()
+    }
+
+    /// Where the template's snapshots are committed
+    pub fn snapshot_dir(&self) -> PathBuf {
+        self.root.join(SNAPSHOT_DIR)
+    }
+
+    /// Render every template file into `dest`, returning the relative paths written
+    pub fn render(&self, dest: &Path, variables: &BTreeMap<String, String>, rules: &[ChmodRule]) -> Result<Vec<PathBuf>> {
+        let files = self.rendered(variables)?;
+        let modes = self.modes(variables, rules)?;
+        for (relative, content) in &files {
+            let target = paths::long(&dest.join(relative));
+            write(&target, content)?;
+            if let Some(mode) = modes.get(relative) {
+                permissions::apply(&target, *mode)?;
+            }
+        }
+        Ok(files.into_keys().collect())
+    }
+
+    /// Rendered contents of every template file, keyed by relative path
+    pub fn rendered(&self, variables: &BTreeMap<String, String>) -> Result<BTreeMap<PathBuf, Vec<u8>>> {
+        let copied = self.patterns(&self.manifest.copy)?;
+        let mut files = BTreeMap::new();
+        for (path, source) in self.files(variables)? {
+            let bytes = fs::read(&source)?;
+            if matches_any(&copied, &path) {
+                files.insert(path, bytes);
+                continue;
+            }
+            // Binary files are copied as they are
+            let content = match String::from_utf8(bytes) {
+                Ok(text) => substitute(&text, variables).into_bytes(),
+                Err(error) => error.into_bytes(),
+            };
+            files.insert(path, content);
+        }
+        Ok(files)
+    }
+
+    /// Modes to set on rendered files: executable ones and scripts get `+x`,
+    /// then the manifest's `[chmod]` rules and `rules` apply, the last match winning
+    pub fn modes(&self, variables: &BTreeMap<String, String>, rules: &[ChmodRule]) -> Result<BTreeMap<PathBuf, Mode>> {
+        let mut all = Vec::new();
+        for (pattern, mode) in &self.manifest.chmod {
+            all.push(ChmodRule::new(pattern, mode)?);
+        }
+        all.extend_from_slice(rules);
+
+        let mut modes = BTreeMap::new();
+        for (path, source) in self.files(variables)? {
+            let executable = permissions::is_executable(&source) || starts_with_shebang(&source);
+            let mode = ChmodRule::mode_for(&all, &path).or(executable.then_some(Mode::Executable));
+            if let Some(mode) = mode {
+                modes.insert(path, mode);
+            }
+        }
+        Ok(modes)
+    }
+
+    /// Template files whose `[when]` conditions hold, as (rendered relative path, source path)
+    fn files(&self, variables: &BTreeMap<String, String>) -> Result<Vec<(PathBuf, PathBuf)>> {
+        let conditions = self
+            .manifest
+            .when
+            .iter()
+            .map(|(pattern, condition)| Ok((self.pattern(pattern)?, condition.as_str())))
+            .collect::<Result<Vec<_>>>()?;
+        let mut files = Vec::new();
+        for (path, source) in self.all_files(variables)? {
+            let mut included = true;
+            for (pattern, condition) in &conditions {
+                if matches_any(std::slice::from_ref(pattern), &path) && !self.holds(condition, variables)? {
+                    included = false;
+                }
+            }
+            if included {
+                files.push((path, source));
+            }
+        }
+        Ok(files)
+    }
+
+    /// Every template file, with the base templates' files underneath, as
+    /// (rendered relative path, source path)
+    fn all_files(&self, variables: &BTreeMap<String, String>) -> Result<Vec<(PathBuf, PathBuf)>> {
+        let mut files: BTreeMap<PathBuf, PathBuf> = match &self.base {
+            Some(base) => base.all_files(variables)?.into_iter().collect(),
+            None => BTreeMap::new(),
+        };
+        files.extend(self.own_files(variables)?);
+        Ok(files.into_iter().collect())
+    }
+
+    /// Files of this template itself, without its bases
+    fn own_files(&self, variables: &BTreeMap<String, String>) -> Result<Vec<(PathBuf, PathBuf)>> {
+        let files_root = if self.root.join("template").is_dir() {
+            self.root.join("template")
+        } else {
+            self.root.clone()
+        };
+
+        let mut files = Vec::new();
+        // Snapshots are only skipped at the top level, where they live
+        let snapshots = self.root.join(SNAPSHOT_DIR);
+        let walker = walkdir::WalkDir::new(&files_root)
+            .min_depth(1)
+            .into_iter()
+            .filter_entry(|entry| entry.file_name() != ".git" && entry.path() != snapshots);
+        for entry in walker {
+            let entry = entry.map_err(|e| PyForgeError::internal(format!("Could not read the template: {}", e)))?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let relative = entry.path().strip_prefix(&files_root).expect("walked under root");
+            if relative == Path::new(MANIFEST_FILE) {
+                continue;
+            }
+            let path = PathBuf::from(substitute(&relative.to_string_lossy(), variables));
+            paths::check_portable(&path, "Template file")?;
+            files.push((path, entry.path().to_path_buf()));
+        }
+        Ok(files)
+    }
+
+    fn pattern(&self, pattern: &str) -> Result<glob::Pattern> {
+        glob::Pattern::new(pattern).map_err(|e| self.invalid(format!("invalid glob '{}': {}", pattern, e)))
+    }
+
+    fn patterns(&self, patterns: &[String]) -> Result<Vec<glob::Pattern>> {
+        patterns.iter().map(|pattern| self.pattern(pattern)).collect()
+    }
+
+    /// Evaluate a `[when]` condition: `name` (set and not false, no, off or 0),
+    /// `not name`, `name == "value"` or `name != "value"`
+    fn holds(&self, condition: &str, variables: &BTreeMap<String, String>) -> Result<bool> {
+        let condition = condition.trim();
+        let value = |name: &str| -> Result<String> {
+            let name = name.trim();
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(self.invalid(format!("invalid condition '{}'", condition)));
+            }
+            Ok(variables.get(name).cloned().unwrap_or_default())
+        };
+        let literal = |text: &str| {
+            let text = text.trim();
+            ['"', '\'']
+                .iter()
+                .find_map(|quote| text.strip_prefix(*quote)?.strip_suffix(*quote))
+                .unwrap_or(text)
+                .to_string()
+        };
+        if let Some((name, expected)) = condition.split_once("==") {
+            Ok(value(name)? == literal(expected))
+        } else if let Some((name, expected)) = condition.split_once("!=") {
+            Ok(value(name)? != literal(expected))
+        } else if let Some(name) = condition.strip_prefix("not ") {
+            Ok(!truthy(&value(name)?))
+        } else {
+            Ok(truthy(&value(condition)?))
+        }
+    }
+
+    fn invalid(&self, message: String) -> PyForgeError {
+        PyForgeError::InvalidConfig {
+            file: self.root.join(MANIFEST_FILE).display().to_string(),
+            source: Box::new(std::io::Error::other(message)),
+        }
+    }
+
+    pub fn record(&self, variables: BTreeMap<String, String>, rules: &[ChmodRule]) -> Record {
+        Record {
+            source: self.source.clone(),
+            commit: self.commit.clone(),
+            version: self.manifest.version.clone(),
+            variables,
+            chmod: rules.iter().map(ToString::to_string).collect(),
+            bases: self.bases(),
+        }
+    }
+}
+
+impl Manifest {
+    /// `template`'s manifest layered over this base manifest: its variables,
+    /// rules and copy globs are added to the base's, and win where both set one
+    fn extended_by(mut self, template: Manifest) -> Manifest {
+        self.variables.extend(template.variables);
+        self.chmod.extend(template.chmod);
+        self.when.extend(template.when);
+        self.copy.extend(template.copy);
+        self.snapshots.extend(template.snapshots);
+        Manifest {
+            name: template.name.or(self.name),
+            version: template.version,
+            extends: template.extends,
+            ..self
+        }
+    }
+}
+
+/// What happened to one file during an update
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Added,
+    Updated,
+    Merged,
+    Conflicted,
+    Removed,
+}
+
+/// Apply the template changes between `old` and `new` renders to `project_root`.
+///
+/// Files the project did not modify are replaced; files both sides modified
+/// are merged with `git merge-file`, leaving conflict markers where the
+/// changes overlap. Added and changed files get their mode from `modes`.
+pub fn apply_update(
+    project_root: &Path,
+    old: &BTreeMap<PathBuf, Vec<u8>>,
+    new: &BTreeMap<PathBuf, Vec<u8>>,
+    modes: &BTreeMap<PathBuf, Mode>,
+) -> Result<Vec<(PathBuf, Change)>> {
+    let paths: BTreeSet<&PathBuf> = old.keys().chain(new.keys()).collect();
+    let mut changes = Vec::new();
+
+    for relative in paths {
+        let (base, theirs) = (old.get(relative), new.get(relative));
+        if base == theirs {
+            continue;
+        }
+        let target = paths::long(&project_root.join(relative));
+        let ours = fs::read(&target).ok();
+
+        let change = match (base, theirs, &ours) {
+            // Deleted from the template: remove it unless the project changed it
+            (Some(base), None, Some(ours)) if ours == base => {
+                fs::remove_file(&target)?;
+                Change::Removed
+            }
+            (_, None, _) => continue,
+            // Deleted by the project: keep it deleted
+            (Some(_), Some(_), None) => continue,
+            (_, Some(theirs), Some(ours)) if ours == theirs => continue,
+            (None, Some(theirs), None) => {
+                write(&target, theirs)?;
+                Change::Added
+            }
+            (Some(base), Some(theirs), Some(ours)) if ours == base => {
+                write(&target, theirs)?;
+                Change::Updated
+            }
+            (base, Some(theirs), Some(ours)) => {
+                let (merged, conflicts) = merge_file(ours, base.map(Vec::as_slice).unwrap_or_default(), theirs)?;
+                write(&target, &merged)?;
+                if conflicts { Change::Conflicted } else { Change::Merged }
+            }
+        };
+        if let Some(mode) = modes.get(relative).filter(|_| change != Change::Removed) {
+            permissions::apply(&target, *mode)?;
+        }
+        changes.push((relative.clone(), change));
+    }
+    Ok(changes)
+}
+
+/// Directory searched for templates referenced by name
+pub fn user_template_dir() -> PathBuf {
+    data_dir().join("templates")
+}
+
+/// Built-in variables every template and feature can use: `git_user_name` and
+/// `git_user_email` (when git has them), `year`, `date` and `os`
+pub fn context() -> BTreeMap<String, String> {
+    let today = OffsetDateTime::now_utc().date();
+    let mut context = BTreeMap::from([
+        ("year".to_string(), today.year().to_string()),
+        ("date".to_string(), today.to_string()),
+        ("os".to_string(), std::env::consts::OS.to_string()),
+    ]);
+    for (name, key) in [("git_user_name", "user.name"), ("git_user_email", "user.email")] {
+        if let Some(value) = git::global_config(key) {
+            context.insert(name.to_string(), value);
+        }
+    }
+    context
+}
+
+/// `env.NAME` variables for the set environment variables `texts` refer to
+pub fn environment<'a>(texts: impl IntoIterator<Item = &'a str>) -> BTreeMap<String, String> {
+    let mut variables = BTreeMap::new();
+    for text in texts {
+        for key in placeholders(text) {
+            if let Some(name) = key.strip_prefix("env.")
+                && let Ok(value) = std::env::var(name)
+            {
+                variables.insert(key.to_string(), value);
+            }
+        }
+    }
+    variables
+}
+
+/// Replace `{{ name }}` placeholders, using the `| default("value")` of unknown
+/// names; unknown names without a default are left as they are
+pub fn substitute(text: &str, variables: &BTreeMap<String, String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        let (key, default) = parse_placeholder(&rest[start + 2..start + end]);
+        out.push_str(&rest[..start]);
+        match variables.get(key).map(String::as_str).or(default) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[start..start + end + 2]),
+        }
+        rest = &rest[start + end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Names referred to by the placeholders in `text`
+fn placeholders(text: &str) -> Vec<&str> {
+    let mut keys = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        keys.push(parse_placeholder(&rest[start + 2..start + end]).0);
+        rest = &rest[start + end + 2..];
+    }
+    keys
+}
+
+/// The name and `default(...)` value of what is between `{{` and `}}`
+fn parse_placeholder(inner: &str) -> (&str, Option<&str>) {
+    let Some((key, filter)) = inner.split_once('|') else {
+        return (inner.trim(), None);
+    };
+    let default = filter
+        .trim()
+        .strip_prefix("default(")
+        .and_then(|argument| argument.strip_suffix(')'))
+        .map(str::trim)
+        .and_then(|quoted| {
+            ['"', '\'']
+                .iter()
+                .find_map(|quote| quoted.strip_prefix(*quote)?.strip_suffix(*quote))
+        });
+    (key.trim(), default)
+}
+
+/// Whether `path` or a directory above it matches one of `patterns`
+fn matches_any(patterns: &[glob::Pattern], path: &Path) -> bool {
+    let options = glob::MatchOptions {
+        require_literal_separator: true,
+        ..Default::default()
+    };
+    path.ancestors()
+        .filter(|ancestor| !ancestor.as_os_str().is_empty())
+        .any(|ancestor| patterns.iter().any(|pattern| pattern.matches_path_with(ancestor, options)))
+}
+
+/// A variable counts as true when set to anything but an empty string or a
+/// false-like word, so answers like `yes`, `y` and `docker` all enable a file
+fn truthy(value: &str) -> bool {
+    !matches!(value.trim().to_lowercase().as_str(), "" | "false" | "no" | "n" | "off" | "0")
+}
+
+/// Three-way merge of file contents, returning the result and whether it has conflicts
+fn merge_file(ours: &[u8], base: &[u8], theirs: &[u8]) -> Result<(Vec<u8>, bool)> {
+    let dir = tempfile::tempdir()?;
+    let paths = [("project", ours), ("previous template", base), ("new template", theirs)]
+        .into_iter()
+        .map(|(label, content)| {
+            let path = dir.path().join(label);
+            fs::write(&path, content).map(|_| path)
+        })
+        .collect::<std::io::Result<Vec<_>>>()?;
+
+    let output = Command::new("git")
+        .args(["merge-file", "-p", "-L", "project", "-L", "previous template", "-L", "new template"])
+        .args(&paths)
+        .output()
+        .map_err(|_| PyForgeError::CommandNotFound { command: "git".to_string() })?;
+    // Exit code is the number of conflicts; negative on error
+    match output.status.code() {
+        Some(0) => Ok((output.stdout, false)),
+        Some(code) if code > 0 => Ok((output.stdout, true)),
+        code => Err(PyForgeError::command_failed("git merge-file", code.unwrap_or(-1))),
+    }
+}
+
+fn write(path: &Path, content: &[u8]) -> Result<()> {
+    let long = paths::long(path);
+    if let Some(parent) = long.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&long, content).map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))
+}
+
+fn starts_with_shebang(path: &Path) -> bool {
+    let mut start = [0u8; 2];
+    fs::File::open(path).and_then(|mut file| file.read_exact(&mut start)).is_ok() && permissions::is_script(&start)
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|_| PyForgeError::CommandNotFound { command: "git".to_string() })?;
+    if !output.status.success() {
+        return Err(PyForgeError::command_failed(format!("git {}", args.join(" ")), output.status.code().unwrap_or(-1)));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// `gh:owner/repo` and `gl:group/project` as git URLs; other sources as they are
+fn expand_shorthand(source: &str) -> String {
+    match source.split_once(':') {
+        Some(("gh", path)) => format!("https://github.com/{}.git", path.trim_end_matches(".git")),
+        Some(("gl", path)) => format!("https://gitlab.com/{}.git", path.trim_end_matches(".git")),
+        _ => source.to_string(),
+    }
+}
+
+/// Whether `source` is a `.tar.gz`, `.tgz` or `.zip` download
+fn is_archive(source: &str) -> bool {
+    let path = source.split(['?', '#']).next().unwrap_or(source);
+    (source.starts_with("https://") || source.starts_with("http://"))
+        && [".tar.gz", ".tgz", ".zip"].iter().any(|extension| path.ends_with(extension))
+}
+
+/// Download and unpack the template archive at `url` into the cache directory,
+/// using the copy there when the download fails. Returns the template root.
+fn fetch_archive(url: &str) -> Result<PathBuf> {
+    let key = format!("{:x}", Sha256::digest(url.as_bytes()));
+    let cache = cache_dir().join("templates");
+    let cached = cache.join(&key[..16]);
+    match download_archive(url, &cache) {
+        Ok(staging) => {
+            // Swap the fresh copy in whole, so an interrupted download leaves the old one usable
+            let _ = fs::remove_dir_all(&cached);
+            fs::rename(staging.keep(), &cached)
+                .map_err(|e| PyForgeError::file_error(format!("Could not cache template {}", url), e))?;
+        }
+        Err(e @ PyForgeError::TemplateNotFound { .. }) => return Err(e),
+        Err(e) if cached.is_dir() => log::warn!("could not download template {} ({}), using cached copy", url, e),
+        Err(e) => return Err(e),
+    }
+
+    // Archives of a repository usually hold one `repo-main/` directory
+    let mut entries = fs::read_dir(&cached)?.flatten().map(|entry| entry.path());
+    match (entries.next(), entries.next()) {
+        (Some(only), None) if only.is_dir() => Ok(only),
+        _ => Ok(cached),
+    }
+}
+
+fn download_archive(url: &str, cache: &Path) -> Result<TempDir> {
+    let network = Config::load_current().map(|config| config.network).unwrap_or_default();
+    let client = index::client_builder(&network)?
+        .build()
+        .map_err(|e| PyForgeError::network_error("Could not create HTTP client", Some(e)))?;
+    let mut response = client
+        .get(url)
+        .send()
+        .map_err(|e| PyForgeError::network_error(format!("Request to '{}' failed", url), Some(e)))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(PyForgeError::TemplateNotFound { template: url.to_string() });
+    }
+    if !response.status().is_success() {
+        return Err(PyForgeError::DownloadFailed {
+            url: url.to_string(),
+            status: response.status().to_string(),
+        });
+    }
+    let mut file = tempfile::tempfile()?;
+    response
+        .copy_to(&mut file)
+        .map_err(|e| PyForgeError::network_error(format!("Could not download '{}'", url), Some(e)))?;
+    file.seek(SeekFrom::Start(0))?;
+
+    fs::create_dir_all(cache)
+        .map_err(|e| PyForgeError::file_error(format!("Could not create {}", cache.display()), e))?;
+    let staging = tempfile::Builder::new().prefix(".download-").tempdir_in(cache)?;
+    let invalid = |message: String| PyForgeError::ParseError {
+        file_type: "template archive".to_string(),
+        message: format!("{}: {}", url, message),
+    };
+    if url.split(['?', '#']).next().unwrap_or(url).ends_with(".zip") {
+        zip::ZipArchive::new(file)
+            .and_then(|mut archive| archive.extract(staging.path()))
+            .map_err(|e| invalid(e.to_string()))?;
+    } else {
+        tar::Archive::new(GzDecoder::new(file))
+            .unpack(staging.path())
+            .map_err(|e| invalid(e.to_string()))?;
+    }
+    Ok(staging)
+}
+
+fn is_remote(source: &str) -> bool {
+    ["https://", "http://", "ssh://", "git://", "file://", "git@"]
+        .iter()
+        .any(|prefix| source.starts_with(prefix))
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
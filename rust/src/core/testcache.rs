@@ -0,0 +1,94 @@
+//! Remembering green test runs, so `pyforge test` can skip a run whose inputs
+//! (sources, tests, path dependencies, lockfile and test command) are unchanged.
+
+use crate::core::dirs::cache_dir;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::lockfile::Lockfile;
+use crate::core::project::Project;
+use crate::core::workspace;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+
+/// Directories that never affect test results
+const SKIPPED_DIRS: &[&str] = &[
+    ".git",
+    ".hg",
+    ".venv",
+    ".venvs",
+    ".tox",
+    ".nox",
+    "__pycache__",
+    ".pytest_cache",
+    ".mypy_cache",
+    ".ruff_cache",
+    "build",
+    "dist",
+    "node_modules",
+];
+
+/// Inputs of one kind of test run (the same command in the same place)
+pub struct TestCache {
+    path: PathBuf,
+    /// Hash of everything the run depends on
+    pub inputs: String,
+}
+
+impl TestCache {
+    /// Hash the project's files, the lockfile and `variant` (command, image, ...)
+    pub fn new(project: &Project, variant: &str) -> Result<Self> {
+        let mut hasher = Sha256::new();
+        hasher.update(variant.as_bytes());
+        hasher.update([0]);
+
+        let lockfile = Lockfile::path(project);
+        if let Ok(content) = fs::read(&lockfile) {
+            hasher.update(&content);
+        }
+
+        // Path dependencies are tested along with the project
+        let mut roots = vec![project.root.clone()];
+        roots.extend(workspace::members(project)?.into_values().map(|member| member.project.root));
+        for root in roots {
+            let mut files: Vec<PathBuf> = Vec::new();
+            let walker = walkdir::WalkDir::new(&root).min_depth(1).into_iter().filter_entry(|entry| {
+                let name = entry.file_name().to_string_lossy();
+                !(entry.file_type().is_dir() && (SKIPPED_DIRS.contains(&name.as_ref()) || name.ends_with(".egg-info")))
+            });
+            for entry in walker {
+                let entry = entry.map_err(|e| PyForgeError::internal(format!("Could not read the project tree: {}", e)))?;
+                if entry.file_type().is_file() {
+                    files.push(entry.into_path());
+                }
+            }
+            files.sort();
+            for file in files {
+                hasher.update(file.to_string_lossy().as_bytes());
+                hasher.update([0]);
+                hasher.update(fs::read(&file).unwrap_or_default());
+                hasher.update([0]);
+            }
+        }
+
+        // One record per project and variant
+        let key = Sha256::digest(format!("{}\0{}", project.root.display(), variant).as_bytes());
+        Ok(Self {
+            path: cache_dir().join("test-cache").join(format!("{:x}", key)),
+            inputs: format!("{:x}", hasher.finalize()),
+        })
+    }
+
+    /// Whether the last green run had the same inputs
+    pub fn is_green(&self) -> bool {
+        fs::read_to_string(&self.path).is_ok_and(|recorded| recorded.trim() == self.inputs)
+    }
+
+    /// Remember a green run with these inputs
+    pub fn record_green(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, &self.inputs)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,222 @@
+//! Span-based timings behind `--timings`.
+//!
+//! Code wraps a phase in `let _span = timings::span("resolve");`. Spans nest
+//! per thread, so time spent in `download` while resolving is reported under
+//! `resolve`. Spans with the same path are added up, and the breakdown is
+//! printed to stderr when the command finishes, even when it fails.
+//!
+//! With `PYFORGE_PROFILE=trace.json`, every span and subprocess is also
+//! written as a Chrome trace event, for chrome://tracing, Perfetto or
+//! speedscope.
+
+use serde_json::json;
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Where the breakdown goes: 0 = off, 1 = human, 2 = JSON
+static FORMAT: AtomicU8 = AtomicU8::new(0);
+static TOTALS: Mutex<Vec<Total>> = Mutex::new(Vec::new());
+
+/// Trace file and the events recorded for it, when `PYFORGE_PROFILE` is set
+static PROFILE: OnceLock<Profile> = OnceLock::new();
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static STACK: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+    static THREAD: Cell<u64> = const { Cell::new(0) };
+}
+
+struct Profile {
+    path: PathBuf,
+    started: Instant,
+    events: Mutex<Vec<serde_json::Value>>,
+}
+
+/// How the breakdown is printed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum TimingsFormat {
+    #[default]
+    Human,
+    Json,
+}
+
+/// Time added up for one span path, in the order spans first started
+struct Total {
+    path: Vec<&'static str>,
+    elapsed: Duration,
+    count: u32,
+}
+
+/// A running span; its time is recorded when dropped
+pub struct Span {
+    started: Instant,
+    /// Command line of a subprocess; these only appear in the trace
+    command: Option<String>,
+}
+
+/// Start timing `name` inside the current thread's open spans
+pub fn span(name: &'static str) -> Span {
+    STACK.with(|stack| stack.borrow_mut().push(name));
+    Span { started: Instant::now(), command: None }
+}
+
+/// Time a subprocess for the trace; `--timings` counts it in the enclosing span
+pub fn subprocess(command: &Command) -> Span {
+    let line = std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|part| part.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ");
+    Span { started: Instant::now(), command: Some(line) }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let elapsed = self.started.elapsed();
+        if let Some(command) = self.command.take() {
+            trace_event(&command, "subprocess", self.started, elapsed);
+            return;
+        }
+        let path = STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            let path = stack.clone();
+            stack.pop();
+            path
+        });
+        log::debug!("span {} took {:?}", path.join("/"), elapsed);
+        if let Some(name) = path.last() {
+            trace_event(name, "phase", self.started, elapsed);
+        }
+
+        let mut totals = TOTALS.lock().unwrap_or_else(|e| e.into_inner());
+        match totals.iter_mut().find(|total| total.path == path) {
+            Some(total) => {
+                total.elapsed += elapsed;
+                total.count += 1;
+            }
+            None => totals.push(Total { path, elapsed, count: 1 }),
+        }
+    }
+}
+
+/// Print the breakdown when the command finishes
+pub fn enable(format: TimingsFormat) {
+    FORMAT.store(
+        match format {
+            TimingsFormat::Human => 1,
+            TimingsFormat::Json => 2,
+        },
+        Ordering::Relaxed,
+    );
+}
+
+/// Print the breakdown to stderr, if enabled
+pub fn report(total: Duration) {
+    let format = FORMAT.load(Ordering::Relaxed);
+    if format == 0 {
+        return;
+    }
+    let totals = TOTALS.lock().unwrap_or_else(|e| e.into_inner());
+
+    // Children are listed under their parent, even when they first ran later
+    let mut ordered: Vec<&Total> = Vec::new();
+    for total in totals.iter().filter(|t| t.path.len() == 1) {
+        add_with_children(total, &totals, &mut ordered);
+    }
+
+    if format == 2 {
+        let spans: Vec<_> = ordered
+            .iter()
+            .map(|t| {
+                json!({
+                    "name": t.path.last(),
+                    "path": t.path.join("/"),
+                    "ms": t.elapsed.as_millis() as u64,
+                    "count": t.count,
+                })
+            })
+            .collect();
+        eprintln!("{}", json!({ "total_ms": total.as_millis() as u64, "spans": spans }));
+        return;
+    }
+
+    eprintln!("⏱️  Timings (total {})", seconds(total));
+    let width = ordered.iter().map(|t| t.path.len() * 2 + t.path.last().map_or(0, |n| n.len())).max().unwrap_or(0);
+    for t in ordered {
+        let label = format!("{}{}", "  ".repeat(t.path.len()), t.path.last().copied().unwrap_or_default());
+        let count = if t.count > 1 { format!(" ({}×)", t.count) } else { String::new() };
+        eprintln!("  {:width$}  {:>7}{}", label, seconds(t.elapsed), count, width = width);
+    }
+    let measured: Duration = totals.iter().filter(|t| t.path.len() == 1).map(|t| t.elapsed).sum();
+    eprintln!(
+        "  {:width$}  {:>7}",
+        "  other",
+        seconds(total.saturating_sub(measured)),
+        width = width
+    );
+}
+
+/// Record trace events into `path` until [`write_profile`]
+pub fn enable_profile(path: PathBuf, started: Instant) {
+    let _ = PROFILE.set(Profile {
+        path,
+        started,
+        events: Mutex::new(Vec::new()),
+    });
+}
+
+/// Write the recorded events as a Chrome trace, with one event spanning the whole run
+pub fn write_profile(command: &str) {
+    let Some(profile) = PROFILE.get() else {
+        return;
+    };
+    trace_event(command, "command", profile.started, profile.started.elapsed());
+    let events = std::mem::take(&mut *profile.events.lock().unwrap_or_else(|e| e.into_inner()));
+    let trace = json!({ "traceEvents": events, "displayTimeUnit": "ms" });
+    match fs::write(&profile.path, trace.to_string()) {
+        Ok(()) => eprintln!("📈 Wrote trace to {}", profile.path.display()),
+        Err(e) => eprintln!("⚠️  Could not write trace to {}: {}", profile.path.display(), e),
+    }
+}
+
+/// A complete ("X") event, on the recording thread's track
+fn trace_event(name: &str, category: &str, started: Instant, elapsed: Duration) {
+    let Some(profile) = PROFILE.get() else {
+        return;
+    };
+    let thread = THREAD.with(|thread| {
+        if thread.get() == 0 {
+            thread.set(NEXT_THREAD.fetch_add(1, Ordering::Relaxed));
+        }
+        thread.get()
+    });
+    let event = json!({
+        "name": name,
+        "cat": category,
+        "ph": "X",
+        "ts": started.saturating_duration_since(profile.started).as_micros() as u64,
+        "dur": elapsed.as_micros() as u64,
+        "pid": std::process::id(),
+        "tid": thread,
+    });
+    profile.events.lock().unwrap_or_else(|e| e.into_inner()).push(event);
+}
+
+fn add_with_children<'a>(total: &'a Total, all: &'a [Total], ordered: &mut Vec<&'a Total>) {
+    ordered.push(total);
+    for child in all
+        .iter()
+        .filter(|t| t.path.len() == total.path.len() + 1 && t.path.starts_with(&total.path))
+    {
+        add_with_children(child, all, ordered);
+    }
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{:.2}s", duration.as_secs_f64())
+}
@@ -0,0 +1,119 @@
+use crate::core::config::Config;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::lockfile::Lockfile;
+use crate::core::markers::Environment;
+use crate::core::project::Project;
+use crate::core::python::{self, Request};
+use crate::core::timings;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Tools that produced a lockfile, recorded so teams can reproduce it exactly
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Toolchain {
+    pub pyforge: String,
+
+    /// Full version of the interpreter dependencies were resolved with
+    pub python: String,
+
+    /// Installer backend, e.g. `pip 24.0`
+    pub installer: String,
+
+    /// Pinned `[build-system].requires`, e.g. `hatchling==1.25.0`
+    #[serde(default)]
+    pub build_requires: Vec<String>,
+}
+
+impl Toolchain {
+    /// The toolchain in use now; `build_requires` is filled in by the resolver
+    pub fn current(environment: &Environment) -> Self {
+        Self {
+            pyforge: env!("CARGO_PKG_VERSION").to_string(),
+            python: environment.python_full_version.clone(),
+            installer: installer_version(),
+            build_requires: Vec::new(),
+        }
+    }
+
+    /// Human-readable differences from `recorded`, ignoring the pinned build requirements
+    pub fn differences(&self, recorded: &Toolchain) -> Vec<String> {
+        [
+            ("pyforge", &recorded.pyforge, &self.pyforge),
+            ("python", &recorded.python, &self.python),
+            ("installer", &recorded.installer, &self.installer),
+        ]
+        .into_iter()
+        .filter(|(_, locked, current)| locked != current)
+        .map(|(tool, locked, current)| format!("{}: locked with {}, running {}", tool, locked, current))
+        .collect()
+    }
+}
+
+/// Compare the lockfile's toolchain with the current one: fail when `frozen`, warn otherwise
+pub fn check(lockfile: &Lockfile, frozen: bool) -> Result<()> {
+    let Some(recorded) = &lockfile.toolchain else {
+        if frozen {
+            return Err(PyForgeError::ToolchainMismatch {
+                differences: vec!["the lockfile records no toolchain; run 'pyforge lock' to record it".to_string()],
+            });
+        }
+        return Ok(());
+    };
+
+    let current = Toolchain::current(&Environment::current()?);
+    let differences = current.differences(recorded);
+    if differences.is_empty() {
+        return Ok(());
+    }
+    if frozen {
+        return Err(PyForgeError::ToolchainMismatch { differences });
+    }
+
+    println!("{} Toolchain differs from the one that produced pyforge.lock:", "⚠️".yellow());
+    for difference in differences {
+        println!("   {} {}", "•".bright_black(), difference);
+    }
+    Ok(())
+}
+
+/// The interpreter pyforge locks for and creates environments with: the
+/// project's pin, else the global `python` setting, else the first
+/// `python3`/`python` on PATH
+pub fn python() -> Result<PathBuf> {
+    static PIN: OnceLock<Option<Request>> = OnceLock::new();
+    let pin = PIN.get_or_init(|| {
+        let project = Project::discover().ok().flatten();
+        let configured = Config::load(project.as_ref()).ok().and_then(|config| config.python);
+        python::pin(project.as_ref(), configured.as_deref()).map(|(request, _)| request)
+    });
+    python::find(pin.as_ref())
+}
+
+/// `pip X.Y` for the interpreter's pip, or `none`. Read from the installed
+/// metadata, since importing pip for `pip --version` takes half a second.
+fn installer_version() -> String {
+    static VERSION: OnceLock<String> = OnceLock::new();
+    VERSION
+        .get_or_init(|| {
+            let _span = timings::span("inspect python");
+            let output = python().ok().and_then(|python| {
+                let mut command = Command::new(python);
+                command.args(["-c", "import importlib.metadata as m; print('pip', m.version('pip'))"]);
+                let _subprocess = timings::subprocess(&command);
+                command.output().ok()
+            });
+            output
+                .filter(|output| output.status.success())
+                .and_then(|output| {
+                    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+                    let mut words = stdout.split_whitespace();
+                    Some(format!("{} {}", words.next()?, words.next()?))
+                })
+                .unwrap_or_else(|| "none".to_string())
+        })
+        .clone()
+}
@@ -0,0 +1,151 @@
+//! Which project directories may run the commands they define.
+//!
+//! Scripts, the test command, build steps and dev watchers in a project's
+//! `[tool.pyforge]` are shell commands written by whoever wrote the project.
+//! In a freshly cloned repository they only run once the directory is
+//! trusted: pyforge asks the first time and remembers the answer in the state
+//! directory. Commands from the user's own config need no trust.
+//!
+//! The project's `[tool.pyforge.build.env]` tables count as commands too:
+//! pip and build backends run with them, and variables such as `LD_PRELOAD`,
+//! `PYTHONPATH` or `PIP_INDEX_URL` run code or redirect installs.
+
+use crate::core::dirs::state_dir;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::project::Project;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Trusted directories, in the state directory
+const TRUST_FILE: &str = "trusted.toml";
+
+/// Set to trust every directory, e.g. in containers running a known checkout
+pub const TRUST_ALL_ENV: &str = "PYFORGE_TRUST_ALL";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Trusted {
+    directories: BTreeSet<PathBuf>,
+}
+
+pub fn trust_path() -> PathBuf {
+    state_dir().join(TRUST_FILE)
+}
+
+/// What `project` defines for pyforge to run, e.g. `script lint: ruff check .`
+pub fn defined_commands(project: &Project) -> Vec<String> {
+    let Some(tool) = project.tool_config() else {
+        return Vec::new();
+    };
+    let command = |value: &toml::Value| value.as_str().map(str::to_string);
+    let mut commands = Vec::new();
+    if let Some(scripts) = tool.get("scripts").and_then(|scripts| scripts.as_table()) {
+        for (name, value) in scripts {
+            commands.extend(command(value).map(|line| format!("script {}: {}", name, line)));
+        }
+    }
+    if let Some(line) = tool.get("test").and_then(|test| test.get("command")).and_then(command) {
+        commands.push(format!("test command: {}", line));
+    }
+    if let Some(steps) = tool.get("build").and_then(|build| build.get("steps")).and_then(|steps| steps.as_array()) {
+        for step in steps {
+            let name = step.get("name").and_then(|name| name.as_str()).unwrap_or("?");
+            commands.extend(step.get("command").and_then(command).map(|line| format!("build step {}: {}", name, line)));
+        }
+    }
+    if let Some(watchers) = tool.get("dev").and_then(|dev| dev.get("watchers")).and_then(|watchers| watchers.as_table()) {
+        for (name, watcher) in watchers {
+            commands.extend(watcher.get("command").and_then(command).map(|line| format!("dev watcher {}: {}", name, line)));
+        }
+    }
+    commands.extend(build_env(project));
+    commands
+}
+
+/// The build environment variables `project` sets for pip and build
+/// backends, from `[tool.pyforge.build.env]` and its profiles
+pub fn build_env(project: &Project) -> Vec<String> {
+    let Some(build) = project.tool_config().and_then(|tool| tool.get("build")) else {
+        return Vec::new();
+    };
+    let mut variables = Vec::new();
+    let mut add = |label: String, env: Option<&toml::Value>| {
+        for (name, value) in env.and_then(|env| env.as_table()).into_iter().flatten() {
+            variables.push(format!("{} {} = {}", label, name, value));
+        }
+    };
+    add("build env".to_string(), build.get("env"));
+    if let Some(profiles) = build.get("profiles").and_then(|profiles| profiles.as_table()) {
+        for (profile, settings) in profiles {
+            add(format!("build env ({})", profile), settings.get("env"));
+        }
+    }
+    variables
+}
+
+/// Whether `project` may run its commands: it defines none, its directory
+/// was trusted, or `PYFORGE_TRUST_ALL` is set
+pub fn is_trusted(project: &Project) -> bool {
+    let root = canonical(&project.root);
+    std::env::var_os(TRUST_ALL_ENV).is_some_and(|value| !value.is_empty() && value != "0")
+        || defined_commands(project).is_empty()
+        || load().directories.contains(&root)
+}
+
+/// Fail unless `project` may apply the build environment variables it sets;
+/// commands ask first with `cli::commands::trust::require`
+pub fn check_build_env(project: &Project) -> Result<()> {
+    if build_env(project).is_empty() || is_trusted(project) {
+        return Ok(());
+    }
+    Err(PyForgeError::UntrustedProject {
+        path: project.root.display().to_string(),
+    })
+}
+
+/// Remember `dir` as trusted
+pub fn trust(dir: &Path) -> Result<()> {
+    let mut trusted = load();
+    if trusted.directories.insert(canonical(dir)) {
+        save(&trusted)?;
+    }
+    Ok(())
+}
+
+/// Forget `dir`; returns whether it was trusted
+pub fn revoke(dir: &Path) -> Result<bool> {
+    let mut trusted = load();
+    let removed = trusted.directories.remove(&canonical(dir));
+    if removed {
+        save(&trusted)?;
+    }
+    Ok(removed)
+}
+
+/// Every trusted directory
+pub fn trusted() -> Vec<PathBuf> {
+    load().directories.into_iter().collect()
+}
+
+fn canonical(dir: &Path) -> PathBuf {
+    dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf())
+}
+
+fn load() -> Trusted {
+    fs::read_to_string(trust_path())
+        .ok()
+        .and_then(|content| toml::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save(trusted: &Trusted) -> Result<()> {
+    let path = trust_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| PyForgeError::file_error(format!("Could not create {}", parent.display()), e))?;
+    }
+    let body = toml::to_string(trusted).map_err(|e| PyForgeError::internal(format!("Could not render trusted directories: {}", e)))?;
+    fs::write(&path, body).map_err(|e| PyForgeError::file_error(format!("Could not write {}", path.display()), e))
+}
@@ -0,0 +1,324 @@
+use crate::core::config::{Config, VenvConfig, VenvInterpreter, VenvStrategy};
+use crate::core::dirs;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::git::Worktree;
+use crate::core::installer;
+use crate::core::lockfile::Lockfile;
+use crate::core::pep508::normalize_name;
+use crate::core::project::Project;
+use crate::core::timings;
+use crate::core::toolchain;
+use crate::core::utils;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Project virtual environment directory, relative to the project root
+pub const VENV_DIR: &str = ".venv";
+
+/// Per-branch environments (`venv-strategy = "per-branch"`), relative to the project root
+pub const BRANCH_VENVS_DIR: &str = ".venvs";
+
+/// Records which checkout last synced an environment
+const SYNC_STATE_FILE: &str = "pyforge-sync.toml";
+
+/// Records how pyforge created an environment
+const METADATA_FILE: &str = "pyforge-venv.toml";
+
+/// The interpreter and pyforge an environment was created with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct VenvMetadata {
+    pub created: String,
+    pub python: PathBuf,
+    pub pyforge: String,
+}
+
+/// The checkout and lockfile an environment was last synced from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SyncState {
+    pub worktree: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    pub input_hash: String,
+}
+
+impl SyncState {
+    pub fn current(project: &Project, lockfile: &Lockfile) -> Self {
+        let worktree = Worktree::discover(&project.root);
+        Self {
+            worktree: worktree.as_ref().map_or_else(|| project.root.clone(), |w| w.root.clone()),
+            branch: worktree.and_then(|w| w.branch),
+            input_hash: lockfile.input_hash.clone(),
+        }
+    }
+}
+
+/// A Python virtual environment
+#[derive(Debug, Clone)]
+pub struct Venv {
+    pub root: PathBuf,
+}
+
+impl Venv {
+    /// Where the project's environment lives under the configured `venv-strategy`.
+    ///
+    /// Outside a git repository, and for a detached HEAD with `per-branch`,
+    /// this is always `.venv` in the project root. With `venv-dir` set, the
+    /// same choice is made, then moved into that directory under a name
+    /// derived from it.
+    pub fn location(project: &Project, config: &Config) -> PathBuf {
+        let location = Self::in_project(project, config);
+        let Some(dir) = config.venv_dir.as_deref().filter(|dir| !dir.is_empty()) else {
+            return location;
+        };
+        let name = project
+            .name()
+            .map(normalize_name)
+            .unwrap_or_else(|| project.root.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default());
+        let hash = format!("{:x}", Sha256::digest(location.to_string_lossy().as_bytes()));
+        dirs::expand_home(dir).join(format!("{}-{}", name, &hash[..12]))
+    }
+
+    fn in_project(project: &Project, config: &Config) -> PathBuf {
+        let default = project.root.join(VENV_DIR);
+        if config.venv_strategy == VenvStrategy::PerWorktree {
+            return default;
+        }
+        let Some(worktree) = Worktree::discover(&project.root) else {
+            return default;
+        };
+        match config.venv_strategy {
+            VenvStrategy::Shared => worktree.in_main(&project.root).map_or(default, |root| root.join(VENV_DIR)),
+            VenvStrategy::PerBranch => match &worktree.branch {
+                Some(branch) => project.root.join(BRANCH_VENVS_DIR).join(branch.replace(['/', '\\'], "-")),
+                None => default,
+            },
+            VenvStrategy::PerWorktree => default,
+        }
+    }
+
+    /// The project's environment, if it has been created
+    pub fn open(project: &Project, config: &Config) -> Option<Self> {
+        let venv = Self {
+            root: Self::location(project, config),
+        };
+        venv.python().is_file().then_some(venv)
+    }
+
+    /// The project's environment, creating it with the configured interpreter if needed
+    pub fn ensure(project: &Project, config: &Config) -> Result<Self> {
+        if let Some(venv) = Self::open(project, config) {
+            return Ok(venv);
+        }
+
+        let venv = Self::create(&Self::location(project, config), &config.venv)?;
+        // Without its seeds the environment would be taken as ready next time
+        if let Err(error) = installer::seed(&venv, config, &project.root) {
+            let _ = fs::remove_dir_all(&venv.root);
+            return Err(error);
+        }
+        Ok(venv)
+    }
+
+    /// Create a new environment at `root` with the configured interpreter
+    pub fn create(root: &Path, settings: &VenvConfig) -> Result<Self> {
+        let _span = timings::span("venv");
+        let python = toolchain::python()?;
+        log::info!("creating virtual environment at {}", root.display());
+
+        let mut command = Command::new(&python);
+        command.args(["-m", "venv"]).args(venv_args(settings));
+        if settings.upgrade_deps && settings.seed.is_none() {
+            command.arg("--upgrade-deps");
+        }
+        command.arg(root);
+        let _span = timings::subprocess(&command);
+        let status = command.status()?;
+        if !status.success() {
+            return Err(PyForgeError::command_failed(
+                format!("{} -m venv {}", python.display(), root.display()),
+                status.code().unwrap_or(-1),
+            ));
+        }
+        let venv = Self { root: root.to_path_buf() };
+        let metadata = VenvMetadata {
+            created: utils::timestamp(),
+            python,
+            pyforge: env!("CARGO_PKG_VERSION").to_string(),
+        };
+        let body = toml::to_string(&metadata).map_err(|e| PyForgeError::internal(format!("Could not render environment metadata: {}", e)))?;
+        fs::write(venv.root.join(METADATA_FILE), body)?;
+        Ok(venv)
+    }
+
+    /// Delete the environment; refuses directories that aren't one
+    pub fn remove(&self) -> Result<()> {
+        if !self.root.join("pyvenv.cfg").is_file() {
+            return Err(PyForgeError::Usage {
+                message: format!("{} has no pyvenv.cfg, so it isn't removed as an environment", self.root.display()),
+            });
+        }
+        fs::remove_dir_all(&self.root).map_err(|e| PyForgeError::file_error(format!("Could not remove {}", self.root.display()), e))
+    }
+
+    /// Upgrade pip and the seed packages, and apply `settings` to the existing
+    /// environment, with the interpreter it was created from
+    pub fn upgrade(&self, settings: &VenvConfig) -> Result<()> {
+        let _span = timings::span("venv");
+        let python = self.base_python().ok_or_else(|| PyForgeError::FileError {
+            message: format!("{} does not name the interpreter the environment was created from", self.root.join("pyvenv.cfg").display()),
+            source: None,
+        })?;
+        let mut command = Command::new(&python);
+        command.args(["-m", "venv", "--upgrade"]);
+        // Configured seeds are installed by pyforge instead
+        if settings.seed.is_none() {
+            command.arg("--upgrade-deps");
+        }
+        command.args(venv_args(settings)).arg(&self.root);
+        let _subprocess = timings::subprocess(&command);
+        let status = command.status()?;
+        if !status.success() {
+            return Err(PyForgeError::command_failed(
+                format!("{} -m venv --upgrade {}", python.display(), self.root.display()),
+                status.code().unwrap_or(-1),
+            ));
+        }
+        Ok(())
+    }
+
+    /// `python -m pip`: the environment's own pip, or the base interpreter's
+    /// pip pointed at the environment with `--python` when it has none
+    pub fn pip_command(&self) -> Command {
+        let has_pip = self.site_packages().is_ok_and(|site| site.join("pip").is_dir());
+        match self.base_python().filter(|_| !has_pip) {
+            Some(base) => {
+                let mut command = Command::new(base);
+                command.args(["-m", "pip", "--python"]).arg(self.python());
+                command
+            }
+            None => {
+                let mut command = self.command();
+                command.args(["-m", "pip"]);
+                command
+            }
+        }
+    }
+
+    /// The base interpreter, from `executable` (or `home`) in pyvenv.cfg
+    pub fn base_python(&self) -> Option<PathBuf> {
+        if let Some(executable) = self.pyvenv_cfg("executable").map(PathBuf::from).filter(|path| path.is_file()) {
+            return Some(executable);
+        }
+        let home = PathBuf::from(self.pyvenv_cfg("home")?);
+        ["python3", "python", "python.exe"].iter().map(|name| home.join(name)).find(|path| path.is_file())
+    }
+
+    /// The Python version, e.g. `3.12.3`, from `version` (or `version_info`) in pyvenv.cfg
+    pub fn python_version(&self) -> Option<String> {
+        self.pyvenv_cfg("version").or_else(|| self.pyvenv_cfg("version_info"))
+    }
+
+    fn pyvenv_cfg(&self, key: &str) -> Option<String> {
+        let cfg = fs::read_to_string(self.root.join("pyvenv.cfg")).ok()?;
+        cfg.lines()
+            .filter_map(|line| line.split_once('='))
+            .find(|(k, _)| k.trim() == key)
+            .map(|(_, v)| v.trim().to_string())
+    }
+
+    /// How pyforge created this environment, if it did
+    pub fn metadata(&self) -> Option<VenvMetadata> {
+        let content = fs::read_to_string(self.root.join(METADATA_FILE)).ok()?;
+        toml::from_str(&content).ok()
+    }
+
+    /// The environment's interpreter
+    pub fn python(&self) -> PathBuf {
+        if cfg!(windows) {
+            self.root.join("Scripts").join("python.exe")
+        } else {
+            self.root.join("bin").join("python")
+        }
+    }
+
+    /// Directory holding the environment's executables
+    pub fn bin_dir(&self) -> PathBuf {
+        self.root.join(if cfg!(windows) { "Scripts" } else { "bin" })
+    }
+
+    /// The environment's `site-packages` directory
+    pub fn site_packages(&self) -> Result<PathBuf> {
+        if cfg!(windows) {
+            return Ok(self.root.join("Lib").join("site-packages"));
+        }
+        let lib = self.root.join("lib");
+        std::fs::read_dir(&lib)
+            .map_err(|e| PyForgeError::file_error(format!("Could not read {}", lib.display()), e))?
+            .flatten()
+            .map(|entry| entry.path().join("site-packages"))
+            .find(|path| path.is_dir())
+            .ok_or_else(|| PyForgeError::DirectoryNotFound {
+                path: lib.join("python3.*/site-packages").display().to_string(),
+            })
+    }
+
+    /// The checkout that last synced this environment, if recorded
+    pub fn sync_state(&self) -> Option<SyncState> {
+        let content = fs::read_to_string(self.root.join(SYNC_STATE_FILE)).ok()?;
+        toml::from_str(&content).ok()
+    }
+
+    pub fn record_sync(&self, state: &SyncState) -> Result<()> {
+        let body = toml::to_string(state).map_err(|e| PyForgeError::internal(format!("Could not render sync state: {}", e)))?;
+        fs::write(self.root.join(SYNC_STATE_FILE), body)?;
+        Ok(())
+    }
+
+    /// The environment's path for display: relative to `project` when inside it
+    pub fn display(&self, project: &Project) -> String {
+        self.root
+            .strip_prefix(&project.root)
+            .unwrap_or(&self.root)
+            .display()
+            .to_string()
+    }
+
+    /// Make `command` run inside the environment, as if it had been activated
+    pub fn activate(&self, command: &mut Command) -> Result<()> {
+        let path = std::env::var_os("PATH").unwrap_or_default();
+        let path = std::env::join_paths(std::iter::once(self.bin_dir()).chain(std::env::split_paths(&path)))
+            .map_err(|e| PyForgeError::internal(format!("Could not build PATH: {}", e)))?;
+        command.env("VIRTUAL_ENV", &self.root).env("PATH", path);
+        Ok(())
+    }
+
+    /// A command running the environment's interpreter
+    pub fn command(&self) -> Command {
+        Command::new(self.python())
+    }
+}
+
+/// `python -m venv` options for `settings`
+fn venv_args(settings: &VenvConfig) -> Vec<String> {
+    let mut args = Vec::new();
+    match settings.interpreter {
+        Some(VenvInterpreter::Symlink) => args.push("--symlinks".to_string()),
+        Some(VenvInterpreter::Copy) => args.push("--copies".to_string()),
+        None => {}
+    }
+    if settings.seed.is_some() {
+        args.push("--without-pip".to_string());
+    }
+    if settings.system_site_packages {
+        args.push("--system-site-packages".to_string());
+    }
+    if let Some(prompt) = &settings.prompt {
+        args.push(format!("--prompt={}", prompt));
+    }
+    args
+}
@@ -0,0 +1,165 @@
+//! Checking installed distributions against their RECORD files and the lockfile.
+
+use crate::core::dist::Metadata;
+use crate::core::error::Result;
+use crate::core::lockfile::Lockfile;
+use crate::core::parallel;
+use crate::core::pep508::normalize_name;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+
+/// Verification result for one installed or locked package
+#[derive(Debug)]
+pub struct PackageCheck {
+    pub name: String,
+    pub version: Option<String>,
+    /// Files with a RECORD hash that were checked
+    pub checked: usize,
+    /// Problems found; empty means the package passed
+    pub problems: Vec<String>,
+}
+
+impl PackageCheck {
+    pub fn passed(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// PEP 610 `direct_url.json`, written by installers for URL and file installs
+#[derive(Deserialize)]
+struct DirectUrl {
+    archive_info: Option<ArchiveInfo>,
+}
+
+#[derive(Deserialize)]
+struct ArchiveInfo {
+    #[serde(default)]
+    hashes: std::collections::BTreeMap<String, String>,
+    hash: Option<String>,
+}
+
+/// Verify every distribution in `site_packages`, plus locked packages that aren't installed;
+/// files are hashed `jobs` at a time
+pub fn verify(site_packages: &Path, lockfile: Option<&Lockfile>, jobs: usize) -> Result<Vec<PackageCheck>> {
+    let mut checks = Vec::new();
+
+    let mut dist_infos: Vec<_> = fs::read_dir(site_packages)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && path.extension().is_some_and(|ext| ext == "dist-info"))
+        .collect();
+    dist_infos.sort();
+
+    for dist_info in dist_infos {
+        let metadata = fs::read_to_string(dist_info.join("METADATA"))
+            .ok()
+            .and_then(|content| Metadata::parse(&content).ok());
+        let Some(metadata) = metadata else {
+            checks.push(PackageCheck {
+                name: dist_info.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                version: None,
+                checked: 0,
+                problems: vec!["METADATA missing or unreadable".to_string()],
+            });
+            continue;
+        };
+
+        let name = normalize_name(&metadata.name);
+        let mut check = PackageCheck {
+            name: name.clone(),
+            version: Some(metadata.version.clone()),
+            checked: 0,
+            problems: Vec::new(),
+        };
+        check_record(site_packages, &dist_info, &mut check, jobs);
+
+        if let Some(lockfile) = lockfile {
+            match lockfile.package(&name) {
+                Some(locked) => {
+                    if locked.version != metadata.version {
+                        check.problems.push(format!("locked version is {}", locked.version));
+                    }
+                    if let Some(installed) = archive_hash(&dist_info)
+                        && !locked.files.iter().any(|f| f.sha256().is_some_and(|h| h.eq_ignore_ascii_case(&installed)))
+                    {
+                        check.problems.push("installed archive does not match any locked hash".to_string());
+                    }
+                }
+                None if !matches!(name.as_str(), "pip" | "setuptools" | "wheel") => {
+                    check.problems.push("not in the lockfile".to_string());
+                }
+                None => {}
+            }
+        }
+        checks.push(check);
+    }
+
+    if let Some(lockfile) = lockfile {
+        for locked in &lockfile.packages {
+            if !checks.iter().any(|check| check.name == locked.name) {
+                checks.push(PackageCheck {
+                    name: locked.name.clone(),
+                    version: None,
+                    checked: 0,
+                    problems: vec![format!("locked at {} but not installed", locked.version)],
+                });
+            }
+        }
+    }
+    Ok(checks)
+}
+
+/// Re-hash every file listed in the distribution's RECORD
+fn check_record(site_packages: &Path, dist_info: &Path, check: &mut PackageCheck, jobs: usize) {
+    let Ok(record) = fs::read_to_string(dist_info.join("RECORD")) else {
+        check.problems.push("RECORD missing (partial or manual install)".to_string());
+        return;
+    };
+
+    let mut entries = Vec::new();
+    for line in record.lines().filter(|line| !line.trim().is_empty()) {
+        // Paths may contain commas; the hash and size are always the last two fields
+        let mut fields = line.rsplitn(3, ',');
+        let (_size, hash, path) = (fields.next(), fields.next(), fields.next());
+        let (Some(hash), Some(path)) = (hash, path) else {
+            continue;
+        };
+        let Some(expected) = hash.strip_prefix("sha256=") else {
+            continue;
+        };
+        entries.push((site_packages.join(path.trim_matches('"')), expected.trim_end_matches('=')));
+    }
+    check.checked += entries.len();
+
+    // `Some(matches)` for files that could be read
+    let results = parallel::map(jobs, &entries, |(path, expected)| {
+        let mut hasher = Sha256::new();
+        let mut file = File::open(path).ok()?;
+        io::copy(&mut file, &mut hasher).ok()?;
+        Some(URL_SAFE_NO_PAD.encode(hasher.finalize()) == *expected)
+    });
+    let missing = results.iter().filter(|result| result.is_none()).count();
+    let modified = results.iter().filter(|result| **result == Some(false)).count();
+
+    if missing > 0 {
+        check.problems.push(format!("{} file(s) missing", missing));
+    }
+    if modified > 0 {
+        check.problems.push(format!("{} file(s) modified", modified));
+    }
+}
+
+/// SHA-256 of the archive the distribution was installed from, if recorded
+fn archive_hash(dist_info: &Path) -> Option<String> {
+    let content = fs::read_to_string(dist_info.join("direct_url.json")).ok()?;
+    let info = serde_json::from_str::<DirectUrl>(&content).ok()?.archive_info?;
+    info.hashes
+        .get("sha256")
+        .cloned()
+        .or_else(|| info.hash?.strip_prefix("sha256=").map(str::to_string))
+}
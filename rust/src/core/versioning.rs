@@ -0,0 +1,100 @@
+//! Versions derived from git tags (`[tool.pyforge] version = { source = "git" }`),
+//! following setuptools-scm's default scheme:
+//!
+//! - on a tag with a clean tree: the tag's version (`1.2.3`)
+//! - N commits after it: the next version as a dev release with the commit as
+//!   local segment (`1.2.4.devN+gabc1234`)
+//! - with uncommitted changes: the date is appended (`1.2.4.dev0+gabc1234.d20240601`)
+//! - without any tag: `0.1.devN+gabc1234`, N counting every commit
+
+use crate::core::config::{Config, VersionSource};
+use crate::core::error::{PyForgeError, Result};
+use crate::core::git;
+use crate::core::pep440::{LocalSegment, Version};
+use crate::core::project::Project;
+
+/// The project's version: `[project].version`, else derived from its configured source
+pub fn resolve(project: &Project, config: &Config) -> Result<String> {
+    if let Some(version) = project.version() {
+        return Ok(version.to_string());
+    }
+    match config.version.source {
+        Some(VersionSource::Git) if is_dynamic(project) => from_git(project, config),
+        Some(VersionSource::Git) => Err(PyForgeError::Usage {
+            message: "Versions from git need `dynamic = [\"version\"]` in [project]".to_string(),
+        }),
+        None => Err(PyForgeError::MissingConfig {
+            key: "project.version".to_string(),
+            example: "[project]\nversion = \"0.1.0\"\n\n# or, from git tags:\n[project]\ndynamic = [\"version\"]\n\n[tool.pyforge]\nversion = { source = \"git\" }".to_string(),
+        }),
+    }
+}
+
+/// Whether pyforge supplies the version at build time
+pub fn is_dynamic(project: &Project) -> bool {
+    project.version().is_none()
+        && project
+            .pyproject
+            .get("project")
+            .and_then(|p| p.get("dynamic"))
+            .and_then(|d| d.as_array())
+            .is_some_and(|dynamic| dynamic.iter().any(|field| field.as_str() == Some("version")))
+}
+
+fn from_git(project: &Project, config: &Config) -> Result<String> {
+    let prefix = &config.version.tag_prefix;
+    let described = git::describe(&project.root, &format!("{}*[0-9]*", prefix)).ok_or_else(|| PyForgeError::Usage {
+        message: format!(
+            "Cannot derive a version from git in {}: not a git repository, or no commits yet",
+            project.root.display()
+        ),
+    })?;
+
+    let tagged = match &described.tag {
+        Some(tag) => {
+            let bare = tag.strip_prefix(prefix.as_str()).unwrap_or(tag);
+            Some(Version::parse(bare).map_err(|_| PyForgeError::Usage {
+                message: format!("Tag '{}' is not a PEP 440 version (after removing the prefix '{}')", tag, prefix),
+            })?)
+        }
+        None => None,
+    };
+
+    let version = match tagged {
+        Some(version) if described.distance == 0 && !described.dirty => version,
+        Some(version) => development(next(&version), &described),
+        None => development(Version::parse("0.1").expect("valid version"), &described),
+    };
+    Ok(version.to_string())
+}
+
+/// The release after `version`: its last number bumped (`1.2.3` → `1.2.4`, `2.0rc1` → `2.0rc2`)
+pub fn next(version: &Version) -> Version {
+    let mut next = version.public();
+    next.post = None;
+    next.dev = None;
+    match &mut next.pre {
+        Some((_, number)) => *number += 1,
+        None => {
+            if let Some(last) = next.release.last_mut() {
+                *last += 1;
+            }
+        }
+    }
+    next
+}
+
+fn development(mut version: Version, described: &git::Describe) -> Version {
+    version.dev = Some(described.distance);
+    version.local = vec![LocalSegment::Text(format!("g{}", described.commit))];
+    if described.dirty {
+        let today = time::OffsetDateTime::now_utc().date();
+        version.local.push(LocalSegment::Text(format!(
+            "d{:04}{:02}{:02}",
+            today.year(),
+            u8::from(today.month()),
+            today.day()
+        )));
+    }
+    version
+}
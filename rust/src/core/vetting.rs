@@ -0,0 +1,114 @@
+//! Supply-chain heuristics for packages being added to a project.
+//!
+//! Nothing here is conclusive: the checks flag names that look like a typo of
+//! a widely used package, and packages too new or too thin to have a track
+//! record, so that a human takes a second look.
+
+use crate::core::dist::DistFilename;
+use crate::core::index::IndexFile;
+use crate::core::pep508::normalize_name;
+use std::collections::BTreeSet;
+use std::sync::LazyLock;
+use time::{Duration, OffsetDateTime};
+
+/// Packages published more recently than this are flagged as new
+const NEW_PACKAGE_AGE: Duration = Duration::days(30);
+
+/// Packages with fewer releases than this are flagged
+const MIN_RELEASES: usize = 3;
+
+static POPULAR: LazyLock<BTreeSet<&'static str>> = LazyLock::new(|| {
+    include_str!("../data/popular-packages.txt")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect()
+});
+
+/// Something about a package worth a second look
+#[derive(Debug, Clone)]
+pub struct Concern {
+    pub kind: &'static str,
+    pub message: String,
+}
+
+/// Check `name` and its index listing against every heuristic
+pub fn vet(name: &str, files: &[IndexFile]) -> Vec<Concern> {
+    let name = normalize_name(name);
+    let mut concerns = Vec::new();
+
+    if let Some(popular) = lookalike(&name) {
+        concerns.push(Concern {
+            kind: "similar-name",
+            message: format!("'{}' is very close to the widely used '{}'; check for a typo", name, popular),
+        });
+    }
+
+    let releases: BTreeSet<String> = files
+        .iter()
+        .filter_map(|file| DistFilename::parse(&file.filename, &name))
+        .map(|parsed| parsed.version.to_string())
+        .collect();
+    if releases.len() < MIN_RELEASES {
+        concerns.push(Concern {
+            kind: "few-releases",
+            message: format!("{} has only {} release(s)", name, releases.len()),
+        });
+    }
+
+    if let Some(first) = files.iter().filter_map(|file| file.upload_time).min() {
+        let age = OffsetDateTime::now_utc() - first;
+        if age < NEW_PACKAGE_AGE {
+            concerns.push(Concern {
+                kind: "new-package",
+                message: format!("{} was first published {} day(s) ago", name, age.whole_days()),
+            });
+        }
+    }
+    concerns
+}
+
+/// The widely used package `name` looks like a mistyped version of, if any
+pub fn lookalike(name: &str) -> Option<&'static str> {
+    let name = normalize_name(name);
+    if POPULAR.contains(name.as_str()) {
+        return None;
+    }
+    let squashed = squash(&name);
+    POPULAR.iter().copied().find(|popular| {
+        // Short names are too dense to compare by edit distance
+        let max_distance = if popular.len() >= 8 { 2 } else if popular.len() >= 5 { 1 } else { 0 };
+        squash(popular) == squashed
+            || popular.strip_prefix("python-").is_some_and(|bare| bare == name)
+            || name.strip_prefix("python-").is_some_and(|bare| bare == *popular)
+            || (max_distance > 0 && edit_distance(popular, &name) <= max_distance)
+    })
+}
+
+/// The name without separators, so `py-yaml` matches `pyyaml`
+fn squash(name: &str) -> String {
+    name.chars().filter(|c| c.is_ascii_alphanumeric()).collect()
+}
+
+/// Optimal string alignment distance: insertions, deletions, substitutions and
+/// adjacent transpositions each count as one edit
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    rows[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1).min(rows[i][j - 1] + 1).min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
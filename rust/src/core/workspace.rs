@@ -0,0 +1,231 @@
+//! Path dependencies on other projects in the same repository (monorepo members).
+//!
+//! `[tool.pyforge.sources]` maps a dependency name to a directory holding its
+//! `pyproject.toml`:
+//!
+//! ```toml
+//! [tool.pyforge.sources]
+//! shared-utils = { path = "../shared-utils" }
+//! ```
+//!
+//! Such packages are resolved from that directory instead of an index,
+//! installed in editable mode, and replaced by a plain version requirement in
+//! built distributions.
+
+use crate::core::config::Config;
+use crate::core::dist::Metadata;
+use crate::core::error::{PyForgeError, Result};
+use crate::core::pep508::{Requirement, normalize_name};
+use crate::core::git::Worktree;
+use crate::core::project::Project;
+use crate::core::venv::Venv;
+use crate::core::versioning;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// How deep below the repository root to look for projects depending on a member
+const DEPENDENT_SEARCH_DEPTH: usize = 4;
+
+/// Directories never searched for projects
+const SKIPPED_DIRS: [&str; 6] = [".git", ".venv", ".venvs", "node_modules", "build", "dist"];
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct SourceEntry {
+    path: String,
+    #[serde(default = "default_editable")]
+    editable: bool,
+}
+
+fn default_editable() -> bool {
+    true
+}
+
+/// A project another project depends on by path
+#[derive(Debug, Clone)]
+pub struct Member {
+    /// Normalized name
+    pub name: String,
+    pub version: String,
+    pub project: Project,
+    /// Location relative to the depending project's root, as recorded in the lockfile
+    pub path: String,
+    pub editable: bool,
+}
+
+impl Member {
+    /// Core metadata equivalent of the member's `[project]` table
+    pub fn metadata(&self) -> Result<Metadata> {
+        let mut requires_dist = Vec::new();
+        for dependency in self.project.dependencies() {
+            requires_dist.push(Requirement::parse(&dependency)?);
+        }
+        for (group, dependencies) in self.project.optional_dependencies() {
+            for dependency in dependencies {
+                let mut requirement = Requirement::parse(&dependency)?;
+                let extra = format!("extra == \"{}\"", group);
+                requirement.marker = Some(match requirement.marker {
+                    Some(marker) => format!("({}) and {}", marker, extra),
+                    None => extra,
+                });
+                requires_dist.push(requirement);
+            }
+        }
+        Ok(Metadata {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            requires_dist,
+            requires_python: self.project.requires_python().map(str::to_string),
+            license: self.project.license(),
+            summary: None,
+        })
+    }
+}
+
+/// Every path dependency of `project`, including those of its members, keyed by name
+pub fn members(project: &Project) -> Result<BTreeMap<String, Member>> {
+    let mut members = BTreeMap::new();
+    let mut pending = vec![project.clone()];
+    while let Some(current) = pending.pop() {
+        for (name, entry) in sources(&current)? {
+            if members.contains_key(&name) {
+                continue;
+            }
+            let member = load_member(project, &current, &name, &entry)?;
+            pending.push(member.project.clone());
+            members.insert(name, member);
+        }
+    }
+    Ok(members)
+}
+
+/// `requirement` as it should appear in published metadata: a member
+/// dependency without a version constraint gets `>=` its current version
+pub fn publishable(requirement: &Requirement, members: &BTreeMap<String, Member>) -> Option<Requirement> {
+    let member = members.get(&requirement.normalized_name())?;
+    if !requirement.specifier.is_empty() {
+        return None;
+    }
+    let mut pinned = requirement.clone();
+    pinned.specifier = format!(">={}", member.version).parse().ok()?;
+    Some(pinned)
+}
+
+/// The project owning `path` and the environment to use for it.
+///
+/// That is the project's own environment, or, for a member without one, the
+/// environment of a project in the same repository that depends on it by path.
+pub fn environment_for(path: &Path) -> Result<Option<(Project, Venv)>> {
+    let dir = if path.is_dir() { path } else { path.parent().unwrap_or(path) };
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let Some(project) = Project::discover_from(&dir)? else {
+        return Ok(None);
+    };
+    let config = Config::load(Some(&project))?;
+    if let Some(venv) = Venv::open(&project, &config) {
+        return Ok(Some((project, venv)));
+    }
+
+    let Some(name) = project.name().map(normalize_name) else {
+        return Ok(None);
+    };
+    let search_root = Worktree::discover(&project.root)
+        .map(|worktree| worktree.root)
+        .or_else(|| project.root.parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| project.root.clone());
+    let candidates = WalkDir::new(&search_root)
+        .max_depth(DEPENDENT_SEARCH_DEPTH)
+        .into_iter()
+        .filter_entry(|entry| entry.depth() == 0 || !SKIPPED_DIRS.iter().any(|skip| entry.file_name() == *skip))
+        .flatten()
+        .filter(|entry| entry.file_name() == "pyproject.toml")
+        .filter_map(|entry| entry.path().parent().map(Path::to_path_buf));
+    for root in candidates {
+        if root == project.root {
+            continue;
+        }
+        let Ok(dependent) = Project::load(&root) else {
+            continue;
+        };
+        let depends = members(&dependent).is_ok_and(|members| {
+            members
+                .get(&name)
+                .is_some_and(|member| member.project.root.canonicalize().is_ok_and(|r| r == project.root))
+        });
+        if !depends {
+            continue;
+        }
+        let config = Config::load(Some(&dependent))?;
+        if let Some(venv) = Venv::open(&dependent, &config) {
+            return Ok(Some((dependent, venv)));
+        }
+    }
+    Ok(None)
+}
+
+fn sources(project: &Project) -> Result<BTreeMap<String, SourceEntry>> {
+    let Some(table) = project.tool_config().and_then(|tool| tool.get("sources")) else {
+        return Ok(BTreeMap::new());
+    };
+    let entries: BTreeMap<String, SourceEntry> =
+        table.clone().try_into().map_err(|e: toml::de::Error| PyForgeError::InvalidConfig {
+            file: project.pyproject_path().display().to_string(),
+            source: Box::new(e),
+        })?;
+    Ok(entries.into_iter().map(|(name, entry)| (normalize_name(&name), entry)).collect())
+}
+
+fn load_member(root: &Project, from: &Project, name: &str, entry: &SourceEntry) -> Result<Member> {
+    let failed = |reason: String| PyForgeError::ResolutionFailed {
+        package: name.to_string(),
+        reason,
+    };
+    let dir = from.root.join(&entry.path);
+    let dir = dir
+        .canonicalize()
+        .map_err(|_| failed(format!("path source {} does not exist", dir.display())))?;
+    if !dir.join("pyproject.toml").is_file() {
+        return Err(failed(format!("path source {} has no pyproject.toml", dir.display())));
+    }
+
+    let project = Project::load(&dir)?;
+    if project.name().map(normalize_name).as_deref() != Some(name) {
+        return Err(failed(format!(
+            "{} is named '{}' in its pyproject.toml",
+            dir.display(),
+            project.name().unwrap_or("<unnamed>")
+        )));
+    }
+    let version = versioning::resolve(&project, &Config::load(Some(&project))?)
+        .map_err(|_| failed(format!("{} has neither a [project].version nor a version from git", dir.display())))?;
+
+    Ok(Member {
+        name: name.to_string(),
+        version,
+        path: relative_to(&root.root, &dir),
+        editable: entry.editable,
+        project,
+    })
+}
+
+/// `path` relative to `base`, walking up with `..` where needed
+fn relative_to(base: &Path, path: &Path) -> String {
+    let base = base.canonicalize().unwrap_or_else(|_| base.to_path_buf());
+    let base: Vec<_> = base.components().collect();
+    let target: Vec<_> = path.components().collect();
+    let common = base.iter().zip(&target).take_while(|(a, b)| a == b).count();
+    if common == 0 {
+        return path.display().to_string();
+    }
+
+    let mut relative = PathBuf::new();
+    for _ in common..base.len() {
+        relative.push("..");
+    }
+    for component in &target[common..] {
+        relative.push(component);
+    }
+    relative.to_string_lossy().replace('\\', "/")
+}
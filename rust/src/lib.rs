@@ -0,0 +1,5 @@
+//! PyForge core engine: CLI definitions and the project management subsystems
+//! shared by the `pyforge` binary and the Python bindings.
+
+pub mod cli;
+pub mod core;
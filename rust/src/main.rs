@@ -1,27 +1,125 @@
-mod cli;
-mod core;
-
-use cli::Cli;
-use core::utils;
-use core::error::{PyForgeError, Result};
+use pyforge_core::cli::{self, Cli};
+use pyforge_core::cli::{alias, deprecated};
+use pyforge_core::cli::args::ErrorFormat;
+use pyforge_core::cli::commands::setup;
+use pyforge_core::core::config::{self, ColorChoice, Config};
+use pyforge_core::core::history::{self, HistoryEntry};
+use pyforge_core::core::notify::{self, Finished};
+use pyforge_core::core::{capture, crash, logging, timings, utils};
+use std::time::Instant;
+use pyforge_core::core::error::{PyForgeError, Result};
 
 fn main() {
-    if let Err(error) = run() {
-        error.display_error();
+    crash::install_panic_hook();
+    let args: Vec<String> = std::env::args().collect();
+    let started = Instant::now();
+    if let Some(path) = std::env::var_os("PYFORGE_PROFILE").filter(|path| !path.is_empty()) {
+        timings::enable_profile(path.into(), started);
+    }
+
+    let result = run();
+    record_history(&args, &result, started);
+    notify_finished(&args, &result, started);
+    timings::report(started.elapsed());
+    timings::write_profile(&format!("pyforge {}", args[1..].join(" ")));
+
+    if let Err(error) = result {
+        logging::record_last_error(&error);
+        match ErrorFormat::detect(&args) {
+            ErrorFormat::Human => error.display_error(),
+            ErrorFormat::Json => eprintln!("{}", error.to_json()),
+        }
         std::process::exit(error.exit_code());
     }
 }
 
 
 fn run() -> Result<()> {
-    let cli = Cli::parse()
-        .map_err(|e| PyForgeError::internal(format!("Error parsing arguments: {}", e)))?;
+    // Built-in commands load their config themselves, so only read it here for
+    // aliases. A broken config shouldn't block `--help` or `doctor`; commands
+    // that need the config report the problem themselves
+    let args: Vec<String> = std::env::args().collect();
+    let aliases = if alias::may_expand(&args) {
+        Config::load_current().map(|c| c.alias).unwrap_or_default()
+    } else {
+        Default::default()
+    };
+    let args = alias::expand(args, &aliases)?;
+    let args = deprecated::rewrite(args)?;
+
+    let cli = Cli::parse_with_aliases(args, &aliases).map_err(|e| match e.kind() {
+        clap::error::ErrorKind::DisplayHelp
+        | clap::error::ErrorKind::DisplayVersion
+        | clap::error::ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand => e.exit(),
+        _ => PyForgeError::Usage { message: e.render().to_string() },
+    })?;
+
+    match config::color_preference() {
+        ColorChoice::Always => colored::control::set_override(true),
+        ColorChoice::Never => colored::control::set_override(false),
+        ColorChoice::Auto => {}
+    }
+    logging::init(cli.log_file.as_deref(), cli.verbose)?;
+    if let Some(format) = cli.timings {
+        timings::enable(format);
+    }
+    if cli.metered {
+        config::set_metered();
+    }
+    if cli.capture_logs {
+        capture::enable();
+    }
+    log::info!("pyforge {} invoked as {:?}", env!("CARGO_PKG_VERSION"), std::env::args().collect::<Vec<_>>());
     
     match cli.command {
-        Some(cmd) => cli::execute_command(cmd),
+        Some(cmd) => {
+            setup::offer_on_first_run(&cmd)?;
+            cli::execute_command(cmd)
+        }
         None => {
             utils::print_welcome();
             Ok(())
         }
     }
-}
\ No newline at end of file
+}
+
+
+fn record_history(args: &[String], result: &Result<()>, started: Instant) {
+    if args.len() < 2 {
+        return;
+    }
+
+    history::record(&HistoryEntry {
+        timestamp: utils::timestamp(),
+        cwd: std::env::current_dir().map(|p| p.display().to_string()).unwrap_or_default(),
+        args: args[1..].to_vec(),
+        exit_code: result.as_ref().err().map_or(0, |e| e.exit_code()),
+        duration_ms: started.elapsed().as_millis(),
+    });
+}
+
+
+fn notify_finished(args: &[String], result: &Result<()>, started: Instant) {
+    let elapsed = started.elapsed();
+    if elapsed < notify::MIN_ELAPSED {
+        return;
+    }
+    // `[notify]` is global-only, so a command that never loaded its config
+    // needs only the global file; a broken one was reported by the command
+    let loaded;
+    let config = match Config::loaded() {
+        Some(config) => config,
+        None => match Config::load(None) {
+            Ok(config) => {
+                loaded = config;
+                &loaded
+            }
+            Err(_) => return,
+        },
+    };
+    notify::notify(config, &Finished {
+        args: &args[1..],
+        exit_code: result.as_ref().err().map_or(0, |e| e.exit_code()),
+        elapsed,
+    });
+}
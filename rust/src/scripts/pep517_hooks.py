@@ -0,0 +1,40 @@
+"""Call a PEP 517 build backend hook; used by `pyforge build`.
+
+Usage: python pep517_hooks.py <hook> <backend> <backend-path-json> <output-dir>
+Prints the hook's result as JSON on the last line of stdout.
+"""
+import importlib
+import json
+import os
+import sys
+
+
+def load_backend(spec, backend_path):
+    for path in reversed(backend_path):
+        sys.path.insert(0, os.path.abspath(path))
+    module_name, _, attributes = spec.partition(":")
+    backend = importlib.import_module(module_name)
+    for attribute in filter(None, attributes.split(".")):
+        backend = getattr(backend, attribute)
+    return backend
+
+
+def main():
+    hook, spec, backend_path, output_dir = sys.argv[1:5]
+    backend = load_backend(spec, json.loads(backend_path))
+
+    if hook in ("get_requires_for_build_wheel", "get_requires_for_build_sdist"):
+        function = getattr(backend, hook, None)
+        result = function() if function else []
+    elif hook in ("build_wheel", "build_sdist"):
+        result = getattr(backend, hook)(output_dir)
+    else:
+        raise SystemExit("unknown hook: " + hook)
+
+    sys.stdout.flush()
+    print()
+    print(json.dumps(result))
+
+
+if __name__ == "__main__":
+    main()